@@ -1,18 +1,14 @@
-use crab_pot::{
-    create_app, middleware::RateLimiter, ApiKeyRepository, ApiKeyService, AppState, LogRepository,
-    LogService, SchemaRepository, SchemaResponse, SchemaService,
-};
+use crab_pot::{create_app, AppState, SchemaResponse};
 use criterion::{
     black_box, criterion_group, criterion_main, AxisScale, Criterion, PlotConfiguration,
 };
 use reqwest::Client;
 use sqlx::{Pool, Postgres};
-use std::sync::Arc;
 use testcontainers_modules::{
     postgres,
     testcontainers::{runners::AsyncRunner, ContainerAsync},
 };
-use tokio::{net::TcpListener, sync::broadcast};
+use tokio::net::TcpListener;
 use tokio_postgres::NoTls;
 use uuid::Uuid;
 
@@ -65,32 +61,17 @@ async fn setup_benchmark_app() -> BenchmarkApp {
         .await
         .expect("Failed to connect to DB");
 
-    let schema_repo = Arc::new(SchemaRepository::new(pool.clone()));
-    let log_repo = Arc::new(LogRepository::new(pool.clone()));
-    let api_key_repo = Arc::new(ApiKeyRepository::new(pool.clone()));
-
-    let schema_service = Arc::new(SchemaService::new(schema_repo.clone(), log_repo.clone()));
-    let log_service = Arc::new(LogService::new(log_repo.clone(), schema_service.clone()));
-    let api_key_service = Arc::new(ApiKeyService::new(api_key_repo.clone()));
+    let app_state = AppState::builder(pool.clone())
+        .with_broadcast_channel_size(16)
+        .build();
 
     let create_api_key_request = crab_pot::models::CreateApiKey::new("Benchmark API Key");
-    let test_api_key = api_key_service
+    let test_api_key = app_state
+        .api_key_service
         .create_api_key(create_api_key_request)
         .await
         .expect("Failed to create test API key");
 
-    let (tx, _) = broadcast::channel(16);
-
-    let rate_limiter = Arc::new(RateLimiter::new());
-
-    let app_state = AppState {
-        schema_service,
-        log_service,
-        api_key_service,
-        log_broadcast: tx,
-        rate_limiter,
-    };
-
     let app = create_app(app_state, pool.clone());
 
     let listener = TcpListener::bind("127.0.0.1:0")
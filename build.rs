@@ -0,0 +1,39 @@
+use std::process::Command;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Build info for GET /version. Docker builds have no `.git`, so the SHA
+    // can be passed in through the GIT_SHA build environment instead.
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|sha| sha.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+    println!(
+        "cargo:rustc-env=BUILT_AT={}",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    );
+
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/logs.proto");
+
+        // protox compiles the proto in Rust, so building needs no `protoc`.
+        let descriptors = protox::compile(["proto/logs.proto"], ["proto"])?;
+        tonic_build::configure().compile_fds(descriptors)?;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 CREATE TABLE IF NOT EXISTS api_keys (
     id SERIAL PRIMARY KEY,
-    key_hash VARCHAR(64) NOT NULL UNIQUE,
-    key_prefix VARCHAR(20),
+    key_hash VARCHAR(255) NOT NULL UNIQUE,
+    key_prefix VARCHAR(64),
     name VARCHAR(255) NOT NULL,
     description TEXT,
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
@@ -11,11 +11,16 @@ CREATE TABLE IF NOT EXISTS api_keys (
     allowed_ips INET[],
     usage_count BIGINT DEFAULT 0,
     rate_limit_per_second INT DEFAULT 10,
-    rate_limit_burst INT DEFAULT 20
+    rate_limit_burst INT DEFAULT 20,
+    tenant_id VARCHAR(64) NOT NULL DEFAULT 'default'
 );
 
 CREATE UNIQUE INDEX IF NOT EXISTS idx_api_keys_key_hash ON api_keys(key_hash);
+-- Salted hashes cannot be looked up by value; their prefix identifies the key.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_keys_salted_key_prefix
+    ON api_keys(key_prefix) WHERE key_hash LIKE '$%';
 CREATE INDEX IF NOT EXISTS idx_api_keys_is_active ON api_keys(is_active) WHERE is_active = TRUE;
 CREATE INDEX IF NOT EXISTS idx_api_keys_expires_at
     ON api_keys(expires_at) WHERE expires_at IS NOT NULL;
 CREATE INDEX IF NOT EXISTS idx_api_keys_last_used_at ON api_keys(last_used_at);
+CREATE INDEX IF NOT EXISTS idx_api_keys_tenant_id ON api_keys(tenant_id);
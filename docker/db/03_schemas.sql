@@ -4,11 +4,26 @@ CREATE TABLE IF NOT EXISTS schemas (
     version VARCHAR(50) NOT NULL,
     description TEXT,
     schema_definition JSONB NOT NULL,
+    webhook_url TEXT,
+    webhook_secret TEXT,
+    strict_formats BOOLEAN NOT NULL DEFAULT FALSE,
+    indexed_fields TEXT[] NOT NULL DEFAULT '{}',
+    max_retained_logs INTEGER CHECK (max_retained_logs > 0),
+    max_log_age_secs INTEGER CHECK (max_log_age_secs > 0),
+    redacted_fields TEXT[] NOT NULL DEFAULT '{}',
+    sample_rate DOUBLE PRECISION CHECK (sample_rate >= 0 AND sample_rate <= 1),
+    labels TEXT[] NOT NULL DEFAULT '{}',
+    max_field_bytes INTEGER CHECK (max_field_bytes > 0),
+    validation_mode TEXT NOT NULL DEFAULT 'strict' CHECK (validation_mode IN ('strict', 'lenient', 'off')),
+    tenant_id VARCHAR(64) NOT NULL DEFAULT 'default',
+    created_by INTEGER REFERENCES api_keys(id) ON DELETE SET NULL,
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW(),
-    UNIQUE(name, version)
+    UNIQUE(tenant_id, name, version)
 );
 
 CREATE INDEX IF NOT EXISTS idx_schemas_name ON schemas(name);
 CREATE INDEX IF NOT EXISTS idx_schemas_name_version ON schemas(name, version);
+CREATE INDEX IF NOT EXISTS idx_schemas_tenant_id ON schemas(tenant_id);
 CREATE INDEX IF NOT EXISTS idx_schemas_created_at_id ON schemas(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_schemas_labels ON schemas USING GIN (labels);
@@ -1,11 +1,34 @@
 CREATE TABLE IF NOT EXISTS logs (
     id SERIAL PRIMARY KEY,
     schema_id UUID NOT NULL REFERENCES schemas(id),
+    -- For compressed rows only the small top-level fields, which keeps them
+    -- visible to filters, level counts and indexes.
     log_data JSONB NOT NULL,
-    created_at TIMESTAMPTZ DEFAULT NOW()
+    -- gzip-compressed full JSON for payloads above LOG_COMPRESSION_THRESHOLD_BYTES.
+    log_data_compressed BYTEA,
+    created_by INTEGER REFERENCES api_keys(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    -- Client-supplied key addressing the log via PUT /logs/schemas/{schema_id}/by-key/{key}.
+    log_key TEXT
 );
 
 CREATE INDEX IF NOT EXISTS idx_logs_schema_id ON logs(schema_id);
 CREATE INDEX IF NOT EXISTS idx_logs_created_at ON logs(created_at);
 CREATE INDEX IF NOT EXISTS idx_logs_schema_created_id ON logs(schema_id, created_at DESC, id DESC);
 CREATE INDEX IF NOT EXISTS idx_logs_data_gin ON logs USING GIN (log_data);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_logs_schema_key ON logs(schema_id, log_key) WHERE log_key IS NOT NULL;
+
+-- Pre-aggregated "count by level over the last 24h" summary, refreshed periodically
+-- by the server (LOG_SUMMARY_REFRESH_SECS). Results may lag behind live data.
+CREATE MATERIALIZED VIEW IF NOT EXISTS log_level_counts_24h AS
+SELECT
+    schema_id,
+    COALESCE(log_data->>'level', 'UNKNOWN') AS level,
+    COUNT(*) AS count,
+    NOW() AS refreshed_at
+FROM logs
+WHERE created_at >= NOW() - INTERVAL '24 hours'
+GROUP BY schema_id, COALESCE(log_data->>'level', 'UNKNOWN');
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_log_level_counts_24h_schema_level
+    ON log_level_counts_24h(schema_id, level);
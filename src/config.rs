@@ -1,35 +1,559 @@
+pub mod defaults;
+mod secrets;
+
+use axum::http::HeaderValue;
+use regex::Regex;
+use sqlx::types::ipnetwork::IpNetwork;
 use std::net::SocketAddr;
+use std::str::FromStr;
+
+use self::defaults::{
+    DEFAULT_API_KEY_CACHE_TTL_SECS, DEFAULT_COMPAT_SAMPLE_SIZE, DEFAULT_MAX_FUTURE_SKEW_SECS,
+    DEFAULT_SLOW_REQUEST_BUFFER_SIZE, DEFAULT_SLOW_REQUEST_THRESHOLD_MS,
+    DEFAULT_WS_MAX_CONNECTIONS,
+};
+use crate::dto::timestamp::TimestampPrecision;
+use crate::error::ErrorBodyFormat;
+
+/// Page size bounds applied to schema and log listings.
+#[derive(Debug, Clone, Copy)]
+pub struct PageLimits {
+    pub default_limit: i32,
+    pub max_limit: i32,
+}
+
+impl Default for PageLimits {
+    fn default() -> Self {
+        Self {
+            default_limit: 10,
+            max_limit: 100,
+        }
+    }
+}
+
+impl PageLimits {
+    /// Falls back to the default when no limit was requested and clamps the
+    /// result to `[1, max_limit]`.
+    pub fn resolve(&self, requested: Option<i32>) -> i32 {
+        requested
+            .unwrap_or(self.default_limit)
+            .clamp(1, self.max_limit.max(1))
+    }
+}
+
+/// Upper bounds on schema definitions, so a pathological definition cannot
+/// make every log validation slow. Exceeding one is a 400 `SCHEMA_TOO_COMPLEX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaComplexityLimits {
+    /// Size of the serialized definition.
+    pub max_bytes: usize,
+    /// Nesting depth of objects and arrays.
+    pub max_depth: usize,
+    /// Entries across all `properties` objects.
+    pub max_properties: usize,
+}
+
+impl Default for SchemaComplexityLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 256 * 1024,
+            max_depth: 64,
+            max_properties: 2000,
+        }
+    }
+}
+
+/// Shape of generated API keys: `prefix` followed by `random_bytes` of
+/// entropy, URL-safe base64 encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyFormat {
+    pub prefix: String,
+    pub random_bytes: usize,
+}
+
+impl ApiKeyFormat {
+    pub const MAX_PREFIX_LEN: usize = 32;
+    /// Random characters kept after the prefix in the stored `key_prefix`,
+    /// enough to tell keys apart in listings.
+    pub const VISIBLE_RANDOM_CHARS: usize = 7;
+    /// Least entropy a key may carry: 128 bits.
+    pub const MIN_RANDOM_BYTES: usize = 16;
+
+    pub fn new(prefix: String, random_bytes: usize) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            prefix.chars().count() <= Self::MAX_PREFIX_LEN,
+            "API_KEY_PREFIX must be at most {} characters",
+            Self::MAX_PREFIX_LEN
+        );
+        anyhow::ensure!(
+            random_bytes >= Self::MIN_RANDOM_BYTES,
+            "API_KEY_RANDOM_BYTES must be at least {}",
+            Self::MIN_RANDOM_BYTES
+        );
+
+        Ok(Self {
+            prefix,
+            random_bytes,
+        })
+    }
+
+    /// The stored `key_prefix` for `plain_key`: the configured prefix plus
+    /// the first few random characters. Counts characters rather than bytes,
+    /// so keys that are short or contain multi-byte characters never panic.
+    pub fn display_prefix(&self, plain_key: &str) -> String {
+        let visible = self.prefix.chars().count() + Self::VISIBLE_RANDOM_CHARS;
+        format!("{}...", plain_key.chars().take(visible).collect::<String>())
+    }
+
+    /// Every stored `key_prefix` `plain_key` could have been issued with,
+    /// under any `API_KEY_PREFIX` up to [`Self::MAX_PREFIX_LEN`] characters,
+    /// so keys keep authenticating after the configured prefix changes.
+    pub fn display_prefix_candidates(plain_key: &str) -> Vec<String> {
+        let chars: Vec<char> = plain_key.chars().collect();
+        let shortest = Self::VISIBLE_RANDOM_CHARS.min(chars.len());
+        let longest = (Self::VISIBLE_RANDOM_CHARS + Self::MAX_PREFIX_LEN).min(chars.len());
+
+        (shortest..=longest)
+            .map(|visible| format!("{}...", chars[..visible].iter().collect::<String>()))
+            .collect()
+    }
+}
+
+impl Default for ApiKeyFormat {
+    fn default() -> Self {
+        Self {
+            prefix: "sk_".to_string(),
+            random_bytes: 32,
+        }
+    }
+}
+
+/// How new API keys are hashed before they are stored. Existing keys keep
+/// verifying with the algorithm their stored hash was made with, so switching
+/// only affects keys created or rotated afterwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ApiKeyHashAlgorithm {
+    /// Unsalted SHA-256, stored as bare hex.
+    #[default]
+    Sha256,
+    /// Argon2id, stored as a PHC string (`$argon2id$...`).
+    Argon2,
+}
+
+impl FromStr for ApiKeyHashAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sha256" => Ok(Self::Sha256),
+            "argon2" => Ok(Self::Argon2),
+            other => anyhow::bail!(
+                "API_KEY_HASH_ALGORITHM must be sha256 or argon2, got '{}'",
+                other
+            ),
+        }
+    }
+}
+
+/// What list endpoints do with query parameters none of their fields use,
+/// e.g. `lim=10` for `limit=10`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownQueryParams {
+    /// Ignore them, as serde does by default.
+    #[default]
+    Ignore,
+    /// Reject the request with 400 `UNKNOWN_QUERY_PARAM`.
+    Reject,
+}
+
+impl FromStr for UnknownQueryParams {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ignore" => Ok(Self::Ignore),
+            "reject" => Ok(Self::Reject),
+            other => anyhow::bail!(
+                "UNKNOWN_QUERY_PARAMS must be ignore or reject, got '{}'",
+                other
+            ),
+        }
+    }
+}
+
+/// How paths with a trailing slash, e.g. `/schemas/`, are routed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Only the exact path matches; `/schemas/` is a 404.
+    #[default]
+    Strict,
+    /// Trailing slashes are removed before routing.
+    Trim,
+}
+
+impl FromStr for TrailingSlash {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(Self::Strict),
+            "trim" => Ok(Self::Trim),
+            other => anyhow::bail!("TRAILING_SLASH must be strict or trim, got '{}'", other),
+        }
+    }
+}
+
+/// How `POST /logs` decides whether a log is kept under its schema's
+/// `sample_rate`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogSampling {
+    /// An independent random draw per log.
+    #[default]
+    Random,
+    /// Derived from the request id, so a retried request with the same
+    /// `X-Request-Id` gets the same decision.
+    Deterministic,
+}
+
+impl FromStr for LogSampling {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "random" => Ok(Self::Random),
+            "deterministic" => Ok(Self::Deterministic),
+            other => anyhow::bail!(
+                "LOG_SAMPLING must be random or deterministic, got '{}'",
+                other
+            ),
+        }
+    }
+}
+
+/// Optional naming conventions for schema names and versions. Each pattern
+/// must match the whole value; without a pattern any non-empty string is
+/// accepted.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaNamingPolicy {
+    pub name_pattern: Option<Regex>,
+    pub version_pattern: Option<Regex>,
+}
+
+impl SchemaNamingPolicy {
+    pub fn new(
+        name_pattern: Option<&str>,
+        version_pattern: Option<&str>,
+    ) -> Result<Self, regex::Error> {
+        let anchored = |pattern: &str| Regex::new(&format!("^(?:{})$", pattern));
+
+        Ok(Self {
+            name_pattern: name_pattern.map(anchored).transpose()?,
+            version_pattern: version_pattern.map(anchored).transpose()?,
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub main_api_addr: SocketAddr,
     pub admin_api_addr: SocketAddr,
+    /// Bind address of the gRPC ingestion service.
+    #[cfg(feature = "grpc")]
+    pub grpc_api_addr: SocketAddr,
     pub broadcast_channel_size: usize,
+    /// Open WebSocket connections allowed at once.
+    pub ws_max_connections: usize,
+    /// Requests taking at least this long are kept for
+    /// `GET /admin/slow-requests`.
+    pub slow_request_threshold_ms: u64,
+    /// Slow requests kept; the oldest is dropped first.
+    pub slow_request_buffer_size: usize,
     pub rust_log: String,
+    pub db_connect_max_attempts: u32,
+    pub db_connect_max_backoff_secs: u64,
+    pub log_summary_refresh_secs: u64,
+    pub log_retention_sweep_secs: u64,
+    /// How long shutdown waits for open connections to finish before
+    /// exiting anyway.
+    pub shutdown_timeout_secs: u64,
+    pub page_limits: PageLimits,
+    pub schema_naming_policy: SchemaNamingPolicy,
+    pub schema_complexity_limits: SchemaComplexityLimits,
+    /// Most recent logs checked by `?check_compat=true` schema updates.
+    pub schema_compat_sample_size: i32,
+    /// `log_data` field naming the schema of a `POST /logs` body without
+    /// `schema_id`; `None` disables inference.
+    pub schema_inference_field: Option<String>,
+    /// Schemas allowed across all tenants; `None` is unlimited.
+    pub max_schemas: Option<u64>,
+    pub error_body_format: ErrorBodyFormat,
+    /// Fractional-second digits of response timestamps.
+    pub timestamp_precision: TimestampPrecision,
+    pub api_key_format: ApiKeyFormat,
+    pub api_key_hash_algorithm: ApiKeyHashAlgorithm,
+    /// How long authenticated API keys are cached in memory; 0 disables.
+    pub api_key_cache_ttl_secs: u64,
+    /// API keys allowed, revoked ones included; `None` is unlimited.
+    pub max_api_keys: Option<u64>,
+    pub db_statement_timeout_ms: u64,
+    pub cors_allowed_origins: Vec<HeaderValue>,
+    /// Source ranges allowed to reach the admin API; empty allows any.
+    pub admin_allowed_ips: Vec<IpNetwork>,
+    /// Proxies whose `X-Forwarded-For` is trusted by the admin allowlist.
+    pub trusted_proxies: Vec<IpNetwork>,
+    /// Enables the destructive `POST /admin/reset`.
+    pub allow_reset: bool,
+    pub unknown_query_params: UnknownQueryParams,
+    pub trailing_slash: TrailingSlash,
+    /// How far in the future an explicit log `created_at` may lie.
+    pub log_max_future_skew_secs: i64,
+    pub log_sampling: LogSampling,
+    /// Log payloads whose JSON is at least this large are stored
+    /// gzip-compressed; `None` disables compression.
+    pub log_compression_threshold_bytes: Option<usize>,
+}
+
+/// Environment variables as seen by [`Config::from_lookup`]. Empty values
+/// count as unset.
+struct Env<F> {
+    lookup: F,
+}
+
+impl<F: Fn(&str) -> Option<String>> Env<F> {
+    fn var(&self, name: &str) -> Option<String> {
+        (self.lookup)(name).filter(|value| !value.is_empty())
+    }
+
+    /// `name` parsed as `T`, or `None` when unset. A value that does not
+    /// parse is an error naming the variable.
+    fn parse<T>(&self, name: &str) -> anyhow::Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.var(name)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid {} '{}': {}", name, value, e))
+            })
+            .transpose()
+    }
+
+    fn parse_or<T>(&self, name: &str, default: T) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        Ok(self.parse(name)?.unwrap_or(default))
+    }
+
+    /// Settings whose `FromStr` error already names the variable.
+    fn setting<T>(&self, name: &str) -> anyhow::Result<T>
+    where
+        T: FromStr<Err = anyhow::Error> + Default,
+    {
+        Ok(self
+            .var(name)
+            .map(|value| value.parse())
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    /// Reads a comma-separated list of CIDRs (or bare addresses) from `name`.
+    fn ip_networks(&self, name: &str) -> anyhow::Result<Vec<IpNetwork>> {
+        self.var(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|network| !network.is_empty())
+            .map(|network| {
+                network
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid {} entry `{}`: {}", name, network, e))
+            })
+            .collect()
+    }
 }
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
-        Ok(Self {
-            database_url: std::env::var("DATABASE_URL")
-                .expect("DATABASE_URL environment variable is not set"),
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
 
-            main_api_addr: std::env::var("MAIN_API_ADDR")
-                .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
-                .parse()?,
+    /// Loads and validates the configuration from `lookup`, which maps an
+    /// environment variable name to its value. Unset settings take their
+    /// defaults; malformed or inconsistent ones are an error naming the
+    /// variable.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let env = Env { lookup };
 
-            admin_api_addr: std::env::var("ADMIN_API_ADDR")
-                .unwrap_or_else(|_| "127.0.0.1:8081".to_string())
-                .parse()?,
+        let config = Self {
+            database_url: secrets::read_secret(
+                "DATABASE_URL",
+                env.var("DATABASE_URL"),
+                env.var("DATABASE_URL_FILE"),
+            )?
+            .ok_or_else(|| anyhow::anyhow!("DATABASE_URL or DATABASE_URL_FILE must be set"))?,
 
-            broadcast_channel_size: std::env::var("BROADCAST_CHANNEL_SIZE")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(100),
+            main_api_addr: env.parse_or("MAIN_API_ADDR", ([0, 0, 0, 0], 8080).into())?,
 
-            rust_log: std::env::var("RUST_LOG")
-                .unwrap_or_else(|_| "tower_http=debug,log_server=debug,info".to_string()),
-        })
+            admin_api_addr: env.parse_or("ADMIN_API_ADDR", ([127, 0, 0, 1], 8081).into())?,
+
+            #[cfg(feature = "grpc")]
+            grpc_api_addr: env.parse_or("GRPC_API_ADDR", ([0, 0, 0, 0], 50051).into())?,
+
+            broadcast_channel_size: env.parse_or("BROADCAST_CHANNEL_SIZE", 100)?,
+
+            ws_max_connections: env.parse_or("WS_MAX_CONNECTIONS", DEFAULT_WS_MAX_CONNECTIONS)?,
+
+            slow_request_threshold_ms: env.parse_or(
+                "SLOW_REQUEST_THRESHOLD_MS",
+                DEFAULT_SLOW_REQUEST_THRESHOLD_MS,
+            )?,
+            slow_request_buffer_size: env
+                .parse_or("SLOW_REQUEST_BUFFER_SIZE", DEFAULT_SLOW_REQUEST_BUFFER_SIZE)?,
+
+            rust_log: env
+                .var("RUST_LOG")
+                .unwrap_or_else(|| "tower_http=debug,log_server=debug,info".to_string()),
+
+            db_connect_max_attempts: env.parse_or("DB_CONNECT_MAX_ATTEMPTS", 10)?,
+
+            db_connect_max_backoff_secs: env.parse_or("DB_CONNECT_MAX_BACKOFF_SECS", 30)?,
+
+            db_statement_timeout_ms: env.parse_or("DB_STATEMENT_TIMEOUT_MS", 0)?,
+
+            log_summary_refresh_secs: env.parse_or("LOG_SUMMARY_REFRESH_SECS", 60)?,
+
+            log_max_future_skew_secs: env
+                .parse_or("LOG_MAX_FUTURE_SKEW_SECS", DEFAULT_MAX_FUTURE_SKEW_SECS)?,
+
+            log_sampling: env.setting("LOG_SAMPLING")?,
+
+            log_compression_threshold_bytes: env
+                .parse("LOG_COMPRESSION_THRESHOLD_BYTES")?
+                .filter(|&bytes| bytes > 0),
+
+            log_retention_sweep_secs: env.parse_or("LOG_RETENTION_SWEEP_SECS", 60)?,
+
+            shutdown_timeout_secs: env.parse_or("SHUTDOWN_TIMEOUT_SECS", 30)?,
+
+            page_limits: PageLimits {
+                default_limit: env
+                    .parse_or("DEFAULT_PAGE_LIMIT", PageLimits::default().default_limit)?,
+                max_limit: env.parse_or("MAX_PAGE_LIMIT", PageLimits::default().max_limit)?,
+            },
+
+            schema_naming_policy: SchemaNamingPolicy::new(
+                env.var("SCHEMA_NAME_PATTERN").as_deref(),
+                env.var("SCHEMA_VERSION_PATTERN").as_deref(),
+            )?,
+
+            schema_complexity_limits: SchemaComplexityLimits {
+                max_bytes: env.parse_or(
+                    "SCHEMA_MAX_BYTES",
+                    SchemaComplexityLimits::default().max_bytes,
+                )?,
+                max_depth: env.parse_or(
+                    "SCHEMA_MAX_DEPTH",
+                    SchemaComplexityLimits::default().max_depth,
+                )?,
+                max_properties: env.parse_or(
+                    "SCHEMA_MAX_PROPERTIES",
+                    SchemaComplexityLimits::default().max_properties,
+                )?,
+            },
+
+            schema_compat_sample_size: env
+                .parse_or("SCHEMA_COMPAT_SAMPLE_SIZE", DEFAULT_COMPAT_SAMPLE_SIZE)?,
+
+            schema_inference_field: env.var("SCHEMA_INFERENCE_FIELD"),
+
+            max_schemas: env.parse("MAX_SCHEMAS")?,
+
+            error_body_format: ErrorBodyFormat {
+                code_field: env
+                    .var("ERROR_CODE_FIELD")
+                    .unwrap_or_else(|| ErrorBodyFormat::default().code_field),
+                message_field: env
+                    .var("ERROR_MESSAGE_FIELD")
+                    .unwrap_or_else(|| ErrorBodyFormat::default().message_field),
+                wrap: env.parse_or("ERROR_BODY_WRAP", false)?,
+            },
+
+            api_key_format: ApiKeyFormat::new(
+                env.var("API_KEY_PREFIX")
+                    .unwrap_or_else(|| ApiKeyFormat::default().prefix),
+                env.parse_or("API_KEY_RANDOM_BYTES", ApiKeyFormat::default().random_bytes)?,
+            )?,
+
+            api_key_hash_algorithm: env.setting("API_KEY_HASH_ALGORITHM")?,
+
+            api_key_cache_ttl_secs: env
+                .parse_or("API_KEY_CACHE_TTL_SECS", DEFAULT_API_KEY_CACHE_TTL_SECS)?,
+
+            max_api_keys: env.parse("MAX_API_KEYS")?,
+
+            timestamp_precision: env.setting("TIMESTAMP_PRECISION")?,
+
+            unknown_query_params: env.setting("UNKNOWN_QUERY_PARAMS")?,
+
+            trailing_slash: env.setting("TRAILING_SLASH")?,
+
+            cors_allowed_origins: env
+                .var("CORS_ALLOWED_ORIGINS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(|origin| {
+                    HeaderValue::from_str(origin).map_err(|e| {
+                        anyhow::anyhow!("Invalid CORS_ALLOWED_ORIGINS entry `{}`: {}", origin, e)
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+
+            admin_allowed_ips: env.ip_networks("ADMIN_ALLOWED_IPS")?,
+
+            trusted_proxies: env.ip_networks("TRUSTED_PROXIES")?,
+
+            allow_reset: env.parse_or("ALLOW_RESET", false)?,
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks the settings that parse on their own but cannot work, alone or
+    /// together.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.broadcast_channel_size > 0,
+            "BROADCAST_CHANNEL_SIZE must be positive"
+        );
+        anyhow::ensure!(
+            self.page_limits.default_limit > 0,
+            "DEFAULT_PAGE_LIMIT must be positive"
+        );
+        anyhow::ensure!(
+            self.page_limits.max_limit >= self.page_limits.default_limit,
+            "MAX_PAGE_LIMIT ({}) must be at least DEFAULT_PAGE_LIMIT ({})",
+            self.page_limits.max_limit,
+            self.page_limits.default_limit
+        );
+        anyhow::ensure!(
+            self.schema_compat_sample_size > 0,
+            "SCHEMA_COMPAT_SAMPLE_SIZE must be positive"
+        );
+        anyhow::ensure!(
+            self.main_api_addr != self.admin_api_addr,
+            "MAIN_API_ADDR and ADMIN_API_ADDR must differ, both are {}",
+            self.main_api_addr
+        );
+
+        Ok(())
     }
 }
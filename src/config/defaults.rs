@@ -0,0 +1,10 @@
+//! Default values of settings that `Config` reads from the environment. The
+//! components they configure fall back to the same values when built without
+//! a `Config`.
+
+pub const DEFAULT_COMPAT_SAMPLE_SIZE: i32 = 1000;
+pub const DEFAULT_API_KEY_CACHE_TTL_SECS: u64 = 5;
+pub const DEFAULT_MAX_FUTURE_SKEW_SECS: i64 = 300;
+pub const DEFAULT_WS_MAX_CONNECTIONS: usize = 1024;
+pub const DEFAULT_SLOW_REQUEST_THRESHOLD_MS: u64 = 1000;
+pub const DEFAULT_SLOW_REQUEST_BUFFER_SIZE: usize = 100;
@@ -0,0 +1,31 @@
+//! Secrets read from the environment, either directly or, following the
+//! container `*_FILE` convention, from a file named by `<NAME>_FILE`.
+
+use anyhow::Context;
+
+/// The secret `name` given the values of `name` and `<name>_FILE`: the
+/// contents of the file at `path` without trailing line breaks, or else
+/// `value`. Setting both, or pointing at an unreadable or empty file, is an
+/// error.
+pub(crate) fn read_secret(
+    name: &str,
+    value: Option<String>,
+    path: Option<String>,
+) -> anyhow::Result<Option<String>> {
+    let file_var = format!("{}_FILE", name);
+
+    match (value, path) {
+        (Some(_), Some(_)) => anyhow::bail!("Set either {} or {}, not both", name, file_var),
+        (Some(value), None) => Ok(Some(value)),
+        (None, Some(path)) => {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {} from '{}'", file_var, path))?;
+            let secret = contents.trim_end_matches(['\r', '\n']);
+            if secret.is_empty() {
+                anyhow::bail!("{} points at an empty file '{}'", file_var, path);
+            }
+            Ok(Some(secret.to_string()))
+        }
+        (None, None) => Ok(None),
+    }
+}
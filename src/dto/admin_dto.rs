@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{dto::timestamp, middleware::SlowRequest};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaintenanceRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaintenanceResponse {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetQuery {
+    pub confirm: Option<String>,
+    #[serde(default)]
+    pub include_api_keys: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResetResponse {
+    pub deleted_logs: i64,
+    pub deleted_schemas: i64,
+    pub deleted_api_keys: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowRequestResponse {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    pub request_id: Option<String>,
+    #[serde(with = "timestamp")]
+    pub started_at: DateTime<Utc>,
+}
+
+impl From<SlowRequest> for SlowRequestResponse {
+    fn from(request: SlowRequest) -> Self {
+        Self {
+            method: request.method,
+            path: request.path,
+            status: request.status,
+            duration_ms: request.duration.as_millis() as u64,
+            request_id: request.request_id,
+            started_at: request.started_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowRequestsResponse {
+    pub threshold_ms: u64,
+    pub capacity: usize,
+    /// Slowest first.
+    pub requests: Vec<SlowRequestResponse>,
+}
@@ -1,9 +1,15 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::types::ipnetwork::IpNetwork;
 use validator::Validate;
 
-use crate::models::{api_key_model::CreatedApiKey, ApiKey};
+use crate::{
+    dto::{common::ListFormat, timestamp},
+    models::{api_key_model::CreatedApiKey, ApiKey, ApiKeyStats},
+    AppError, AppResult, ErrorCode,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyResponse {
@@ -11,14 +17,18 @@ pub struct ApiKeyResponse {
     pub key_prefix: Option<String>,
     pub name: String,
     pub description: Option<String>,
+    #[serde(with = "timestamp")]
     pub created_at: DateTime<Utc>,
+    #[serde(with = "timestamp::option")]
     pub last_used_at: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp::option")]
     pub expires_at: Option<DateTime<Utc>>,
     pub is_active: bool,
     pub allowed_ips: Option<Vec<IpNetwork>>,
     pub usage_count: Option<i64>,
     pub rate_limit_per_second: Option<i32>,
     pub rate_limit_burst: Option<i32>,
+    pub tenant_id: String,
 }
 
 impl From<ApiKey> for ApiKeyResponse {
@@ -36,10 +46,17 @@ impl From<ApiKey> for ApiKeyResponse {
             usage_count: value.usage_count,
             rate_limit_per_second: value.rate_limit_per_second,
             rate_limit_burst: value.rate_limit_burst,
+            tenant_id: value.tenant_id,
         }
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListApiKeysQuery {
+    #[serde(default)]
+    pub format: ListFormat,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeysResponse {
     pub api_keys: Vec<ApiKeyResponse>,
@@ -54,11 +71,14 @@ impl From<Vec<ApiKey>> for ApiKeysResponse {
 }
 
 #[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
 pub struct CreateApiKeyRequest {
     #[validate(length(min = 1, message = "API key name cannot be empty"))]
     pub name: String,
     pub description: Option<String>,
     pub expires_at: Option<DateTime<Utc>>,
+    /// Relative alternative to `expires_at`, e.g. `30d` or `12h`.
+    pub expires_in: Option<String>,
     pub allowed_ips: Option<Vec<IpNetwork>>,
     #[validate(range(
         min = 1,
@@ -72,6 +92,57 @@ pub struct CreateApiKeyRequest {
         message = "Burst limit must be between 1 and 20000"
     ))]
     pub rate_limit_burst: Option<i32>,
+    /// Defaults to the `default` tenant.
+    #[validate(length(
+        min = 1,
+        max = 64,
+        message = "Tenant id must be between 1 and 64 characters"
+    ))]
+    pub tenant_id: Option<String>,
+}
+
+impl CreateApiKeyRequest {
+    /// The absolute expiry: `expires_at`, or `now + expires_in`. Setting
+    /// both is an error.
+    pub fn resolve_expires_at(&self, now: DateTime<Utc>) -> AppResult<Option<DateTime<Utc>>> {
+        let Some(expires_in) = &self.expires_in else {
+            return Ok(self.expires_at);
+        };
+        if self.expires_at.is_some() {
+            return Err(
+                AppError::bad_request("Set either `expires_at` or `expires_in`, not both")
+                    .with_code(ErrorCode::InvalidExpiry),
+            );
+        }
+
+        parse_relative_duration(expires_in)
+            .and_then(|duration| now.checked_add_signed(duration))
+            .map(Some)
+            .ok_or_else(|| {
+                AppError::bad_request(format!(
+                    "Invalid `expires_in` '{}': expected a positive number followed by s, m, h, d or w, e.g. `30d`",
+                    expires_in
+                ))
+                .with_code(ErrorCode::InvalidExpiry)
+            })
+    }
+}
+
+/// Parses `<positive integer><unit>` with unit `s`, `m`, `h`, `d` or `w`.
+fn parse_relative_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit_start = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: i64 = amount.parse().ok().filter(|&amount| amount > 0)?;
+
+    match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,7 +151,9 @@ pub struct CreateApiKeyResponse {
     pub key: String,
     pub key_prefix: Option<String>,
     pub name: String,
+    #[serde(with = "timestamp")]
     pub created_at: DateTime<Utc>,
+    #[serde(with = "timestamp::option")]
     pub expires_at: Option<DateTime<Utc>>,
 }
 
@@ -96,3 +169,133 @@ impl From<CreatedApiKey> for CreateApiKeyResponse {
         }
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct RotateExpiringQuery {
+    /// How far ahead to look, e.g. `7d`; same syntax as `expires_in`.
+    pub within: String,
+}
+
+impl RotateExpiringQuery {
+    /// The end of the window starting at `now`.
+    pub fn expiring_before(&self, now: DateTime<Utc>) -> AppResult<DateTime<Utc>> {
+        window_end(&self.within, now)
+    }
+}
+
+/// Default `within` of `GET /api-keys/stats`.
+pub const DEFAULT_EXPIRING_SOON_WINDOW: &str = "7d";
+
+#[derive(Debug, Deserialize)]
+pub struct ApiKeyStatsQuery {
+    /// Window counted as `expiring_soon`; defaults to
+    /// [`DEFAULT_EXPIRING_SOON_WINDOW`].
+    pub within: Option<String>,
+}
+
+impl ApiKeyStatsQuery {
+    pub fn expiring_before(&self, now: DateTime<Utc>) -> AppResult<DateTime<Utc>> {
+        window_end(
+            self.within
+                .as_deref()
+                .unwrap_or(DEFAULT_EXPIRING_SOON_WINDOW),
+            now,
+        )
+    }
+}
+
+/// Key counts of `GET /api-keys/stats`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyStatsResponse {
+    pub total: i64,
+    pub active: i64,
+    pub expired: i64,
+    pub revoked: i64,
+    pub expiring_soon: i64,
+}
+
+impl From<ApiKeyStats> for ApiKeyStatsResponse {
+    fn from(value: ApiKeyStats) -> Self {
+        Self {
+            total: value.total,
+            active: value.active,
+            expired: value.expired,
+            revoked: value.revoked,
+            expiring_soon: value.expiring_soon,
+        }
+    }
+}
+
+/// `now` plus a relative `within` window such as `7d`.
+fn window_end(within: &str, now: DateTime<Utc>) -> AppResult<DateTime<Utc>> {
+    parse_relative_duration(within)
+        .and_then(|duration| now.checked_add_signed(duration))
+        .ok_or_else(|| {
+            AppError::bad_request(format!(
+                "Invalid `within` '{}': expected a positive number followed by s, m, h, d or w, e.g. `7d`",
+                within
+            ))
+            .with_code(ErrorCode::InvalidWindow)
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotatedApiKeysResponse {
+    pub rotated: Vec<CreateApiKeyResponse>,
+}
+
+impl From<Vec<CreatedApiKey>> for RotatedApiKeysResponse {
+    fn from(value: Vec<CreatedApiKey>) -> Self {
+        Self {
+            rotated: value.into_iter().map(CreateApiKeyResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AllowedIpRequest {
+    pub cidr: String,
+}
+
+pub const MAX_BATCH_DELETE_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct BatchDeleteApiKeysRequest {
+    #[validate(length(
+        min = 1,
+        max = "MAX_BATCH_DELETE_SIZE",
+        message = "Batch must contain between 1 and 100 ids"
+    ))]
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchDeleteStatus {
+    Deleted,
+    NotFound,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchDeleteApiKeysResponse {
+    pub results: BTreeMap<i32, BatchDeleteStatus>,
+}
+
+impl BatchDeleteApiKeysResponse {
+    pub fn new(requested: &[i32], deleted: &[i32]) -> Self {
+        let results = requested
+            .iter()
+            .map(|id| {
+                let status = if deleted.contains(id) {
+                    BatchDeleteStatus::Deleted
+                } else {
+                    BatchDeleteStatus::NotFound
+                };
+                (*id, status)
+            })
+            .collect();
+
+        Self { results }
+    }
+}
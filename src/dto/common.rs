@@ -6,6 +6,12 @@ pub struct CursorMetadata<T> {
     pub next_cursor: Option<T>,
     pub prev_cursor: Option<T>,
     pub has_more: bool,
+    /// Whether a page exists before this one. Derived from the request, so a
+    /// forward page fetched with a cursor always reports `true`.
+    pub has_prev: bool,
+    /// Total number of matching items, only computed with `include_total=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -13,3 +19,51 @@ pub struct DeletedResponse<T> {
     pub deleted: bool,
     pub data: T,
 }
+
+/// Response shape of the list endpoints, picked with `?format=`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListFormat {
+    /// The resource-specific shape, e.g. `{schemas, cursor}`.
+    #[default]
+    Legacy,
+    /// The unified [`Page`] envelope.
+    Page,
+}
+
+/// Unified list envelope: `{ "data": [...], "pagination": {...} }`.
+#[derive(Debug, Serialize)]
+pub struct Page<T, C> {
+    pub data: Vec<T>,
+    pub pagination: CursorMetadata<C>,
+}
+
+impl<T, C> Page<T, C> {
+    pub fn new(
+        data: impl IntoIterator<Item = impl Into<T>>,
+        pagination: CursorMetadata<C>,
+    ) -> Self {
+        Self {
+            data: data.into_iter().map(Into::into).collect(),
+            pagination,
+        }
+    }
+
+    /// A single page holding every item, for endpoints that do not paginate.
+    pub fn complete(data: impl IntoIterator<Item = impl Into<T>>) -> Self {
+        let data: Vec<T> = data.into_iter().map(Into::into).collect();
+        let count = data.len();
+
+        Self {
+            data,
+            pagination: CursorMetadata {
+                limit: count as i32,
+                next_cursor: None,
+                prev_cursor: None,
+                has_more: false,
+                has_prev: false,
+                total: Some(count as i64),
+            },
+        }
+    }
+}
@@ -1,10 +1,19 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::{dto::common::CursorMetadata, AppError, AppResult, Log};
+use crate::{
+    dto::{
+        common::{CursorMetadata, ListFormat, Page},
+        timestamp,
+    },
+    models::{query_params::validate_json_filters, LevelCountSource, LevelCounts, LogFilter},
+    AppError, AppResult, ErrorCode, Log,
+};
 
 fn validate_string_not_empty(string: &str) -> Result<(), validator::ValidationError> {
     if string.trim().is_empty() {
@@ -21,17 +30,22 @@ fn validate_log_data_is_object(value: &Value) -> Result<(), validator::Validatio
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
 pub struct CreateLogRequest {
+    /// May be omitted on `POST /logs` when `log_data` names its schema in
+    /// the `SCHEMA_INFERENCE_FIELD`.
     #[validate(custom(
         function = "validate_string_not_empty",
         message = "Schema ID cannot be empty"
     ))]
-    pub schema_id: String,
+    pub schema_id: Option<String>,
     #[validate(custom(
         function = "validate_log_data_is_object",
         message = "Log data must be a JSON object"
     ))]
     pub log_data: Value,
+    /// Overrides the creation time, e.g. when backfilling historical logs.
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 impl CreateLogRequest {
@@ -39,12 +53,16 @@ impl CreateLogRequest {
         self.validate()
             .map_err(|e| AppError::bad_request(format!("Validation failed: {}", e)))?;
 
-        let schema_id = Uuid::parse_str(&self.schema_id)
+        let schema_id = self.schema_id.ok_or_else(|| {
+            AppError::bad_request("schema_id is required").with_code(ErrorCode::MissingSchemaId)
+        })?;
+        let schema_id = Uuid::parse_str(&schema_id)
             .map_err(|e| AppError::bad_request(format!("Invalid UUID: {}", e)))?;
 
         Ok(CreateLogRequestValidated {
             schema_id,
             log_data: self.log_data,
+            created_at: self.created_at,
         })
     }
 }
@@ -53,6 +71,117 @@ impl CreateLogRequest {
 pub struct CreateLogRequestValidated {
     pub schema_id: Uuid,
     pub log_data: Value,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Body of `PUT /logs/schemas/{schema_id}/by-key/{key}`.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PutLogByKeyRequest {
+    #[validate(custom(
+        function = "validate_log_data_is_object",
+        message = "Log data must be a JSON object"
+    ))]
+    pub log_data: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestLineError {
+    /// 1-based line number in the NDJSON body.
+    pub line: usize,
+    pub error: String,
+}
+
+/// Body of the 202 answered when `POST /logs` drops a log under its
+/// schema's `sample_rate`.
+#[derive(Debug, Serialize)]
+pub struct SampledOutResponse {
+    pub status: &'static str,
+    pub schema_id: Uuid,
+}
+
+impl SampledOutResponse {
+    pub fn new(schema_id: Uuid) -> Self {
+        Self {
+            status: "ACCEPTED_SAMPLED_OUT",
+            schema_id,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IngestLogsResponse {
+    pub created: usize,
+    pub failed: Vec<IngestLineError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkCreateLogsQuery {
+    /// Validate every item without inserting or broadcasting anything.
+    #[serde(default)]
+    pub validate_only: bool,
+    /// `false` skips the WebSocket/SSE broadcast of the created logs.
+    #[serde(default = "default_broadcast")]
+    pub broadcast: bool,
+}
+
+/// Query of `POST /logs` and `POST /logs/ingest`.
+#[derive(Debug, Deserialize)]
+pub struct BroadcastQuery {
+    /// `false` skips the WebSocket/SSE broadcast of the created logs, e.g.
+    /// for backfills; webhooks are still dispatched.
+    #[serde(default = "default_broadcast")]
+    pub broadcast: bool,
+}
+
+fn default_broadcast() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkItemError {
+    pub error: ErrorCode,
+    pub message: String,
+}
+
+impl From<AppError> for BulkItemError {
+    fn from(error: AppError) -> Self {
+        Self {
+            error: error.code(),
+            message: error.user_message(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    pub index: usize,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BulkItemError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkCreateLogsResponse {
+    pub validate_only: bool,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+}
+
+impl BulkCreateLogsResponse {
+    pub fn new(validate_only: bool, results: Vec<BulkItemResult>) -> Self {
+        let succeeded = results.iter().filter(|result| result.ok).count();
+
+        Self {
+            validate_only,
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -65,7 +194,9 @@ pub struct PaginationMetadata {
 
 #[derive(Debug, Serialize)]
 pub struct TimeWindowMetadata {
+    #[serde(with = "timestamp::option")]
     pub date_begin: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp::option")]
     pub date_end: Option<DateTime<Utc>>,
 }
 
@@ -78,19 +209,45 @@ pub struct PaginatedLogsResponse {
     pub pagination: PaginationMetadata,
 }
 
+/// The query as the server interpreted it, echoed back with a page of logs.
+#[derive(Debug, Serialize)]
+pub struct AppliedLogQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<Value>,
+    #[serde(with = "timestamp::option", skip_serializing_if = "Option::is_none")]
+    pub date_begin: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp::option", skip_serializing_if = "Option::is_none")]
+    pub date_end: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<i32>,
+    pub cursor: Option<i32>,
+    /// Effective page size after applying the default and maximum.
+    pub limit: i32,
+    pub direction: Direction,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CursorLogsResponse {
     pub schema_id: Uuid,
     pub logs: Vec<LogResponse>,
     pub cursor: CursorMetadata<i32>,
+    pub query: AppliedLogQuery,
 }
 
 impl CursorLogsResponse {
-    pub fn new(schema_id: Uuid, logs: Vec<Log>, cursor: CursorMetadata<i32>) -> Self {
+    pub fn new(
+        schema_id: Uuid,
+        logs: Vec<Log>,
+        cursor: CursorMetadata<i32>,
+        query: AppliedLogQuery,
+    ) -> Self {
         Self {
             schema_id,
             logs: logs.into_iter().map(LogResponse::from).collect(),
             cursor,
+            query,
         }
     }
 }
@@ -100,6 +257,17 @@ impl CursorLogsResponse {
 pub enum LogsResponse {
     Paginated(PaginatedLogsResponse),
     Cursor(CursorLogsResponse),
+    Page(Page<LogResponse, i32>),
+}
+
+impl LogsResponse {
+    /// Key of the array holding the logs in the serialized body.
+    pub fn logs_key(&self) -> &'static str {
+        match self {
+            Self::Paginated(_) | Self::Cursor(_) => "logs",
+            Self::Page(_) => "data",
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -107,7 +275,9 @@ pub struct LogResponse {
     pub id: i32,
     pub log_data: Value,
     pub schema_id: Uuid,
-    pub created_at: String,
+    pub created_by: Option<i32>,
+    #[serde(with = "timestamp")]
+    pub created_at: DateTime<Utc>,
 }
 
 impl From<Log> for LogResponse {
@@ -116,36 +286,286 @@ impl From<Log> for LogResponse {
             id: log.id,
             log_data: log.log_data,
             schema_id: log.schema_id,
-            created_at: log.created_at.to_rfc3339(),
+            created_by: log.created_by,
+            created_at: log.created_at,
         }
     }
 }
 
-fn default_limit() -> i32 {
-    10
-}
-
-fn default_direction() -> Direction {
-    Direction::Forward
-}
-
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
+    #[default]
     Forward,
     Backward,
 }
 
+impl Direction {
+    /// Parses the `direction` pagination parameter, defaulting to `Forward`
+    /// when absent and rejecting unknown values with `INVALID_DIRECTION`.
+    pub fn parse(value: Option<&str>) -> AppResult<Self> {
+        match value {
+            None | Some("forward") => Ok(Direction::Forward),
+            Some("backward") => Ok(Direction::Backward),
+            Some(other) => Err(AppError::bad_request(format!(
+                "Invalid direction '{}': expected 'forward' or 'backward'",
+                other
+            ))
+            .with_code(ErrorCode::InvalidDirection)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteLogQuery {
+    /// `false` answers with 204 No Content instead of echoing the deleted log.
+    pub return_deleted: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct QueryLogsRequest {
     pub date_begin: Option<DateTime<Utc>>,
     pub date_end: Option<DateTime<Utc>>,
     pub filters: Option<Value>,
+    pub created_by: Option<i32>,
+    /// Only logs with `id <= anchor`, e.g. the newest id at first load.
+    pub anchor: Option<i32>,
     pub cursor: Option<i32>,
-    #[serde(default = "default_limit")]
-    pub limit: i32,
-    #[serde(default = "default_direction")]
-    pub direction: Direction,
+    pub limit: Option<i32>,
+    pub direction: Option<String>,
+    #[serde(default)]
+    pub include_total: bool,
+    /// Output-only key renames, e.g. `created_at:timestamp,log_data.msg:message`.
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub format: ListFormat,
+}
+
+impl QueryLogsRequest {
+    pub fn aliases(&self) -> AppResult<Vec<FieldAlias>> {
+        match &self.alias {
+            Some(spec) => FieldAlias::parse_spec(spec),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn direction(&self) -> AppResult<Direction> {
+        Direction::parse(self.direction.as_deref())
+    }
+
+    /// Query strings carry `filters` as a JSON-encoded string, request bodies
+    /// as a JSON object.
+    pub fn json_filters(&self) -> AppResult<Option<Value>> {
+        let filters = match &self.filters {
+            Some(Value::String(raw)) => serde_json::from_str(raw)
+                .map(Some)
+                .map_err(|e| AppError::bad_request(format!("Invalid filters JSON: {}", e)))?,
+            filters => filters.clone(),
+        };
+
+        if let Some(filters) = &filters {
+            validate_json_filters(filters)?;
+        }
+        Ok(filters)
+    }
+}
+
+/// Body of `POST /logs/schemas/{schema_id}/query`: a [`QueryLogsRequest`]
+/// plus an optional boolean filter expression under `where`.
+#[derive(Debug, Deserialize)]
+pub struct FilterLogsRequest {
+    #[serde(rename = "where")]
+    pub expression: Option<Value>,
+    #[serde(flatten)]
+    pub query: QueryLogsRequest,
+}
+
+impl FilterLogsRequest {
+    pub fn expression(&self) -> AppResult<Option<LogFilter>> {
+        self.expression.as_ref().map(LogFilter::parse).transpose()
+    }
+}
+
+/// Explicit keyset position for exports; both fields must be given together.
+#[derive(Debug, Deserialize)]
+pub struct ExportLogsQuery {
+    pub after_created_at: Option<DateTime<Utc>>,
+    pub after_id: Option<i32>,
+    pub limit: Option<i32>,
+}
+
+impl ExportLogsQuery {
+    pub fn keyset(&self) -> AppResult<Option<(DateTime<Utc>, i32)>> {
+        match (self.after_created_at, self.after_id) {
+            (Some(created_at), Some(id)) => Ok(Some((created_at, id))),
+            (None, None) => Ok(None),
+            _ => Err(AppError::bad_request(
+                "after_created_at and after_id must be provided together",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportPosition {
+    pub after_created_at: String,
+    pub after_id: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportLogsResponse {
+    pub schema_id: Uuid,
+    pub logs: Vec<LogResponse>,
+    pub has_more: bool,
+    /// Position of the last returned log, to pass as the next page's keyset.
+    pub next: Option<ExportPosition>,
+}
+
+impl ExportLogsResponse {
+    pub fn new(schema_id: Uuid, logs: Vec<Log>, has_more: bool) -> Self {
+        let next = logs.last().map(|log| ExportPosition {
+            after_created_at: log.created_at.to_rfc3339(),
+            after_id: log.id,
+        });
+
+        Self {
+            schema_id,
+            logs: logs.into_iter().map(LogResponse::from).collect(),
+            has_more,
+            next,
+        }
+    }
+}
+
+/// Renames the key at `path` (dot-separated, relative to a log object) to
+/// `rename_to` within the same parent object. Only affects response output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldAlias {
+    pub path: Vec<String>,
+    pub rename_to: String,
+}
+
+impl FieldAlias {
+    pub fn parse_spec(spec: &str) -> AppResult<Vec<Self>> {
+        spec.split(',')
+            .map(|entry| {
+                let invalid = || {
+                    AppError::bad_request(format!(
+                        "Invalid alias '{}': expected 'field:new_name' or 'parent.field:new_name'",
+                        entry
+                    ))
+                    .with_code(ErrorCode::InvalidAlias)
+                };
+
+                let (from, to) = entry.trim().split_once(':').ok_or_else(invalid)?;
+                let path: Vec<String> = from.split('.').map(str::to_string).collect();
+
+                if path.iter().any(|segment| segment.is_empty())
+                    || to.is_empty()
+                    || to.contains(['.', ':'])
+                {
+                    return Err(invalid());
+                }
+
+                Ok(Self {
+                    path,
+                    rename_to: to.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Applies the rename to a serialized log; missing keys are left alone.
+    pub fn apply(&self, log: &mut Value) {
+        let Some((key, parents)) = self.path.split_last() else {
+            return;
+        };
+
+        let mut target = log;
+        for parent in parents {
+            match target.get_mut(parent) {
+                Some(next) => target = next,
+                None => return,
+            }
+        }
+
+        if let Some(object) = target.as_object_mut() {
+            if let Some(value) = object.remove(key) {
+                object.insert(self.rename_to.clone(), value);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LevelCountsQuery {
+    pub date_begin: Option<DateTime<Utc>>,
+    pub date_end: Option<DateTime<Utc>>,
+}
+
+fn default_days() -> i32 {
+    30
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CountByDayQuery {
+    #[serde(default = "default_days")]
+    pub days: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelCountsResponse {
+    pub schema_id: Uuid,
+    pub source: LevelCountSource,
+    #[serde(with = "timestamp::option", skip_serializing_if = "Option::is_none")]
+    pub refreshed_at: Option<DateTime<Utc>>,
+    pub timewindow: TimeWindowMetadata,
+    pub counts: BTreeMap<String, i64>,
+}
+
+impl LevelCountsResponse {
+    pub fn new(schema_id: Uuid, query: LevelCountsQuery, level_counts: LevelCounts) -> Self {
+        let timewindow = match level_counts.source {
+            LevelCountSource::Summary => TimeWindowMetadata {
+                date_begin: level_counts
+                    .refreshed_at
+                    .map(|refreshed_at| refreshed_at - Duration::hours(24)),
+                date_end: level_counts.refreshed_at,
+            },
+            LevelCountSource::Live => TimeWindowMetadata {
+                date_begin: query.date_begin,
+                date_end: query.date_end,
+            },
+        };
+
+        Self {
+            schema_id,
+            source: level_counts.source,
+            refreshed_at: level_counts.refreshed_at,
+            timewindow,
+            counts: level_counts
+                .counts
+                .into_iter()
+                .map(|c| (c.level, c.count))
+                .collect(),
+        }
+    }
+}
+
+/// A single log operation, accepted by `POST /logs/action` as an alternative to
+/// the dedicated REST endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LogAction {
+    Create { schema_id: Uuid, log_data: Value },
+    Delete { id: i32 },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LogActionResponse {
+    Created { log: LogResponse },
+    Deleted { log: LogResponse },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,12 +575,21 @@ pub enum LogEvent {
         id: i32,
         schema_id: Uuid,
         log_data: Value,
-        created_at: String,
+        #[serde(with = "timestamp")]
+        created_at: DateTime<Utc>,
     },
     Deleted {
         id: i32,
         schema_id: Uuid,
     },
+    /// A log's data was replaced, e.g. by `PATCH /logs/{id}`.
+    Updated {
+        id: i32,
+        schema_id: Uuid,
+        log_data: Value,
+        #[serde(with = "timestamp")]
+        created_at: DateTime<Utc>,
+    },
 }
 
 impl LogEvent {
@@ -169,7 +598,16 @@ impl LogEvent {
             id: log.id,
             schema_id: log.schema_id,
             log_data: log.log_data,
-            created_at: log.created_at.to_rfc3339(),
+            created_at: log.created_at,
+        }
+    }
+
+    pub fn updated_from(log: Log) -> Self {
+        LogEvent::Updated {
+            id: log.id,
+            schema_id: log.schema_id,
+            log_data: log.log_data,
+            created_at: log.created_at,
         }
     }
 
@@ -184,6 +622,7 @@ impl LogEvent {
         match self {
             LogEvent::Created { schema_id, .. } => *schema_id,
             LogEvent::Deleted { schema_id, .. } => *schema_id,
+            LogEvent::Updated { schema_id, .. } => *schema_id,
         }
     }
 }
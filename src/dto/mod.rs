@@ -1,17 +1,41 @@
+pub mod admin_dto;
 pub mod api_key_dto;
 pub mod common;
 pub mod log_dto;
+pub mod patch_dto;
 pub mod schema_dto;
+pub mod timestamp;
+pub mod ws_dto;
 
-pub use common::CursorMetadata;
+pub use common::{CursorMetadata, ListFormat, Page};
 
 pub use schema_dto::{
-    CreateSchemaRequest, DeleteSchemaQuery, GetSchemasQuery, SchemaResponse, UpdateSchemaRequest,
+    BatchGetSchemasRequest, BatchGetSchemasResponse, CreateSchemaRequest, DeleteSchemaQuery,
+    DeleteSchemaResponse, GetSchemaQuery, GetSchemasQuery, LogRangeResponse, SchemaResponse,
+    UpdateSchemaQuery, UpdateSchemaRequest, ValidateLogDataQuery, ValidateLogDataRequest,
+    ValidateLogDataResponse, VersionCompatibility,
 };
 
 pub use log_dto::{
-    CreateLogRequest, CursorLogsResponse, Direction, LogEvent, LogResponse, LogsResponse,
-    PaginatedLogsResponse, PaginationMetadata, QueryLogsRequest, TimeWindowMetadata,
+    AppliedLogQuery, BroadcastQuery, BulkCreateLogsQuery, BulkCreateLogsResponse, BulkItemResult,
+    CountByDayQuery, CreateLogRequest, CursorLogsResponse, DeleteLogQuery, Direction,
+    ExportLogsQuery, ExportLogsResponse, FieldAlias, FilterLogsRequest, IngestLineError,
+    IngestLogsResponse, LevelCountsQuery, LevelCountsResponse, LogAction, LogActionResponse,
+    LogEvent, LogResponse, LogsResponse, PaginatedLogsResponse, PaginationMetadata,
+    PutLogByKeyRequest, QueryLogsRequest, SampledOutResponse, TimeWindowMetadata,
 };
 
-pub use api_key_dto::{ApiKeyResponse, ApiKeysResponse, CreateApiKeyRequest, CreateApiKeyResponse};
+pub use api_key_dto::{
+    AllowedIpRequest, ApiKeyResponse, ApiKeyStatsQuery, ApiKeyStatsResponse, ApiKeysResponse,
+    BatchDeleteApiKeysRequest, BatchDeleteApiKeysResponse, BatchDeleteStatus, CreateApiKeyRequest,
+    CreateApiKeyResponse, ListApiKeysQuery, RotateExpiringQuery, RotatedApiKeysResponse,
+};
+
+pub use patch_dto::PatchDocument;
+
+pub use ws_dto::{SequencedLogEvent, WsClientFrame, WsControlFrame};
+
+pub use admin_dto::{
+    MaintenanceRequest, MaintenanceResponse, ResetQuery, ResetResponse, SlowRequestResponse,
+    SlowRequestsResponse,
+};
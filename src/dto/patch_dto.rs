@@ -0,0 +1,88 @@
+use axum::http::{header, HeaderMap};
+use json_patch::{Patch, PatchErrorKind, PatchOperation};
+use serde_json::Value;
+
+use crate::{AppError, AppResult, ErrorCode};
+
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+pub const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// Body of a PATCH request, chosen by `Content-Type`: RFC 7396 merge patch
+/// (the default, also for `application/json`) or an RFC 6902 operation list.
+#[derive(Debug, Clone)]
+pub enum PatchDocument {
+    Merge(Value),
+    Json(Patch),
+}
+
+fn invalid_patch(msg: impl Into<String>) -> AppError {
+    AppError::bad_request(msg).with_code(ErrorCode::InvalidPatch)
+}
+
+impl PatchDocument {
+    pub fn parse(headers: &HeaderMap, body: &[u8]) -> AppResult<Self> {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("application/json");
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+
+        match mime {
+            JSON_PATCH_CONTENT_TYPE => serde_json::from_slice(body)
+                .map(PatchDocument::Json)
+                .map_err(|e| invalid_patch(format!("Invalid JSON Patch: {}", e))),
+            MERGE_PATCH_CONTENT_TYPE | "application/json" => serde_json::from_slice(body)
+                .map(PatchDocument::Merge)
+                .map_err(|e| invalid_patch(format!("Invalid merge patch: {}", e))),
+            other => Err(AppError::bad_request(format!(
+                "Unsupported Content-Type '{}': expected {} or {}",
+                other, MERGE_PATCH_CONTENT_TYPE, JSON_PATCH_CONTENT_TYPE
+            ))
+            .with_code(ErrorCode::InvalidContentType)),
+        }
+    }
+
+    /// Top-level fields of the target that the operations of a JSON Patch
+    /// read or write through `path` or `from`; `None` stands for the whole
+    /// document. Empty for a merge patch.
+    pub fn json_patch_fields(&self) -> Vec<Option<String>> {
+        let PatchDocument::Json(patch) = self else {
+            return Vec::new();
+        };
+
+        patch
+            .iter()
+            .flat_map(|operation| {
+                let from = match operation {
+                    PatchOperation::Move(operation) => Some(&operation.from),
+                    PatchOperation::Copy(operation) => Some(&operation.from),
+                    _ => None,
+                };
+                std::iter::once(operation.path()).chain(from.map(|from| from.as_ref()))
+            })
+            .map(|pointer| pointer.first().map(|token| token.decoded().into_owned()))
+            .collect()
+    }
+
+    /// Applies the patch to `target`. JSON Patch is atomic: on any failed
+    /// operation `target` is left untouched. A failed `test` operation is a
+    /// 409 `PATCH_TEST_FAILED`, any other failure a 400 `INVALID_PATCH`.
+    pub fn apply(&self, target: &mut Value) -> AppResult<()> {
+        match self {
+            PatchDocument::Merge(patch) => {
+                json_patch::merge(target, patch);
+                Ok(())
+            }
+            PatchDocument::Json(patch) => json_patch::patch(target, patch).map_err(|e| {
+                let error = match e.kind {
+                    PatchErrorKind::TestFailed => {
+                        AppError::conflict(format!("Test operation failed at '{}'", e.path))
+                            .with_code(ErrorCode::PatchTestFailed)
+                    }
+                    _ => invalid_patch(e.to_string()),
+                };
+                error.with_detail("operation", e.operation)
+            }),
+        }
+    }
+}
@@ -1,11 +1,17 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    dto::{common::CursorMetadata, log_dto::Direction},
-    Schema,
+    dto::{
+        common::{CursorMetadata, ListFormat},
+        log_dto::Direction,
+        timestamp,
+    },
+    models::{LogRange, LogValidationReport, SchemaChange, ValidationMode},
+    AppResult, Schema,
 };
 
 fn validate_string_not_empty(string: &str) -> Result<(), validator::ValidationError> {
@@ -16,6 +22,7 @@ fn validate_string_not_empty(string: &str) -> Result<(), validator::ValidationEr
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
 pub struct CreateSchemaRequest {
     #[validate(custom(
         function = "validate_string_not_empty",
@@ -29,9 +36,34 @@ pub struct CreateSchemaRequest {
     pub version: String,
     pub description: Option<String>,
     pub schema_definition: Value,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    #[serde(default)]
+    pub strict_formats: bool,
+    /// Top-level `log_data` fields to back with a per-schema expression index.
+    #[serde(default)]
+    pub indexed_fields: Vec<String>,
+    /// Keep only this many of the newest logs; older ones are swept.
+    pub max_retained_logs: Option<i32>,
+    /// Logs older than this many seconds are swept.
+    pub max_log_age_secs: Option<i32>,
+    /// Top-level `log_data` fields that are stored but never returned.
+    #[serde(default)]
+    pub redacted_fields: Vec<String>,
+    /// Fraction (0.0-1.0) of logs `POST /logs` keeps; unset keeps all.
+    pub sample_rate: Option<f64>,
+    /// Tags such as `team:payments`, matched by `GET /schemas?label=`.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// `strict` (default), `lenient` or `off`.
+    #[serde(default)]
+    pub validation_mode: ValidationMode,
+    /// Largest string value, in bytes, accepted anywhere in `log_data`.
+    pub max_field_bytes: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateSchemaRequest {
     #[validate(custom(
         function = "validate_string_not_empty",
@@ -45,6 +77,30 @@ pub struct UpdateSchemaRequest {
     pub version: String,
     pub description: Option<String>,
     pub schema_definition: Value,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    #[serde(default)]
+    pub strict_formats: bool,
+    /// Top-level `log_data` fields to back with a per-schema expression index.
+    #[serde(default)]
+    pub indexed_fields: Vec<String>,
+    /// Keep only this many of the newest logs; older ones are swept.
+    pub max_retained_logs: Option<i32>,
+    /// Logs older than this many seconds are swept.
+    pub max_log_age_secs: Option<i32>,
+    /// Top-level `log_data` fields that are stored but never returned.
+    #[serde(default)]
+    pub redacted_fields: Vec<String>,
+    /// Fraction (0.0-1.0) of logs `POST /logs` keeps; unset keeps all.
+    pub sample_rate: Option<f64>,
+    /// Tags such as `team:payments`, matched by `GET /schemas?label=`.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// `strict` (default), `lenient` or `off`.
+    #[serde(default)]
+    pub validation_mode: ValidationMode,
+    /// Largest string value, in bytes, accepted anywhere in `log_data`.
+    pub max_field_bytes: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,8 +110,34 @@ pub struct SchemaResponse {
     pub version: String,
     pub description: Option<String>,
     pub schema_definition: Value,
-    pub created_at: String,
-    pub updated_at: String,
+    pub webhook_url: Option<String>,
+    pub strict_formats: bool,
+    pub indexed_fields: Vec<String>,
+    pub max_retained_logs: Option<i32>,
+    pub max_log_age_secs: Option<i32>,
+    pub redacted_fields: Vec<String>,
+    pub sample_rate: Option<f64>,
+    pub labels: Vec<String>,
+    pub validation_mode: ValidationMode,
+    pub max_field_bytes: Option<i32>,
+    pub tenant_id: String,
+    pub created_by: Option<i32>,
+    #[serde(with = "timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "timestamp")]
+    pub updated_at: DateTime<Utc>,
+    /// Only set by `GET /schemas/{id}?include_log_range=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_range: Option<LogRangeResponse>,
+}
+
+impl SchemaResponse {
+    pub fn with_log_range(self, log_range: LogRange) -> Self {
+        Self {
+            log_range: Some(LogRangeResponse::from(log_range)),
+            ..self
+        }
+    }
 }
 
 impl From<Schema> for SchemaResponse {
@@ -66,8 +148,40 @@ impl From<Schema> for SchemaResponse {
             version: schema.version,
             description: schema.description,
             schema_definition: schema.schema_definition,
-            created_at: schema.created_at.to_rfc3339(),
-            updated_at: schema.updated_at.to_rfc3339(),
+            webhook_url: schema.webhook_url,
+            strict_formats: schema.strict_formats,
+            indexed_fields: schema.indexed_fields,
+            max_retained_logs: schema.max_retained_logs,
+            max_log_age_secs: schema.max_log_age_secs,
+            redacted_fields: schema.redacted_fields,
+            sample_rate: schema.sample_rate,
+            labels: schema.labels,
+            validation_mode: schema.validation_mode,
+            max_field_bytes: schema.max_field_bytes,
+            tenant_id: schema.tenant_id,
+            created_by: schema.created_by,
+            created_at: schema.created_at,
+            updated_at: schema.updated_at,
+            log_range: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRangeResponse {
+    #[serde(with = "timestamp::option")]
+    pub logs_from: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp::option")]
+    pub logs_to: Option<DateTime<Utc>>,
+    pub log_count: i64,
+}
+
+impl From<LogRange> for LogRangeResponse {
+    fn from(range: LogRange) -> Self {
+        Self {
+            logs_from: range.logs_from,
+            logs_to: range.logs_to,
+            log_count: range.log_count,
         }
     }
 }
@@ -85,6 +199,42 @@ impl From<Vec<Schema>> for SchemasResponse {
     }
 }
 
+pub const MAX_BATCH_GET_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct BatchGetSchemasRequest {
+    #[validate(length(
+        min = 1,
+        max = "MAX_BATCH_GET_SIZE",
+        message = "Batch must contain between 1 and 100 ids"
+    ))]
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchGetSchemasResponse {
+    pub schemas: Vec<SchemaResponse>,
+    /// Requested ids with no matching schema, in request order.
+    pub missing: Vec<Uuid>,
+}
+
+impl BatchGetSchemasResponse {
+    pub fn new(requested: &[Uuid], found: Vec<Schema>) -> Self {
+        let mut missing: Vec<Uuid> = Vec::new();
+        for id in requested {
+            if !found.iter().any(|schema| schema.id == *id) && !missing.contains(id) {
+                missing.push(*id);
+            }
+        }
+
+        Self {
+            schemas: found.into_iter().map(SchemaResponse::from).collect(),
+            missing,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct CursorSchemasResponse {
     pub schemas: Vec<SchemaResponse>,
@@ -100,26 +250,110 @@ impl CursorSchemasResponse {
     }
 }
 
-fn default_limit() -> i32 {
-    10
-}
-
-fn default_direction() -> Direction {
-    Direction::Forward
-}
-
 #[derive(Debug, Deserialize)]
 pub struct GetSchemasQuery {
     pub name: Option<String>,
     pub version: Option<String>,
+    pub search: Option<String>,
+    pub label: Option<String>,
     pub cursor: Option<Uuid>,
-    #[serde(default = "default_limit")]
-    pub limit: i32,
-    #[serde(default = "default_direction")]
-    pub direction: Direction,
+    pub limit: Option<i32>,
+    pub direction: Option<String>,
+    #[serde(default)]
+    pub include_total: bool,
+    #[serde(default)]
+    pub format: ListFormat,
+}
+
+impl GetSchemasQuery {
+    pub fn direction(&self) -> AppResult<Direction> {
+        Direction::parse(self.direction.as_deref())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateLogDataRequest {
+    pub log_data: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetSchemaQuery {
+    /// Attaches the oldest and newest `created_at` of the schema's logs.
+    #[serde(default)]
+    pub include_log_range: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateLogDataQuery {
+    /// Adds the missing / wrong type / extra field breakdown.
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidateLogDataResponse {
+    pub valid: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub report: Option<LogValidationReport>,
+}
+
+impl ValidateLogDataResponse {
+    pub fn new(report: LogValidationReport, verbose: bool) -> Self {
+        Self {
+            valid: report.is_valid(),
+            report: verbose.then_some(report),
+        }
+    }
+}
+
+/// Entry of `GET /schemas/{id}/compatible-versions`: whether logs valid for
+/// the requested schema also fit this version.
+#[derive(Debug, Serialize)]
+pub struct VersionCompatibility {
+    pub schema_id: Uuid,
+    pub version: String,
+    pub compatible: bool,
+    pub breaking_changes: Vec<SchemaChange>,
+}
+
+impl VersionCompatibility {
+    pub fn new(schema: Schema, changes: Vec<SchemaChange>) -> Self {
+        let breaking_changes: Vec<SchemaChange> = changes
+            .into_iter()
+            .filter(SchemaChange::is_breaking)
+            .collect();
+
+        Self {
+            schema_id: schema.id,
+            version: schema.version,
+            compatible: breaking_changes.is_empty(),
+            breaking_changes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteSchemaResponse {
+    pub deleted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<SchemaResponse>,
+    /// Number of logs removed together with the schema by `force=true`.
+    pub deleted_logs: usize,
+}
+
+/// Query of `PUT` and `PATCH /schemas/{id}`.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSchemaQuery {
+    /// Reject the update with 409 `INCOMPATIBLE_SCHEMA` if the new
+    /// definition fails any of the schema's most recent logs.
+    #[serde(default)]
+    pub check_compat: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct DeleteSchemaQuery {
     pub force: Option<bool>,
+    /// `false` answers with 204 No Content instead of echoing the deleted schema.
+    pub return_deleted: Option<bool>,
 }
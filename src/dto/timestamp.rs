@@ -0,0 +1,102 @@
+//! Serde helper for response timestamps: RFC 3339 in UTC with a `Z` suffix
+//! and the process-wide fractional-second precision. Use it with
+//! `#[serde(with = "timestamp")]`, or `timestamp::option` for `Option`s.
+
+use std::{str::FromStr, sync::OnceLock};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Fractional-second digits written in response timestamps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampPrecision {
+    /// As many digits (0, 3, 6 or 9) as the value needs.
+    #[default]
+    Auto,
+    Secs,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl TimestampPrecision {
+    fn seconds_format(self) -> SecondsFormat {
+        match self {
+            Self::Auto => SecondsFormat::AutoSi,
+            Self::Secs => SecondsFormat::Secs,
+            Self::Millis => SecondsFormat::Millis,
+            Self::Micros => SecondsFormat::Micros,
+            Self::Nanos => SecondsFormat::Nanos,
+        }
+    }
+}
+
+impl FromStr for TimestampPrecision {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "secs" => Ok(Self::Secs),
+            "millis" => Ok(Self::Millis),
+            "micros" => Ok(Self::Micros),
+            "nanos" => Ok(Self::Nanos),
+            other => anyhow::bail!(
+                "TIMESTAMP_PRECISION must be auto, secs, millis, micros or nanos, got '{}'",
+                other
+            ),
+        }
+    }
+}
+
+static TIMESTAMP_PRECISION: OnceLock<TimestampPrecision> = OnceLock::new();
+
+/// Sets the process-wide timestamp precision. Only the first call takes
+/// effect; returns whether this call did.
+pub fn set_timestamp_precision(precision: TimestampPrecision) -> bool {
+    TIMESTAMP_PRECISION.set(precision).is_ok()
+}
+
+fn timestamp_precision() -> TimestampPrecision {
+    *TIMESTAMP_PRECISION.get_or_init(TimestampPrecision::default)
+}
+
+pub fn format_with(timestamp: &DateTime<Utc>, precision: TimestampPrecision) -> String {
+    timestamp.to_rfc3339_opts(precision.seconds_format(), true)
+}
+
+/// Formats `timestamp` with the process-wide precision.
+pub fn format(timestamp: &DateTime<Utc>) -> String {
+    format_with(timestamp, timestamp_precision())
+}
+
+pub fn serialize<S: Serializer>(
+    timestamp: &DateTime<Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(timestamp))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    DateTime::<Utc>::deserialize(deserializer)
+}
+
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => super::serialize(timestamp, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<DateTime<Utc>>::deserialize(deserializer)
+    }
+}
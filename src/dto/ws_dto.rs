@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::dto::LogEvent;
+
+/// Control frames sent by the server on `/ws/logs`, distinguished from log
+/// events by their `type` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsControlFrame {
+    /// Sent right after the upgrade to confirm the subscription. `schema_id` is
+    /// `null` when subscribed to all schemas; `replay` is the number of past
+    /// `created` events that follow, oldest first, before live ones.
+    Subscribed {
+        schema_id: Option<Uuid>,
+        replay: u32,
+    },
+    /// Answers a client `subscribe` frame: events sent after this one are
+    /// narrowed by `filters`, or unfiltered again when it is `null`.
+    FiltersApplied { filters: Option<Value> },
+    /// Answers a client frame that could not be applied; the previous
+    /// filters stay in effect.
+    Error { error: String, message: String },
+}
+
+/// Frames a client may send on `/ws/logs`, distinguished by their `type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsClientFrame {
+    /// Narrows live `created` events to those whose `log_data` matches
+    /// `filters`, an object in the syntax of the HTTP `filters` parameter.
+    /// `null` or no `filters` removes the filter.
+    Subscribe {
+        #[serde(default)]
+        filters: Option<Value>,
+    },
+}
+
+/// A [`LogEvent`] as sent on `/ws/logs`, numbered per connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedLogEvent {
+    /// Starts at 1 and increases by one per event. Events skipped because the
+    /// connection lagged behind the broadcast still consume numbers, so a gap
+    /// tells the client it missed events.
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: LogEvent,
+}
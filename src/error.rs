@@ -3,76 +3,197 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
 use std::fmt;
 
 use crate::middleware::RequestId;
 
+/// Field names of JSON error bodies. The default produces
+/// `{"error": <code>, "message": <text>, ...}`; with `wrap` the fields are
+/// nested under a top-level `error` object instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorBodyFormat {
+    pub code_field: String,
+    pub message_field: String,
+    pub wrap: bool,
+}
+
+impl Default for ErrorBodyFormat {
+    fn default() -> Self {
+        Self {
+            code_field: "error".to_string(),
+            message_field: "message".to_string(),
+            wrap: false,
+        }
+    }
+}
+
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal,)+) => {
+        /// Machine-readable codes carried in the `error` field of error
+        /// bodies. This is the complete catalog; clients can match on the
+        /// strings, so existing ones must never change.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant,)+
+        }
+
+        impl ErrorCode {
+            /// Every code, in declaration order.
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)+];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)+
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Default for not-found errors.
+    NotFound => "NOT_FOUND",
+    /// Default for validation errors.
+    ValidationError => "VALIDATION_ERROR",
+    /// Default for forbidden errors.
+    Forbidden => "FORBIDDEN",
+    /// Default for conflicts.
+    Conflict => "CONFLICT",
+    /// Default for database failures.
+    DatabaseError => "DATABASE_ERROR",
+    /// Default for internal errors.
+    InternalError => "INTERNAL_ERROR",
+    /// Default for bad requests.
+    BadRequest => "BAD_REQUEST",
+    /// Default for log data or schema definitions failing validation.
+    SchemaValidationError => "SCHEMA_VALIDATION_ERROR",
+    /// Default for temporarily unavailable services.
+    ServiceUnavailable => "SERVICE_UNAVAILABLE",
+    /// Default for upstream timeouts.
+    GatewayTimeout => "GATEWAY_TIMEOUT",
+    ConfirmationRequired => "CONFIRMATION_REQUIRED",
+    EmptyBody => "EMPTY_BODY",
+    FieldTooLarge => "FIELD_TOO_LARGE",
+    IncompatibleSchema => "INCOMPATIBLE_SCHEMA",
+    InvalidAlias => "INVALID_ALIAS",
+    InvalidContentType => "INVALID_CONTENT_TYPE",
+    InvalidCursor => "INVALID_CURSOR",
+    InvalidDateRange => "INVALID_DATE_RANGE",
+    InvalidDirection => "INVALID_DIRECTION",
+    InvalidExpiry => "INVALID_EXPIRY",
+    InvalidFilter => "INVALID_FILTER",
+    InvalidInput => "INVALID_INPUT",
+    InvalidJson => "INVALID_JSON",
+    InvalidName => "INVALID_NAME",
+    InvalidPatch => "INVALID_PATCH",
+    InvalidPath => "INVALID_PATH",
+    InvalidQuery => "INVALID_QUERY",
+    InvalidSchemaReference => "INVALID_SCHEMA_REFERENCE",
+    InvalidTimestamp => "INVALID_TIMESTAMP",
+    InvalidVersion => "INVALID_VERSION",
+    InvalidWindow => "INVALID_WINDOW",
+    IpNotAllowed => "IP_NOT_ALLOWED",
+    LimitReached => "LIMIT_REACHED",
+    Maintenance => "MAINTENANCE",
+    MissingSchemaId => "MISSING_SCHEMA_ID",
+    PatchTestFailed => "PATCH_TEST_FAILED",
+    PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+    /// A statement hit `DB_STATEMENT_TIMEOUT_MS`.
+    QueryTimeout => "QUERY_TIMEOUT",
+    ResetDisabled => "RESET_DISABLED",
+    SchemaConflict => "SCHEMA_CONFLICT",
+    SchemaInUse => "SCHEMA_IN_USE",
+    SchemaTooComplex => "SCHEMA_TOO_COMPLEX",
+    TooManyConnections => "TOO_MANY_CONNECTIONS",
+    UnknownQueryParam => "UNKNOWN_QUERY_PARAM",
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppError {
     kind: AppErrorKind,
     request_id: Option<String>,
+    code: Option<ErrorCode>,
+    status: Option<StatusCode>,
+    details: Map<String, Value>,
 }
 
 #[derive(Debug, Clone)]
 pub enum AppErrorKind {
     NotFound(String),              // Resource not found (404)
     ValidationError(String),       // Validation error (400)
+    Forbidden(String),             // Caller not allowed (403)
     Conflict(String),              // Conflict with existing resource (409)
     DatabaseError(String),         // Database operation failed (500)
     InternalError(String),         // Internal server error (500)
     BadRequest(String),            // Bad request (400)
     SchemaValidationError(String), // Schema validation failed (422)
+    ServiceUnavailable(String),    // Temporarily unavailable (503)
+    GatewayTimeout(String),        // Upstream (database) timed out (504)
 }
 
 impl AppError {
-    pub fn not_found(msg: impl Into<String>) -> Self {
+    fn from_kind(kind: AppErrorKind) -> Self {
         Self {
-            kind: AppErrorKind::NotFound(msg.into()),
+            kind,
             request_id: None,
+            code: None,
+            status: None,
+            details: Map::new(),
         }
     }
 
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Self::from_kind(AppErrorKind::NotFound(msg.into()))
+    }
+
     pub fn validation_error(msg: impl Into<String>) -> Self {
-        Self {
-            kind: AppErrorKind::ValidationError(msg.into()),
-            request_id: None,
-        }
+        Self::from_kind(AppErrorKind::ValidationError(msg.into()))
+    }
+
+    pub fn forbidden(msg: impl Into<String>) -> Self {
+        Self::from_kind(AppErrorKind::Forbidden(msg.into()))
     }
 
     pub fn conflict(msg: impl Into<String>) -> Self {
-        Self {
-            kind: AppErrorKind::Conflict(msg.into()),
-            request_id: None,
-        }
+        Self::from_kind(AppErrorKind::Conflict(msg.into()))
     }
 
     pub fn database_error(msg: impl Into<String>) -> Self {
-        Self {
-            kind: AppErrorKind::DatabaseError(msg.into()),
-            request_id: None,
-        }
+        Self::from_kind(AppErrorKind::DatabaseError(msg.into()))
     }
 
     pub fn internal_error(msg: impl Into<String>) -> Self {
-        Self {
-            kind: AppErrorKind::InternalError(msg.into()),
-            request_id: None,
-        }
+        Self::from_kind(AppErrorKind::InternalError(msg.into()))
     }
 
     pub fn bad_request(msg: impl Into<String>) -> Self {
-        Self {
-            kind: AppErrorKind::BadRequest(msg.into()),
-            request_id: None,
-        }
+        Self::from_kind(AppErrorKind::BadRequest(msg.into()))
     }
 
     pub fn schema_validation_error(msg: impl Into<String>) -> Self {
-        Self {
-            kind: AppErrorKind::SchemaValidationError(msg.into()),
-            request_id: None,
-        }
+        Self::from_kind(AppErrorKind::SchemaValidationError(msg.into()))
+    }
+
+    pub fn service_unavailable(msg: impl Into<String>) -> Self {
+        Self::from_kind(AppErrorKind::ServiceUnavailable(msg.into()))
+    }
+
+    pub fn gateway_timeout(msg: impl Into<String>) -> Self {
+        Self::from_kind(AppErrorKind::GatewayTimeout(msg.into()))
     }
 
     pub fn context(mut self, context: impl Into<String>) -> Self {
@@ -82,6 +203,9 @@ impl AppError {
             AppErrorKind::ValidationError(msg) => {
                 AppErrorKind::ValidationError(format!("{}: {}", context, msg))
             }
+            AppErrorKind::Forbidden(msg) => {
+                AppErrorKind::Forbidden(format!("{}: {}", context, msg))
+            }
             AppErrorKind::Conflict(msg) => AppErrorKind::Conflict(format!("{}: {}", context, msg)),
             AppErrorKind::DatabaseError(msg) => {
                 AppErrorKind::DatabaseError(format!("{}: {}", context, msg))
@@ -95,6 +219,12 @@ impl AppError {
             AppErrorKind::SchemaValidationError(msg) => {
                 AppErrorKind::SchemaValidationError(format!("{}: {}", context, msg))
             }
+            AppErrorKind::ServiceUnavailable(msg) => {
+                AppErrorKind::ServiceUnavailable(format!("{}: {}", context, msg))
+            }
+            AppErrorKind::GatewayTimeout(msg) => {
+                AppErrorKind::GatewayTimeout(format!("{}: {}", context, msg))
+            }
         };
         self
     }
@@ -104,37 +234,74 @@ impl AppError {
         self
     }
 
+    /// Overrides the generic error code derived from the error kind.
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Overrides the HTTP status derived from the error kind.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Attaches an extra structured field to the error response body.
+    pub fn with_detail(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.details.insert(key.into(), value.into());
+        self
+    }
+
     pub fn is_not_found(&self) -> bool {
         matches!(self.kind, AppErrorKind::NotFound(_))
     }
 
-    fn error_type(&self) -> &str {
+    /// The explicit code if one was set, otherwise the default for the kind.
+    pub fn code(&self) -> ErrorCode {
+        if let Some(code) = self.code {
+            return code;
+        }
+
         match self.kind {
-            AppErrorKind::NotFound(_) => "NOT_FOUND",
-            AppErrorKind::ValidationError(_) => "VALIDATION_ERROR",
-            AppErrorKind::Conflict(_) => "CONFLICT",
-            AppErrorKind::DatabaseError(_) => "DATABASE_ERROR",
-            AppErrorKind::InternalError(_) => "INTERNAL_ERROR",
-            AppErrorKind::BadRequest(_) => "BAD_REQUEST",
-            AppErrorKind::SchemaValidationError(_) => "SCHEMA_VALIDATION_ERROR",
+            AppErrorKind::NotFound(_) => ErrorCode::NotFound,
+            AppErrorKind::ValidationError(_) => ErrorCode::ValidationError,
+            AppErrorKind::Forbidden(_) => ErrorCode::Forbidden,
+            AppErrorKind::Conflict(_) => ErrorCode::Conflict,
+            AppErrorKind::DatabaseError(_) => ErrorCode::DatabaseError,
+            AppErrorKind::InternalError(_) => ErrorCode::InternalError,
+            AppErrorKind::BadRequest(_) => ErrorCode::BadRequest,
+            AppErrorKind::SchemaValidationError(_) => ErrorCode::SchemaValidationError,
+            AppErrorKind::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+            AppErrorKind::GatewayTimeout(_) => ErrorCode::GatewayTimeout,
         }
     }
 
-    fn status_code(&self) -> StatusCode {
+    pub(crate) fn error_type(&self) -> &'static str {
+        self.code().as_str()
+    }
+
+    pub(crate) fn status_code(&self) -> StatusCode {
+        if let Some(status) = self.status {
+            return status;
+        }
+
         match self.kind {
             AppErrorKind::NotFound(_) => StatusCode::NOT_FOUND,
             AppErrorKind::ValidationError(_) | AppErrorKind::BadRequest(_) => {
                 StatusCode::BAD_REQUEST
             }
+            AppErrorKind::Forbidden(_) => StatusCode::FORBIDDEN,
             AppErrorKind::Conflict(_) => StatusCode::CONFLICT,
             AppErrorKind::DatabaseError(_) | AppErrorKind::InternalError(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
             AppErrorKind::SchemaValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppErrorKind::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppErrorKind::GatewayTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
         }
     }
 
-    fn user_message(&self) -> String {
+    pub(crate) fn user_message(&self) -> String {
         match &self.kind {
             // For internal errors, don't expose details
             AppErrorKind::DatabaseError(msg) => {
@@ -155,36 +322,62 @@ impl fmt::Display for AppError {
         match &self.kind {
             AppErrorKind::NotFound(msg) => write!(f, "{}", msg),
             AppErrorKind::ValidationError(msg) => write!(f, "{}", msg),
+            AppErrorKind::Forbidden(msg) => write!(f, "{}", msg),
             AppErrorKind::Conflict(msg) => write!(f, "{}", msg),
             AppErrorKind::DatabaseError(msg) => write!(f, "{}", msg),
             AppErrorKind::InternalError(msg) => write!(f, "{}", msg),
             AppErrorKind::BadRequest(msg) => write!(f, "{}", msg),
             AppErrorKind::SchemaValidationError(msg) => write!(f, "{}", msg),
+            AppErrorKind::ServiceUnavailable(msg) => write!(f, "{}", msg),
+            AppErrorKind::GatewayTimeout(msg) => write!(f, "{}", msg),
         }
     }
 }
 
 impl std::error::Error for AppError {}
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
+impl AppError {
+    /// Renders the error with an explicit body format instead of the default.
+    pub fn into_response_with(self, format: &ErrorBodyFormat) -> Response {
         let status = self.status_code();
-        let error_type = self.error_type();
+        let error_type = self.error_type().to_string();
         let message = self.user_message();
 
-        let mut body = json!({
-            "error": error_type,
-            "message": message,
-        });
+        let mut body = Map::new();
+        body.insert(format.code_field.clone(), json!(error_type));
+        body.insert(format.message_field.clone(), json!(message));
+
+        for (key, value) in self.details {
+            body.insert(key, value);
+        }
 
         if let Some(request_id) = self.request_id {
-            body["request_id"] = json!(request_id);
+            body.insert("request_id".to_string(), json!(request_id));
         }
 
+        let body = if format.wrap {
+            json!({ "error": body })
+        } else {
+            Value::Object(body)
+        };
+
         (status, Json(body)).into_response()
     }
 }
 
+/// Renders the default body format. The error itself travels along as a
+/// response extension, so [`error_body_middleware`] can render it again in
+/// the configured format.
+///
+/// [`error_body_middleware`]: crate::middleware::error_body_middleware
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut response = self.clone().into_response_with(&ErrorBodyFormat::default());
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         AppError::internal_error(err.to_string())
@@ -214,6 +407,13 @@ impl From<sqlx::Error> for AppError {
                                 return AppError::bad_request("Referenced resource does not exist");
                             }
                         }
+                        // query_canceled, raised when statement_timeout expires
+                        "57014" => {
+                            return AppError::gateway_timeout(
+                                "The query took too long and was cancelled",
+                            )
+                            .with_code(ErrorCode::QueryTimeout)
+                        }
                         _ => {}
                     }
                 }
@@ -0,0 +1,194 @@
+//! gRPC ingestion service (`grpc` feature), defined in `proto/logs.proto`.
+//! Calls go through the same API key checks, `LogService` validation,
+//! webhooks and broadcast as `POST /logs`.
+
+use std::{net::SocketAddr, sync::atomic::Ordering};
+
+use tonic::{
+    metadata::{MetadataMap, MetadataValue},
+    Code, Request, Response, Status, Streaming,
+};
+
+use crate::{
+    dto::{timestamp, CreateLogRequest as CreateLogBody},
+    handlers::log_handlers::publish_created,
+    middleware::{authorize_api_key, check_rate_limit, maintenance_error, ApiKeyRejection},
+    models::ApiKey,
+    services::log_service::Redactor,
+    AppError, AppState, Log,
+};
+
+pub mod proto {
+    tonic::include_proto!("crabpot.logs.v1");
+}
+
+use proto::{
+    log_ingest_server::{LogIngest, LogIngestServer},
+    CreateLogRequest, CreateLogsStreamReply, LogReply,
+};
+
+/// Maps an `AppError` to the closest gRPC status; the error code (e.g.
+/// `SCHEMA_VALIDATION_ERROR`) is sent as `x-error-code` metadata.
+fn status_from(error: AppError) -> Status {
+    let code = match error.status_code().as_u16() {
+        400 | 422 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::NotFound,
+        409 => Code::AlreadyExists,
+        429 => Code::ResourceExhausted,
+        503 => Code::Unavailable,
+        504 => Code::DeadlineExceeded,
+        _ => Code::Internal,
+    };
+
+    let mut status = Status::new(code, error.user_message());
+    if let Ok(value) = MetadataValue::try_from(error.error_type()) {
+        status.metadata_mut().insert("x-error-code", value);
+    }
+    status
+}
+
+fn rate_limited() -> Status {
+    Status::resource_exhausted("Rate limit exceeded")
+}
+
+impl From<Log> for LogReply {
+    fn from(log: Log) -> Self {
+        Self {
+            id: log.id,
+            schema_id: log.schema_id.to_string(),
+            log_data_json: log.log_data.to_string(),
+            created_at: timestamp::format(&log.created_at),
+        }
+    }
+}
+
+pub struct LogIngestService {
+    state: AppState,
+}
+
+impl LogIngestService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    pub fn into_server(self) -> LogIngestServer<Self> {
+        LogIngestServer::new(self)
+    }
+
+    /// Resolves the `authorization: Bearer <key>` metadata to a valid key
+    /// through the same checks as `api_key_middleware`.
+    async fn authenticate(
+        &self,
+        metadata: &MetadataMap,
+        remote_addr: Option<SocketAddr>,
+    ) -> Result<ApiKey, Status> {
+        let plain_key = metadata
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        authorize_api_key(&self.state, plain_key, remote_addr.map(|addr| addr.ip()))
+            .await
+            .map_err(|rejection| match rejection {
+                ApiKeyRejection::Unauthorized => Status::unauthenticated("Invalid API key"),
+                ApiKeyRejection::IpNotAllowed => Status::permission_denied("Client IP not allowed"),
+                ApiKeyRejection::RateLimited(_) => rate_limited(),
+                ApiKeyRejection::Internal(e) => status_from(e),
+            })
+    }
+
+    /// Writes are refused in maintenance mode, like the HTTP write routes.
+    async fn create(
+        &self,
+        api_key: &ApiKey,
+        request: CreateLogRequest,
+        redactor: &mut Redactor,
+    ) -> Result<Log, Status> {
+        if self.state.maintenance.load(Ordering::Relaxed) {
+            return Err(status_from(maintenance_error()));
+        }
+
+        let log_data = serde_json::from_str(&request.log_data_json).map_err(|e| {
+            Status::invalid_argument(format!("log_data_json is not valid JSON: {}", e))
+        })?;
+        let payload = CreateLogBody {
+            schema_id: Some(request.schema_id),
+            log_data,
+            created_at: None,
+        }
+        .validate_and_transform()
+        .map_err(status_from)?;
+
+        let log = self
+            .state
+            .log_service
+            .create_log_at(
+                payload.schema_id,
+                &api_key.tenant_id,
+                payload.log_data,
+                Some(api_key.id),
+                payload.created_at,
+            )
+            .await
+            .map_err(status_from)?;
+
+        Ok(publish_created(&self.state, log, None, true, redactor).await)
+    }
+}
+
+#[tonic::async_trait]
+impl LogIngest for LogIngestService {
+    async fn create_log(
+        &self,
+        request: Request<CreateLogRequest>,
+    ) -> Result<Response<LogReply>, Status> {
+        let api_key = self
+            .authenticate(request.metadata(), request.remote_addr())
+            .await?;
+        let log = self
+            .create(
+                &api_key,
+                request.into_inner(),
+                &mut self.state.log_service.redactor(),
+            )
+            .await?;
+
+        Ok(Response::new(LogReply::from(log)))
+    }
+
+    async fn create_logs_stream(
+        &self,
+        request: Request<Streaming<CreateLogRequest>>,
+    ) -> Result<Response<CreateLogsStreamReply>, Status> {
+        let api_key = self
+            .authenticate(request.metadata(), request.remote_addr())
+            .await?;
+        let mut stream = request.into_inner();
+
+        // The call itself took the first message's rate limit token; every
+        // further message takes its own.
+        let mut redactor = self.state.log_service.redactor();
+        let mut ids = Vec::new();
+        while let Some(item) = stream.message().await? {
+            let log = async {
+                if !ids.is_empty() {
+                    check_rate_limit(&self.state, &api_key).map_err(|_| rate_limited())?;
+                }
+                self.create(&api_key, item, &mut redactor).await
+            }
+            .await
+            .map_err(|status| {
+                Status::with_metadata(
+                    status.code(),
+                    format!("Log at index {} failed: {}", ids.len(), status.message()),
+                    status.metadata().clone(),
+                )
+            })?;
+            ids.push(log.id);
+        }
+
+        Ok(Response::new(CreateLogsStreamReply { ids }))
+    }
+}
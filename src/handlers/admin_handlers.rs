@@ -0,0 +1,126 @@
+use std::sync::atomic::Ordering;
+
+use axum::{extract::State, Extension};
+
+use crate::{
+    dto::{
+        LogResponse, MaintenanceRequest, MaintenanceResponse, ResetQuery, ResetResponse,
+        SlowRequestsResponse,
+    },
+    error::WithRequestId,
+    handlers::{
+        extract::{Json, Path, Query},
+        log_handlers::publish_created,
+    },
+    middleware::{RequestId, TraceContext},
+    AppError, AppResult, AppState, ErrorCode,
+};
+
+/// Value of `?confirm=` that `POST /admin/reset` requires.
+pub const RESET_CONFIRMATION: &str = "DELETE_EVERYTHING";
+
+pub async fn set_maintenance(
+    State(state): State<AppState>,
+    Json(payload): Json<MaintenanceRequest>,
+) -> Json<MaintenanceResponse> {
+    state.maintenance.store(payload.enabled, Ordering::Relaxed);
+
+    if payload.enabled {
+        tracing::warn!("Maintenance mode enabled; write operations are disabled");
+    } else {
+        tracing::info!("Maintenance mode disabled");
+    }
+
+    Json(MaintenanceResponse {
+        enabled: payload.enabled,
+    })
+}
+
+/// The slowest recent requests to the main API, slowest first.
+pub async fn get_slow_requests(State(state): State<AppState>) -> Json<SlowRequestsResponse> {
+    let log = &state.slow_requests;
+
+    Json(SlowRequestsResponse {
+        threshold_ms: log.threshold().as_millis() as u64,
+        capacity: log.capacity(),
+        requests: log.slowest().into_iter().map(Into::into).collect(),
+    })
+}
+
+/// Publishes an existing log again as a created event, to webhooks and live
+/// subscribers, without inserting a row. Lets consumers be checked after
+/// they reconnect.
+pub async fn replay_log(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Extension(request_id): Extension<RequestId>,
+    trace_context: Option<Extension<TraceContext>>,
+) -> AppResult<Json<LogResponse>> {
+    let log = state
+        .log_service
+        .get_log_by_id_any_tenant(id)
+        .await
+        .with_req_id(&request_id)?;
+
+    tracing::info!("Replaying log {} of schema {}", log.id, log.schema_id);
+
+    let log = publish_created(
+        &state,
+        log,
+        trace_context.as_deref(),
+        true,
+        &mut state.log_service.redactor(),
+    )
+    .await;
+
+    Ok(Json(LogResponse::from(log)))
+}
+
+/// Deletes every log and schema, and with `?include_api_keys=true` every
+/// API key. Only available when `ALLOW_RESET` is set, and every call must
+/// confirm with `?confirm=DELETE_EVERYTHING`.
+pub async fn reset_data(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(query): Query<ResetQuery>,
+) -> AppResult<Json<ResetResponse>> {
+    if !state.allow_reset {
+        return Err(
+            AppError::forbidden("Reset is disabled; set ALLOW_RESET=true to enable it")
+                .with_code(ErrorCode::ResetDisabled)
+                .with_request_id(&request_id),
+        );
+    }
+
+    if query.confirm.as_deref() != Some(RESET_CONFIRMATION) {
+        return Err(AppError::bad_request(format!(
+            "Reset deletes all data; confirm with ?confirm={}",
+            RESET_CONFIRMATION
+        ))
+        .with_code(ErrorCode::ConfirmationRequired)
+        .with_request_id(&request_id));
+    }
+
+    let counts = state
+        .schema_service
+        .purge_all(query.include_api_keys)
+        .await
+        .with_req_id(&request_id)?;
+
+    if query.include_api_keys {
+        state.api_key_service.clear_cache();
+    }
+
+    tracing::warn!(
+        "Reset deleted {} logs, {} schemas and {} API keys",
+        counts.logs,
+        counts.schemas,
+        counts.api_keys
+    );
+
+    Ok(Json(ResetResponse {
+        deleted_logs: counts.logs,
+        deleted_schemas: counts.schemas,
+        deleted_api_keys: counts.api_keys,
+    }))
+}
@@ -1,16 +1,24 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::{header, HeaderMap, StatusCode},
-    Extension, Json,
+    response::{IntoResponse, Response},
+    Extension,
 };
+use chrono::Utc;
 use validator::Validate;
 
 use crate::{
     dto::{
-        api_key_dto::{ApiKeyResponse, ApiKeysResponse, CreateApiKeyRequest, CreateApiKeyResponse},
-        common::DeletedResponse,
+        api_key_dto::{
+            AllowedIpRequest, ApiKeyResponse, ApiKeyStatsQuery, ApiKeyStatsResponse,
+            ApiKeysResponse, BatchDeleteApiKeysRequest, BatchDeleteApiKeysResponse,
+            CreateApiKeyRequest, CreateApiKeyResponse, ListApiKeysQuery, RotateExpiringQuery,
+            RotatedApiKeysResponse,
+        },
+        common::{DeletedResponse, ListFormat, Page},
     },
     error::WithRequestId,
+    handlers::extract::{Json, Path, Query},
     middleware::RequestId,
     models::CreateApiKey,
     AppError, AppResult, AppState,
@@ -19,11 +27,14 @@ use crate::{
 pub async fn create_api_key(
     State(state): State<AppState>,
     Extension(request_id): Extension<RequestId>,
-    Json(payload): Json<CreateApiKeyRequest>,
+    Json(mut payload): Json<CreateApiKeyRequest>,
 ) -> AppResult<(StatusCode, HeaderMap, Json<CreateApiKeyResponse>)> {
     payload
         .validate()
         .map_err(|e| AppError::validation_error(format!("Validation failed: {}", e)))?;
+    payload.expires_at = payload
+        .resolve_expires_at(Utc::now())
+        .with_req_id(&request_id)?;
 
     let created_api_key = state
         .api_key_service
@@ -50,14 +61,19 @@ pub async fn create_api_key(
 
 pub async fn get_api_keys(
     State(state): State<AppState>,
+    Query(query): Query<ListApiKeysQuery>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<Json<ApiKeysResponse>> {
+) -> AppResult<Response> {
     let api_keys = state
         .api_key_service
         .list_api_keys()
         .await
         .with_req_id(&request_id)?;
-    Ok(Json(ApiKeysResponse::from(api_keys)))
+
+    Ok(match query.format {
+        ListFormat::Legacy => Json(ApiKeysResponse::from(api_keys)).into_response(),
+        ListFormat::Page => Json(Page::<ApiKeyResponse, i32>::complete(api_keys)).into_response(),
+    })
 }
 
 pub async fn get_api_key_by_id(
@@ -103,3 +119,101 @@ pub async fn rotate_api_key(
 
     Ok(Json(CreateApiKeyResponse::from(rotated_key)))
 }
+
+/// Rotates every active key expiring within `?within=`. The response is
+/// the only place the new plaintext keys appear.
+pub async fn rotate_expiring_api_keys(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(query): Query<RotateExpiringQuery>,
+) -> AppResult<Json<RotatedApiKeysResponse>> {
+    let until = query.expiring_before(Utc::now()).with_req_id(&request_id)?;
+
+    let rotated = state
+        .api_key_service
+        .rotate_expiring_api_keys(until)
+        .await
+        .with_req_id(&request_id)?;
+
+    tracing::warn!(
+        "Rotated {} API keys expiring within {}: {:?}",
+        rotated.len(),
+        query.within,
+        rotated.iter().map(|key| key.api_key.id).collect::<Vec<_>>()
+    );
+
+    Ok(Json(RotatedApiKeysResponse::from(rotated)))
+}
+
+pub async fn get_api_key_stats(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(query): Query<ApiKeyStatsQuery>,
+) -> AppResult<Json<ApiKeyStatsResponse>> {
+    let until = query.expiring_before(Utc::now()).with_req_id(&request_id)?;
+
+    let stats = state
+        .api_key_service
+        .get_api_key_stats(until)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(ApiKeyStatsResponse::from(stats)))
+}
+
+pub async fn add_allowed_ip(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(api_key_id): Path<i32>,
+    Json(payload): Json<AllowedIpRequest>,
+) -> AppResult<Json<ApiKeyResponse>> {
+    let api_key = state
+        .api_key_service
+        .add_allowed_ip(api_key_id, &payload.cidr)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(ApiKeyResponse::from(api_key)))
+}
+
+pub async fn remove_allowed_ip(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path((api_key_id, cidr)): Path<(i32, String)>,
+) -> AppResult<Json<ApiKeyResponse>> {
+    let api_key = state
+        .api_key_service
+        .remove_allowed_ip(api_key_id, &cidr)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(ApiKeyResponse::from(api_key)))
+}
+
+pub async fn batch_delete_api_keys(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Json(payload): Json<BatchDeleteApiKeysRequest>,
+) -> AppResult<Json<BatchDeleteApiKeysResponse>> {
+    payload
+        .validate()
+        .map_err(|e| AppError::validation_error(format!("Validation failed: {}", e)))
+        .with_req_id(&request_id)?;
+
+    let deleted_ids = state
+        .api_key_service
+        .delete_api_keys(&payload.ids)
+        .await
+        .with_req_id(&request_id)?;
+
+    tracing::info!(
+        "Batch deleted {} of {} requested API keys",
+        deleted_ids.len(),
+        payload.ids.len()
+    );
+
+    Ok(Json(BatchDeleteApiKeysResponse::new(
+        &payload.ids,
+        &deleted_ids,
+    )))
+}
@@ -0,0 +1,185 @@
+//! Drop-in replacements for axum's `Json`, `Path` and `Query` extractors whose
+//! rejections are rendered as the usual JSON error body instead of axum's
+//! plain-text ones.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{
+        rejection::{JsonRejection, PathRejection},
+        FromRequest, FromRequestParts, Request,
+    },
+    http::{request::Parts, Extensions, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{middleware::RequestId, AppError, ErrorCode, UnknownQueryParams};
+
+/// JSON request body / response. An empty body is a 400 `EMPTY_BODY`, a
+/// malformed one a 400 `INVALID_JSON`; a well-formed body that does not
+/// match the target type keeps axum's 422, a missing `Content-Type` its 415
+/// and an oversized body its 413.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+/// Path parameters. Values that do not parse are a 400 `INVALID_PATH`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+/// Query string. Values that do not parse are a 400 `INVALID_QUERY`; with
+/// an [`UnknownQueryParams::Reject`] extension on the router, parameters no
+/// field uses are a 400 `UNKNOWN_QUERY_PARAM`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+fn with_request_id(error: AppError, extensions: &Extensions) -> AppError {
+    match extensions.get::<RequestId>() {
+        Some(request_id) => error.with_request_id(request_id),
+        None => error,
+    }
+}
+
+/// Keeps the status axum picked for `rejection`; syntax errors, shape
+/// mismatches, a missing `Content-Type` and oversized bodies get their own
+/// error codes.
+fn json_rejection(rejection: JsonRejection) -> AppError {
+    let status = rejection.status();
+    match rejection {
+        JsonRejection::MissingJsonContentType(_) => {
+            AppError::bad_request("Expected request with `Content-Type: application/json`")
+                .with_code(ErrorCode::InvalidContentType)
+                .with_status(status)
+        }
+        JsonRejection::JsonDataError(rejection) => {
+            AppError::schema_validation_error(rejection.body_text())
+                .with_code(ErrorCode::InvalidJson)
+        }
+        JsonRejection::JsonSyntaxError(rejection) => {
+            AppError::bad_request(rejection.body_text()).with_code(ErrorCode::InvalidJson)
+        }
+        rejection if status == StatusCode::PAYLOAD_TOO_LARGE => {
+            AppError::bad_request(rejection.body_text())
+                .with_code(ErrorCode::PayloadTooLarge)
+                .with_status(status)
+        }
+        rejection => AppError::bad_request(rejection.body_text()).with_status(status),
+    }
+}
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let headers = req.headers().clone();
+        let extensions = req.extensions().clone();
+
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|rejection| with_request_id(json_rejection(rejection.into()), &extensions))?;
+        // Size of JSON bodies per request, for capacity planning.
+        tracing::Span::current().record("body_bytes", bytes.len());
+
+        if bytes.is_empty() {
+            return Err(with_request_id(
+                AppError::bad_request("Request body is empty; expected a JSON document")
+                    .with_code(ErrorCode::EmptyBody),
+                &extensions,
+            ));
+        }
+
+        let mut req = Request::new(Body::from(bytes));
+        *req.headers_mut() = headers;
+        *req.extensions_mut() = extensions.clone();
+
+        match axum::Json::<T>::from_request(req, state).await {
+            Ok(axum::Json(value)) => Ok(Json(value)),
+            Err(rejection) => Err(with_request_id(json_rejection(rejection), &extensions)),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Path(value)),
+            Err(rejection @ PathRejection::FailedToDeserializePathParams(_)) => {
+                Err(with_request_id(
+                    AppError::bad_request(rejection.body_text()).with_code(ErrorCode::InvalidPath),
+                    &parts.extensions,
+                ))
+            }
+            Err(rejection) => Err(with_request_id(
+                AppError::internal_error(rejection.body_text()),
+                &parts.extensions,
+            )),
+        }
+    }
+}
+
+/// Deserializes `query`, failing on the parameters serde would ignore.
+fn deserialize_strict<T: DeserializeOwned>(query: &str) -> Result<T, AppError> {
+    let mut unknown = Vec::new();
+    let deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+
+    let value = serde_ignored::deserialize(deserializer, |path| unknown.push(path.to_string()))
+        .map_err(|e| {
+            AppError::bad_request(format!("Failed to deserialize query string: {}", e))
+                .with_code(ErrorCode::InvalidQuery)
+        })?;
+
+    if !unknown.is_empty() {
+        let names = unknown
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(
+            AppError::bad_request(format!("Unknown query parameter(s): {}", names))
+                .with_code(ErrorCode::UnknownQueryParam)
+                .with_detail("unknown_params", unknown),
+        );
+    }
+
+    Ok(value)
+}
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if parts.extensions.get::<UnknownQueryParams>() == Some(&UnknownQueryParams::Reject) {
+            return deserialize_strict(parts.uri.query().unwrap_or_default())
+                .map(Query)
+                .map_err(|error| with_request_id(error, &parts.extensions));
+        }
+
+        match axum::extract::Query::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Query(value)) => Ok(Query(value)),
+            Err(rejection) => Err(with_request_id(
+                AppError::bad_request(rejection.body_text()).with_code(ErrorCode::InvalidQuery),
+                &parts.extensions,
+            )),
+        }
+    }
+}
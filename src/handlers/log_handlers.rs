@@ -1,37 +1,90 @@
 use axum::{
-    extract::{Path, Query, State},
+    body::{Body, Bytes},
+    extract::State,
     http::{header, HeaderMap, StatusCode},
-    Extension, Json,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures_util::{StreamExt, TryStreamExt};
+use tokio_util::{
+    codec::{FramedRead, LinesCodec, LinesCodecError},
+    io::StreamReader,
 };
 use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
     dto::{
-        common::DeletedResponse, CreateLogRequest, CursorLogsResponse, LogEvent, LogResponse,
-        LogsResponse, QueryLogsRequest,
+        common::DeletedResponse, AppliedLogQuery, BroadcastQuery, BulkCreateLogsQuery,
+        BulkCreateLogsResponse, BulkItemResult, CountByDayQuery, CreateLogRequest,
+        CursorLogsResponse, DeleteLogQuery, ExportLogsQuery, ExportLogsResponse, FilterLogsRequest,
+        IngestLineError, IngestLogsResponse, LevelCountsQuery, LevelCountsResponse, ListFormat,
+        LogAction, LogActionResponse, LogEvent, LogResponse, LogsResponse, Page, PatchDocument,
+        PutLogByKeyRequest, QueryLogsRequest, SampledOutResponse,
     },
     error::WithRequestId,
-    middleware::RequestId,
-    models::query_params::LogQueryParams,
-    AppError, AppResult, AppState, SchemaNameVersion,
+    handlers::{
+        extract::{Json, Path, Query},
+        total_count_response,
+    },
+    middleware::{ApiKeyId, RequestId, TenantId, TraceContext},
+    models::{
+        query_params::LogQueryParams, schema_name_version::empty_segment_error, DailyLogCount,
+        LogFilter, SchemaLogCount,
+    },
+    services::log_service::Redactor,
+    AppError, AppResult, AppState, ErrorCode, Log, SchemaNameVersion,
 };
 
 pub async fn create_log(
     State(state): State<AppState>,
+    Query(query): Query<BroadcastQuery>,
     Extension(request_id): Extension<RequestId>,
-    Json(payload): Json<CreateLogRequest>,
-) -> AppResult<(StatusCode, HeaderMap, Json<LogResponse>)> {
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Extension(api_key_id): Extension<ApiKeyId>,
+    trace_context: Option<Extension<TraceContext>>,
+    Json(mut payload): Json<CreateLogRequest>,
+) -> AppResult<Response> {
+    if payload.schema_id.is_none() {
+        if let Some(schema) = state
+            .schema_service
+            .infer_schema(&mut payload.log_data, &tenant_id)
+            .await
+            .with_req_id(&request_id)?
+        {
+            payload.schema_id = Some(schema.id.to_string());
+        }
+    }
     let validated_payload = payload.validate_and_transform().with_req_id(&request_id)?;
 
-    let log = state
+    let Some(log) = state
         .log_service
-        .create_log(validated_payload.schema_id, validated_payload.log_data)
+        .create_log_sampled(
+            validated_payload.schema_id,
+            &tenant_id,
+            validated_payload.log_data,
+            Some(api_key_id.0),
+            validated_payload.created_at,
+            request_id.as_str(),
+        )
         .await
-        .with_req_id(&request_id)?;
-
-    let _ = state
-        .log_broadcast
-        .send(LogEvent::created_from(log.clone()));
+        .with_req_id(&request_id)?
+    else {
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(SampledOutResponse::new(validated_payload.schema_id)),
+        )
+            .into_response());
+    };
+    let trace_context = trace_context.map(|Extension(context)| context);
+    let log = publish_created(
+        &state,
+        log,
+        trace_context.as_ref(),
+        query.broadcast,
+        &mut state.log_service.redactor(),
+    )
+    .await;
 
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -41,31 +94,446 @@ pub async fn create_log(
         })?,
     );
 
-    Ok((StatusCode::CREATED, headers, Json(LogResponse::from(log))))
+    Ok((StatusCode::CREATED, headers, Json(LogResponse::from(log))).into_response())
+}
+
+/// Dispatches a newly created log to its schema's webhook unredacted, then,
+/// unless `broadcast` is false, broadcasts it with the schema's
+/// `redacted_fields` removed. Returns the redacted log. Cannot fail, as the
+/// log is already stored.
+pub(crate) async fn publish_created(
+    state: &AppState,
+    log: Log,
+    trace_context: Option<&TraceContext>,
+    broadcast: bool,
+    redactor: &mut Redactor,
+) -> Log {
+    if let Some(schema) = redactor.schema(log.schema_id).await {
+        state.webhook_service.dispatch(
+            LogEvent::created_from(log.clone()),
+            schema,
+            trace_context.cloned(),
+        );
+    }
+
+    let log = redactor.redact(log).await;
+    if broadcast {
+        let _ = state
+            .log_broadcast
+            .send(LogEvent::created_from(log.clone()));
+    }
+
+    log
+}
+
+/// Like [`publish_created`], for a log whose data was replaced.
+async fn publish_updated(
+    state: &AppState,
+    log: Log,
+    trace_context: Option<&TraceContext>,
+    redactor: &mut Redactor,
+) -> Log {
+    if let Some(schema) = redactor.schema(log.schema_id).await {
+        state.webhook_service.dispatch(
+            LogEvent::updated_from(log.clone()),
+            schema,
+            trace_context.cloned(),
+        );
+    }
+
+    let log = redactor.redact(log).await;
+    let _ = state
+        .log_broadcast
+        .send(LogEvent::updated_from(log.clone()));
+
+    log
+}
+
+pub const MAX_BULK_LOGS: usize = 1000;
+
+/// Creates each item independently, reporting per-item results. With
+/// `validate_only` the items go through the same checks but nothing is
+/// inserted, dispatched or broadcast.
+pub async fn create_logs_bulk(
+    State(state): State<AppState>,
+    Query(query): Query<BulkCreateLogsQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Extension(api_key_id): Extension<ApiKeyId>,
+    trace_context: Option<Extension<TraceContext>>,
+    Json(items): Json<Vec<CreateLogRequest>>,
+) -> AppResult<Json<BulkCreateLogsResponse>> {
+    if items.len() > MAX_BULK_LOGS {
+        return Err(AppError::bad_request(format!(
+            "Bulk requests are limited to {} logs",
+            MAX_BULK_LOGS
+        ))
+        .with_request_id(&request_id));
+    }
+
+    let trace_context = trace_context.map(|Extension(context)| context);
+    let mut redactor = state.log_service.redactor();
+    let mut results = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        let outcome = match item.validate_and_transform() {
+            Ok(item) if query.validate_only => {
+                match state.log_service.check_created_at(item.created_at) {
+                    Ok(()) => state
+                        .log_service
+                        .validate_log(item.schema_id, &tenant_id, &item.log_data)
+                        .await
+                        .map(|_| None),
+                    Err(e) => Err(e),
+                }
+            }
+            Ok(item) => match state
+                .log_service
+                .create_log_at(
+                    item.schema_id,
+                    &tenant_id,
+                    item.log_data,
+                    Some(api_key_id.0),
+                    item.created_at,
+                )
+                .await
+            {
+                Ok(log) => Ok(Some(
+                    publish_created(
+                        &state,
+                        log,
+                        trace_context.as_ref(),
+                        query.broadcast,
+                        &mut redactor,
+                    )
+                    .await,
+                )),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+
+        results.push(match outcome {
+            Ok(log) => BulkItemResult {
+                index,
+                ok: true,
+                id: log.map(|log| log.id),
+                error: None,
+            },
+            Err(e) => BulkItemResult {
+                index,
+                ok: false,
+                id: None,
+                error: Some(e.into()),
+            },
+        });
+    }
+
+    Ok(Json(BulkCreateLogsResponse::new(
+        query.validate_only,
+        results,
+    )))
+}
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+pub const MAX_NDJSON_LINE_BYTES: usize = 1024 * 1024;
+
+/// Streams an NDJSON body of `CreateLogRequest` lines, inserting each line as
+/// it is read so the batch is never buffered as a whole. Failed lines are
+/// reported and do not stop the ingestion.
+#[allow(clippy::too_many_arguments)]
+pub async fn ingest_logs(
+    State(state): State<AppState>,
+    Query(query): Query<BroadcastQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Extension(api_key_id): Extension<ApiKeyId>,
+    trace_context: Option<Extension<TraceContext>>,
+    headers: HeaderMap,
+    body: Body,
+) -> AppResult<Json<IngestLogsResponse>> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with(NDJSON_CONTENT_TYPE) {
+        return Err(
+            AppError::bad_request(format!("Content-Type must be {}", NDJSON_CONTENT_TYPE))
+                .with_code(ErrorCode::InvalidContentType)
+                .with_request_id(&request_id),
+        );
+    }
+
+    let reader = StreamReader::new(body.into_data_stream().map_err(std::io::Error::other));
+    let mut lines = FramedRead::new(
+        reader,
+        LinesCodec::new_with_max_length(MAX_NDJSON_LINE_BYTES),
+    );
+
+    let trace_context = trace_context.map(|Extension(context)| context);
+    let mut redactor = state.log_service.redactor();
+    let mut summary = IngestLogsResponse::default();
+    let mut line_number = 0;
+    while let Some(line) = lines.next().await {
+        line_number += 1;
+
+        let line = match line {
+            Ok(line) => line,
+            Err(LinesCodecError::MaxLineLengthExceeded) => {
+                summary.failed.push(IngestLineError {
+                    line: line_number,
+                    error: format!("Line exceeds {} bytes", MAX_NDJSON_LINE_BYTES),
+                });
+                continue;
+            }
+            Err(LinesCodecError::Io(e)) => {
+                summary.failed.push(IngestLineError {
+                    line: line_number,
+                    error: format!("Failed to read request body: {}", e),
+                });
+                break;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match ingest_line(
+            &state,
+            &line,
+            &tenant_id,
+            api_key_id.0,
+            trace_context.as_ref(),
+            query.broadcast,
+            &mut redactor,
+        )
+        .await
+        {
+            Ok(()) => summary.created += 1,
+            Err(e) => summary.failed.push(IngestLineError {
+                line: line_number,
+                error: e.user_message(),
+            }),
+        }
+    }
+
+    Ok(Json(summary))
+}
+
+async fn ingest_line(
+    state: &AppState,
+    line: &str,
+    tenant_id: &str,
+    created_by: i32,
+    trace_context: Option<&TraceContext>,
+    broadcast: bool,
+    redactor: &mut Redactor,
+) -> AppResult<()> {
+    let request: CreateLogRequest = serde_json::from_str(line)
+        .map_err(|e| AppError::bad_request(format!("Invalid JSON: {}", e)))?;
+    let request = request.validate_and_transform()?;
+
+    let log = state
+        .log_service
+        .create_log_at(
+            request.schema_id,
+            tenant_id,
+            request.log_data,
+            Some(created_by),
+            request.created_at,
+        )
+        .await?;
+    publish_created(state, log, trace_context, broadcast, redactor).await;
+
+    Ok(())
+}
+
+/// Applies a [`LogAction`] with the same side effects as the REST endpoints:
+/// created logs are dispatched to webhooks, and both actions are broadcast.
+pub async fn handle_log_action(
+    state: &AppState,
+    action: LogAction,
+    tenant_id: &str,
+    created_by: Option<i32>,
+    trace_context: Option<&TraceContext>,
+) -> AppResult<LogActionResponse> {
+    match action {
+        LogAction::Create {
+            schema_id,
+            log_data,
+        } => {
+            let log = state
+                .log_service
+                .create_log(schema_id, tenant_id, log_data, created_by)
+                .await?;
+            let log = publish_created(
+                state,
+                log,
+                trace_context,
+                true,
+                &mut state.log_service.redactor(),
+            )
+            .await;
+
+            Ok(LogActionResponse::Created {
+                log: LogResponse::from(log),
+            })
+        }
+        LogAction::Delete { id } => {
+            let log = state.log_service.delete_log(id, tenant_id).await?;
+
+            let _ = state
+                .log_broadcast
+                .send(LogEvent::deleted_from(log.clone()));
+
+            let log = state.log_service.redactor().redact(log).await;
+            Ok(LogActionResponse::Deleted {
+                log: LogResponse::from(log),
+            })
+        }
+    }
+}
+
+pub async fn log_action(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Extension(api_key_id): Extension<ApiKeyId>,
+    trace_context: Option<Extension<TraceContext>>,
+    Json(action): Json<LogAction>,
+) -> AppResult<(StatusCode, Json<LogActionResponse>)> {
+    let trace_context = trace_context.map(|Extension(context)| context);
+    let response = handle_log_action(
+        &state,
+        action,
+        &tenant_id,
+        Some(api_key_id.0),
+        trace_context.as_ref(),
+    )
+    .await
+    .with_req_id(&request_id)?;
+
+    let status = match response {
+        LogActionResponse::Created { .. } => StatusCode::CREATED,
+        LogActionResponse::Deleted { .. } => StatusCode::OK,
+    };
+
+    Ok((status, Json(response)))
 }
 
 pub async fn get_log_by_id(
     State(state): State<AppState>,
     Path(id): Path<i32>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
 ) -> AppResult<Json<LogResponse>> {
     let log = state
         .log_service
-        .get_log_by_id(id)
+        .get_log_by_id(id, &tenant_id)
         .await
         .with_req_id(&request_id)?;
+    let log = state.log_service.redactor().redact(log).await;
 
     Ok(Json(LogResponse::from(log)))
 }
 
+/// Patches a log's `log_data` with a merge patch or JSON Patch; the result is
+/// validated against the log's schema before it is stored, then published as
+/// an `updated` event.
+pub async fn patch_log(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    trace_context: Option<Extension<TraceContext>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<LogResponse>> {
+    let patch = PatchDocument::parse(&headers, &body).with_req_id(&request_id)?;
+
+    let log = state
+        .log_service
+        .patch_log_data(id, &tenant_id, &patch)
+        .await
+        .with_req_id(&request_id)?;
+    let trace_context = trace_context.map(|Extension(context)| context);
+    let log = publish_updated(
+        &state,
+        log,
+        trace_context.as_ref(),
+        &mut state.log_service.redactor(),
+    )
+    .await;
+
+    Ok(Json(LogResponse::from(log)))
+}
+
+/// `PUT /logs/schemas/{schema_id}/by-key/{key}`: creates the log addressed
+/// by `key` (201) or replaces the data of the existing one (200).
+#[allow(clippy::too_many_arguments)]
+pub async fn put_log_by_key(
+    State(state): State<AppState>,
+    Path((schema_id, key)): Path<(Uuid, String)>,
+    Query(query): Query<BroadcastQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Extension(api_key_id): Extension<ApiKeyId>,
+    trace_context: Option<Extension<TraceContext>>,
+    Json(payload): Json<PutLogByKeyRequest>,
+) -> AppResult<Response> {
+    payload
+        .validate()
+        .map_err(|e| AppError::bad_request(format!("Validation failed: {}", e)))
+        .with_req_id(&request_id)?;
+
+    let (log, created) = state
+        .log_service
+        .put_log_by_key(
+            schema_id,
+            &tenant_id,
+            &key,
+            payload.log_data,
+            Some(api_key_id.0),
+        )
+        .await
+        .with_req_id(&request_id)?;
+
+    if !created {
+        let log = state.log_service.redactor().redact(log).await;
+        return Ok(Json(LogResponse::from(log)).into_response());
+    }
+
+    let trace_context = trace_context.map(|Extension(context)| context);
+    let log = publish_created(
+        &state,
+        log,
+        trace_context.as_ref(),
+        query.broadcast,
+        &mut state.log_service.redactor(),
+    )
+    .await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::LOCATION,
+        format!("/logs/{}", log.id).parse().map_err(|e| {
+            AppError::internal_error(format!("Failed to create Location header: {}", e))
+        })?,
+    );
+
+    Ok((StatusCode::CREATED, headers, Json(LogResponse::from(log))).into_response())
+}
+
 pub async fn delete_log(
     State(state): State<AppState>,
     Path(id): Path<i32>,
+    Query(params): Query<DeleteLogQuery>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<Json<DeletedResponse<LogResponse>>> {
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
     let deleted_log = state
         .log_service
-        .delete_log(id)
+        .delete_log(id, &tenant_id)
         .await
         .with_req_id(&request_id)?;
 
@@ -73,41 +541,90 @@ pub async fn delete_log(
         .log_broadcast
         .send(LogEvent::deleted_from(deleted_log.clone()));
 
+    if !params.return_deleted.unwrap_or(true) {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
+    let deleted_log = state.log_service.redactor().redact(deleted_log).await;
     Ok(Json(DeletedResponse {
         deleted: true,
         data: LogResponse::from(deleted_log),
-    }))
+    })
+    .into_response())
 }
 
 async fn get_logs_internal(
     state: AppState,
     schema_id: Uuid,
+    tenant_id: String,
     params: QueryLogsRequest,
+    expression: Option<LogFilter>,
     request_id: RequestId,
-) -> AppResult<Json<LogsResponse>> {
+) -> AppResult<Response> {
+    let aliases = params.aliases().with_req_id(&request_id)?;
     let filters = LogQueryParams {
         date_begin: params.date_begin,
         date_end: params.date_end,
-        json_filters: params.filters,
+        json_filters: params.json_filters().with_req_id(&request_id)?,
+        created_by: params.created_by,
+        anchor: params.anchor,
+        expression,
+        ..Default::default()
+    };
+    let applied_query = AppliedLogQuery {
+        filters: filters.json_filters.clone(),
+        date_begin: params.date_begin,
+        date_end: params.date_end,
+        created_by: params.created_by,
+        anchor: params.anchor,
+        cursor: params.cursor,
+        limit: state.page_limits.resolve(params.limit),
+        direction: params.direction().with_req_id(&request_id)?,
     };
 
     let (logs, cursor_metadata) = state
         .log_service
         .get_cursor_logs(
             schema_id,
+            &tenant_id,
             params.cursor,
-            params.limit,
+            applied_query.limit,
             filters,
-            params.direction,
+            applied_query.direction,
+            params.include_total,
         )
         .await
         .with_req_id(&request_id)?;
+    let logs = state.log_service.redactor().redact_all(logs).await;
 
-    Ok(Json(LogsResponse::Cursor(CursorLogsResponse::new(
-        schema_id,
-        logs,
-        cursor_metadata,
-    ))))
+    let response = match params.format {
+        ListFormat::Legacy => LogsResponse::Cursor(CursorLogsResponse::new(
+            schema_id,
+            logs,
+            cursor_metadata,
+            applied_query,
+        )),
+        ListFormat::Page => LogsResponse::Page(Page::new(logs, cursor_metadata)),
+    };
+
+    if aliases.is_empty() {
+        return Ok(Json(response).into_response());
+    }
+
+    let mut body = serde_json::to_value(&response)
+        .map_err(|e| AppError::internal_error(format!("Failed to serialize logs: {}", e)))?;
+    if let Some(logs) = body
+        .get_mut(response.logs_key())
+        .and_then(|logs| logs.as_array_mut())
+    {
+        for log in logs {
+            for alias in &aliases {
+                alias.apply(log);
+            }
+        }
+    }
+
+    Ok(Json(body).into_response())
 }
 
 pub async fn get_logs(
@@ -115,32 +632,95 @@ pub async fn get_logs(
     Path(schema_id): Path<Uuid>,
     Query(params): Query<QueryLogsRequest>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<Json<LogsResponse>> {
-    get_logs_internal(state, schema_id, params, request_id).await
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
+    get_logs_internal(state, schema_id, tenant_id, params, None, request_id).await
+}
+
+/// `HEAD /logs/schemas/{schema_id}`: runs only the count query of
+/// [`get_logs`].
+pub async fn head_logs(
+    State(state): State<AppState>,
+    Path(schema_id): Path<Uuid>,
+    Query(params): Query<QueryLogsRequest>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
+    let filters = LogQueryParams {
+        date_begin: params.date_begin,
+        date_end: params.date_end,
+        json_filters: params.json_filters().with_req_id(&request_id)?,
+        created_by: params.created_by,
+        anchor: params.anchor,
+        ..Default::default()
+    };
+
+    let total = state
+        .log_service
+        .count_cursor_logs(schema_id, &tenant_id, &filters)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(total_count_response(total))
 }
 
 pub async fn get_logs_query(
     State(state): State<AppState>,
     Path(schema_id): Path<Uuid>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
     Json(payload): Json<QueryLogsRequest>,
-) -> AppResult<Json<LogsResponse>> {
-    get_logs_internal(state, schema_id, payload, request_id).await
+) -> AppResult<Response> {
+    get_logs_internal(state, schema_id, tenant_id, payload, None, request_id).await
+}
+
+/// Like [`get_logs_query`], additionally narrowing the page with the boolean
+/// filter expression in the body's `where`.
+pub async fn filter_logs(
+    State(state): State<AppState>,
+    Path(schema_id): Path<Uuid>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Json(payload): Json<FilterLogsRequest>,
+) -> AppResult<Response> {
+    let expression = payload.expression().with_req_id(&request_id)?;
+    get_logs_internal(
+        state,
+        schema_id,
+        tenant_id,
+        payload.query,
+        expression,
+        request_id,
+    )
+    .await
 }
 
 async fn get_logs_with_schema_resolve_internal(
     state: AppState,
     schema_ref: SchemaNameVersion,
+    tenant_id: String,
     params: QueryLogsRequest,
     request_id: RequestId,
-) -> AppResult<Json<LogsResponse>> {
+) -> AppResult<Response> {
+    schema_ref.validate().with_req_id(&request_id)?;
+
     let schema = state
         .schema_service
-        .resolve_schema(&schema_ref)
+        .resolve_schema(&schema_ref, &tenant_id)
         .await
         .with_req_id(&request_id)?;
 
-    get_logs_internal(state, schema.id, params, request_id).await
+    get_logs_internal(state, schema.id, tenant_id, params, None, request_id).await
+}
+
+/// Empty path segments never match `{name}` or `{version}`; these handlers
+/// answer such paths with the same 400 as a blank segment instead of a 404.
+pub async fn reject_empty_schema_name(Extension(request_id): Extension<RequestId>) -> AppError {
+    empty_segment_error("name").with_request_id(&request_id)
+}
+
+pub async fn reject_empty_schema_version(Extension(request_id): Extension<RequestId>) -> AppError {
+    empty_segment_error("version").with_request_id(&request_id)
 }
 
 pub async fn get_logs_by_schema_name_and_version(
@@ -148,19 +728,21 @@ pub async fn get_logs_by_schema_name_and_version(
     Path((schema_name, schema_version)): Path<(String, String)>,
     Query(params): Query<QueryLogsRequest>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<Json<LogsResponse>> {
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
     let schema_ref = SchemaNameVersion::with_version(schema_name, schema_version);
-    get_logs_with_schema_resolve_internal(state, schema_ref, params, request_id).await
+    get_logs_with_schema_resolve_internal(state, schema_ref, tenant_id, params, request_id).await
 }
 
 pub async fn get_logs_by_schema_name_and_version_query(
     State(state): State<AppState>,
     Path((schema_name, schema_version)): Path<(String, String)>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
     Json(payload): Json<QueryLogsRequest>,
-) -> AppResult<Json<LogsResponse>> {
+) -> AppResult<Response> {
     let schema_ref = SchemaNameVersion::with_version(schema_name, schema_version);
-    get_logs_with_schema_resolve_internal(state, schema_ref, payload, request_id).await
+    get_logs_with_schema_resolve_internal(state, schema_ref, tenant_id, payload, request_id).await
 }
 
 pub async fn get_logs_by_schema_name_latest(
@@ -168,29 +750,32 @@ pub async fn get_logs_by_schema_name_latest(
     Path(schema_name): Path<String>,
     Query(params): Query<QueryLogsRequest>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<Json<LogsResponse>> {
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
     let schema_ref = SchemaNameVersion::latest(schema_name);
-    get_logs_with_schema_resolve_internal(state, schema_ref, params, request_id).await
+    get_logs_with_schema_resolve_internal(state, schema_ref, tenant_id, params, request_id).await
 }
 
 pub async fn get_logs_by_schema_name_latest_query(
     State(state): State<AppState>,
     Path(schema_name): Path<String>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
     Json(payload): Json<QueryLogsRequest>,
-) -> AppResult<Json<LogsResponse>> {
+) -> AppResult<Response> {
     let schema_ref = SchemaNameVersion::latest(schema_name);
-    get_logs_with_schema_resolve_internal(state, schema_ref, payload, request_id).await
+    get_logs_with_schema_resolve_internal(state, schema_ref, tenant_id, payload, request_id).await
 }
 
 pub async fn get_initial_cursor(
     State(state): State<AppState>,
     Path(schema_id): Path<Uuid>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
 ) -> AppResult<Json<serde_json::Value>> {
     let cursor = state
         .log_service
-        .get_initial_cursor(schema_id)
+        .get_initial_cursor(schema_id, &tenant_id)
         .await
         .with_req_id(&request_id)?;
 
@@ -199,3 +784,87 @@ pub async fn get_initial_cursor(
         "initial_cursor": cursor
     })))
 }
+
+pub async fn export_logs(
+    State(state): State<AppState>,
+    Path(schema_id): Path<Uuid>,
+    Query(query): Query<ExportLogsQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Json<ExportLogsResponse>> {
+    let after = query.keyset().with_req_id(&request_id)?;
+
+    let (logs, has_more) = state
+        .log_service
+        .export_logs(
+            schema_id,
+            &tenant_id,
+            after,
+            state.page_limits.resolve(query.limit),
+        )
+        .await
+        .with_req_id(&request_id)?;
+    let logs = state.log_service.redactor().redact_all(logs).await;
+
+    Ok(Json(ExportLogsResponse::new(schema_id, logs, has_more)))
+}
+
+pub async fn get_level_counts(
+    State(state): State<AppState>,
+    Path(schema_id): Path<Uuid>,
+    Query(query): Query<LevelCountsQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Json<LevelCountsResponse>> {
+    let level_counts = state
+        .log_service
+        .get_level_counts(schema_id, &tenant_id, query.date_begin, query.date_end)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(LevelCountsResponse::new(
+        schema_id,
+        query,
+        level_counts,
+    )))
+}
+
+/// How long clients may reuse a `GET /logs/counts-by-schema` response.
+const COUNTS_BY_SCHEMA_MAX_AGE_SECS: u32 = 30;
+
+pub async fn get_log_counts_by_schema(
+    State(state): State<AppState>,
+    Query(query): Query<LevelCountsQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<impl IntoResponse> {
+    let counts: Vec<SchemaLogCount> = state
+        .log_service
+        .get_counts_by_schema(&tenant_id, query.date_begin, query.date_end)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok((
+        [(
+            header::CACHE_CONTROL,
+            format!("private, max-age={}", COUNTS_BY_SCHEMA_MAX_AGE_SECS),
+        )],
+        Json(counts),
+    ))
+}
+
+pub async fn get_daily_log_counts(
+    State(state): State<AppState>,
+    Path(schema_id): Path<Uuid>,
+    Query(query): Query<CountByDayQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Json<Vec<DailyLogCount>>> {
+    let counts = state
+        .log_service
+        .get_daily_counts(schema_id, &tenant_id, query.days)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(counts))
+}
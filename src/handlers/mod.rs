@@ -1,16 +1,43 @@
+pub mod admin_handlers;
 pub mod api_key_handlers;
+pub mod extract;
 pub mod log_handlers;
 pub mod schema_handlers;
+pub mod sse_handlers;
 pub mod ws_handlers;
 
+use axum::{
+    http::{HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+};
+
+pub use admin_handlers::{get_slow_requests, replay_log, reset_data, set_maintenance};
 pub use api_key_handlers::{
-    create_api_key, delete_api_key, get_api_key_by_id, get_api_keys, rotate_api_key,
+    add_allowed_ip, batch_delete_api_keys, create_api_key, delete_api_key, get_api_key_by_id,
+    get_api_key_stats, get_api_keys, remove_allowed_ip, rotate_api_key, rotate_expiring_api_keys,
 };
 pub use log_handlers::{
-    create_log, delete_log, get_initial_cursor, get_log_by_id, get_logs, get_logs_query,
+    create_log, create_logs_bulk, delete_log, export_logs, filter_logs, get_daily_log_counts,
+    get_initial_cursor, get_level_counts, get_log_by_id, get_log_counts_by_schema, get_logs,
+    get_logs_query, head_logs, ingest_logs, log_action, patch_log, put_log_by_key,
 };
 pub use schema_handlers::{
-    create_schema, delete_schema, get_schema_by_id, get_schema_by_name_and_version,
-    get_schema_by_name_latest, get_schemas, update_schema,
+    batch_get_schemas, create_schema, delete_schema, get_compatible_versions, get_schema_by_id,
+    get_schema_by_name_and_version, get_schema_by_name_latest, get_schema_definition,
+    get_schema_definition_by_name_and_version, get_schemas, head_schemas, patch_schema,
+    update_schema, validate_log_data,
 };
+pub use sse_handlers::stream_logs;
 pub use ws_handlers::ws_handler;
+
+/// Number of items a list endpoint matches, sent in answer to `HEAD`.
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Bodyless `HEAD` answer of a list endpoint: only the count was queried.
+fn total_count_response(total: i64) -> Response {
+    [(
+        HeaderName::from_static(TOTAL_COUNT_HEADER),
+        HeaderValue::from(total),
+    )]
+    .into_response()
+}
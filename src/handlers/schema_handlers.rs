@@ -1,38 +1,79 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::{header, HeaderMap, StatusCode},
-    Extension, Json,
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
 };
+use serde_json::json;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     dto::{
-        common::DeletedResponse, schema_dto::CursorSchemasResponse, CreateSchemaRequest,
-        DeleteSchemaQuery, GetSchemasQuery, SchemaResponse, UpdateSchemaRequest,
+        schema_dto::CursorSchemasResponse, BatchGetSchemasRequest, BatchGetSchemasResponse,
+        CreateSchemaRequest, CursorMetadata, DeleteSchemaQuery, DeleteSchemaResponse,
+        GetSchemaQuery, GetSchemasQuery, ListFormat, LogEvent, Page, PatchDocument, SchemaResponse,
+        UpdateSchemaQuery, UpdateSchemaRequest, ValidateLogDataQuery, ValidateLogDataRequest,
+        ValidateLogDataResponse, VersionCompatibility,
     },
     error::WithRequestId,
-    middleware::RequestId,
-    models::SchemaQueryParams,
-    AppError, AppResult, AppState,
+    handlers::{
+        extract::{Json, Path, Query},
+        total_count_response,
+    },
+    middleware::{ApiKeyId, RequestId, TenantId},
+    models::{CreateSchema, SchemaQueryParams},
+    AppError, AppResult, AppState, ErrorCode, Schema,
 };
 
+const SCHEMA_JSON_CONTENT_TYPE: &str = "application/schema+json";
+
 pub async fn get_schemas(
     State(state): State<AppState>,
     Query(query): Query<GetSchemasQuery>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<(HeaderMap, Json<CursorSchemasResponse>)> {
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<(HeaderMap, Response)> {
+    let direction = query.direction().with_req_id(&request_id)?;
     let filters = SchemaQueryParams {
         name: query.name,
         version: query.version,
+        search: query.search,
+        label: query.label,
+        tenant_id: Some(tenant_id),
     };
 
     let (schemas, cursor_metadata) = state
         .schema_service
-        .get_cursor_schemas(query.cursor, query.limit, filters, query.direction)
+        .get_cursor_schemas(
+            query.cursor,
+            state.page_limits.resolve(query.limit),
+            filters,
+            direction,
+            query.include_total,
+        )
         .await
         .with_req_id(&request_id)?;
 
+    let headers = next_page_link(&cursor_metadata)?;
+
+    let body = match query.format {
+        ListFormat::Legacy => {
+            Json(CursorSchemasResponse::new(schemas, cursor_metadata)).into_response()
+        }
+        ListFormat::Page => {
+            Json(Page::<SchemaResponse, _>::new(schemas, cursor_metadata)).into_response()
+        }
+    };
+
+    Ok((headers, body))
+}
+
+/// `Link` header pointing at the page after the one described by
+/// `cursor_metadata`, if there is one.
+fn next_page_link(cursor_metadata: &CursorMetadata<Uuid>) -> AppResult<HeaderMap> {
     let mut headers = HeaderMap::new();
     if let Some(next_cursor) = &cursor_metadata.next_cursor {
         headers.insert(
@@ -45,20 +86,56 @@ pub async fn get_schemas(
         );
     }
 
-    Ok((
-        headers,
-        Json(CursorSchemasResponse::new(schemas, cursor_metadata)),
-    ))
+    Ok(headers)
+}
+
+/// `HEAD /schemas`: the headers of [`get_schemas`] plus the number of
+/// matching schemas, without a body.
+pub async fn head_schemas(
+    State(state): State<AppState>,
+    Query(query): Query<GetSchemasQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
+    let direction = query.direction().with_req_id(&request_id)?;
+    let filters = SchemaQueryParams {
+        name: query.name,
+        version: query.version,
+        search: query.search,
+        label: query.label,
+        tenant_id: Some(tenant_id),
+    };
+
+    // The next cursor, and with it the `Link` header, needs the page itself.
+    let (_, cursor_metadata) = state
+        .schema_service
+        .get_cursor_schemas(
+            query.cursor,
+            state.page_limits.resolve(query.limit),
+            filters,
+            direction,
+            true,
+        )
+        .await
+        .with_req_id(&request_id)?;
+
+    let mut response = total_count_response(cursor_metadata.total.unwrap_or_default());
+    response
+        .headers_mut()
+        .extend(next_page_link(&cursor_metadata)?);
+
+    Ok(response)
 }
 
 pub async fn get_schema_by_name_latest(
     State(state): State<AppState>,
     Path(schema_name): Path<String>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
 ) -> AppResult<Json<SchemaResponse>> {
     let schema = state
         .schema_service
-        .get_schema_by_name(&schema_name)
+        .get_schema_by_name(&schema_name, &tenant_id)
         .await
         .with_req_id(&request_id)?;
 
@@ -69,10 +146,11 @@ pub async fn get_schema_by_name_and_version(
     State(state): State<AppState>,
     Path((schema_name, schema_version)): Path<(String, String)>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
 ) -> AppResult<Json<SchemaResponse>> {
     let schema = state
         .schema_service
-        .get_by_name_and_version(&schema_name, &schema_version)
+        .get_by_name_and_version(&schema_name, &schema_version, &tenant_id)
         .await
         .with_req_id(&request_id)?;
 
@@ -82,20 +160,162 @@ pub async fn get_schema_by_name_and_version(
 pub async fn get_schema_by_id(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<GetSchemaQuery>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
 ) -> AppResult<Json<SchemaResponse>> {
     let schema = state
         .schema_service
-        .get_schema_by_id(id)
+        .get_schema_by_id(id, Some(&tenant_id))
         .await
         .with_req_id(&request_id)?;
 
-    Ok(Json(SchemaResponse::from(schema)))
+    let mut response = SchemaResponse::from(schema);
+    if query.include_log_range {
+        let range = state
+            .schema_service
+            .get_log_range(id)
+            .await
+            .with_req_id(&request_id)?;
+        response = response.with_log_range(range);
+    }
+
+    Ok(Json(response))
+}
+
+pub async fn batch_get_schemas(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Json(payload): Json<BatchGetSchemasRequest>,
+) -> AppResult<Json<BatchGetSchemasResponse>> {
+    payload
+        .validate()
+        .map_err(|e| AppError::validation_error(format!("Validation failed: {}", e)))
+        .with_req_id(&request_id)?;
+
+    let schemas = state
+        .schema_service
+        .get_schemas_by_ids(&payload.ids, &tenant_id)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(BatchGetSchemasResponse::new(&payload.ids, schemas)))
+}
+
+/// Checks `log_data` against the schema without storing anything.
+pub async fn validate_log_data(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ValidateLogDataQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Json(payload): Json<ValidateLogDataRequest>,
+) -> AppResult<Json<ValidateLogDataResponse>> {
+    let report = state
+        .schema_service
+        .validate_log_data_detailed(id, &tenant_id, &payload.log_data)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(ValidateLogDataResponse::new(report, query.verbose)))
+}
+
+pub async fn get_schema_definition(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let schema = state
+        .schema_service
+        .get_schema_by_id(id, Some(&tenant_id))
+        .await
+        .with_req_id(&request_id)?;
+
+    schema_definition_response(&headers, schema).with_req_id(&request_id)
+}
+
+/// Diffs the schema against every other version of its name.
+pub async fn get_compatible_versions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Json<Vec<VersionCompatibility>>> {
+    let versions = state
+        .schema_service
+        .compatible_versions(id, &tenant_id)
+        .await
+        .with_req_id(&request_id)?;
+
+    Ok(Json(
+        versions
+            .into_iter()
+            .map(|(schema, changes)| VersionCompatibility::new(schema, changes))
+            .collect(),
+    ))
+}
+
+pub async fn get_schema_definition_by_name_and_version(
+    State(state): State<AppState>,
+    Path((schema_name, schema_version)): Path<(String, String)>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let schema = state
+        .schema_service
+        .get_by_name_and_version(&schema_name, &schema_version, &tenant_id)
+        .await
+        .with_req_id(&request_id)?;
+
+    schema_definition_response(&headers, schema).with_req_id(&request_id)
+}
+
+/// Serves the bare `schema_definition` with a content-hash ETag, answering
+/// `304 Not Modified` when the client's `If-None-Match` already matches.
+fn schema_definition_response(request_headers: &HeaderMap, schema: Schema) -> AppResult<Response> {
+    let body = serde_json::to_vec(&schema.schema_definition).map_err(|e| {
+        AppError::internal_error(format!("Failed to serialize schema definition: {}", e))
+    })?;
+
+    let etag = format!("\"{:x}\"", Sha256::digest(&body));
+    let etag = HeaderValue::from_str(&etag)
+        .map_err(|e| AppError::internal_error(format!("Failed to create ETag header: {}", e)))?;
+
+    let not_modified = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == "*" || tag.trim().as_bytes() == etag.as_bytes())
+        });
+
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(SCHEMA_JSON_CONTENT_TYPE),
+            ),
+            (header::ETAG, etag),
+        ],
+        body,
+    )
+        .into_response())
 }
 
 pub async fn create_schema(
     State(state): State<AppState>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    Extension(api_key_id): Extension<ApiKeyId>,
     Json(payload): Json<CreateSchemaRequest>,
 ) -> AppResult<(StatusCode, HeaderMap, Json<SchemaResponse>)> {
     payload
@@ -104,12 +324,11 @@ pub async fn create_schema(
 
     let schema = state
         .schema_service
-        .create_schema(
-            payload.name,
-            payload.version,
-            payload.description,
-            payload.schema_definition,
-        )
+        .create_schema(CreateSchema {
+            tenant_id,
+            created_by: Some(api_key_id.0),
+            ..CreateSchema::from(payload)
+        })
         .await
         .with_req_id(&request_id)?;
 
@@ -129,28 +348,113 @@ pub async fn create_schema(
     ))
 }
 
+/// Stores `request` as schema `id`, first checking it against the schema's
+/// recent logs when `check_compat` is set.
+async fn apply_schema_update(
+    state: &AppState,
+    id: Uuid,
+    request: CreateSchema,
+    check_compat: bool,
+) -> AppResult<Schema> {
+    if check_compat {
+        state
+            .schema_service
+            .check_compatibility(id, &request)
+            .await?;
+    }
+
+    state.schema_service.update_schema(id, request).await
+}
+
 pub async fn update_schema(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<UpdateSchemaQuery>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
     Json(payload): Json<UpdateSchemaRequest>,
 ) -> AppResult<Json<SchemaResponse>> {
     payload
         .validate()
         .map_err(|e| crate::AppError::validation_error(format!("Validation failed: {}", e)))?;
 
-    let schema = state
+    let schema = apply_schema_update(
+        &state,
+        id,
+        CreateSchema {
+            tenant_id,
+            ..CreateSchema::from(payload)
+        },
+        query.check_compat,
+    )
+    .await
+    .with_req_id(&request_id)?;
+
+    Ok(Json(SchemaResponse::from(schema)))
+}
+
+/// Applies a merge patch or JSON Patch to the schema's editable fields and
+/// runs the result through the same validation as `PUT /schemas/{id}`. The
+/// webhook secret is not part of the patched document and is kept unless the
+/// patch sets it.
+pub async fn patch_schema(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<UpdateSchemaQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<SchemaResponse>> {
+    let patch = PatchDocument::parse(&headers, &body).with_req_id(&request_id)?;
+
+    let existing = state
         .schema_service
-        .update_schema(
-            id,
-            payload.name,
-            payload.version,
-            payload.description,
-            payload.schema_definition,
-        )
+        .get_schema_by_id(id, Some(&tenant_id))
         .await
         .with_req_id(&request_id)?;
 
+    let mut document = json!({
+        "name": existing.name,
+        "version": existing.version,
+        "description": existing.description,
+        "schema_definition": existing.schema_definition,
+        "webhook_url": existing.webhook_url,
+        "strict_formats": existing.strict_formats,
+        "indexed_fields": existing.indexed_fields,
+        "max_retained_logs": existing.max_retained_logs,
+        "max_log_age_secs": existing.max_log_age_secs,
+        "redacted_fields": existing.redacted_fields,
+        "sample_rate": existing.sample_rate,
+        "labels": existing.labels,
+        "validation_mode": existing.validation_mode,
+        "max_field_bytes": existing.max_field_bytes,
+    });
+    patch.apply(&mut document).with_req_id(&request_id)?;
+
+    let mut payload: UpdateSchemaRequest = serde_json::from_value(document)
+        .map_err(|e| AppError::validation_error(format!("Invalid patched schema: {}", e)))
+        .with_req_id(&request_id)?;
+    payload
+        .validate()
+        .map_err(|e| AppError::validation_error(format!("Validation failed: {}", e)))
+        .with_req_id(&request_id)?;
+    if payload.webhook_secret.is_none() {
+        payload.webhook_secret = existing.webhook_secret;
+    }
+
+    let schema = apply_schema_update(
+        &state,
+        id,
+        CreateSchema {
+            tenant_id,
+            ..CreateSchema::from(payload)
+        },
+        query.check_compat,
+    )
+    .await
+    .with_req_id(&request_id)?;
+
     Ok(Json(SchemaResponse::from(schema)))
 }
 
@@ -159,28 +463,63 @@ pub async fn delete_schema(
     Path(id): Path<Uuid>,
     Query(params): Query<DeleteSchemaQuery>,
     Extension(request_id): Extension<RequestId>,
-) -> AppResult<Json<DeletedResponse<SchemaResponse>>> {
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Response> {
     let force = params.force.unwrap_or(false);
 
-    let deleted_schema = state
+    let subscribers = state.ws_subscribers.count(id);
+    if !force && subscribers > 0 {
+        // Only answer 409 for schemas the caller can see.
+        state
+            .schema_service
+            .get_schema_by_id(id, Some(&tenant_id))
+            .await
+            .with_req_id(&request_id)?;
+        return Err(AppError::conflict(format!(
+            "Schema {} has {} live WebSocket subscriber(s); use force=true to delete it anyway",
+            id, subscribers
+        ))
+        .with_code(ErrorCode::SchemaInUse)
+        .with_detail("subscribers", subscribers)
+        .with_request_id(&request_id));
+    }
+
+    let (deleted_schema, deleted_log_ids) = state
         .schema_service
-        .delete_schema(id, force)
+        .delete_schema(id, &tenant_id, force)
         .await
         .with_req_id(&request_id)?;
 
-    Ok(Json(DeletedResponse {
+    for log_id in &deleted_log_ids {
+        let _ = state.log_broadcast.send(LogEvent::Deleted {
+            id: *log_id,
+            schema_id: id,
+        });
+    }
+
+    let return_deleted = params.return_deleted.unwrap_or(true);
+    if !return_deleted && deleted_log_ids.is_empty() {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
+    Ok(Json(DeleteSchemaResponse {
         deleted: true,
-        data: SchemaResponse::from(deleted_schema),
-    }))
+        data: return_deleted.then(|| SchemaResponse::from(deleted_schema)),
+        deleted_logs: deleted_log_ids.len(),
+    })
+    .into_response())
 }
 
+/// `GET /schemas/cursor/initial`: the newest schema's id, or `null` when
+/// there are no schemas.
 pub async fn get_schemas_initial_cursor(
     State(state): State<AppState>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
 ) -> AppResult<Json<serde_json::Value>> {
     let cursor = state
         .schema_service
-        .get_initial_cursor()
+        .get_initial_cursor(&tenant_id)
         .await
         .with_req_id(&request_id)?;
 
@@ -0,0 +1,99 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::{
+    dto::LogEvent,
+    error::WithRequestId,
+    handlers::extract::{Path, Query},
+    middleware::{RequestId, TenantId},
+    AppError, AppResult, AppState,
+};
+
+pub const DEFAULT_SSE_HEARTBEAT_SECS: u64 = 15;
+pub const MAX_SSE_HEARTBEAT_SECS: u64 = 300;
+
+#[derive(Debug, Deserialize)]
+pub struct LogStreamQuery {
+    /// Seconds between keep-alive comments.
+    pub heartbeat: Option<u64>,
+}
+
+/// Server-Sent Events fallback for clients that cannot open a WebSocket.
+/// Forwards the schema's live `LogEvent`s as `created`/`updated`/`deleted`
+/// events.
+pub async fn stream_logs(
+    State(state): State<AppState>,
+    Path(schema_id): Path<Uuid>,
+    Query(query): Query<LogStreamQuery>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let heartbeat = query.heartbeat.unwrap_or(DEFAULT_SSE_HEARTBEAT_SECS);
+    if heartbeat == 0 || heartbeat > MAX_SSE_HEARTBEAT_SECS {
+        return Err(AppError::bad_request(format!(
+            "heartbeat must be between 1 and {} seconds",
+            MAX_SSE_HEARTBEAT_SECS
+        )))
+        .with_req_id(&request_id);
+    }
+
+    let _ = state
+        .schema_service
+        .get_schema_by_id(schema_id, Some(&tenant_id))
+        .await
+        .with_req_id(&request_id)?;
+
+    let rx = state.log_broadcast.subscribe();
+    let shutdown = state.shutdown.clone();
+    let events = stream::unfold(rx, move |rx| schema_events(rx, schema_id, shutdown.clone()));
+
+    Ok(Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(heartbeat))
+            .text("heartbeat"),
+    ))
+}
+
+/// Waits for the next event of `schema_id`. Events missed because the
+/// subscriber lagged are skipped; the stream ends when the channel closes or
+/// the server shuts down, so graceful shutdown is not held up by it.
+async fn schema_events(
+    mut rx: broadcast::Receiver<LogEvent>,
+    schema_id: Uuid,
+    shutdown: CancellationToken,
+) -> Option<(Result<Event, Infallible>, broadcast::Receiver<LogEvent>)> {
+    loop {
+        let received = tokio::select! {
+            received = rx.recv() => received,
+            _ = shutdown.cancelled() => return None,
+        };
+        match received {
+            Ok(log_event) if log_event.schema_id() == schema_id => {
+                let name = match log_event {
+                    LogEvent::Created { .. } => "created",
+                    LogEvent::Deleted { .. } => "deleted",
+                    LogEvent::Updated { .. } => "updated",
+                };
+                match Event::default().event(name).json_data(&log_event) {
+                    Ok(event) => return Some((Ok(event), rx)),
+                    Err(e) => tracing::warn!("Failed to serialize SSE event: {}", e),
+                }
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("SSE subscriber lagged, skipped {} events", skipped);
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
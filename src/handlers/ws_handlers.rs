@@ -1,33 +1,168 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
-        Query, State,
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State,
     },
-    response::Response,
+    http::HeaderValue,
+    response::{IntoResponse, Response},
     Extension,
 };
-use futures_util::{sink::SinkExt, stream::StreamExt};
+use futures_util::{
+    sink::SinkExt,
+    stream::{SplitSink, StreamExt},
+};
 use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::{broadcast::error::RecvError, mpsc, OwnedSemaphorePermit, Semaphore};
 use uuid::Uuid;
 
-use crate::{error::WithRequestId, AppResult};
-use crate::{middleware::RequestId, AppState};
+use crate::{
+    dto::{Direction, LogEvent, SequencedLogEvent, WsClientFrame, WsControlFrame},
+    error::WithRequestId,
+    handlers::extract::Query,
+    models::query_params::{validate_json_filters, JsonFilter, LogQueryParams},
+    AppError, AppResult, ErrorCode, Log,
+};
+use crate::{
+    middleware::{RequestId, TenantId},
+    AppState,
+};
+
+pub const MAX_WS_REPLAY: u32 = 1000;
+/// Close reason sent with 1001 (going away) when the server shuts down.
+pub const SHUTDOWN_CLOSE_REASON: &str = "server shutting down";
+
+/// Caps the number of open WebSocket connections across the server. Each
+/// connection holds a permit until its socket task ends.
+#[derive(Clone)]
+pub struct WsConnectionLimit {
+    semaphore: Arc<Semaphore>,
+    max: usize,
+}
+
+impl WsConnectionLimit {
+    pub fn new(max: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max)),
+            max,
+        }
+    }
+
+    fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.semaphore.clone().try_acquire_owned().ok()
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+/// Open WebSocket connections per subscribed `schema_id`, so that a schema
+/// is not deleted from under live clients.
+#[derive(Clone, Default)]
+pub struct WsSubscribers {
+    counts: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl WsSubscribers {
+    fn subscribe(&self, schema_id: Uuid) -> WsSubscription {
+        *self.lock().entry(schema_id).or_default() += 1;
+        WsSubscription {
+            subscribers: self.clone(),
+            schema_id,
+        }
+    }
+
+    /// Number of open connections subscribed to `schema_id`.
+    pub fn count(&self, schema_id: Uuid) -> usize {
+        self.lock().get(&schema_id).copied().unwrap_or(0)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, usize>> {
+        self.counts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Counts its connection as a subscriber of `schema_id` until dropped.
+struct WsSubscription {
+    subscribers: WsSubscribers,
+    schema_id: Uuid,
+}
+
+impl Drop for WsSubscription {
+    fn drop(&mut self) {
+        let mut counts = self.subscribers.lock();
+        if let Some(count) = counts.get_mut(&self.schema_id) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.schema_id);
+            }
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct WebSocketQuery {
     pub schema_id: Option<Uuid>,
+    /// Number of most recent logs of `schema_id` to send before live events.
+    #[serde(default)]
+    pub replay: u32,
 }
 
+/// Header repeating the error code of a rejected upgrade, for WebSocket
+/// clients that only expose the status line and headers of the response.
+pub const ERROR_CODE_HEADER: &str = "x-error-code";
+
+/// Upgrades to a WebSocket streaming log events. Rejections carry the usual
+/// JSON error body plus the error code in [`ERROR_CODE_HEADER`].
 pub async fn ws_handler(
     State(state): State<AppState>,
     Extension(request_id): Extension<RequestId>,
+    Extension(TenantId(tenant_id)): Extension<TenantId>,
     Query(query): Query<WebSocketQuery>,
     ws: WebSocketUpgrade,
+) -> Response {
+    match upgrade(state, request_id, tenant_id, query, ws).await {
+        Ok(response) => response,
+        Err(error) => {
+            let error_code = HeaderValue::from_str(error.error_type()).ok();
+            let mut response = error.into_response();
+            if let Some(error_code) = error_code {
+                response.headers_mut().insert(ERROR_CODE_HEADER, error_code);
+            }
+            response
+        }
+    }
+}
+
+async fn upgrade(
+    state: AppState,
+    request_id: RequestId,
+    tenant_id: String,
+    query: WebSocketQuery,
+    ws: WebSocketUpgrade,
 ) -> AppResult<Response> {
+    if query.replay > MAX_WS_REPLAY {
+        return Err(AppError::bad_request(format!(
+            "replay must be at most {}",
+            MAX_WS_REPLAY
+        )))
+        .with_req_id(&request_id);
+    }
+
+    if query.replay > 0 && query.schema_id.is_none() {
+        return Err(AppError::bad_request("replay requires a schema_id")).with_req_id(&request_id);
+    }
+
     if let Some(schema_id) = query.schema_id {
         let _ = state
             .schema_service
-            .get_schema_by_id(schema_id)
+            .get_schema_by_id(schema_id, Some(&tenant_id))
             .await
             .with_req_id(&request_id)?;
 
@@ -39,25 +174,249 @@ pub async fn ws_handler(
         tracing::debug!("WebSocket connection requested for all schemas");
     }
 
-    Ok(ws.on_upgrade(move |socket| handle_socket(socket, state, query)))
+    let Some(permit) = state.ws_connections.try_acquire() else {
+        tracing::warn!(
+            "Rejecting WebSocket connection: {} connections open",
+            state.ws_connections.max()
+        );
+        return Err(AppError::service_unavailable(
+            "Too many open WebSocket connections, retry later",
+        )
+        .with_code(ErrorCode::TooManyConnections))
+        .with_req_id(&request_id);
+    };
+
+    let subscription = query
+        .schema_id
+        .map(|schema_id| state.ws_subscribers.subscribe(schema_id));
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        handle_socket(socket, state, query, tenant_id).await;
+        drop(subscription);
+        drop(permit);
+    }))
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState, query: WebSocketQuery) {
+/// Fetches the most recent `replay` logs of the subscribed schema, oldest first.
+async fn fetch_replay(state: &AppState, query: &WebSocketQuery, tenant_id: &str) -> Vec<Log> {
+    let Some(schema_id) = query.schema_id.filter(|_| query.replay > 0) else {
+        return Vec::new();
+    };
+
+    let logs = match state
+        .log_service
+        .get_cursor_logs(
+            schema_id,
+            tenant_id,
+            None,
+            query.replay as i32,
+            LogQueryParams::default(),
+            Direction::Forward,
+            false,
+        )
+        .await
+    {
+        Ok((logs, _)) => Ok(state.log_service.redactor().redact_all(logs).await),
+        Err(e) => Err(e),
+    };
+
+    match logs {
+        Ok(mut logs) => {
+            logs.reverse();
+            logs
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch WebSocket replay: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Whether `schema_id` belongs to `tenant_id`, remembering the answer so
+/// each schema is looked up at most once per connection.
+async fn in_tenant(
+    state: &AppState,
+    tenant_id: &str,
+    schema_id: Uuid,
+    known: &mut HashMap<Uuid, bool>,
+) -> bool {
+    if let Some(visible) = known.get(&schema_id) {
+        return *visible;
+    }
+
+    let visible = state
+        .schema_service
+        .get_schema_by_id(schema_id, Some(tenant_id))
+        .await
+        .is_ok();
+    known.insert(schema_id, visible);
+    visible
+}
+
+/// `filters` of a client `subscribe` frame, split into the same conditions
+/// the HTTP `filters` parameter is queried with.
+struct EventFilter {
+    filters: Value,
+    conditions: Vec<JsonFilter>,
+}
+
+impl EventFilter {
+    /// `None` for `null`, which removes the filter.
+    fn parse(filters: Option<Value>) -> AppResult<Option<Self>> {
+        let Some(filters) = filters.filter(|filters| !filters.is_null()) else {
+            return Ok(None);
+        };
+        if !filters.is_object() {
+            return Err(AppError::bad_request("filters must be a JSON object")
+                .with_code(ErrorCode::InvalidFilter));
+        }
+        validate_json_filters(&filters)?;
+
+        let conditions = LogQueryParams {
+            json_filters: Some(filters.clone()),
+            ..Default::default()
+        }
+        .json_filter_conditions();
+
+        Ok(Some(Self {
+            filters,
+            conditions,
+        }))
+    }
+
+    /// `deleted` events carry no `log_data` and always pass.
+    fn matches(&self, event: &LogEvent) -> bool {
+        match event {
+            LogEvent::Created { log_data, .. } | LogEvent::Updated { log_data, .. } => self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(log_data)),
+            LogEvent::Deleted { .. } => true,
+        }
+    }
+}
+
+/// Sends `event` numbered `seq`; returns false once the client is gone.
+async fn send_event(sender: &mut SplitSink<WebSocket, Message>, seq: u64, event: LogEvent) -> bool {
+    match serde_json::to_string(&SequencedLogEvent { seq, event }) {
+        Ok(json) => sender.send(Message::Text(json.into())).await.is_ok(),
+        Err(_) => true,
+    }
+}
+
+/// Sends an unnumbered control frame; returns false once the client is gone.
+async fn send_control(sender: &mut SplitSink<WebSocket, Message>, frame: &WsControlFrame) -> bool {
+    match serde_json::to_string(frame) {
+        Ok(json) => sender.send(Message::Text(json.into())).await.is_ok(),
+        Err(_) => true,
+    }
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    state: AppState,
+    query: WebSocketQuery,
+    tenant_id: String,
+) {
     let (mut sender, mut receiver) = socket.split();
+
+    // Subscribe before fetching the replay so no event is missed in between;
+    // created events at or below the replay's high-water mark are then dropped
+    // from the live stream, as the replay already delivered them.
     let mut rx = state.log_broadcast.subscribe();
+    let replay = fetch_replay(&state, &query, &tenant_id).await;
+    let high_water_mark = replay.iter().map(|log| log.id).max();
 
+    let subscribed = WsControlFrame::Subscribed {
+        schema_id: query.schema_id,
+        replay: replay.len() as u32,
+    };
+    if !send_control(&mut sender, &subscribed).await {
+        return;
+    }
+
+    let mut seq: u64 = 0;
+    for log in replay {
+        seq += 1;
+        if !send_event(&mut sender, seq, LogEvent::created_from(log)).await {
+            return;
+        }
+    }
+
+    // Filters parsed by the receive task, applied by the send task.
+    let (filter_tx, mut filter_rx) = mpsc::channel::<AppResult<Option<EventFilter>>>(8);
+
+    let shutdown = state.shutdown.clone();
     let mut send_task = tokio::spawn(async move {
-        while let Ok(log_event) = rx.recv().await {
+        let mut known_schemas = HashMap::new();
+        let mut event_filter: Option<EventFilter> = None;
+        loop {
+            let received = tokio::select! {
+                received = rx.recv() => received,
+                Some(parsed) = filter_rx.recv() => {
+                    let frame = match parsed {
+                        Ok(filter) => {
+                            event_filter = filter;
+                            WsControlFrame::FiltersApplied {
+                                filters: event_filter.as_ref().map(|f| f.filters.clone()),
+                            }
+                        }
+                        Err(e) => WsControlFrame::Error {
+                            error: e.error_type().to_string(),
+                            message: e.to_string(),
+                        },
+                    };
+                    if !send_control(&mut sender, &frame).await {
+                        break;
+                    }
+                    continue;
+                }
+                _ = shutdown.cancelled() => {
+                    let close = CloseFrame {
+                        code: close_code::AWAY,
+                        reason: SHUTDOWN_CLOSE_REASON.into(),
+                    };
+                    let _ = sender.send(Message::Close(Some(close))).await;
+                    break;
+                }
+            };
+
+            let log_event = match received {
+                Ok(log_event) => log_event,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("WebSocket client lagged, skipped {} events", skipped);
+                    seq += skipped;
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
             let should_send = match &query.schema_id {
                 Some(schema_id) => log_event.schema_id() == *schema_id,
-                None => true,
+                None => {
+                    in_tenant(
+                        &state,
+                        &tenant_id,
+                        log_event.schema_id(),
+                        &mut known_schemas,
+                    )
+                    .await
+                }
             };
 
-            if should_send {
-                if let Ok(json) = serde_json::to_string(&log_event) {
-                    if sender.send(Message::Text(json.into())).await.is_err() {
-                        break;
-                    }
+            let already_replayed = match (&log_event, high_water_mark) {
+                (LogEvent::Created { id, .. }, Some(mark)) => *id <= mark,
+                _ => false,
+            };
+
+            let filtered_out = event_filter
+                .as_ref()
+                .is_some_and(|filter| !filter.matches(&log_event));
+
+            if should_send && !already_replayed && !filtered_out {
+                seq += 1;
+                if !send_event(&mut sender, seq, log_event).await {
+                    break;
                 }
             }
         }
@@ -73,9 +432,16 @@ async fn handle_socket(socket: WebSocket, state: AppState, query: WebSocketQuery
                     tracing::debug!("Received ping: {:?}", ping);
                 }
                 Message::Pong(_) => {}
-                Message::Text(text) => {
-                    tracing::debug!("Received text message: {}", text);
-                }
+                Message::Text(text) => match serde_json::from_str::<WsClientFrame>(&text) {
+                    Ok(WsClientFrame::Subscribe { filters }) => {
+                        if filter_tx.send(EventFilter::parse(filters)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => {
+                        tracing::debug!("Received text message: {}", text);
+                    }
+                },
                 _ => {}
             }
         }
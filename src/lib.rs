@@ -1,20 +1,23 @@
 use axum::{
-    http::StatusCode,
+    http::{HeaderValue, StatusCode},
     middleware as axum_middleware,
     response::Json,
-    routing::{delete, get, post, put},
-    Router,
+    routing::{delete, get, patch, post, put},
+    Extension, Router,
 };
 use serde_json::json;
 use sqlx::PgPool;
-use std::sync::Arc;
+use std::sync::{atomic::AtomicBool, Arc};
 use tokio::sync::broadcast;
-use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tokio_util::sync::CancellationToken;
+use tower::{Layer, ServiceBuilder};
+use tower_http::{normalize_path::NormalizePathLayer, trace::TraceLayer};
 
 pub mod config;
 pub mod dto;
 pub mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod handlers;
 pub mod middleware;
 pub mod models;
@@ -22,35 +25,84 @@ pub mod repositories;
 pub mod services;
 
 use crate::{
+    config::defaults::{
+        DEFAULT_API_KEY_CACHE_TTL_SECS, DEFAULT_COMPAT_SAMPLE_SIZE, DEFAULT_MAX_FUTURE_SKEW_SECS,
+        DEFAULT_SLOW_REQUEST_BUFFER_SIZE, DEFAULT_SLOW_REQUEST_THRESHOLD_MS,
+        DEFAULT_WS_MAX_CONNECTIONS,
+    },
     handlers::{
-        create_log, create_schema, delete_log, delete_schema, get_initial_cursor, get_log_by_id,
-        get_logs, get_logs_query, get_schema_by_id, get_schema_by_name_and_version,
-        get_schema_by_name_latest, get_schemas,
+        batch_get_schemas, create_log, create_logs_bulk, create_schema, delete_log, delete_schema,
+        export_logs, filter_logs, get_compatible_versions, get_daily_log_counts,
+        get_initial_cursor, get_level_counts, get_log_by_id, get_log_counts_by_schema, get_logs,
+        get_logs_query, get_schema_by_id, get_schema_by_name_and_version,
+        get_schema_by_name_latest, get_schema_definition,
+        get_schema_definition_by_name_and_version, get_schemas, head_logs, head_schemas,
+        ingest_logs, log_action,
         log_handlers::{
             get_logs_by_schema_name_and_version, get_logs_by_schema_name_and_version_query,
             get_logs_by_schema_name_latest, get_logs_by_schema_name_latest_query,
+            reject_empty_schema_name, reject_empty_schema_version,
         },
+        patch_log, patch_schema, put_log_by_key,
         schema_handlers::get_schemas_initial_cursor,
-        update_schema, ws_handler,
+        stream_logs, update_schema, validate_log_data, ws_handler,
+        ws_handlers::{WsConnectionLimit, WsSubscribers},
+    },
+    middleware::{
+        admin_ip_allowlist_middleware, api_key_middleware, cors_layer, error_body_middleware,
+        maintenance_middleware, preflight_no_content, server_version_header,
+        slow_request_middleware,
     },
-    middleware::api_key_middleware,
 };
 
-pub use config::Config;
+/// Crate version, git commit and build time of the running binary; the
+/// latter two are set by `build.rs`.
+pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_SHA: &str = env!("GIT_SHA");
+pub const BUILT_AT: &str = env!("BUILT_AT");
+
+pub use config::{
+    ApiKeyFormat, ApiKeyHashAlgorithm, Config, LogSampling, PageLimits, SchemaComplexityLimits,
+    SchemaNamingPolicy, TrailingSlash, UnknownQueryParams,
+};
 pub use dto::{LogEvent, PaginatedLogsResponse, PaginationMetadata, SchemaResponse};
-pub use error::{AppError, AppResult};
-pub use middleware::request_id::{RequestIdLayer, RequestIdMakeSpan};
-pub use models::{Log, Schema, SchemaNameVersion};
-pub use repositories::{ApiKeyRepository, LogRepository, SchemaRepository};
-pub use services::{ApiKeyService, LogService, SchemaService};
+pub use error::{AppError, AppResult, ErrorBodyFormat, ErrorCode};
+pub use middleware::{
+    request_id::{RequestIdLayer, RequestIdMakeSpan},
+    AdminIpAllowlist, SlowRequestLog,
+};
+pub use models::{Log, Schema, SchemaNameVersion, ValidationMode};
+pub use repositories::{
+    ApiKeyRepository, InMemoryLogRepository, InMemorySchemaRepository, LogRepository,
+    SchemaRepository,
+};
+pub use services::{ApiKeyService, LogService, SchemaService, WebhookService};
 
 #[derive(Clone)]
 pub struct AppState {
     pub schema_service: Arc<SchemaService>,
     pub log_service: Arc<LogService>,
     pub api_key_service: Arc<ApiKeyService>,
+    pub webhook_service: Arc<WebhookService>,
     pub log_broadcast: broadcast::Sender<LogEvent>,
     pub rate_limiter: Arc<middleware::RateLimiter>,
+    pub page_limits: PageLimits,
+    /// When set, writes to schemas and logs are rejected with 503.
+    pub maintenance: Arc<AtomicBool>,
+    /// Origins allowed by CORS; empty allows any origin.
+    pub cors_allowed_origins: Vec<HeaderValue>,
+    pub unknown_query_params: UnknownQueryParams,
+    pub trailing_slash: TrailingSlash,
+    pub admin_allowlist: Arc<AdminIpAllowlist>,
+    /// Enables `POST /admin/reset`.
+    pub allow_reset: bool,
+    /// Cancelled when the server starts shutting down; long-lived
+    /// connections such as WebSockets close themselves on it.
+    pub shutdown: CancellationToken,
+    pub ws_connections: WsConnectionLimit,
+    pub ws_subscribers: WsSubscribers,
+    pub slow_requests: SlowRequestLog,
+    pub error_body_format: ErrorBodyFormat,
 }
 
 impl AppState {
@@ -58,15 +110,288 @@ impl AppState {
         schema_service: Arc<SchemaService>,
         log_service: Arc<LogService>,
         api_key_service: Arc<ApiKeyService>,
+        webhook_service: Arc<WebhookService>,
         log_broadcast: broadcast::Sender<LogEvent>,
         rate_limiter: Arc<middleware::RateLimiter>,
+        page_limits: PageLimits,
     ) -> Self {
         Self {
             schema_service,
             log_service,
             api_key_service,
+            webhook_service,
             log_broadcast,
             rate_limiter,
+            page_limits,
+            maintenance: Arc::new(AtomicBool::new(false)),
+            cors_allowed_origins: Vec::new(),
+            unknown_query_params: UnknownQueryParams::default(),
+            trailing_slash: TrailingSlash::default(),
+            admin_allowlist: Arc::new(AdminIpAllowlist::default()),
+            allow_reset: false,
+            shutdown: CancellationToken::new(),
+            ws_connections: WsConnectionLimit::new(DEFAULT_WS_MAX_CONNECTIONS),
+            ws_subscribers: WsSubscribers::default(),
+            slow_requests: SlowRequestLog::default(),
+            error_body_format: ErrorBodyFormat::default(),
+        }
+    }
+
+    /// Wires the Postgres-backed repositories and services on `pool`.
+    pub fn builder(pool: PgPool) -> AppStateBuilder {
+        AppStateBuilder::new(pool)
+    }
+}
+
+/// Builds an [`AppState`] from a pool, so `main`, tests and benches share one
+/// wiring of repositories, services and the broadcast channel.
+pub struct AppStateBuilder {
+    pool: PgPool,
+    broadcast_channel_size: usize,
+    page_limits: PageLimits,
+    schema_naming_policy: SchemaNamingPolicy,
+    schema_complexity_limits: SchemaComplexityLimits,
+    schema_compat_sample_size: i32,
+    schema_inference_field: Option<String>,
+    max_schemas: Option<u64>,
+    api_key_format: ApiKeyFormat,
+    api_key_hash_algorithm: ApiKeyHashAlgorithm,
+    api_key_cache_ttl: std::time::Duration,
+    max_api_keys: Option<u64>,
+    cors_allowed_origins: Vec<HeaderValue>,
+    unknown_query_params: UnknownQueryParams,
+    trailing_slash: TrailingSlash,
+    admin_allowlist: AdminIpAllowlist,
+    allow_reset: bool,
+    log_max_future_skew: chrono::Duration,
+    log_sampling: LogSampling,
+    log_compression_threshold: Option<usize>,
+    db_statement_timeout_ms: u64,
+    ws_max_connections: usize,
+    slow_request_threshold: std::time::Duration,
+    slow_request_buffer_size: usize,
+    error_body_format: ErrorBodyFormat,
+}
+
+impl AppStateBuilder {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            broadcast_channel_size: 100,
+            page_limits: PageLimits::default(),
+            schema_naming_policy: SchemaNamingPolicy::default(),
+            schema_complexity_limits: SchemaComplexityLimits::default(),
+            schema_compat_sample_size: DEFAULT_COMPAT_SAMPLE_SIZE,
+            schema_inference_field: None,
+            max_schemas: None,
+            api_key_format: ApiKeyFormat::default(),
+            api_key_hash_algorithm: ApiKeyHashAlgorithm::default(),
+            api_key_cache_ttl: std::time::Duration::from_secs(DEFAULT_API_KEY_CACHE_TTL_SECS),
+            max_api_keys: None,
+            cors_allowed_origins: Vec::new(),
+            unknown_query_params: UnknownQueryParams::default(),
+            trailing_slash: TrailingSlash::default(),
+            admin_allowlist: AdminIpAllowlist::default(),
+            allow_reset: false,
+            log_max_future_skew: chrono::Duration::seconds(DEFAULT_MAX_FUTURE_SKEW_SECS),
+            log_sampling: LogSampling::default(),
+            log_compression_threshold: None,
+            db_statement_timeout_ms: 0,
+            ws_max_connections: DEFAULT_WS_MAX_CONNECTIONS,
+            slow_request_threshold: std::time::Duration::from_millis(
+                DEFAULT_SLOW_REQUEST_THRESHOLD_MS,
+            ),
+            slow_request_buffer_size: DEFAULT_SLOW_REQUEST_BUFFER_SIZE,
+            error_body_format: ErrorBodyFormat::default(),
+        }
+    }
+
+    /// Takes every setting that affects the state from `config`.
+    pub fn with_config(self, config: &Config) -> Self {
+        Self {
+            broadcast_channel_size: config.broadcast_channel_size,
+            page_limits: config.page_limits,
+            schema_naming_policy: config.schema_naming_policy.clone(),
+            schema_complexity_limits: config.schema_complexity_limits,
+            schema_compat_sample_size: config.schema_compat_sample_size,
+            schema_inference_field: config.schema_inference_field.clone(),
+            max_schemas: config.max_schemas,
+            api_key_format: config.api_key_format.clone(),
+            api_key_hash_algorithm: config.api_key_hash_algorithm,
+            api_key_cache_ttl: std::time::Duration::from_secs(config.api_key_cache_ttl_secs),
+            max_api_keys: config.max_api_keys,
+            cors_allowed_origins: config.cors_allowed_origins.clone(),
+            unknown_query_params: config.unknown_query_params,
+            trailing_slash: config.trailing_slash,
+            admin_allowlist: AdminIpAllowlist::new(
+                config.admin_allowed_ips.clone(),
+                config.trusted_proxies.clone(),
+            ),
+            allow_reset: config.allow_reset,
+            log_max_future_skew: chrono::Duration::seconds(config.log_max_future_skew_secs),
+            log_sampling: config.log_sampling,
+            log_compression_threshold: config.log_compression_threshold_bytes,
+            db_statement_timeout_ms: config.db_statement_timeout_ms,
+            ws_max_connections: config.ws_max_connections,
+            slow_request_threshold: std::time::Duration::from_millis(
+                config.slow_request_threshold_ms,
+            ),
+            slow_request_buffer_size: config.slow_request_buffer_size,
+            error_body_format: config.error_body_format.clone(),
+            ..self
+        }
+    }
+
+    pub fn with_broadcast_channel_size(mut self, size: usize) -> Self {
+        self.broadcast_channel_size = size;
+        self
+    }
+
+    pub fn with_page_limits(mut self, page_limits: PageLimits) -> Self {
+        self.page_limits = page_limits;
+        self
+    }
+
+    pub fn with_log_compression_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.log_compression_threshold = threshold;
+        self
+    }
+
+    pub fn with_db_statement_timeout(mut self, timeout_ms: u64) -> Self {
+        self.db_statement_timeout_ms = timeout_ms;
+        self
+    }
+
+    pub fn with_log_sampling(mut self, sampling: LogSampling) -> Self {
+        self.log_sampling = sampling;
+        self
+    }
+
+    pub fn with_schema_complexity_limits(mut self, limits: SchemaComplexityLimits) -> Self {
+        self.schema_complexity_limits = limits;
+        self
+    }
+
+    pub fn with_schema_compat_sample_size(mut self, size: i32) -> Self {
+        self.schema_compat_sample_size = size;
+        self
+    }
+
+    pub fn with_schema_inference_field(mut self, field: Option<String>) -> Self {
+        self.schema_inference_field = field;
+        self
+    }
+
+    pub fn with_max_schemas(mut self, max: Option<u64>) -> Self {
+        self.max_schemas = max;
+        self
+    }
+
+    pub fn with_max_api_keys(mut self, max: Option<u64>) -> Self {
+        self.max_api_keys = max;
+        self
+    }
+
+    pub fn with_unknown_query_params(mut self, unknown_query_params: UnknownQueryParams) -> Self {
+        self.unknown_query_params = unknown_query_params;
+        self
+    }
+
+    pub fn with_trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    pub fn with_admin_allowlist(mut self, allowlist: AdminIpAllowlist) -> Self {
+        self.admin_allowlist = allowlist;
+        self
+    }
+
+    pub fn with_allow_reset(mut self, allow_reset: bool) -> Self {
+        self.allow_reset = allow_reset;
+        self
+    }
+
+    pub fn with_error_body_format(mut self, format: ErrorBodyFormat) -> Self {
+        self.error_body_format = format;
+        self
+    }
+
+    pub fn with_ws_max_connections(mut self, max: usize) -> Self {
+        self.ws_max_connections = max;
+        self
+    }
+
+    /// Requests taking at least `threshold` are kept, up to `buffer_size`.
+    pub fn with_slow_requests(
+        mut self,
+        threshold: std::time::Duration,
+        buffer_size: usize,
+    ) -> Self {
+        self.slow_request_threshold = threshold;
+        self.slow_request_buffer_size = buffer_size;
+        self
+    }
+
+    pub fn with_api_key_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.api_key_cache_ttl = ttl;
+        self
+    }
+
+    pub fn build(self) -> AppState {
+        let schema_repository = Arc::new(SchemaRepository::new(self.pool.clone()));
+        let log_repository = Arc::new(
+            LogRepository::new(self.pool.clone())
+                .with_compression_threshold(self.log_compression_threshold)
+                .with_statement_timeout(self.db_statement_timeout_ms),
+        );
+        let api_key_repository = Arc::new(ApiKeyRepository::new(self.pool));
+
+        let schema_service = Arc::new(
+            SchemaService::new(schema_repository, log_repository.clone())
+                .with_naming_policy(self.schema_naming_policy)
+                .with_complexity_limits(self.schema_complexity_limits)
+                .with_compat_sample_size(self.schema_compat_sample_size)
+                .with_inference_field(self.schema_inference_field)
+                .with_max_schemas(self.max_schemas),
+        );
+        let log_service = Arc::new(
+            LogService::new(log_repository, schema_service.clone())
+                .with_max_future_skew(self.log_max_future_skew)
+                .with_sampling(self.log_sampling),
+        );
+        let api_key_service = Arc::new(
+            ApiKeyService::new(api_key_repository)
+                .with_key_format(self.api_key_format)
+                .with_hash_algorithm(self.api_key_hash_algorithm)
+                .with_cache_ttl(self.api_key_cache_ttl)
+                .with_max_keys(self.max_api_keys),
+        );
+        let webhook_service = Arc::new(WebhookService::new());
+
+        let (log_broadcast, _) = broadcast::channel(self.broadcast_channel_size);
+
+        AppState {
+            cors_allowed_origins: self.cors_allowed_origins,
+            unknown_query_params: self.unknown_query_params,
+            trailing_slash: self.trailing_slash,
+            admin_allowlist: Arc::new(self.admin_allowlist),
+            allow_reset: self.allow_reset,
+            ws_connections: WsConnectionLimit::new(self.ws_max_connections),
+            slow_requests: SlowRequestLog::new(
+                self.slow_request_threshold,
+                self.slow_request_buffer_size,
+            ),
+            error_body_format: self.error_body_format,
+            ..AppState::new(
+                schema_service,
+                log_service,
+                api_key_service,
+                webhook_service,
+                log_broadcast,
+                Arc::new(middleware::RateLimiter::new()),
+                self.page_limits,
+            )
         }
     }
 }
@@ -80,18 +405,43 @@ async fn health_check() -> Result<Json<serde_json::Value>, StatusCode> {
     })))
 }
 
+async fn version() -> Json<serde_json::Value> {
+    Json(json!({
+        "version": SERVER_VERSION,
+        "git_sha": GIT_SHA,
+        "built_at": BUILT_AT
+    }))
+}
+
+/// Routing happens before any `Router::layer` middleware runs, so paths are
+/// normalized by a service wrapping the whole router.
+fn with_trailing_slash(router: Router, trailing_slash: TrailingSlash) -> Router {
+    match trailing_slash {
+        TrailingSlash::Strict => router,
+        TrailingSlash::Trim => {
+            Router::new().fallback_service(NormalizePathLayer::trim_trailing_slash().layer(router))
+        }
+    }
+}
+
 pub fn create_app(app_state: AppState, _pool: PgPool) -> Router {
     let public_routes = Router::new()
         .route("/", get(health_check))
-        .route("/health", get(health_check));
+        .route("/health", get(health_check))
+        .route("/version", get(version));
 
     let schema_routes = Router::new()
-        .route("/schemas", get(get_schemas))
-        .route("/schemas", post(create_schema))
+        .route("/schemas", get(get_schemas).head(head_schemas))
         .route("/schemas/{id}", get(get_schema_by_id))
-        .route("/schemas/{id}", put(update_schema))
-        .route("/schemas/{id}", delete(delete_schema))
+        .route("/schemas/{id}/definition", get(get_schema_definition))
+        .route(
+            "/schemas/{id}/compatible-versions",
+            get(get_compatible_versions),
+        )
+        .route("/schemas/{id}/logs/count-by-day", get(get_daily_log_counts))
         .route("/schemas/cursor/initial", get(get_schemas_initial_cursor))
+        .route("/schemas/batch-get", post(batch_get_schemas))
+        .route("/schemas/{id}/validate", post(validate_log_data))
         .route(
             "/schemas/by-name/{schema_name}/latest",
             get(get_schema_by_name_latest),
@@ -99,19 +449,28 @@ pub fn create_app(app_state: AppState, _pool: PgPool) -> Router {
         .route(
             "/schemas/by-name/{schema_name}/versions/{schema_version}",
             get(get_schema_by_name_and_version),
+        )
+        .route(
+            "/schemas/by-name/{schema_name}/versions/{schema_version}/definition",
+            get(get_schema_definition_by_name_and_version),
         );
 
     let log_routes = Router::new()
-        .route("/logs", post(create_log))
-        // .route("/logs/bulk", post(create_logs_bulk))
         .route("/logs/{id}", get(get_log_by_id))
-        .route("/logs/{id}", delete(delete_log))
-        .route("/logs/schemas/{schema_id}", get(get_logs))
+        .route("/logs/counts-by-schema", get(get_log_counts_by_schema))
+        .route("/logs/schemas/{schema_id}", get(get_logs).head(head_logs))
         .route("/logs/schemas/{schema_id}", post(get_logs_query))
+        .route("/logs/schemas/{schema_id}/query", post(filter_logs))
         .route(
             "/logs/schemas/{schema_id}/cursor/initial",
             get(get_initial_cursor),
         )
+        .route(
+            "/logs/schemas/{schema_id}/counts/level",
+            get(get_level_counts),
+        )
+        .route("/logs/schemas/{schema_id}/export", get(export_logs))
+        .route("/logs/schemas/{schema_id}/stream", get(stream_logs))
         .route(
             "/logs/by-schema-name/{name}/latest",
             get(get_logs_by_schema_name_latest),
@@ -127,34 +486,88 @@ pub fn create_app(app_state: AppState, _pool: PgPool) -> Router {
         .route(
             "/logs/by-schema-name/{name}/versions/{version}",
             post(get_logs_by_schema_name_and_version_query),
+        )
+        .route(
+            "/logs/by-schema-name//latest",
+            get(reject_empty_schema_name).post(reject_empty_schema_name),
+        )
+        .route(
+            "/logs/by-schema-name//versions/{version}",
+            get(reject_empty_schema_name).post(reject_empty_schema_name),
+        )
+        .route(
+            "/logs/by-schema-name/{name}/versions/",
+            get(reject_empty_schema_version).post(reject_empty_schema_version),
         );
 
+    let write_routes = Router::new()
+        .route("/schemas", post(create_schema))
+        .route("/schemas/{id}", put(update_schema))
+        .route("/schemas/{id}", patch(patch_schema))
+        .route("/schemas/{id}", delete(delete_schema))
+        .route("/logs", post(create_log))
+        .route("/logs/bulk", post(create_logs_bulk))
+        .route("/logs/ingest", post(ingest_logs))
+        .route("/logs/action", post(log_action))
+        .route("/logs/{id}", patch(patch_log))
+        .route("/logs/{id}", delete(delete_log))
+        .route(
+            "/logs/schemas/{schema_id}/by-key/{key}",
+            put(put_log_by_key),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            maintenance_middleware,
+        ));
+
     let ws_routes = Router::new().route("/ws/logs", get(ws_handler));
 
     let protected_routes = Router::new()
         .merge(schema_routes)
         .merge(log_routes)
+        .merge(write_routes)
         .merge(ws_routes)
         .layer(axum_middleware::from_fn_with_state(
             app_state.clone(),
             api_key_middleware,
         ));
 
-    Router::new()
+    let cors = cors_layer(&app_state.cors_allowed_origins);
+    let unknown_query_params = app_state.unknown_query_params;
+    let trailing_slash = app_state.trailing_slash;
+    let slow_requests = app_state.slow_requests.clone();
+    let error_body_format = app_state.error_body_format.clone();
+
+    let router = Router::new()
         .merge(public_routes)
         .merge(protected_routes)
         .with_state(app_state)
         .layer(
             ServiceBuilder::new()
+                .layer(axum_middleware::from_fn_with_state(
+                    error_body_format,
+                    error_body_middleware,
+                ))
+                .layer(Extension(unknown_query_params))
                 .layer(axum_middleware::from_fn(RequestIdLayer::middleware))
+                .layer(axum_middleware::from_fn_with_state(
+                    slow_requests,
+                    slow_request_middleware,
+                ))
                 .layer(TraceLayer::new_for_http().make_span_with(RequestIdMakeSpan))
-                .layer(CorsLayer::permissive()),
-        )
+                .layer(axum_middleware::from_fn(preflight_no_content))
+                .layer(axum_middleware::from_fn(server_version_header))
+                .layer(cors),
+        );
+
+    with_trailing_slash(router, trailing_slash)
 }
 
 pub fn create_admin_app(app_state: AppState) -> Router {
     use crate::handlers::{
-        create_api_key, delete_api_key, get_api_key_by_id, get_api_keys, rotate_api_key,
+        add_allowed_ip, batch_delete_api_keys, create_api_key, delete_api_key, get_api_key_by_id,
+        get_api_key_stats, get_api_keys, get_slow_requests, remove_allowed_ip, replay_log,
+        reset_data, rotate_api_key, rotate_expiring_api_keys, set_maintenance,
     };
 
     let admin_health_check = || async {
@@ -165,19 +578,48 @@ pub fn create_admin_app(app_state: AppState) -> Router {
         }))
     };
 
-    Router::new()
+    let cors = cors_layer(&app_state.cors_allowed_origins);
+    let allowlist = app_state.admin_allowlist.clone();
+    let trailing_slash = app_state.trailing_slash;
+    let error_body_format = app_state.error_body_format.clone();
+
+    let router = Router::new()
         .route("/", get(admin_health_check))
         .route("/health", get(admin_health_check))
+        .route("/admin/maintenance", post(set_maintenance))
+        .route("/admin/reset", post(reset_data))
+        .route("/admin/slow-requests", get(get_slow_requests))
+        .route("/logs/{id}/replay", post(replay_log))
         .route("/api-keys", post(create_api_key))
         .route("/api-keys", get(get_api_keys))
+        .route("/api-keys/batch-delete", post(batch_delete_api_keys))
+        .route("/api-keys/rotate-expiring", post(rotate_expiring_api_keys))
+        .route("/api-keys/stats", get(get_api_key_stats))
         .route("/api-keys/{key_id}", get(get_api_key_by_id))
         .route("/api-keys/{key_id}", delete(delete_api_key))
         .route("/api-keys/{key_id}/rotate", post(rotate_api_key))
+        .route("/api-keys/{key_id}/allowed-ips", post(add_allowed_ip))
+        .route(
+            "/api-keys/{key_id}/allowed-ips/{cidr}",
+            delete(remove_allowed_ip),
+        )
         .with_state(app_state)
         .layer(
             ServiceBuilder::new()
+                .layer(axum_middleware::from_fn_with_state(
+                    error_body_format,
+                    error_body_middleware,
+                ))
                 .layer(axum_middleware::from_fn(RequestIdLayer::middleware))
                 .layer(TraceLayer::new_for_http().make_span_with(RequestIdMakeSpan))
-                .layer(CorsLayer::permissive()),
-        )
+                .layer(axum_middleware::from_fn(preflight_no_content))
+                .layer(axum_middleware::from_fn(server_version_header))
+                .layer(cors)
+                .layer(axum_middleware::from_fn_with_state(
+                    allowlist,
+                    admin_ip_allowlist_middleware,
+                )),
+        );
+
+    with_trailing_slash(router, trailing_slash)
 }
@@ -1,11 +1,83 @@
-use crab_pot::{
-    create_admin_app, create_app, middleware::RateLimiter, ApiKeyRepository, ApiKeyService,
-    AppState, Config, LogRepository, LogService, SchemaRepository, SchemaService,
-};
+use crab_pot::{create_admin_app, create_app, AppState, Config};
+use sqlx::PgPool;
+use std::env;
 use std::net::SocketAddr;
-use std::{env, sync::Arc};
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+/// Waits for Ctrl+C or SIGTERM, then cancels `shutdown` so both servers stop
+/// accepting connections and open WebSockets are closed with 1001.
+async fn cancel_on_signal(shutdown: CancellationToken) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown signal received, draining connections...");
+    shutdown.cancel();
+}
+
+async fn connect_with_retry(config: &Config) -> anyhow::Result<PgPool> {
+    let max_attempts = config.db_connect_max_attempts.max(1);
+    let max_backoff = Duration::from_secs(config.db_connect_max_backoff_secs);
+    let mut backoff = Duration::from_millis(500);
+    let mut attempt = 1;
+
+    loop {
+        tracing::info!(
+            "Connecting to database (attempt {}/{})...",
+            attempt,
+            max_attempts
+        );
+
+        let result = PgPool::connect(&config.database_url).await;
+
+        match result {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt < max_attempts => {
+                tracing::warn!(
+                    "Database connection failed: {}. Retrying in {:?}",
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(max_backoff);
+                attempt += 1;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Giving up on database connection after {} attempts",
+                    attempt
+                );
+                return Err(e.into());
+            }
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -23,34 +95,38 @@ async fn main() -> anyhow::Result<()> {
         .with_span_events(FmtSpan::CLOSE)
         .init();
 
-    let pool = sqlx::postgres::PgPool::connect(&config.database_url).await?;
+    crab_pot::dto::timestamp::set_timestamp_precision(config.timestamp_precision);
+
+    let pool = connect_with_retry(&config).await?;
     tracing::info!("✅ Database connected successfully!");
 
-    let schema_repository = Arc::new(SchemaRepository::new(pool.clone()));
-    let log_repository = Arc::new(LogRepository::new(pool.clone()));
-    let api_key_repository = Arc::new(ApiKeyRepository::new(pool.clone()));
-
-    let schema_service = Arc::new(SchemaService::new(
-        schema_repository.clone(),
-        log_repository.clone(),
-    ));
-    let log_service = Arc::new(LogService::new(
-        log_repository.clone(),
-        schema_service.clone(),
-    ));
-    let api_key_service = Arc::new(ApiKeyService::new(api_key_repository.clone()));
-
-    let (log_broadcast_tx, _) = broadcast::channel(config.broadcast_channel_size);
-
-    let rate_limiter = Arc::new(RateLimiter::new());
-
-    let app_state = AppState {
-        schema_service,
-        log_service,
-        api_key_service,
-        log_broadcast: log_broadcast_tx,
-        rate_limiter,
-    };
+    let app_state = AppState::builder(pool.clone()).with_config(&config).build();
+
+    let summary_log_service = app_state.log_service.clone();
+    let summary_refresh_interval = Duration::from_secs(config.log_summary_refresh_secs.max(1));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(summary_refresh_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = summary_log_service.refresh_level_counts_summary().await {
+                tracing::warn!("{}", e);
+            }
+        }
+    });
+
+    let retention_log_service = app_state.log_service.clone();
+    let retention_sweep_interval = Duration::from_secs(config.log_retention_sweep_secs.max(1));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(retention_sweep_interval);
+        loop {
+            interval.tick().await;
+            match retention_log_service.enforce_retention().await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Retention sweep deleted {} logs", deleted),
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+    });
 
     let app = create_app(app_state.clone(), pool);
     let admin_app = create_admin_app(app_state.clone());
@@ -64,13 +140,18 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("  GET, POST         /schemas");
     tracing::info!("  GET               /schemas/cursor/initial");
     tracing::info!("  GET, PUT, DELETE  /schemas/{{id}}");
+    tracing::info!("  GET               /schemas/{{id}}/definition");
+    tracing::info!("  GET               /schemas/{{id}}/logs/count-by-day");
     tracing::info!("  GET               /schemas/by-name/{{name}}/latest");
     tracing::info!("  GET               /schemas/by-name/{{name}}/versions/{{version}}");
+    tracing::info!("  GET               /schemas/by-name/{{name}}/versions/{{version}}/definition");
     tracing::info!("Logs:");
     tracing::info!("  POST         /logs");
+    tracing::info!("  POST         /logs/action");
     tracing::info!("  GET, DELETE  /logs/{{id}}");
     tracing::info!("  GET, POST    /logs/schemas/{{schema_id}}");
     tracing::info!("  GET          /logs/schemas/{{schema_id}}/cursor/initial");
+    tracing::info!("  GET          /logs/schemas/{{schema_id}}/counts/level");
     tracing::info!("  GET, POST    /logs/by-schema-name/{{name}}/latest");
     tracing::info!("  GET, POST    /logs/by-schema-name/{{name}}/versions/{{version}}");
     tracing::info!("WebSocket:");
@@ -97,21 +178,73 @@ async fn main() -> anyhow::Result<()> {
     let main_listener = TcpListener::bind(config.main_api_addr).await?;
     let admin_listener = TcpListener::bind(config.admin_api_addr).await?;
 
+    let shutdown = app_state.shutdown.clone();
+    tokio::spawn(cancel_on_signal(shutdown.clone()));
+
+    let admin_shutdown = shutdown.clone();
     let admin_server = tokio::spawn(async move {
         tracing::info!("Starting Admin API server...");
-        if let Err(e) = axum::serve(admin_listener, admin_app).await {
+        if let Err(e) = axum::serve(
+            admin_listener,
+            admin_app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(admin_shutdown.cancelled_owned())
+        .await
+        {
             tracing::error!("Admin API server error: {}", e);
         }
     });
 
+    #[cfg(feature = "grpc")]
+    let grpc_server = {
+        let grpc_addr = config.grpc_api_addr;
+        let grpc_service = crab_pot::grpc::LogIngestService::new(app_state.clone()).into_server();
+        let grpc_shutdown = shutdown.clone();
+        tracing::info!("📡 gRPC ingestion running at {}", grpc_addr);
+        tokio::spawn(async move {
+            if let Err(e) = tonic::transport::Server::builder()
+                .add_service(grpc_service)
+                .serve_with_shutdown(grpc_addr, grpc_shutdown.cancelled_owned())
+                .await
+            {
+                tracing::error!("gRPC server error: {}", e);
+            }
+        })
+    };
+
     tracing::info!("Starting Main API server...");
-    let main_result = axum::serve(
+    let main_server = axum::serve(
         main_listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
-    .await;
-
-    let _ = tokio::join!(admin_server);
+    .with_graceful_shutdown(shutdown.clone().cancelled_owned());
+    let servers = async {
+        let main_result = main_server.await;
+        let _ = admin_server.await;
+        #[cfg(feature = "grpc")]
+        let _ = grpc_server.await;
+        main_result
+    };
+    tokio::pin!(servers);
+
+    // A client that never disconnects (e.g. a long export or an SSE stream
+    // behind a proxy) must not keep the process alive past the drain timeout.
+    let drain_timeout = Duration::from_secs(config.shutdown_timeout_secs);
+    let main_result = tokio::select! {
+        result = &mut servers => result,
+        _ = shutdown.cancelled() => {
+            match tokio::time::timeout(drain_timeout, &mut servers).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!(
+                        "Connections still open after {}s, shutting down anyway",
+                        drain_timeout.as_secs()
+                    );
+                    Ok(())
+                }
+            }
+        }
+    };
 
     main_result?;
     Ok(())
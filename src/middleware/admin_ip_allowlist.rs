@@ -0,0 +1,108 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::types::ipnetwork::IpNetwork;
+
+use crate::{middleware::RequestId, AppError, ErrorCode};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Source ranges allowed to reach the admin app. An empty allowlist lets
+/// every client through.
+#[derive(Debug, Clone, Default)]
+pub struct AdminIpAllowlist {
+    allowed: Vec<IpNetwork>,
+    trusted_proxies: Vec<IpNetwork>,
+}
+
+fn contains(networks: &[IpNetwork], ip: IpAddr) -> bool {
+    networks.iter().any(|network| network.contains(ip))
+}
+
+impl AdminIpAllowlist {
+    pub fn new(allowed: Vec<IpNetwork>, trusted_proxies: Vec<IpNetwork>) -> Self {
+        Self {
+            allowed,
+            trusted_proxies,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_empty()
+    }
+
+    /// The peer address, unless the peer is a trusted proxy: then the
+    /// right-most `X-Forwarded-For` entry that is not a trusted proxy itself.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = peer.to_canonical();
+        if !contains(&self.trusted_proxies, client) {
+            return client;
+        }
+
+        let forwarded: Vec<&str> = headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+
+        for entry in forwarded.into_iter().rev() {
+            let Ok(ip) = entry.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = ip.to_canonical();
+            if !contains(&self.trusted_proxies, client) {
+                break;
+            }
+        }
+
+        client
+    }
+
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        self.is_empty() || contains(&self.allowed, ip)
+    }
+}
+
+/// Rejects admin requests whose client IP is outside the allowlist with 403
+/// `IP_NOT_ALLOWED`. Needs the server to provide `ConnectInfo`; without it a
+/// non-empty allowlist rejects everything.
+pub async fn admin_ip_allowlist_middleware(
+    State(allowlist): State<Arc<AdminIpAllowlist>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if allowlist.is_empty() {
+        return next.run(request).await;
+    }
+
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| allowlist.client_ip(addr.ip(), request.headers()));
+
+    if client_ip.is_some_and(|ip| allowlist.allows(ip)) {
+        return next.run(request).await;
+    }
+
+    tracing::warn!(
+        "Rejected admin request from {}",
+        client_ip.map_or_else(|| "an unknown address".to_string(), |ip| ip.to_string())
+    );
+
+    let mut error = AppError::forbidden("Client IP is not allowed to use the admin API")
+        .with_code(ErrorCode::IpNotAllowed);
+    if let Some(request_id) = request.extensions().get::<RequestId>() {
+        error = error.with_request_id(request_id);
+    }
+
+    error.into_response()
+}
@@ -1,4 +1,7 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 
 use axum::{
     extract::{ConnectInfo, Request, State},
@@ -10,86 +13,148 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
-use crate::{services::api_key_service::ApiKeyService, AppState};
+use crate::{middleware::rate_limiter::RateLimitError, models::ApiKey, AppError, AppState};
+
+/// Id of the API key that authenticated the current request.
+#[derive(Clone, Copy, Debug)]
+pub struct ApiKeyId(pub i32);
+
+/// Tenant of the API key that authenticated the current request. Schemas and
+/// logs outside this tenant are invisible to the request.
+#[derive(Clone, Debug)]
+pub struct TenantId(pub String);
+
+/// Why [`authorize_api_key`] turned a caller away.
+#[derive(Debug)]
+pub enum ApiKeyRejection {
+    /// No key, or one that is unknown, revoked or expired.
+    Unauthorized,
+    /// The key does not allow the caller's IP.
+    IpNotAllowed,
+    RateLimited(RateLimitError),
+    Internal(AppError),
+}
 
-pub async fn api_key_middleware(
-    State(app_state): State<AppState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    mut request: Request,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    let auth_header = request
-        .headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+/// Requests per second and burst size allowed to `api_key`.
+fn rate_limits(api_key: &ApiKey) -> (u32, u32) {
+    let per_second = api_key.rate_limit_per_second.unwrap_or(10) as u32;
+    let burst = api_key
+        .rate_limit_burst
+        .map(|b| b as u32)
+        .unwrap_or(per_second * 2);
+    (per_second, burst)
+}
 
-    let plain_key = auth_header
-        .strip_prefix("Bearer ")
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+/// Takes one request from `api_key`'s rate limit.
+pub fn check_rate_limit(app_state: &AppState, api_key: &ApiKey) -> Result<(), RateLimitError> {
+    let (per_second, burst) = rate_limits(api_key);
+    app_state
+        .rate_limiter
+        .check_rate_limit(&api_key.key_hash, per_second, burst)
+        .inspect_err(|err| {
+            tracing::warn!(
+                "Rate limit exceeded for key: {} - {}",
+                api_key.display_key(),
+                err
+            );
+        })
+}
 
-    let key_hash = ApiKeyService::hash_key(plain_key);
+/// Authenticates `plain_key` for a caller at `client_ip`, checks the key's
+/// allowed IPs and takes one request from its rate limit, then records the
+/// key's usage. An unknown `client_ip` only passes keys without an IP
+/// allowlist. Shared by the HTTP middleware and the gRPC service.
+pub async fn authorize_api_key(
+    app_state: &AppState,
+    plain_key: Option<&str>,
+    client_ip: Option<IpAddr>,
+) -> Result<ApiKey, ApiKeyRejection> {
+    let plain_key = plain_key.ok_or(ApiKeyRejection::Unauthorized)?;
 
     let api_key = app_state
         .api_key_service
-        .find_valid_by_hash(&key_hash)
+        .authenticate(plain_key)
         .await
         .map_err(|e| {
             if e.is_not_found() {
-                StatusCode::UNAUTHORIZED
+                ApiKeyRejection::Unauthorized
             } else {
-                StatusCode::INTERNAL_SERVER_ERROR
+                ApiKeyRejection::Internal(e)
             }
         })?;
 
-    let client_ip = addr.ip();
-    if !api_key.is_ip_allowed(&client_ip) {
-        return Err(StatusCode::FORBIDDEN);
-    }
-
-    let rate_limit_per_second = api_key.rate_limit_per_second.unwrap_or(10) as u32;
-    let rate_limit_burst = api_key
-        .rate_limit_burst
-        .map(|b| b as u32)
-        .unwrap_or(rate_limit_per_second * 2);
-
-    if let Err(err) =
-        app_state
-            .rate_limiter
-            .check_rate_limit(&key_hash, rate_limit_per_second, rate_limit_burst)
-    {
-        tracing::warn!(
-            "Rate limit exceeded for key: {} - {}",
-            api_key.display_key(),
-            err
-        );
-
-        let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
-        let headers = response.headers_mut();
-        headers.insert(
-            HeaderName::from_static("x-ratelimit-limit"),
-            HeaderValue::from(err.limit),
-        );
-        headers.insert(
-            HeaderName::from_static("x-ratelimit-remaining"),
-            HeaderValue::from(err.remaining),
-        );
-        headers.insert(
-            HeaderName::from_static("retry-after"),
-            HeaderValue::from(err.retry_after),
-        );
-        return Ok(response);
+    let ip_allowed = match client_ip {
+        Some(ip) => api_key.is_ip_allowed(&ip),
+        None => api_key.allowed_ips.as_ref().is_none_or(Vec::is_empty),
+    };
+    if !ip_allowed {
+        return Err(ApiKeyRejection::IpNotAllowed);
     }
 
-    let rate_limit_status =
-        app_state
-            .rate_limiter
-            .get_status(&key_hash, rate_limit_per_second, rate_limit_burst);
+    check_rate_limit(app_state, &api_key).map_err(ApiKeyRejection::RateLimited)?;
 
+    let api_key_service = app_state.api_key_service.clone();
+    let key_hash = api_key.key_hash.clone();
     tokio::spawn(async move {
-        let _ = app_state.api_key_service.update_usage(&key_hash).await;
+        let _ = api_key_service.update_usage(&key_hash).await;
     });
 
+    Ok(api_key)
+}
+
+pub async fn api_key_middleware(
+    State(app_state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let plain_key = request
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    let api_key = match authorize_api_key(&app_state, plain_key, Some(addr.ip())).await {
+        Ok(api_key) => api_key,
+        Err(ApiKeyRejection::Unauthorized) => return Err(StatusCode::UNAUTHORIZED),
+        Err(ApiKeyRejection::IpNotAllowed) => return Err(StatusCode::FORBIDDEN),
+        Err(ApiKeyRejection::Internal(_)) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(ApiKeyRejection::RateLimited(err)) => {
+            let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+            let headers = response.headers_mut();
+            headers.insert(
+                HeaderName::from_static("x-ratelimit-limit"),
+                HeaderValue::from(err.limit),
+            );
+            headers.insert(
+                HeaderName::from_static("x-ratelimit-remaining"),
+                HeaderValue::from(err.remaining),
+            );
+
+            let jitter_ms = err.retry_jitter_ms();
+            headers.insert(
+                HeaderName::from_static("retry-after"),
+                HeaderValue::from(err.retry_after_with_jitter(jitter_ms)),
+            );
+            headers.insert(
+                HeaderName::from_static("x-ratelimit-retry-jitter"),
+                HeaderValue::from(jitter_ms),
+            );
+            return Ok(response);
+        }
+    };
+
+    let (rate_limit_per_second, rate_limit_burst) = rate_limits(&api_key);
+    let rate_limit_status = app_state.rate_limiter.get_status(
+        &api_key.key_hash,
+        rate_limit_per_second,
+        rate_limit_burst,
+    );
+
+    request.extensions_mut().insert(ApiKeyId(api_key.id));
+    request
+        .extensions_mut()
+        .insert(TenantId(api_key.tenant_id.clone()));
     request.extensions_mut().insert(Arc::new(api_key));
 
     let mut response = next.run(request).await;
@@ -0,0 +1,35 @@
+use axum::{
+    extract::Request,
+    http::{HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// CORS for both APIs. Without configured origins any origin is allowed;
+/// otherwise only the listed ones are echoed back.
+pub fn cors_layer(allowed_origins: &[HeaderValue]) -> CorsLayer {
+    if allowed_origins.is_empty() {
+        return CorsLayer::permissive();
+    }
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(allowed_origins.iter().cloned()))
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers(Any)
+}
+
+/// `CorsLayer` answers every `OPTIONS` request itself, before routing and the
+/// API key check, but with 200; preflights are reported as 204 No Content.
+/// Must be layered outside the `CorsLayer`.
+pub async fn preflight_no_content(request: Request, next: Next) -> Response {
+    let is_preflight = request.method() == Method::OPTIONS;
+    let mut response = next.run(request).await;
+
+    if is_preflight && response.status() == StatusCode::OK {
+        *response.status_mut() = StatusCode::NO_CONTENT;
+    }
+
+    response
+}
@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Request, State},
+    http::header::CONTENT_LENGTH,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::{AppError, ErrorBodyFormat};
+
+/// Renders error bodies produced by [`AppError`] in `format`, keeping the
+/// response's status and headers. Other responses, and every response under
+/// the default format, pass through untouched. Must wrap every layer that
+/// can reject a request.
+pub async fn error_body_middleware(
+    State(format): State<ErrorBodyFormat>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    if format == ErrorBodyFormat::default() {
+        return response;
+    }
+
+    let Some(error) = response.extensions().get::<AppError>().cloned() else {
+        return response;
+    };
+
+    let (mut parts, _) = response.into_parts();
+    let (_, body) = error.into_response_with(&format).into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, body)
+}
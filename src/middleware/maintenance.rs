@@ -0,0 +1,37 @@
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{middleware::RequestId, AppError, AppState, ErrorCode};
+
+/// 503 `MAINTENANCE`, returned for writes while maintenance mode is enabled.
+pub fn maintenance_error() -> AppError {
+    AppError::service_unavailable(
+        "The server is in maintenance mode; write operations are temporarily disabled",
+    )
+    .with_code(ErrorCode::Maintenance)
+}
+
+/// Rejects the wrapped routes with 503 `MAINTENANCE` while maintenance mode is
+/// enabled. Only layered on write routes so reads stay available.
+pub async fn maintenance_middleware(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if app_state.maintenance.load(Ordering::Relaxed) {
+        let mut error = maintenance_error();
+
+        if let Some(request_id) = request.extensions().get::<RequestId>() {
+            error = error.with_request_id(request_id);
+        }
+
+        return error.into_response();
+    }
+
+    next.run(request).await
+}
@@ -1,7 +1,23 @@
+pub mod admin_ip_allowlist;
 pub mod api_key;
+pub mod cors;
+pub mod error_body;
+pub mod maintenance;
 pub mod rate_limiter;
 pub mod request_id;
+pub mod server_version;
+pub mod slow_requests;
+pub mod trace_context;
 
-pub use api_key::api_key_middleware;
+pub use admin_ip_allowlist::{admin_ip_allowlist_middleware, AdminIpAllowlist};
+pub use api_key::{
+    api_key_middleware, authorize_api_key, check_rate_limit, ApiKeyId, ApiKeyRejection, TenantId,
+};
+pub use cors::{cors_layer, preflight_no_content};
+pub use error_body::error_body_middleware;
+pub use maintenance::{maintenance_error, maintenance_middleware};
 pub use rate_limiter::RateLimiter;
 pub use request_id::{RequestId, RequestIdLayer, RequestIdMakeSpan};
+pub use server_version::server_version_header;
+pub use slow_requests::{slow_request_middleware, SlowRequest, SlowRequestLog};
+pub use trace_context::TraceContext;
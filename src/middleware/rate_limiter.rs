@@ -1,7 +1,12 @@
 use dashmap::DashMap;
+use rand::Rng;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+/// Upper bound for the random delay added on top of the window reset when
+/// suggesting a retry time, so that throttled clients don't retry in lockstep.
+pub const MAX_RETRY_JITTER_MS: u64 = 1000;
+
 #[derive(Clone)]
 pub struct RateLimiter {
     // key_hash -> (request_count, window_start, last_cleanup)
@@ -55,11 +60,10 @@ impl RateLimiter {
         }
 
         if entry.count >= burst_size {
-            let retry_after = window
-                .saturating_sub(now.duration_since(entry.window_start))
-                .as_secs();
+            let reset_in = window.saturating_sub(now.duration_since(entry.window_start));
             return Err(RateLimitError {
-                retry_after,
+                retry_after: reset_in.as_secs_f64().ceil() as u64,
+                reset_in_ms: reset_in.as_millis() as u64,
                 limit: max_per_second,
                 remaining: 0,
             });
@@ -124,10 +128,24 @@ impl Default for RateLimiter {
 #[derive(Debug, Clone)]
 pub struct RateLimitError {
     pub retry_after: u64,
+    pub reset_in_ms: u64,
     pub limit: u32,
     pub remaining: u32,
 }
 
+impl RateLimitError {
+    /// Picks a random jitter in `0..=MAX_RETRY_JITTER_MS` milliseconds.
+    pub fn retry_jitter_ms(&self) -> u64 {
+        rand::rng().random_range(0..=MAX_RETRY_JITTER_MS)
+    }
+
+    /// Seconds to wait before retrying, rounded up so the hint never lands
+    /// before the window resets, plus the given jitter.
+    pub fn retry_after_with_jitter(&self, jitter_ms: u64) -> u64 {
+        (self.reset_in_ms + jitter_ms).div_ceil(1000).max(1)
+    }
+}
+
 impl std::fmt::Display for RateLimitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1,8 +1,10 @@
 use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
 use tower_http::trace::MakeSpan;
-use tracing::Span;
+use tracing::{field, Span};
 use uuid::Uuid;
 
+use super::TraceContext;
+
 pub const REQUEST_ID_HEADER: &str = "X-Request-ID";
 
 #[derive(Clone, Debug)]
@@ -47,6 +49,9 @@ impl RequestIdLayer {
             .unwrap_or_else(RequestId::new);
 
         request.extensions_mut().insert(request_id.clone());
+        if let Some(trace_context) = TraceContext::from_headers(request.headers()) {
+            request.extensions_mut().insert(trace_context);
+        }
 
         let mut response = next.run(request).await;
 
@@ -77,12 +82,22 @@ impl<B> MakeSpan<B> for RequestIdMakeSpan {
             .map(|r| r.as_str())
             .unwrap_or("unknown");
 
-        tracing::info_span!(
+        let span = tracing::info_span!(
             "http_request",
             method = %request.method(),
             uri = %request.uri(),
             version = ?request.version(),
             request_id = %request_id,
-        )
+            trace_id = field::Empty,
+            parent_span_id = field::Empty,
+            body_bytes = field::Empty,
+        );
+
+        if let Some(trace_context) = request.extensions().get::<TraceContext>() {
+            span.record("trace_id", field::display(&trace_context.trace_id));
+            span.record("parent_span_id", field::display(&trace_context.parent_id));
+        }
+
+        span
     }
 }
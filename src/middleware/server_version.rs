@@ -0,0 +1,22 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::SERVER_VERSION;
+
+pub const SERVER_VERSION_HEADER: &str = "x-server-version";
+
+/// Tags every response with the running crate version.
+pub async fn server_version_header(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+
+    response.headers_mut().insert(
+        HeaderName::from_static(SERVER_VERSION_HEADER),
+        HeaderValue::from_static(SERVER_VERSION),
+    );
+
+    response
+}
@@ -0,0 +1,125 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+
+use crate::config::defaults::{
+    DEFAULT_SLOW_REQUEST_BUFFER_SIZE, DEFAULT_SLOW_REQUEST_THRESHOLD_MS,
+};
+use crate::middleware::RequestId;
+
+/// A request that took at least the slow request threshold.
+#[derive(Debug, Clone)]
+pub struct SlowRequest {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub duration: Duration,
+    pub request_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Ring buffer of the most recent slow requests; once full, each new entry
+/// drops the oldest. A capacity of 0 records nothing.
+#[derive(Clone)]
+pub struct SlowRequestLog {
+    threshold: Duration,
+    capacity: usize,
+    entries: Arc<Mutex<VecDeque<SlowRequest>>>,
+}
+
+impl SlowRequestLog {
+    pub fn new(threshold: Duration, capacity: usize) -> Self {
+        Self {
+            threshold,
+            capacity,
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn record(&self, request: SlowRequest) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.lock();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(request);
+    }
+
+    /// The recorded requests, slowest first.
+    pub fn slowest(&self) -> Vec<SlowRequest> {
+        let mut requests: Vec<_> = self.lock().iter().cloned().collect();
+        requests.sort_by_key(|request| Reverse(request.duration));
+        requests
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<SlowRequest>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for SlowRequestLog {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_millis(DEFAULT_SLOW_REQUEST_THRESHOLD_MS),
+            DEFAULT_SLOW_REQUEST_BUFFER_SIZE,
+        )
+    }
+}
+
+/// Times each request and records it in the log when it took at least the
+/// threshold. Must run inside the request id middleware to pick up the id.
+pub async fn slow_request_middleware(
+    State(log): State<SlowRequestLog>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|request_id| request_id.0.clone());
+    let started_at = Utc::now();
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    let duration = start.elapsed();
+    if duration >= log.threshold {
+        tracing::warn!(
+            "Slow request: {} {} took {} ms",
+            method,
+            path,
+            duration.as_millis()
+        );
+        log.record(SlowRequest {
+            method,
+            path,
+            status: response.status().as_u16(),
+            duration,
+            request_id,
+            started_at,
+        });
+    }
+
+    response
+}
@@ -0,0 +1,69 @@
+use axum::http::HeaderMap;
+use rand::Rng;
+
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// W3C Trace Context carried in the `traceparent` header:
+/// `{version}-{trace-id}-{parent-id}-{trace-flags}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: String,
+    pub parent_id: String,
+    pub flags: String,
+}
+
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+impl TraceContext {
+    /// Parses a `traceparent` value, rejecting malformed or all-zero ids and
+    /// the reserved `ff` version as the spec requires.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+
+        if !is_lower_hex(version, 2) || version == "ff" {
+            return None;
+        }
+        // Version 00 has exactly four fields; later versions may append more.
+        if version == "00" && parts.next().is_some() {
+            return None;
+        }
+        if !is_lower_hex(trace_id, 32) || trace_id.bytes().all(|b| b == b'0') {
+            return None;
+        }
+        if !is_lower_hex(parent_id, 16) || parent_id.bytes().all(|b| b == b'0') {
+            return None;
+        }
+        if !is_lower_hex(flags, 2) {
+            return None;
+        }
+
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            flags: flags.to_string(),
+        })
+    }
+
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Self::parse)
+    }
+
+    /// `traceparent` for an outbound call made on behalf of this request:
+    /// same trace, fresh span id.
+    pub fn child_header(&self) -> String {
+        let span_id = rand::rng().random_range(1..=u64::MAX);
+        format!("00-{}-{:016x}-{}", self.trace_id, span_id, self.flags)
+    }
+}
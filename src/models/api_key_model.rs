@@ -6,6 +6,9 @@ use sqlx::{prelude::FromRow, types::ipnetwork::IpNetwork};
 
 use crate::dto::api_key_dto::CreateApiKeyRequest;
 
+/// Tenant of keys created without one, and of all pre-existing data.
+pub const DEFAULT_TENANT_ID: &str = "default";
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct ApiKey {
     pub id: i32,
@@ -22,6 +25,7 @@ pub struct ApiKey {
     pub allowed_ips: Option<Vec<IpNetwork>>,
     pub rate_limit_per_second: Option<i32>,
     pub rate_limit_burst: Option<i32>,
+    pub tenant_id: String,
 }
 
 impl ApiKey {
@@ -50,6 +54,17 @@ impl ApiKey {
     }
 }
 
+/// Key counts by state. `active` keys are enabled and unexpired;
+/// `expiring_soon` is the subset of them expiring within the window.
+#[derive(Debug, Clone, Copy, FromRow)]
+pub struct ApiKeyStats {
+    pub total: i64,
+    pub active: i64,
+    pub expired: i64,
+    pub revoked: i64,
+    pub expiring_soon: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreateApiKey {
     pub name: String,
@@ -58,6 +73,7 @@ pub struct CreateApiKey {
     pub allowed_ips: Option<Vec<IpNetwork>>,
     pub rate_limit_per_second: Option<i32>,
     pub rate_limit_burst: Option<i32>,
+    pub tenant_id: String,
 }
 
 impl CreateApiKey {
@@ -69,6 +85,7 @@ impl CreateApiKey {
             allowed_ips: None,
             rate_limit_per_second: None,
             rate_limit_burst: None,
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
         }
     }
 }
@@ -82,6 +99,9 @@ impl From<CreateApiKeyRequest> for CreateApiKey {
             allowed_ips: value.allowed_ips,
             rate_limit_per_second: value.rate_limit_per_second,
             rate_limit_burst: value.rate_limit_burst,
+            tenant_id: value
+                .tenant_id
+                .unwrap_or_else(|| DEFAULT_TENANT_ID.to_string()),
         }
     }
 }
@@ -102,4 +122,5 @@ pub struct NewApiKey {
     pub allowed_ips: Option<Vec<IpAddr>>,
     pub rate_limit_per_second: Option<i32>,
     pub rate_limit_burst: Option<i32>,
+    pub tenant_id: String,
 }
@@ -1,13 +1,151 @@
-use chrono::{DateTime, Utc};
+use std::io::{Read, Write};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::{AppError, AppResult};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Log {
     pub id: i32,
     pub schema_id: Uuid,
     pub log_data: Value,
+    pub created_by: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
+
+impl Log {
+    /// Removes the given top-level keys from `log_data`.
+    pub fn redact(&mut self, fields: &[String]) {
+        if let Value::Object(data) = &mut self.log_data {
+            for field in fields {
+                data.remove(field);
+            }
+        }
+    }
+}
+
+/// Top-level fields serialized to at most this many bytes stay in
+/// `log_data` when the payload is compressed.
+pub const SEARCHABLE_FIELD_MAX_BYTES: usize = 256;
+
+/// A `logs` row as stored: large payloads are kept gzip-compressed in
+/// `log_data_compressed`, with `log_data` holding their
+/// [`searchable_projection`].
+#[derive(Debug, FromRow)]
+pub(crate) struct StoredLog {
+    id: i32,
+    schema_id: Uuid,
+    log_data: Value,
+    log_data_compressed: Option<Vec<u8>>,
+    created_by: Option<i32>,
+    created_at: DateTime<Utc>,
+}
+
+impl StoredLog {
+    pub(crate) fn into_log(self) -> AppResult<Log> {
+        let log_data = match self.log_data_compressed {
+            Some(compressed) => decompress_log_data(&compressed).map_err(|e| {
+                AppError::internal_error(format!(
+                    "Failed to decompress data of log {}: {}",
+                    self.id, e
+                ))
+            })?,
+            None => self.log_data,
+        };
+
+        Ok(Log {
+            id: self.id,
+            schema_id: self.schema_id,
+            log_data,
+            created_by: self.created_by,
+            created_at: self.created_at,
+        })
+    }
+}
+
+/// What `log_data` keeps of a compressed payload: its top-level fields of
+/// at most [`SEARCHABLE_FIELD_MAX_BYTES`], so that filters, level counts
+/// and field indexes still see them.
+pub(crate) fn searchable_projection(log_data: &Value) -> Value {
+    let Value::Object(fields) = log_data else {
+        return Value::Object(Map::new());
+    };
+
+    fields
+        .iter()
+        .filter(|(_, value)| {
+            serde_json::to_vec(value).is_ok_and(|json| json.len() <= SEARCHABLE_FIELD_MAX_BYTES)
+        })
+        .map(|(field, value)| (field.clone(), value.clone()))
+        .collect()
+}
+
+/// Gzips serialized log data for `log_data_compressed`.
+pub(crate) fn compress_log_data(json: &[u8]) -> AppResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(json)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| AppError::internal_error(format!("Failed to compress log data: {}", e)))
+}
+
+fn decompress_log_data(compressed: &[u8]) -> anyhow::Result<Value> {
+    let mut json = Vec::new();
+    GzDecoder::new(compressed).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LogLevelCount {
+    pub level: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelCountSource {
+    Summary,
+    Live,
+}
+
+#[derive(Debug, Clone)]
+pub struct LevelCounts {
+    pub counts: Vec<LogLevelCount>,
+    pub source: LevelCountSource,
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SchemaLogCount {
+    pub schema_id: Uuid,
+    pub name: String,
+    pub version: String,
+    pub count: i64,
+}
+
+/// Time span of a schema's logs; both ends are `None` without logs.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, FromRow)]
+pub struct LogRange {
+    pub logs_from: Option<DateTime<Utc>>,
+    pub logs_to: Option<DateTime<Utc>>,
+    pub log_count: i64,
+}
+
+/// Rows removed by a full data reset.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PurgedCounts {
+    pub logs: i64,
+    pub schemas: i64,
+    pub api_keys: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyLogCount {
+    pub day: NaiveDate,
+    pub count: i64,
+}
@@ -4,8 +4,14 @@ pub mod query_params;
 pub mod schema_model;
 pub mod schema_name_version;
 
-pub use api_key_model::{ApiKey, CreateApiKey};
-pub use log_model::Log;
-pub use query_params::{LogQueryParams, SchemaQueryParams};
-pub use schema_model::Schema;
+pub use api_key_model::{ApiKey, ApiKeyStats, CreateApiKey, DEFAULT_TENANT_ID};
+pub use log_model::{
+    DailyLogCount, LevelCountSource, LevelCounts, Log, LogLevelCount, LogRange, PurgedCounts,
+    SchemaLogCount,
+};
+pub use query_params::{FilterOp, LogFilter, LogQueryParams, SchemaQueryParams};
+pub use schema_model::{
+    CreateSchema, FieldTypeMismatch, FieldViolation, LogValidationReport, RetentionPolicy, Schema,
+    SchemaChange, ValidationMode,
+};
 pub use schema_name_version::SchemaNameVersion;
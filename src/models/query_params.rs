@@ -1,15 +1,485 @@
+use std::cmp::Ordering;
+
 use chrono::{DateTime, Utc};
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use crate::{AppError, AppResult, ErrorCode};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LogQueryParams {
     pub date_begin: Option<DateTime<Utc>>,
     pub date_end: Option<DateTime<Utc>>,
     pub json_filters: Option<Value>,
+    pub created_by: Option<i32>,
+    /// Excludes logs with a larger id, pinning a view while new logs arrive.
+    pub anchor: Option<i32>,
+    /// Boolean filter expression, applied on top of `json_filters`.
+    pub expression: Option<LogFilter>,
+    /// The schema's `indexed_fields`; equality filters on them also get a
+    /// `log_data ->> 'field'` predicate their expression index can serve.
+    pub indexed_fields: Vec<String>,
+}
+
+/// Rejects `date_begin > date_end` with 400 `INVALID_DATE_RANGE`. A single
+/// bound is an open-ended range.
+pub fn validate_date_range(
+    date_begin: Option<DateTime<Utc>>,
+    date_end: Option<DateTime<Utc>>,
+) -> AppResult<()> {
+    match (date_begin, date_end) {
+        (Some(begin), Some(end)) if begin > end => Err(AppError::bad_request(format!(
+            "date_begin ({}) must not be after date_end ({})",
+            begin.to_rfc3339(),
+            end.to_rfc3339()
+        ))
+        .with_code(ErrorCode::InvalidDateRange)),
+        _ => Ok(()),
+    }
+}
+
+pub const MAX_FILTER_DEPTH: usize = 8;
+pub const MAX_FILTER_PREDICATES: usize = 64;
+const MAX_FIELD_SEGMENT_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// The field equals one of the values of an array.
+    In,
+    /// The field is an array holding the given element(s), like `$contains`.
+    Contains,
+    /// The field is present (`true`) or absent (`false`).
+    Exists,
+}
+
+impl FilterOp {
+    fn parse(op: &str) -> Option<Self> {
+        Some(match op {
+            "eq" => FilterOp::Eq,
+            "ne" => FilterOp::Ne,
+            "gt" => FilterOp::Gt,
+            "gte" => FilterOp::Gte,
+            "lt" => FilterOp::Lt,
+            "lte" => FilterOp::Lte,
+            "in" => FilterOp::In,
+            "contains" => FilterOp::Contains,
+            "exists" => FilterOp::Exists,
+            _ => return None,
+        })
+    }
+
+    pub fn sql_comparison(self) -> Option<&'static str> {
+        match self {
+            FilterOp::Gt => Some(" > "),
+            FilterOp::Gte => Some(" >= "),
+            FilterOp::Lt => Some(" < "),
+            FilterOp::Lte => Some(" <= "),
+            _ => None,
+        }
+    }
+}
+
+/// Boolean combination of predicates on `log_data` fields. A predicate on a
+/// missing field is false, so `not` of it is true.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogFilter {
+    And(Vec<LogFilter>),
+    Or(Vec<LogFilter>),
+    Not(Box<LogFilter>),
+    Field {
+        /// Dot-separated field name split into segments.
+        path: Vec<String>,
+        op: FilterOp,
+        value: Value,
+    },
+}
+
+fn invalid_filter(msg: impl Into<String>) -> AppError {
+    AppError::bad_request(msg).with_code(ErrorCode::InvalidFilter)
+}
+
+impl LogFilter {
+    /// Adds the top-level `log_data` keys the expression tests to `fields`.
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            LogFilter::And(children) | LogFilter::Or(children) => children
+                .iter()
+                .for_each(|child| child.collect_fields(fields)),
+            LogFilter::Not(child) => child.collect_fields(fields),
+            LogFilter::Field { path, .. } => fields.extend(path.first().map(String::as_str)),
+        }
+    }
+
+    /// Parses `{"and": [..]}`, `{"or": [..]}`, `{"not": {..}}` (several of
+    /// which may share one object and are then and-ed) and predicates
+    /// `{"field": "a.b", "op": "eq", "value": 1}`; `op` defaults to `eq`.
+    pub fn parse(value: &Value) -> AppResult<Self> {
+        let mut predicates = 0;
+        Self::parse_node(value, 1, &mut predicates)
+    }
+
+    fn parse_node(value: &Value, depth: usize, predicates: &mut usize) -> AppResult<Self> {
+        if depth > MAX_FILTER_DEPTH {
+            return Err(invalid_filter(format!(
+                "Filter is nested deeper than {} levels",
+                MAX_FILTER_DEPTH
+            )));
+        }
+
+        let Value::Object(node) = value else {
+            return Err(invalid_filter("Filter nodes must be JSON objects"));
+        };
+
+        if node.contains_key("field") {
+            *predicates += 1;
+            if *predicates > MAX_FILTER_PREDICATES {
+                return Err(invalid_filter(format!(
+                    "Filter has more than {} predicates",
+                    MAX_FILTER_PREDICATES
+                )));
+            }
+            return Self::parse_predicate(node);
+        }
+
+        let mut combined = Vec::new();
+        for (key, child) in node {
+            let filter = match key.as_str() {
+                "and" | "or" => {
+                    let children = child
+                        .as_array()
+                        .filter(|children| !children.is_empty())
+                        .ok_or_else(|| {
+                            invalid_filter(format!("'{}' must be a non-empty array", key))
+                        })?
+                        .iter()
+                        .map(|child| Self::parse_node(child, depth + 1, predicates))
+                        .collect::<AppResult<Vec<_>>>()?;
+                    if key == "and" {
+                        LogFilter::And(children)
+                    } else {
+                        LogFilter::Or(children)
+                    }
+                }
+                "not" => LogFilter::Not(Box::new(Self::parse_node(child, depth + 1, predicates)?)),
+                other => {
+                    return Err(invalid_filter(format!(
+                        "Unknown filter key '{}': expected and, or, not or field",
+                        other
+                    )))
+                }
+            };
+            combined.push(filter);
+        }
+
+        match combined.len() {
+            0 => Err(invalid_filter("Filter nodes cannot be empty")),
+            1 => Ok(combined.remove(0)),
+            _ => Ok(LogFilter::And(combined)),
+        }
+    }
+
+    fn parse_predicate(node: &Map<String, Value>) -> AppResult<Self> {
+        if let Some(key) = node
+            .keys()
+            .find(|key| !["field", "op", "value"].contains(&key.as_str()))
+        {
+            return Err(invalid_filter(format!(
+                "Unknown predicate key '{}': expected field, op and value",
+                key
+            )));
+        }
+
+        let field = node
+            .get("field")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_filter("Predicate 'field' must be a string"))?;
+        let path: Vec<String> = field.split('.').map(str::to_string).collect();
+        let safe_segment = |segment: &String| {
+            !segment.is_empty()
+                && segment.len() <= MAX_FIELD_SEGMENT_LEN
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        };
+        if !path.iter().all(safe_segment) {
+            return Err(invalid_filter(format!(
+                "Invalid field '{}': segments must be 1-{} characters of letters, digits, '_' or '-'",
+                field, MAX_FIELD_SEGMENT_LEN
+            )));
+        }
+
+        let op_name = node.get("op").and_then(Value::as_str).unwrap_or("eq");
+        let op = FilterOp::parse(op_name)
+            .ok_or_else(|| invalid_filter(format!("Unknown operator '{}'", op_name)))?;
+
+        let value = node
+            .get("value")
+            .cloned()
+            .ok_or_else(|| invalid_filter(format!("Predicate on '{}' has no value", field)))?;
+        let value = match (op, value) {
+            (FilterOp::Gt | FilterOp::Gte | FilterOp::Lt | FilterOp::Lte, value)
+                if !value.is_number() && !value.is_string() =>
+            {
+                return Err(invalid_filter(format!(
+                    "Operator '{}' needs a number or string value",
+                    op_name
+                )))
+            }
+            (FilterOp::In, value) if !value.is_array() => {
+                return Err(invalid_filter("Operator 'in' needs an array value"))
+            }
+            (FilterOp::Exists, value) if !value.is_boolean() => {
+                return Err(invalid_filter("Operator 'exists' needs a boolean value"))
+            }
+            (FilterOp::Contains, value) if !value.is_array() => Value::Array(vec![value]),
+            (_, value) => value,
+        };
+
+        Ok(LogFilter::Field { path, op, value })
+    }
+
+    /// Evaluates the filter against `log_data` the way its SQL rendering in
+    /// the query builder does.
+    pub fn matches(&self, log_data: &Value) -> bool {
+        match self {
+            LogFilter::And(children) => children.iter().all(|child| child.matches(log_data)),
+            LogFilter::Or(children) => children.iter().any(|child| child.matches(log_data)),
+            LogFilter::Not(child) => !child.matches(log_data),
+            LogFilter::Field { path, op, value } => {
+                let field = path
+                    .iter()
+                    .try_fold(log_data, |current, segment| match current {
+                        Value::Array(items) => {
+                            segment.parse::<usize>().ok().and_then(|i| items.get(i))
+                        }
+                        _ => current.get(segment),
+                    });
+
+                match op {
+                    FilterOp::Eq => field == Some(value),
+                    FilterOp::Ne => field.is_some_and(|field| field != value),
+                    FilterOp::Gt | FilterOp::Gte | FilterOp::Lt | FilterOp::Lte => {
+                        let ordering = match (field, value) {
+                            (Some(Value::Number(a)), Value::Number(b)) => a
+                                .as_f64()
+                                .zip(b.as_f64())
+                                .and_then(|(a, b)| a.partial_cmp(&b)),
+                            (Some(Value::String(a)), Value::String(b)) => Some(a.cmp(b)),
+                            _ => None,
+                        };
+                        ordering.is_some_and(|ordering| match op {
+                            FilterOp::Gt => ordering == Ordering::Greater,
+                            FilterOp::Gte => ordering != Ordering::Less,
+                            FilterOp::Lt => ordering == Ordering::Less,
+                            _ => ordering != Ordering::Greater,
+                        })
+                    }
+                    FilterOp::In => field.is_some_and(|field| {
+                        value
+                            .as_array()
+                            .is_some_and(|candidates| candidates.contains(field))
+                    }),
+                    FilterOp::Contains => field.is_some_and(|field| json_contains(field, value)),
+                    FilterOp::Exists => field.is_some() == value.as_bool().unwrap_or(true),
+                }
+            }
+        }
+    }
+}
+
+/// Mirrors the Postgres `@>` containment operator for JSONB values.
+pub fn json_contains(haystack: &Value, needle: &Value) -> bool {
+    match (haystack, needle) {
+        (Value::Object(haystack), Value::Object(needle)) => needle.iter().all(|(key, value)| {
+            haystack
+                .get(key)
+                .is_some_and(|candidate| json_contains(candidate, value))
+        }),
+        (Value::Array(haystack), Value::Array(needle)) => needle.iter().all(|value| {
+            haystack
+                .iter()
+                .any(|candidate| json_contains(candidate, value))
+        }),
+        (Value::Array(haystack), scalar) if !scalar.is_object() => {
+            haystack.iter().any(|candidate| candidate == scalar)
+        }
+        _ => haystack == needle,
+    }
+}
+
+/// Operator that matches when `log_data->field` is an array holding the given
+/// element(s): `{"tags": {"$contains": "b"}}`.
+pub const CONTAINS_OPERATOR: &str = "$contains";
+
+/// Operator that matches when `log_data->>field` equals one of the given
+/// scalars: `{"level": {"$in": ["ERROR", "FATAL"]}}`.
+pub const IN_OPERATOR: &str = "$in";
+
+/// Text form of a JSON scalar as Postgres' `->>` renders it; `None` for
+/// `null`, arrays and objects.
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Operands of `$in` as text, or `None` unless they are an array of scalars.
+fn in_operands(value: &Value) -> Option<Vec<String>> {
+    value.as_array()?.iter().map(scalar_text).collect()
+}
+
+/// Rejects `$in` operands other than an array of strings, numbers or
+/// booleans with 400 `INVALID_FILTER`.
+pub fn validate_json_filters(filters: &Value) -> AppResult<()> {
+    let Value::Object(filters) = filters else {
+        return Ok(());
+    };
+
+    for (field, value) in filters {
+        let Some(operand) = value
+            .as_object()
+            .and_then(|operator| operator.get(IN_OPERATOR))
+        else {
+            continue;
+        };
+        if in_operands(operand).is_none() {
+            return Err(invalid_filter(format!(
+                "`{}` of `{}` must be an array of strings, numbers or booleans",
+                IN_OPERATOR, field
+            ))
+            .with_detail("field", field.as_str()));
+        }
+    }
+
+    Ok(())
+}
+
+/// A single condition parsed from the `filters` JSON object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonFilter {
+    /// `log_data @> value`
+    Contains(Value),
+    /// `log_data->field @> elements`, where `elements` is always an array.
+    ArrayContains { field: String, elements: Value },
+    /// `log_data->>field = ANY(values)`
+    In { field: String, values: Vec<String> },
+}
+
+impl JsonFilter {
+    /// Evaluates the condition against `log_data` the way Postgres does.
+    pub fn matches(&self, log_data: &Value) -> bool {
+        match self {
+            JsonFilter::Contains(value) => json_contains(log_data, value),
+            JsonFilter::ArrayContains { field, elements } => log_data
+                .get(field)
+                .is_some_and(|value| json_contains(value, elements)),
+            JsonFilter::In { field, values } => log_data
+                .get(field)
+                .and_then(scalar_text)
+                .is_some_and(|text| values.contains(&text)),
+        }
+    }
+}
+
+impl LogQueryParams {
+    /// Top-level `log_data` keys tested by `json_filters` or `expression`.
+    pub fn filtered_fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        if let Some(Value::Object(filters)) = &self.json_filters {
+            fields.extend(filters.keys().map(String::as_str));
+        }
+        if let Some(expression) = &self.expression {
+            expression.collect_fields(&mut fields);
+        }
+        fields
+    }
+
+    /// Splits the JSON filters into operator conditions and the remaining plain
+    /// containment object. Filters that are not objects are ignored, and so
+    /// is an `$in` that [`validate_json_filters`] would reject.
+    pub fn json_filter_conditions(&self) -> Vec<JsonFilter> {
+        let Some(Value::Object(filters)) = &self.json_filters else {
+            return Vec::new();
+        };
+
+        let mut plain = Map::new();
+        let mut conditions = Vec::new();
+
+        for (field, value) in filters {
+            let operator = match value {
+                Value::Object(operator) if operator.len() == 1 => operator,
+                _ => {
+                    plain.insert(field.clone(), value.clone());
+                    continue;
+                }
+            };
+
+            if let Some(elements) = operator.get(CONTAINS_OPERATOR) {
+                let elements = match elements {
+                    Value::Array(_) => elements.clone(),
+                    element => Value::Array(vec![element.clone()]),
+                };
+                conditions.push(JsonFilter::ArrayContains {
+                    field: field.clone(),
+                    elements,
+                });
+            } else if let Some(operand) = operator.get(IN_OPERATOR) {
+                if let Some(values) = in_operands(operand) {
+                    conditions.push(JsonFilter::In {
+                        field: field.clone(),
+                        values,
+                    });
+                }
+            } else {
+                plain.insert(field.clone(), value.clone());
+            }
+        }
+
+        if !plain.is_empty() {
+            conditions.insert(0, JsonFilter::Contains(Value::Object(plain)));
+        }
+
+        conditions
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct SchemaQueryParams {
     pub name: Option<String>,
     pub version: Option<String>,
+    /// Case-insensitive substring matched against name or description.
+    pub search: Option<String>,
+    /// Only schemas carrying this label.
+    pub label: Option<String>,
+    /// Restricts results to one tenant's schemas.
+    pub tenant_id: Option<String>,
+}
+
+impl SchemaQueryParams {
+    /// `ILIKE` pattern for `search`, with `%`, `_` and `\` escaped so they
+    /// match literally.
+    pub fn search_pattern(&self) -> Option<String> {
+        let search = self.search.as_deref().filter(|s| !s.is_empty())?;
+        let escaped = search
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        Some(format!("%{}%", escaped))
+    }
+
+    /// Whether `name` contains `search`, ignoring case. Used to rank name
+    /// matches ahead of description-only matches.
+    pub fn name_matches_search(&self, name: &str) -> bool {
+        self.search
+            .as_deref()
+            .is_some_and(|search| name.to_lowercase().contains(&search.to_lowercase()))
+    }
 }
@@ -4,6 +4,9 @@ use serde_json::Value;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::dto::{CreateSchemaRequest, UpdateSchemaRequest};
+use crate::models::api_key_model::DEFAULT_TENANT_ID;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Schema {
     pub id: Uuid,
@@ -11,6 +14,192 @@ pub struct Schema {
     pub version: String,
     pub description: Option<String>,
     pub schema_definition: Value,
+    pub webhook_url: Option<String>,
+    #[serde(skip_serializing)]
+    pub webhook_secret: Option<String>,
+    pub strict_formats: bool,
+    pub indexed_fields: Vec<String>,
+    pub max_retained_logs: Option<i32>,
+    /// Logs older than this many seconds are swept.
+    pub max_log_age_secs: Option<i32>,
+    pub redacted_fields: Vec<String>,
+    /// Fraction of new logs kept by `POST /logs`; `None` keeps all.
+    pub sample_rate: Option<f64>,
+    /// Free-form tags for organizing schemas, e.g. `team:payments`.
+    pub labels: Vec<String>,
+    pub validation_mode: ValidationMode,
+    /// Largest string value, in bytes, allowed anywhere in `log_data`;
+    /// `None` means no per-field limit.
+    pub max_field_bytes: Option<i32>,
+    pub tenant_id: String,
+    pub created_by: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// Retention rules of a schema with at least one of them set, as read by the
+/// retention sweep.
+#[derive(Debug, Clone, Copy, FromRow)]
+pub struct RetentionPolicy {
+    pub schema_id: Uuid,
+    pub max_retained_logs: Option<i32>,
+    pub max_log_age_secs: Option<i32>,
+}
+
+/// What happens to a log that fails its schema on write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum ValidationMode {
+    /// Rejected with HTTP 422.
+    #[default]
+    Strict,
+    /// Stored with the failures recorded under `_validation_errors`.
+    Lenient,
+    /// Stored without being validated.
+    Off,
+}
+
+/// Breakdown of why `log_data` does not match a schema. Paths are JSON
+/// pointers into `log_data`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogValidationReport {
+    /// Required fields that are absent.
+    pub missing: Vec<String>,
+    /// Fields present with a type the schema does not allow.
+    pub wrong_type: Vec<FieldTypeMismatch>,
+    /// Fields rejected by `additionalProperties: false`.
+    pub extra: Vec<String>,
+    /// Every other failed keyword (`minimum`, `format`, `enum`, ...).
+    pub other: Vec<FieldViolation>,
+}
+
+impl LogValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty()
+            && self.wrong_type.is_empty()
+            && self.extra.is_empty()
+            && self.other.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldTypeMismatch {
+    pub path: String,
+    pub expected: Vec<String>,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldViolation {
+    pub path: String,
+    pub keyword: String,
+    pub message: String,
+}
+
+/// One difference between two schema definitions, at a JSON pointer into
+/// the logs they describe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum SchemaChange {
+    FieldAdded {
+        path: String,
+        required: bool,
+    },
+    FieldRemoved {
+        path: String,
+    },
+    FieldBecameRequired {
+        path: String,
+    },
+    FieldBecameOptional {
+        path: String,
+    },
+    TypeChanged {
+        path: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl SchemaChange {
+    /// Whether logs written against the old definition may fail the new one.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Self::FieldAdded { required, .. } => *required,
+            Self::FieldRemoved { .. }
+            | Self::FieldBecameRequired { .. }
+            | Self::TypeChanged { .. } => true,
+            Self::FieldBecameOptional { .. } => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateSchema {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub schema_definition: Value,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub strict_formats: bool,
+    pub indexed_fields: Vec<String>,
+    pub max_retained_logs: Option<i32>,
+    pub max_log_age_secs: Option<i32>,
+    pub redacted_fields: Vec<String>,
+    pub sample_rate: Option<f64>,
+    pub labels: Vec<String>,
+    pub validation_mode: ValidationMode,
+    pub max_field_bytes: Option<i32>,
+    pub tenant_id: String,
+    pub created_by: Option<i32>,
+}
+
+impl From<CreateSchemaRequest> for CreateSchema {
+    fn from(value: CreateSchemaRequest) -> Self {
+        Self {
+            name: value.name,
+            version: value.version,
+            description: value.description,
+            schema_definition: value.schema_definition,
+            webhook_url: value.webhook_url,
+            webhook_secret: value.webhook_secret,
+            strict_formats: value.strict_formats,
+            indexed_fields: value.indexed_fields,
+            max_retained_logs: value.max_retained_logs,
+            max_log_age_secs: value.max_log_age_secs,
+            redacted_fields: value.redacted_fields,
+            sample_rate: value.sample_rate,
+            labels: value.labels,
+            validation_mode: value.validation_mode,
+            max_field_bytes: value.max_field_bytes,
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            created_by: None,
+        }
+    }
+}
+
+impl From<UpdateSchemaRequest> for CreateSchema {
+    fn from(value: UpdateSchemaRequest) -> Self {
+        Self {
+            name: value.name,
+            version: value.version,
+            description: value.description,
+            schema_definition: value.schema_definition,
+            webhook_url: value.webhook_url,
+            webhook_secret: value.webhook_secret,
+            strict_formats: value.strict_formats,
+            indexed_fields: value.indexed_fields,
+            max_retained_logs: value.max_retained_logs,
+            max_log_age_secs: value.max_log_age_secs,
+            redacted_fields: value.redacted_fields,
+            sample_rate: value.sample_rate,
+            labels: value.labels,
+            validation_mode: value.validation_mode,
+            max_field_bytes: value.max_field_bytes,
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            created_by: None,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{AppError, AppResult, ErrorCode};
+
 #[derive(Debug, Clone)]
 pub struct SchemaNameVersion {
     pub name: String,
@@ -22,4 +24,33 @@ impl SchemaNameVersion {
             version: None,
         }
     }
+
+    /// Parses `name/version`, or a bare `name` for the latest version.
+    pub fn parse(reference: &str) -> Self {
+        match reference.rsplit_once('/') {
+            Some((name, version)) => Self::with_version(name.to_string(), version.to_string()),
+            None => Self::latest(reference.to_string()),
+        }
+    }
+
+    /// Rejects a blank name or version with 400 `INVALID_INPUT`.
+    pub fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(empty_segment_error("name"));
+        }
+        if self
+            .version
+            .as_deref()
+            .is_some_and(|version| version.trim().is_empty())
+        {
+            return Err(empty_segment_error("version"));
+        }
+        Ok(())
+    }
+}
+
+/// Error for an empty schema name or version path segment.
+pub fn empty_segment_error(segment: &str) -> AppError {
+    AppError::bad_request(format!("Schema {} cannot be empty", segment))
+        .with_code(ErrorCode::InvalidInput)
 }
@@ -1,14 +1,15 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{types::ipnetwork::IpNetwork, PgPool};
 
 use crate::{
-    models::{api_key_model::NewApiKey, ApiKey},
+    models::{api_key_model::NewApiKey, ApiKey, ApiKeyStats},
     AppResult,
 };
 
 const API_KEY_COLUMNS: &str = r#"
     id, key_hash, key_prefix, name, description, created_at,
     last_used_at, expires_at, is_active, usage_count, allowed_ips,
-    rate_limit_per_second, rate_limit_burst
+    rate_limit_per_second, rate_limit_burst, tenant_id
 "#;
 
 pub struct ApiKeyRepository {
@@ -60,14 +61,53 @@ impl ApiKeyRepository {
         Ok(result)
     }
 
+    /// The valid key with a salted (`$`-tagged) hash stored under one of
+    /// `key_prefixes`, preferring the longest. Salted hashes cannot be looked
+    /// up by value; their prefixes are unique instead, so this is the one
+    /// candidate to verify against.
+    pub async fn get_valid_salted_by_prefix(
+        &self,
+        key_prefixes: &[String],
+    ) -> AppResult<Option<ApiKey>> {
+        let result = sqlx::query_as::<_, ApiKey>(&format!(
+            "SELECT {}
+            FROM api_keys
+            WHERE key_prefix = ANY($1)
+                AND key_hash LIKE '$%'
+                AND is_active = true
+                AND (expires_at IS NULL OR expires_at > NOW())
+            ORDER BY length(key_prefix) DESC
+            LIMIT 1",
+            API_KEY_COLUMNS
+        ))
+        .bind(key_prefixes)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Whether a key with a salted hash is already stored under `key_prefix`,
+    /// revoked and expired ones included.
+    pub async fn salted_prefix_exists(&self, key_prefix: &str) -> AppResult<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM api_keys WHERE key_prefix = $1 AND key_hash LIKE '$%')",
+        )
+        .bind(key_prefix)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
     pub async fn create(&self, new_key: &NewApiKey) -> AppResult<ApiKey> {
         let api_key = sqlx::query_as::<_, ApiKey>(
             r#"
-            INSERT INTO api_keys (key_hash, key_prefix, name, description, expires_at, allowed_ips, rate_limit_per_second, rate_limit_burst)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO api_keys (key_hash, key_prefix, name, description, expires_at, allowed_ips, rate_limit_per_second, rate_limit_burst, tenant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING id, key_hash, key_prefix, name, description, created_at, 
                       last_used_at, expires_at, is_active, usage_count, allowed_ips,
-                      rate_limit_per_second, rate_limit_burst
+                      rate_limit_per_second, rate_limit_burst, tenant_id
             "#,
         )
         .bind(&new_key.key_hash)
@@ -78,6 +118,7 @@ impl ApiKeyRepository {
         .bind(&new_key.allowed_ips)
         .bind(new_key.rate_limit_per_second)
         .bind(new_key.rate_limit_burst)
+        .bind(&new_key.tenant_id)
         .fetch_one(&self.pool)
         .await?;
 
@@ -123,7 +164,7 @@ impl ApiKeyRepository {
             WHERE id = $1
             RETURNING id, key_hash, key_prefix, name, description, created_at, 
                       last_used_at, expires_at, is_active, usage_count, allowed_ips,
-                      rate_limit_per_second, rate_limit_burst
+                      rate_limit_per_second, rate_limit_burst, tenant_id
             "#,
         )
         .bind(key_id)
@@ -140,9 +181,9 @@ impl ApiKeyRepository {
             r#"
             SELECT id, key_hash, key_prefix, name, description, created_at, 
                    last_used_at, expires_at, is_active, usage_count, allowed_ips,
-                   rate_limit_per_second, rate_limit_burst
+                   rate_limit_per_second, rate_limit_burst, tenant_id
             FROM api_keys 
-            ORDER BY created_at DESC
+            ORDER BY created_at DESC, id DESC
             "#,
         )
         .fetch_all(&self.pool)
@@ -156,7 +197,7 @@ impl ApiKeyRepository {
             r#"
             SELECT id, key_hash, key_prefix, name, description, created_at, 
                    last_used_at, expires_at, is_active, usage_count, allowed_ips,
-                   rate_limit_per_second, rate_limit_burst
+                   rate_limit_per_second, rate_limit_burst, tenant_id
             FROM api_keys 
             WHERE is_active = true 
                 AND expires_at IS NOT NULL 
@@ -169,6 +210,56 @@ impl ApiKeyRepository {
         Ok(expired_active_api_keys)
     }
 
+    /// Active keys that have not expired yet but will by `until`, soonest first.
+    pub async fn get_active_expiring_before(&self, until: DateTime<Utc>) -> AppResult<Vec<ApiKey>> {
+        let api_keys = sqlx::query_as::<_, ApiKey>(&format!(
+            "SELECT {}
+            FROM api_keys
+            WHERE is_active = true
+                AND expires_at > NOW()
+                AND expires_at <= $1
+            ORDER BY expires_at, id",
+            API_KEY_COLUMNS
+        ))
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(api_keys)
+    }
+
+    pub async fn count(&self) -> AppResult<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_keys")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Revoked keys count as revoked whether or not they have also expired.
+    pub async fn stats(&self, expiring_before: DateTime<Utc>) -> AppResult<ApiKeyStats> {
+        let stats = sqlx::query_as::<_, ApiKeyStats>(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (
+                    WHERE is_active AND (expires_at IS NULL OR expires_at > NOW())
+                ) AS active,
+                COUNT(*) FILTER (WHERE is_active AND expires_at <= NOW()) AS expired,
+                COUNT(*) FILTER (WHERE NOT is_active) AS revoked,
+                COUNT(*) FILTER (
+                    WHERE is_active AND expires_at > NOW() AND expires_at <= $1
+                ) AS expiring_soon
+            FROM api_keys
+            "#,
+        )
+        .bind(expiring_before)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
     pub async fn delete(&self, id: i32) -> AppResult<Option<ApiKey>> {
         let deleted_api_key = sqlx::query_as::<_, ApiKey>(
             r#"
@@ -176,7 +267,7 @@ impl ApiKeyRepository {
             WHERE id = $1
             RETURNING id, key_hash, key_prefix, name, description, created_at, 
                       last_used_at, expires_at, is_active, usage_count, allowed_ips,
-                      rate_limit_per_second, rate_limit_burst
+                      rate_limit_per_second, rate_limit_burst, tenant_id
             "#,
         )
         .bind(id)
@@ -185,4 +276,56 @@ impl ApiKeyRepository {
 
         Ok(deleted_api_key)
     }
+
+    pub async fn delete_many(&self, ids: &[i32]) -> AppResult<Vec<i32>> {
+        let deleted_ids =
+            sqlx::query_scalar::<_, i32>("DELETE FROM api_keys WHERE id = ANY($1) RETURNING id")
+                .bind(ids)
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(deleted_ids)
+    }
+
+    pub async fn add_allowed_ip(&self, id: i32, network: IpNetwork) -> AppResult<Option<ApiKey>> {
+        let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+            "UPDATE api_keys
+            SET allowed_ips = CASE
+                WHEN $2 = ANY(COALESCE(allowed_ips, '{{}}')) THEN allowed_ips
+                ELSE array_append(COALESCE(allowed_ips, '{{}}'), $2)
+            END
+            WHERE id = $1
+            RETURNING {}",
+            API_KEY_COLUMNS
+        ))
+        .bind(id)
+        .bind(network)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
+
+    /// Removes `network` from the key's allowed IPs. An empty list allows
+    /// every IP, so the last entry is never removed: `None` when the key is
+    /// missing or `network` is its only entry.
+    pub async fn remove_allowed_ip(
+        &self,
+        id: i32,
+        network: IpNetwork,
+    ) -> AppResult<Option<ApiKey>> {
+        let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+            "UPDATE api_keys
+            SET allowed_ips = array_remove(allowed_ips, $2)
+            WHERE id = $1 AND allowed_ips IS DISTINCT FROM ARRAY[$2]::inet[]
+            RETURNING {}",
+            API_KEY_COLUMNS
+        ))
+        .bind(id)
+        .bind(network)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(api_key)
+    }
 }
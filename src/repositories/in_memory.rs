@@ -0,0 +1,644 @@
+//! In-memory repository implementations for exercising the service and handler
+//! layers without a database. Production code uses the Postgres repositories.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::query_params::LogQueryParams;
+use crate::models::{
+    DailyLogCount, Log, LogLevelCount, LogRange, PurgedCounts, RetentionPolicy, Schema,
+    SchemaLogCount, SchemaQueryParams,
+};
+use crate::repositories::log_repository::LogRepositoryTrait;
+use crate::repositories::schema_repository::SchemaRepositoryTrait;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Keeps the rows past `cursor_key` in `(created_at, id)` order, sorted and
+/// limited the same way as the Postgres cursor queries (`limit + 1` rows).
+fn page_after_cursor<T, K: Ord + Copy>(
+    mut rows: Vec<T>,
+    cursor_key: Option<(DateTime<Utc>, K)>,
+    limit: i32,
+    forward: bool,
+    key: impl Fn(&T) -> (DateTime<Utc>, K),
+) -> Vec<T> {
+    if let Some(cursor_key) = cursor_key {
+        rows.retain(|row| {
+            if forward {
+                key(row) < cursor_key
+            } else {
+                key(row) > cursor_key
+            }
+        });
+    }
+
+    if forward {
+        rows.sort_by_key(|row| Reverse(key(row)));
+    } else {
+        rows.sort_by_key(&key);
+    }
+
+    rows.truncate((limit + 1).max(0) as usize);
+    rows
+}
+
+fn version_key(version: &str) -> Vec<Option<i64>> {
+    version
+        .split('.')
+        .take(3)
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Schemas, plus the log store that operations spanning schemas and logs
+/// (forced deletes, purges, per-schema counts) work on. Hand the same store
+/// to the log service through [`Self::logs`].
+#[derive(Default)]
+pub struct InMemorySchemaRepository {
+    schemas: Mutex<Vec<Schema>>,
+    logs: Arc<InMemoryLogRepository>,
+}
+
+impl InMemorySchemaRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn logs(&self) -> Arc<InMemoryLogRepository> {
+        self.logs.clone()
+    }
+
+    fn filtered(&self, filters: Option<&SchemaQueryParams>) -> Vec<Schema> {
+        lock(&self.schemas)
+            .iter()
+            .filter(|schema| {
+                filters.is_none_or(|filters| {
+                    filters
+                        .tenant_id
+                        .as_ref()
+                        .is_none_or(|tenant_id| &schema.tenant_id == tenant_id)
+                        && filters
+                            .name
+                            .as_ref()
+                            .is_none_or(|name| &schema.name == name)
+                        && filters
+                            .version
+                            .as_ref()
+                            .is_none_or(|version| &schema.version == version)
+                        && filters
+                            .search
+                            .as_deref()
+                            .filter(|search| !search.is_empty())
+                            .is_none_or(|search| {
+                                let search = search.to_lowercase();
+                                schema.name.to_lowercase().contains(&search)
+                                    || schema
+                                        .description
+                                        .as_deref()
+                                        .is_some_and(|d| d.to_lowercase().contains(&search))
+                            })
+                        && filters
+                            .label
+                            .as_ref()
+                            .is_none_or(|label| schema.labels.contains(label))
+                })
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SchemaRepositoryTrait for InMemorySchemaRepository {
+    async fn get_all(&self, params: Option<SchemaQueryParams>) -> AppResult<Vec<Schema>> {
+        let mut schemas = self.filtered(params.as_ref());
+        schemas.sort_by_key(|schema| Reverse((schema.created_at, schema.id)));
+        Ok(schemas)
+    }
+
+    async fn get_all_with_cursor(
+        &self,
+        cursor: Option<Uuid>,
+        limit: i32,
+        filters: SchemaQueryParams,
+        forward: bool,
+    ) -> AppResult<Vec<Schema>> {
+        // A cursor that no longer exists matches nothing, as in the SQL subquery.
+        let cursor_key = match cursor {
+            Some(id) => match self.get_by_id(id, None).await? {
+                Some(schema) => Some((schema.created_at, schema.id)),
+                None => return Ok(Vec::new()),
+            },
+            None => None,
+        };
+
+        let schemas = self.filtered(Some(&filters));
+        Ok(page_after_cursor(
+            schemas,
+            cursor_key,
+            limit,
+            forward,
+            |schema| (schema.created_at, schema.id),
+        ))
+    }
+
+    async fn count(&self, filters: Option<&SchemaQueryParams>) -> AppResult<i64> {
+        Ok(self.filtered(filters).len() as i64)
+    }
+
+    async fn get_by_id(&self, id: Uuid, tenant_id: Option<&str>) -> AppResult<Option<Schema>> {
+        Ok(lock(&self.schemas)
+            .iter()
+            .find(|schema| {
+                schema.id == id && tenant_id.is_none_or(|tenant_id| schema.tenant_id == tenant_id)
+            })
+            .cloned())
+    }
+
+    async fn get_by_ids(&self, ids: &[Uuid], tenant_id: &str) -> AppResult<Vec<Schema>> {
+        let mut schemas: Vec<Schema> = lock(&self.schemas)
+            .iter()
+            .filter(|schema| ids.contains(&schema.id) && schema.tenant_id == tenant_id)
+            .cloned()
+            .collect();
+        schemas.sort_by_key(|schema| (schema.created_at, schema.id));
+        Ok(schemas)
+    }
+
+    async fn get_by_name_latest(&self, name: &str, tenant_id: &str) -> AppResult<Option<Schema>> {
+        Ok(lock(&self.schemas)
+            .iter()
+            .filter(|schema| schema.name == name && schema.tenant_id == tenant_id)
+            .max_by_key(|schema| version_key(&schema.version))
+            .cloned())
+    }
+
+    async fn get_by_name_and_version(
+        &self,
+        name: &str,
+        version: &str,
+        tenant_id: &str,
+    ) -> AppResult<Option<Schema>> {
+        Ok(lock(&self.schemas)
+            .iter()
+            .find(|schema| {
+                schema.name == name && schema.version == version && schema.tenant_id == tenant_id
+            })
+            .cloned())
+    }
+
+    async fn get_latest_schema_id(&self, tenant_id: &str) -> AppResult<Option<Uuid>> {
+        Ok(lock(&self.schemas)
+            .iter()
+            .filter(|schema| schema.tenant_id == tenant_id)
+            .max_by_key(|schema| (schema.created_at, schema.id))
+            .map(|schema| schema.id))
+    }
+
+    async fn get_retention_policies(&self) -> AppResult<Vec<RetentionPolicy>> {
+        Ok(lock(&self.schemas)
+            .iter()
+            .filter(|schema| {
+                schema.max_retained_logs.is_some() || schema.max_log_age_secs.is_some()
+            })
+            .map(|schema| RetentionPolicy {
+                schema_id: schema.id,
+                max_retained_logs: schema.max_retained_logs,
+                max_log_age_secs: schema.max_log_age_secs,
+            })
+            .collect())
+    }
+
+    async fn create(&self, schema: &Schema) -> AppResult<Schema> {
+        lock(&self.schemas).push(schema.clone());
+        Ok(schema.clone())
+    }
+
+    async fn update(&self, id: Uuid, schema: &Schema) -> AppResult<Option<Schema>> {
+        let mut schemas = lock(&self.schemas);
+        let Some(existing) = schemas
+            .iter_mut()
+            .find(|existing| existing.id == id && existing.tenant_id == schema.tenant_id)
+        else {
+            return Ok(None);
+        };
+
+        *existing = Schema {
+            id,
+            created_at: existing.created_at,
+            ..schema.clone()
+        };
+
+        Ok(Some(existing.clone()))
+    }
+
+    async fn delete(&self, id: Uuid, tenant_id: &str) -> AppResult<Option<Schema>> {
+        let mut schemas = lock(&self.schemas);
+        let position = schemas
+            .iter()
+            .position(|schema| schema.id == id && schema.tenant_id == tenant_id);
+        Ok(position.map(|position| schemas.remove(position)))
+    }
+
+    /// Holds both locks throughout, so the two deletes happen as one step.
+    async fn delete_with_logs(
+        &self,
+        id: Uuid,
+        tenant_id: &str,
+    ) -> AppResult<Option<(Schema, Vec<i32>)>> {
+        let mut schemas = lock(&self.schemas);
+        let Some(position) = schemas
+            .iter()
+            .position(|schema| schema.id == id && schema.tenant_id == tenant_id)
+        else {
+            return Ok(None);
+        };
+
+        let mut store = lock(&self.logs.store);
+        let log_ids = store
+            .logs
+            .iter()
+            .filter(|log| log.schema_id == id)
+            .map(|log| log.id)
+            .collect();
+        store.logs.retain(|log| log.schema_id != id);
+        store.keys.retain(|(schema_id, _), _| *schema_id != id);
+
+        Ok(Some((schemas.remove(position), log_ids)))
+    }
+
+    /// API keys are not held in memory, so `include_api_keys` has nothing to
+    /// purge and `api_keys` is always 0.
+    async fn purge_all(&self, _include_api_keys: bool) -> AppResult<PurgedCounts> {
+        let mut schemas = lock(&self.schemas);
+        let mut store = lock(&self.logs.store);
+
+        let counts = PurgedCounts {
+            logs: store.logs.len() as i64,
+            schemas: schemas.len() as i64,
+            api_keys: 0,
+        };
+        schemas.clear();
+        store.logs.clear();
+        store.keys.clear();
+
+        Ok(counts)
+    }
+
+    async fn count_logs_by_schema(
+        &self,
+        tenant_id: &str,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<SchemaLogCount>> {
+        let mut schemas = self.filtered(Some(&SchemaQueryParams {
+            tenant_id: Some(tenant_id.to_string()),
+            ..Default::default()
+        }));
+        schemas.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+        let range = LogQueryParams {
+            date_begin,
+            date_end,
+            ..Default::default()
+        };
+        let mut counts = Vec::with_capacity(schemas.len());
+        for schema in schemas {
+            counts.push(SchemaLogCount {
+                count: self
+                    .logs
+                    .count_by_schema_id(schema.id, Some(&range))
+                    .await?,
+                schema_id: schema.id,
+                name: schema.name,
+                version: schema.version,
+            });
+        }
+        Ok(counts)
+    }
+}
+
+#[derive(Default)]
+struct LogStore {
+    logs: Vec<Log>,
+    next_id: i32,
+    keys: HashMap<(Uuid, String), i32>,
+}
+
+#[derive(Default)]
+pub struct InMemoryLogRepository {
+    store: Mutex<LogStore>,
+}
+
+impl InMemoryLogRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn matching(&self, schema_id: Uuid, filters: Option<&LogQueryParams>) -> Vec<Log> {
+        lock(&self.store)
+            .logs
+            .iter()
+            .filter(|log| log.schema_id == schema_id)
+            .filter(|log| {
+                let Some(filters) = filters else {
+                    return true;
+                };
+
+                let json_matches = filters
+                    .json_filter_conditions()
+                    .iter()
+                    .all(|condition| condition.matches(&log.log_data));
+                let date_matches = filters
+                    .date_begin
+                    .is_none_or(|begin| log.created_at >= begin)
+                    && filters.date_end.is_none_or(|end| log.created_at <= end);
+                let creator_matches = filters
+                    .created_by
+                    .is_none_or(|created_by| log.created_by == Some(created_by));
+                let anchor_matches = filters.anchor.is_none_or(|anchor| log.id <= anchor);
+                let expression_matches = filters
+                    .expression
+                    .as_ref()
+                    .is_none_or(|expression| expression.matches(&log.log_data));
+
+                json_matches
+                    && date_matches
+                    && creator_matches
+                    && anchor_matches
+                    && expression_matches
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn level_counts(logs: impl Iterator<Item = Log>) -> Vec<LogLevelCount> {
+        let mut counts = BTreeMap::<String, i64>::new();
+        for log in logs {
+            let level = log
+                .log_data
+                .get("level")
+                .map(|level| match level {
+                    Value::String(level) => level.clone(),
+                    other => other.to_string(),
+                })
+                .unwrap_or_else(|| "UNKNOWN".to_string());
+            *counts.entry(level).or_default() += 1;
+        }
+
+        counts
+            .into_iter()
+            .map(|(level, count)| LogLevelCount { level, count })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LogRepositoryTrait for InMemoryLogRepository {
+    async fn get_all_with_cursor(
+        &self,
+        schema_id: Uuid,
+        cursor: Option<i32>,
+        limit: i32,
+        filters: LogQueryParams,
+        forward: bool,
+    ) -> AppResult<Vec<Log>> {
+        let cursor_key = match cursor {
+            Some(id) => match lock(&self.store).logs.iter().find(|log| log.id == id) {
+                Some(log) => Some((log.created_at, log.id)),
+                None => return Ok(Vec::new()),
+            },
+            None => None,
+        };
+
+        let logs = self.matching(schema_id, Some(&filters));
+        Ok(page_after_cursor(logs, cursor_key, limit, forward, |log| {
+            (log.created_at, log.id)
+        }))
+    }
+
+    async fn get_after_keyset(
+        &self,
+        schema_id: Uuid,
+        after: Option<(DateTime<Utc>, i32)>,
+        limit: i32,
+    ) -> AppResult<Vec<Log>> {
+        let logs = self.matching(schema_id, None);
+        Ok(page_after_cursor(logs, after, limit, false, |log| {
+            (log.created_at, log.id)
+        }))
+    }
+
+    async fn get_by_id(&self, id: i32) -> AppResult<Option<Log>> {
+        Ok(lock(&self.store)
+            .logs
+            .iter()
+            .find(|log| log.id == id)
+            .cloned())
+    }
+
+    async fn create(&self, log: &Log) -> AppResult<Log> {
+        let mut store = lock(&self.store);
+        store.next_id += 1;
+
+        let created_log = Log {
+            id: store.next_id,
+            ..log.clone()
+        };
+        store.logs.push(created_log.clone());
+
+        Ok(created_log)
+    }
+
+    async fn update_data(&self, id: i32, log_data: &Value) -> AppResult<Option<Log>> {
+        let mut store = lock(&self.store);
+        let Some(log) = store.logs.iter_mut().find(|log| log.id == id) else {
+            return Ok(None);
+        };
+
+        log.log_data = log_data.clone();
+        Ok(Some(log.clone()))
+    }
+
+    async fn upsert_by_key(&self, log: &Log, key: &str) -> AppResult<(Log, bool)> {
+        let mut store = lock(&self.store);
+        let existing_id = store.keys.get(&(log.schema_id, key.to_string())).copied();
+        if let Some(existing) = store
+            .logs
+            .iter_mut()
+            .find(|stored| Some(stored.id) == existing_id)
+        {
+            existing.log_data = log.log_data.clone();
+            return Ok((existing.clone(), false));
+        }
+
+        store.next_id += 1;
+        let created_log = Log {
+            id: store.next_id,
+            ..log.clone()
+        };
+        store
+            .keys
+            .insert((log.schema_id, key.to_string()), created_log.id);
+        store.logs.push(created_log.clone());
+
+        Ok((created_log, true))
+    }
+
+    async fn delete(&self, id: i32) -> AppResult<Option<Log>> {
+        let mut store = lock(&self.store);
+        let position = store.logs.iter().position(|log| log.id == id);
+        Ok(position.map(|position| store.logs.remove(position)))
+    }
+
+    async fn delete_all_by_schema_id(&self, schema_id: Uuid) -> AppResult<Vec<i32>> {
+        let mut store = lock(&self.store);
+        let ids = store
+            .logs
+            .iter()
+            .filter(|log| log.schema_id == schema_id)
+            .map(|log| log.id)
+            .collect();
+        store.logs.retain(|log| log.schema_id != schema_id);
+        Ok(ids)
+    }
+
+    async fn delete_beyond_count(&self, schema_id: Uuid, keep: i32) -> AppResult<i64> {
+        let mut store = lock(&self.store);
+        let mut newest: Vec<_> = store
+            .logs
+            .iter()
+            .filter(|log| log.schema_id == schema_id)
+            .map(|log| (log.created_at, log.id))
+            .collect();
+        newest.sort_by_key(|key| Reverse(*key));
+        let Some(&(created_at, id)) = newest.get(keep.max(0) as usize) else {
+            return Ok(0);
+        };
+
+        let before = store.logs.len();
+        store.logs.retain(|log| {
+            log.schema_id != schema_id || (log.created_at, log.id) > (created_at, id)
+        });
+        Ok((before - store.logs.len()) as i64)
+    }
+
+    async fn delete_created_before(
+        &self,
+        schema_id: Uuid,
+        cutoff: DateTime<Utc>,
+    ) -> AppResult<i64> {
+        let mut store = lock(&self.store);
+        let before = store.logs.len();
+        store
+            .logs
+            .retain(|log| log.schema_id != schema_id || log.created_at >= cutoff);
+        Ok((before - store.logs.len()) as i64)
+    }
+
+    async fn count_by_schema_id(
+        &self,
+        schema_id: Uuid,
+        query_params: Option<&LogQueryParams>,
+    ) -> AppResult<i64> {
+        Ok(self.matching(schema_id, query_params).len() as i64)
+    }
+
+    async fn get_latest_log_id(&self, schema_id: Uuid) -> AppResult<Option<i32>> {
+        Ok(lock(&self.store)
+            .logs
+            .iter()
+            .filter(|log| log.schema_id == schema_id)
+            .map(|log| log.id)
+            .max())
+    }
+
+    async fn get_range(&self, schema_id: Uuid) -> AppResult<LogRange> {
+        let store = lock(&self.store);
+        let created_at = store
+            .logs
+            .iter()
+            .filter(|log| log.schema_id == schema_id)
+            .map(|log| log.created_at);
+
+        Ok(
+            created_at.fold(LogRange::default(), |range, created_at| LogRange {
+                logs_from: Some(
+                    range
+                        .logs_from
+                        .map_or(created_at, |from| from.min(created_at)),
+                ),
+                logs_to: Some(range.logs_to.map_or(created_at, |to| to.max(created_at))),
+                log_count: range.log_count + 1,
+            }),
+        )
+    }
+
+    async fn count_by_level(
+        &self,
+        schema_id: Uuid,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<LogLevelCount>> {
+        let logs = self.matching(schema_id, None).into_iter().filter(|log| {
+            date_begin.is_none_or(|begin| log.created_at >= begin)
+                && date_end.is_none_or(|end| log.created_at <= end)
+        });
+
+        Ok(Self::level_counts(logs))
+    }
+
+    /// There is no summary table in memory, so the last 24h are counted live.
+    async fn get_level_counts_summary(
+        &self,
+        schema_id: Uuid,
+    ) -> AppResult<(Vec<LogLevelCount>, Option<DateTime<Utc>>)> {
+        let now = Utc::now();
+        let counts = self
+            .count_by_level(schema_id, Some(now - Duration::hours(24)), None)
+            .await?;
+
+        Ok((counts, Some(now)))
+    }
+
+    async fn refresh_level_counts_summary(&self) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn count_by_day(&self, schema_id: Uuid, days: i32) -> AppResult<Vec<DailyLogCount>> {
+        let today = Utc::now().date_naive();
+        let logs = self.matching(schema_id, None);
+
+        Ok((0..days as i64)
+            .rev()
+            .map(|offset| {
+                let day = today - Duration::days(offset);
+                let count = logs
+                    .iter()
+                    .filter(|log| log.created_at.date_naive() == day)
+                    .count() as i64;
+                DailyLogCount { day, count }
+            })
+            .collect())
+    }
+
+    async fn create_field_index(&self, _schema_id: Uuid, _field: &str) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn drop_field_index(&self, _schema_id: Uuid, _field: &str) -> AppResult<()> {
+        Ok(())
+    }
+}
@@ -1,14 +1,20 @@
+use std::borrow::Cow;
+
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{Executor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
+use crate::models::log_model::{compress_log_data, searchable_projection, StoredLog};
 use crate::models::query_params::LogQueryParams;
-use crate::models::Log;
+use crate::models::{DailyLogCount, Log, LogLevelCount, LogRange};
 use crate::repositories::query_builder::LogQueryBuilder;
 
 #[async_trait]
-pub trait LogRepositoryTrait {
+pub trait LogRepositoryTrait: Send + Sync {
     async fn get_all_with_cursor(
         &self,
         schema_id: Uuid,
@@ -17,10 +23,30 @@ pub trait LogRepositoryTrait {
         filters: LogQueryParams,
         forward: bool,
     ) -> AppResult<Vec<Log>>;
+    /// Up to `limit + 1` logs strictly after `after`, ordered by
+    /// `(created_at, id)` ascending.
+    async fn get_after_keyset(
+        &self,
+        schema_id: Uuid,
+        after: Option<(DateTime<Utc>, i32)>,
+        limit: i32,
+    ) -> AppResult<Vec<Log>>;
     async fn get_by_id(&self, id: i32) -> AppResult<Option<Log>>;
     async fn create(&self, log: &Log) -> AppResult<Log>;
+    async fn update_data(&self, id: i32, log_data: &Value) -> AppResult<Option<Log>>;
+    /// Inserts the log under `key`, or replaces the data of the schema's
+    /// existing log with that key. The flag is true when a row was inserted.
+    async fn upsert_by_key(&self, log: &Log, key: &str) -> AppResult<(Log, bool)>;
+    /// Deletes and returns the log in one step; of two concurrent deletes of
+    /// the same log only one gets it back.
     async fn delete(&self, id: i32) -> AppResult<Option<Log>>;
-    async fn delete_all_by_schema_id(&self, schema_id: Uuid) -> AppResult<i64>;
+    /// Deletes every log of a schema and returns their ids.
+    async fn delete_all_by_schema_id(&self, schema_id: Uuid) -> AppResult<Vec<i32>>;
+    /// Deletes all but the `keep` newest logs of a schema.
+    async fn delete_beyond_count(&self, schema_id: Uuid, keep: i32) -> AppResult<i64>;
+    /// Deletes the logs of a schema created before `cutoff`.
+    async fn delete_created_before(&self, schema_id: Uuid, cutoff: DateTime<Utc>)
+        -> AppResult<i64>;
 
     async fn count_by_schema_id(
         &self,
@@ -29,16 +55,114 @@ pub trait LogRepositoryTrait {
     ) -> AppResult<i64>;
 
     async fn get_latest_log_id(&self, schema_id: Uuid) -> AppResult<Option<i32>>;
+    /// Oldest and newest `created_at` of the schema's logs, with their count.
+    async fn get_range(&self, schema_id: Uuid) -> AppResult<LogRange>;
+
+    async fn count_by_level(
+        &self,
+        schema_id: Uuid,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<LogLevelCount>>;
+    async fn get_level_counts_summary(
+        &self,
+        schema_id: Uuid,
+    ) -> AppResult<(Vec<LogLevelCount>, Option<DateTime<Utc>>)>;
+    async fn refresh_level_counts_summary(&self) -> AppResult<()>;
+
+    async fn count_by_day(&self, schema_id: Uuid, days: i32) -> AppResult<Vec<DailyLogCount>>;
+
+    async fn create_field_index(&self, schema_id: Uuid, field: &str) -> AppResult<()>;
+    async fn drop_field_index(&self, schema_id: Uuid, field: &str) -> AppResult<()>;
+}
+
+/// Prefix shared by the names of all per-schema field indexes.
+pub(crate) const FIELD_INDEX_PREFIX: &str = "idx_logs_field_";
+
+/// Deterministic name for the per-schema index on `log_data->>field`, hashed to
+/// stay within Postgres' 63 character identifier limit.
+fn field_index_name(schema_id: Uuid, field: &str) -> String {
+    let digest = Sha256::digest(format!("{}:{}", schema_id, field));
+    let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", FIELD_INDEX_PREFIX, hash)
+}
+
+#[derive(sqlx::FromRow)]
+struct UpsertedLog {
+    #[sqlx(flatten)]
+    log: StoredLog,
+    inserted: bool,
+}
+
+fn into_logs(rows: Vec<StoredLog>) -> AppResult<Vec<Log>> {
+    rows.into_iter().map(StoredLog::into_log).collect()
 }
 
 #[derive(Clone)]
 pub struct LogRepository {
     pool: PgPool,
+    /// Serialized payloads of at least this many bytes are stored
+    /// gzip-compressed; `None` stores everything as JSONB.
+    compression_threshold: Option<usize>,
+    /// `statement_timeout` for filtered and aggregating reads, in
+    /// milliseconds; 0 leaves the server default.
+    statement_timeout_ms: u64,
 }
 
 impl LogRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            compression_threshold: None,
+            statement_timeout_ms: 0,
+        }
+    }
+
+    pub fn with_statement_timeout(mut self, statement_timeout_ms: u64) -> Self {
+        self.statement_timeout_ms = statement_timeout_ms;
+        self
+    }
+
+    pub fn with_compression_threshold(mut self, compression_threshold: Option<usize>) -> Self {
+        self.compression_threshold = compression_threshold;
+        self
+    }
+
+    /// Values for the `(log_data, log_data_compressed)` columns.
+    fn log_data_columns<'a>(
+        &self,
+        log_data: &'a Value,
+    ) -> AppResult<(Cow<'a, Value>, Option<Vec<u8>>)> {
+        let Some(threshold) = self.compression_threshold else {
+            return Ok((Cow::Borrowed(log_data), None));
+        };
+
+        let json = serde_json::to_vec(log_data).map_err(|e| {
+            AppError::internal_error(format!("Failed to serialize log data: {}", e))
+        })?;
+        if json.len() < threshold {
+            return Ok((Cow::Borrowed(log_data), None));
+        }
+
+        Ok((
+            Cow::Owned(searchable_projection(log_data)),
+            Some(compress_log_data(&json)?),
+        ))
+    }
+
+    /// A transaction for a read that a pathological filter or a large schema
+    /// could make slow, with the configured `statement_timeout` applied to it
+    /// alone. Writes and maintenance DDL never carry the timeout.
+    async fn begin_read(&self) -> AppResult<Transaction<'static, Postgres>> {
+        let mut tx = self.pool.begin().await?;
+        if self.statement_timeout_ms > 0 {
+            let sql = format!(
+                "SET LOCAL statement_timeout = {}",
+                self.statement_timeout_ms
+            );
+            tx.execute(sql.as_str()).await?;
+        }
+        Ok(tx)
     }
 }
 
@@ -52,6 +176,7 @@ impl LogRepositoryTrait for LogRepository {
         filters: LogQueryParams,
         forward: bool,
     ) -> AppResult<Vec<Log>> {
+        let mut tx = self.begin_read().await?;
         let fetch_limit = limit + 1;
         let order = if forward { "DESC" } else { "ASC" };
 
@@ -63,48 +188,114 @@ impl LogRepositoryTrait for LogRepository {
             .then_order_by("id", order)
             .limit(fetch_limit)
             .build()
-            .build_query_as::<Log>()
-            .fetch_all(&self.pool)
+            .build_query_as::<StoredLog>()
+            .fetch_all(&mut *tx)
             .await?;
+        tx.commit().await?;
 
-        Ok(logs)
+        into_logs(logs)
+    }
+
+    async fn get_after_keyset(
+        &self,
+        schema_id: Uuid,
+        after: Option<(DateTime<Utc>, i32)>,
+        limit: i32,
+    ) -> AppResult<Vec<Log>> {
+        let mut tx = self.begin_read().await?;
+        let logs = LogQueryBuilder::select()
+            .schema_id(schema_id)
+            .after_keyset(after)
+            .order_by("created_at", "ASC")
+            .then_order_by("id", "ASC")
+            .limit(limit + 1)
+            .build()
+            .build_query_as::<StoredLog>()
+            .fetch_all(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        into_logs(logs)
     }
 
     async fn get_by_id(&self, id: i32) -> AppResult<Option<Log>> {
-        let log = sqlx::query_as::<_, Log>("SELECT * FROM logs WHERE id = $1")
+        let log = sqlx::query_as::<_, StoredLog>("SELECT * FROM logs WHERE id = $1")
             .bind(id)
             .fetch_optional(&self.pool)
             .await?;
 
-        Ok(log)
+        log.map(StoredLog::into_log).transpose()
     }
 
     async fn create(&self, log: &Log) -> AppResult<Log> {
-        let created_log = sqlx::query_as::<_, Log>(
+        let (log_data, log_data_compressed) = self.log_data_columns(&log.log_data)?;
+
+        let created_log = sqlx::query_as::<_, StoredLog>(
             r#"
-            INSERT INTO logs (schema_id, log_data, created_at)
-            VALUES ($1, $2, $3)
+            INSERT INTO logs (schema_id, log_data, log_data_compressed, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING *
             "#,
         )
         .bind(log.schema_id)
-        .bind(&log.log_data)
+        .bind(log_data.as_ref())
+        .bind(log_data_compressed)
+        .bind(log.created_by)
         .bind(log.created_at)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(created_log)
+        created_log.into_log()
     }
 
-    async fn delete(&self, id: i32) -> AppResult<Option<Log>> {
-        let deleted_log = sqlx::query_as::<_, Log>(
-            "DELETE FROM logs WHERE id = $1 RETURNING id, schema_id, log_data, created_at",
+    async fn update_data(&self, id: i32, log_data: &Value) -> AppResult<Option<Log>> {
+        let (log_data, log_data_compressed) = self.log_data_columns(log_data)?;
+
+        let updated_log = sqlx::query_as::<_, StoredLog>(
+            "UPDATE logs SET log_data = $2, log_data_compressed = $3 WHERE id = $1 RETURNING *",
         )
         .bind(id)
+        .bind(log_data.as_ref())
+        .bind(log_data_compressed)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(deleted_log)
+        updated_log.map(StoredLog::into_log).transpose()
+    }
+
+    async fn upsert_by_key(&self, log: &Log, key: &str) -> AppResult<(Log, bool)> {
+        let (log_data, log_data_compressed) = self.log_data_columns(&log.log_data)?;
+
+        // xmax is only zero for a freshly inserted row version.
+        let upserted = sqlx::query_as::<_, UpsertedLog>(
+            r#"
+            INSERT INTO logs (schema_id, log_data, log_data_compressed, created_by, created_at, log_key)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (schema_id, log_key) WHERE log_key IS NOT NULL
+            DO UPDATE SET log_data = EXCLUDED.log_data, log_data_compressed = EXCLUDED.log_data_compressed
+            RETURNING *, (xmax = 0) AS inserted
+            "#,
+        )
+        .bind(log.schema_id)
+        .bind(log_data.as_ref())
+        .bind(log_data_compressed)
+        .bind(log.created_by)
+        .bind(log.created_at)
+        .bind(key)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((upserted.log.into_log()?, upserted.inserted))
+    }
+
+    async fn delete(&self, id: i32) -> AppResult<Option<Log>> {
+        let deleted_log =
+            sqlx::query_as::<_, StoredLog>("DELETE FROM logs WHERE id = $1 RETURNING *")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        deleted_log.map(StoredLog::into_log).transpose()
     }
 
     async fn count_by_schema_id(
@@ -112,20 +303,57 @@ impl LogRepositoryTrait for LogRepository {
         schema_id: Uuid,
         query_params: Option<&LogQueryParams>,
     ) -> AppResult<i64> {
+        let mut tx = self.begin_read().await?;
         let count: i64 = LogQueryBuilder::count()
             .schema_id(schema_id)
             .filters(query_params)
             .build()
             .build_query_scalar()
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await?;
+        tx.commit().await?;
 
         Ok(count)
     }
 
-    async fn delete_all_by_schema_id(&self, schema_id: Uuid) -> AppResult<i64> {
-        let result = sqlx::query("DELETE FROM logs WHERE schema_id = $1")
+    async fn delete_all_by_schema_id(&self, schema_id: Uuid) -> AppResult<Vec<i32>> {
+        let ids = sqlx::query_scalar("DELETE FROM logs WHERE schema_id = $1 RETURNING id")
+            .bind(schema_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(ids)
+    }
+
+    async fn delete_beyond_count(&self, schema_id: Uuid, keep: i32) -> AppResult<i64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM logs
+            WHERE schema_id = $1
+              AND id NOT IN (
+                  SELECT id FROM logs
+                  WHERE schema_id = $1
+                  ORDER BY created_at DESC, id DESC
+                  LIMIT $2
+              )
+            "#,
+        )
+        .bind(schema_id)
+        .bind(keep as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as i64)
+    }
+
+    async fn delete_created_before(
+        &self,
+        schema_id: Uuid,
+        cutoff: DateTime<Utc>,
+    ) -> AppResult<i64> {
+        let result = sqlx::query("DELETE FROM logs WHERE schema_id = $1 AND created_at < $2")
             .bind(schema_id)
+            .bind(cutoff)
             .execute(&self.pool)
             .await?;
 
@@ -147,4 +375,147 @@ impl LogRepositoryTrait for LogRepository {
 
         Ok(result)
     }
+
+    async fn get_range(&self, schema_id: Uuid) -> AppResult<LogRange> {
+        let mut tx = self.begin_read().await?;
+        let range = sqlx::query_as::<_, LogRange>(
+            r#"
+            SELECT
+                MIN(created_at) AS logs_from,
+                MAX(created_at) AS logs_to,
+                COUNT(*) AS log_count
+            FROM logs
+            WHERE schema_id = $1
+            "#,
+        )
+        .bind(schema_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(range)
+    }
+
+    async fn count_by_level(
+        &self,
+        schema_id: Uuid,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<LogLevelCount>> {
+        let mut tx = self.begin_read().await?;
+        let counts = sqlx::query_as::<_, LogLevelCount>(
+            r#"
+            SELECT COALESCE(log_data->>'level', 'UNKNOWN') AS level, COUNT(*) AS count
+            FROM logs
+            WHERE schema_id = $1
+              AND ($2::timestamptz IS NULL OR created_at >= $2)
+              AND ($3::timestamptz IS NULL OR created_at <= $3)
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+        )
+        .bind(schema_id)
+        .bind(date_begin)
+        .bind(date_end)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(counts)
+    }
+
+    async fn get_level_counts_summary(
+        &self,
+        schema_id: Uuid,
+    ) -> AppResult<(Vec<LogLevelCount>, Option<DateTime<Utc>>)> {
+        let rows = sqlx::query_as::<_, (String, i64, DateTime<Utc>)>(
+            r#"
+            SELECT level, count, refreshed_at
+            FROM log_level_counts_24h
+            WHERE schema_id = $1
+            ORDER BY level
+            "#,
+        )
+        .bind(schema_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let refreshed_at = rows.first().map(|(_, _, refreshed_at)| *refreshed_at);
+        let counts = rows
+            .into_iter()
+            .map(|(level, count, _)| LogLevelCount { level, count })
+            .collect();
+
+        Ok((counts, refreshed_at))
+    }
+
+    async fn refresh_level_counts_summary(&self) -> AppResult<()> {
+        sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY log_level_counts_24h")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn count_by_day(&self, schema_id: Uuid, days: i32) -> AppResult<Vec<DailyLogCount>> {
+        let mut tx = self.begin_read().await?;
+        let counts = sqlx::query_as::<_, DailyLogCount>(
+            r#"
+            SELECT series.day::date AS day, COUNT(logs.id) AS count
+            FROM generate_series(
+                date_trunc('day', NOW() AT TIME ZONE 'UTC') - ($2::int - 1) * INTERVAL '1 day',
+                date_trunc('day', NOW() AT TIME ZONE 'UTC'),
+                INTERVAL '1 day'
+            ) AS series(day)
+            LEFT JOIN logs
+                ON logs.schema_id = $1
+                AND date_trunc('day', logs.created_at AT TIME ZONE 'UTC') = series.day
+            GROUP BY series.day
+            ORDER BY series.day
+            "#,
+        )
+        .bind(schema_id)
+        .bind(days)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(counts)
+    }
+
+    /// DDL cannot take bind parameters, so `field` must already be validated as
+    /// a plain identifier; quotes are still escaped as a second guard.
+    ///
+    /// The index is built `CONCURRENTLY` so writes to `logs` are not blocked
+    /// meanwhile. That cannot run inside a transaction, so both statements
+    /// run on one pooled connection. A failed build leaves an invalid index
+    /// behind, which is dropped so the next attempt does not skip it as
+    /// already existing.
+    async fn create_field_index(&self, schema_id: Uuid, field: &str) -> AppResult<()> {
+        let index_name = field_index_name(schema_id, field);
+        let sql = format!(
+            "CREATE INDEX CONCURRENTLY IF NOT EXISTS {} ON logs ((log_data->>'{}')) WHERE schema_id = '{}'",
+            index_name,
+            field.replace('\'', "''"),
+            schema_id
+        );
+
+        let mut conn = self.pool.acquire().await?;
+        if let Err(e) = conn.execute(sql.as_str()).await {
+            let drop_sql = format!("DROP INDEX CONCURRENTLY IF EXISTS {}", index_name);
+            let _ = conn.execute(drop_sql.as_str()).await;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    async fn drop_field_index(&self, schema_id: Uuid, field: &str) -> AppResult<()> {
+        let sql = format!(
+            "DROP INDEX IF EXISTS {}",
+            field_index_name(schema_id, field)
+        );
+
+        sqlx::query(&sql).execute(&self.pool).await?;
+        Ok(())
+    }
 }
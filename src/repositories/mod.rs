@@ -1,8 +1,10 @@
 pub mod api_key_repository;
+pub mod in_memory;
 pub mod log_repository;
 pub mod query_builder;
 pub mod schema_repository;
 
 pub use api_key_repository::ApiKeyRepository;
+pub use in_memory::{InMemoryLogRepository, InMemorySchemaRepository};
 pub use log_repository::LogRepository;
 pub use schema_repository::SchemaRepository;
@@ -1,7 +1,12 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::{Postgres, QueryBuilder};
 use uuid::Uuid;
 
-use crate::models::{query_params::LogQueryParams, SchemaQueryParams};
+use crate::models::{
+    query_params::{JsonFilter, LogQueryParams},
+    FilterOp, LogFilter, SchemaQueryParams,
+};
 
 macro_rules! impl_common_builder_methods {
     ($builder:ty) => {
@@ -64,8 +69,21 @@ impl<'a> SchemaQueryBuilder<'a> {
         }
     }
 
+    pub fn count() -> Self {
+        let query = QueryBuilder::new("SELECT COUNT(*) FROM schemas");
+        Self {
+            query,
+            conditions_added: false,
+        }
+    }
+
     pub fn filters(mut self, params: Option<&'a SchemaQueryParams>) -> Self {
         if let Some(query_params) = params {
+            if let Some(tenant_id) = &query_params.tenant_id {
+                self.add_condition();
+                self.query.push("tenant_id = ");
+                self.query.push_bind(tenant_id);
+            }
             if let Some(name) = &query_params.name {
                 self.add_condition();
                 self.query.push("name = ");
@@ -76,6 +94,20 @@ impl<'a> SchemaQueryBuilder<'a> {
                 self.query.push("version = ");
                 self.query.push_bind(version);
             }
+            if let Some(pattern) = query_params.search_pattern() {
+                self.add_condition();
+                self.query.push("(name ILIKE ");
+                self.query.push_bind(pattern.clone());
+                self.query.push(" OR description ILIKE ");
+                self.query.push_bind(pattern);
+                self.query.push(")");
+            }
+            if let Some(label) = &query_params.label {
+                self.add_condition();
+                self.query.push("labels @> ARRAY[");
+                self.query.push_bind(label);
+                self.query.push("]");
+            }
         }
         self
     }
@@ -114,6 +146,13 @@ impl<'a> SchemaQueryBuilder<'a> {
 
 impl_common_builder_methods!(SchemaQueryBuilder<'a>);
 
+/// Pushes `log_data ->> 'field'` with the field inlined: an expression index
+/// only matches a literal key, not a bind parameter. Indexed field names are
+/// validated as plain identifiers; quotes are still escaped as a second guard.
+fn push_indexed_field(query: &mut QueryBuilder<'_, Postgres>, field: &str) {
+    query.push(format!("(log_data ->> '{}')", field.replace('\'', "''")));
+}
+
 pub struct LogQueryBuilder<'a> {
     query: QueryBuilder<'a, Postgres>,
     conditions_added: bool,
@@ -145,24 +184,167 @@ impl<'a> LogQueryBuilder<'a> {
 
     pub fn filters(mut self, params: Option<&'a LogQueryParams>) -> Self {
         if let Some(query_params) = params {
-            if let Some(filter_value) = &query_params.json_filters {
-                if filter_value.as_object().is_some() {
-                    self.add_condition();
-                    self.query.push("log_data @> ");
-                    self.query.push_bind(filter_value);
+            for condition in query_params.json_filter_conditions() {
+                self.add_condition();
+                match condition {
+                    JsonFilter::Contains(value) => {
+                        self.query.push("log_data @> ");
+                        self.query.push_bind(value.clone());
+                        self.push_indexed_equalities(&value, &query_params.indexed_fields);
+                    }
+                    JsonFilter::ArrayContains { field, elements } => {
+                        self.query.push("log_data -> ");
+                        self.query.push_bind(field);
+                        self.query.push(" @> ");
+                        self.query.push_bind(elements);
+                    }
+                    JsonFilter::In { field, values } => {
+                        if query_params.indexed_fields.contains(&field) {
+                            push_indexed_field(&mut self.query, &field);
+                        } else {
+                            self.query.push("log_data ->> ");
+                            self.query.push_bind(field);
+                        }
+                        self.query.push(" = ANY(");
+                        self.query.push_bind(values);
+                        self.query.push(")");
+                    }
                 }
             }
-            if let (Some(begin), Some(end)) = (query_params.date_begin, query_params.date_end) {
+            if let Some(begin) = query_params.date_begin {
                 self.add_condition();
-                self.query.push("created_at BETWEEN ");
+                self.query.push("created_at >= ");
                 self.query.push_bind(begin);
-                self.query.push(" AND ");
+            }
+            if let Some(end) = query_params.date_end {
+                self.add_condition();
+                self.query.push("created_at <= ");
                 self.query.push_bind(end);
             }
+            if let Some(created_by) = query_params.created_by {
+                self.add_condition();
+                self.query.push("created_by = ");
+                self.query.push_bind(created_by);
+            }
+            if let Some(anchor) = query_params.anchor {
+                self.add_condition();
+                self.query.push("id <= ");
+                self.query.push_bind(anchor);
+            }
+            if let Some(expression) = &query_params.expression {
+                self.add_condition();
+                self.push_expression(expression);
+            }
         }
         self
     }
 
+    /// Repeats the string and boolean values of a containment filter on
+    /// indexed fields as `log_data ->> 'field' = $n`, the form their
+    /// expression indexes match; `@>` alone cannot use them. Numbers are left
+    /// to `@>`, as their text form depends on how they were written.
+    fn push_indexed_equalities(&mut self, contains: &Value, indexed_fields: &[String]) {
+        let Value::Object(fields) = contains else {
+            return;
+        };
+
+        for (field, value) in fields {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                Value::Bool(flag) => flag.to_string(),
+                _ => continue,
+            };
+            if indexed_fields.contains(field) {
+                self.query.push(" AND ");
+                push_indexed_field(&mut self.query, field);
+                self.query.push(" = ");
+                self.query.push_bind(text);
+            }
+        }
+    }
+
+    /// Renders a filter expression with every value bound as a parameter.
+    /// Predicates are wrapped in `COALESCE(.., FALSE)` so a missing field
+    /// yields false rather than NULL, keeping `NOT` two-valued.
+    fn push_expression(&mut self, expression: &LogFilter) {
+        match expression {
+            LogFilter::And(children) | LogFilter::Or(children) => {
+                let separator = if matches!(expression, LogFilter::And(_)) {
+                    " AND "
+                } else {
+                    " OR "
+                };
+                self.query.push("(");
+                for (index, child) in children.iter().enumerate() {
+                    if index > 0 {
+                        self.query.push(separator);
+                    }
+                    self.push_expression(child);
+                }
+                self.query.push(")");
+            }
+            LogFilter::Not(child) => {
+                self.query.push("NOT ");
+                self.push_expression(child);
+            }
+            LogFilter::Field { path, op, value } => {
+                let push_field = |query: &mut QueryBuilder<'a, Postgres>| {
+                    query.push("log_data #> ");
+                    query.push_bind(path.clone());
+                };
+
+                match op {
+                    FilterOp::Eq | FilterOp::Ne => {
+                        // A missing field yields NULL, so neither matches it.
+                        self.query.push("COALESCE(");
+                        push_field(&mut self.query);
+                        self.query
+                            .push(if *op == FilterOp::Ne { " <> " } else { " = " });
+                        self.query.push_bind(value.clone());
+                        self.query.push(", FALSE)");
+                    }
+                    FilterOp::Gt | FilterOp::Gte | FilterOp::Lt | FilterOp::Lte => {
+                        // JSONB orders values of different types by type, so
+                        // only compare against fields of the value's type.
+                        self.query.push("COALESCE(jsonb_typeof(");
+                        push_field(&mut self.query);
+                        self.query.push(") = jsonb_typeof(");
+                        self.query.push_bind(value.clone());
+                        self.query.push(") AND ");
+                        push_field(&mut self.query);
+                        self.query.push(op.sql_comparison().unwrap_or(" = "));
+                        self.query.push_bind(value.clone());
+                        self.query.push(", FALSE)");
+                    }
+                    FilterOp::In => {
+                        self.query
+                            .push("EXISTS (SELECT 1 FROM jsonb_array_elements(");
+                        self.query.push_bind(value.clone());
+                        self.query.push(") AS candidate WHERE candidate = ");
+                        push_field(&mut self.query);
+                        self.query.push(")");
+                    }
+                    FilterOp::Contains => {
+                        self.query.push("COALESCE(");
+                        push_field(&mut self.query);
+                        self.query.push(" @> ");
+                        self.query.push_bind(value.clone());
+                        self.query.push(", FALSE)");
+                    }
+                    FilterOp::Exists => {
+                        self.query.push("(");
+                        push_field(&mut self.query);
+                        if value.as_bool().unwrap_or(true) {
+                            self.query.push(" IS NOT NULL)");
+                        } else {
+                            self.query.push(" IS NULL)");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     pub fn cursor(mut self, cursor_id: Option<i32>, forward: bool) -> Self {
         if let Some(id) = cursor_id {
             self.add_condition();
@@ -191,6 +373,19 @@ impl<'a> LogQueryBuilder<'a> {
         }
         self
     }
+
+    /// Rows strictly after `(created_at, id)` in ascending keyset order.
+    pub fn after_keyset(mut self, after: Option<(DateTime<Utc>, i32)>) -> Self {
+        if let Some((created_at, id)) = after {
+            self.add_condition();
+            self.query.push("(created_at, id) > (");
+            self.query.push_bind(created_at);
+            self.query.push(", ");
+            self.query.push_bind(id);
+            self.query.push(")");
+        }
+        self
+    }
 }
 
 impl_common_builder_methods!(LogQueryBuilder<'a>);
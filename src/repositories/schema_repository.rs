@@ -1,12 +1,14 @@
 use crate::error::AppResult;
-use crate::models::{Schema, SchemaQueryParams};
+use crate::models::{PurgedCounts, RetentionPolicy, Schema, SchemaLogCount, SchemaQueryParams};
+use crate::repositories::log_repository::FIELD_INDEX_PREFIX;
 use crate::repositories::query_builder::SchemaQueryBuilder;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 #[async_trait]
-pub trait SchemaRepositoryTrait {
+pub trait SchemaRepositoryTrait: Send + Sync {
     async fn get_all(&self, params: Option<SchemaQueryParams>) -> AppResult<Vec<Schema>>;
     async fn get_all_with_cursor(
         &self,
@@ -15,14 +17,48 @@ pub trait SchemaRepositoryTrait {
         filters: SchemaQueryParams,
         forward: bool,
     ) -> AppResult<Vec<Schema>>;
-    async fn get_by_id(&self, id: Uuid) -> AppResult<Option<Schema>>;
-    async fn get_by_name_latest(&self, name: &str) -> AppResult<Option<Schema>>;
-    async fn get_by_name_and_version(&self, name: &str, version: &str)
-        -> AppResult<Option<Schema>>;
-    async fn get_latest_schema_id(&self) -> AppResult<Option<Uuid>>;
+    async fn count(&self, filters: Option<&SchemaQueryParams>) -> AppResult<i64>;
+    /// `tenant_id` of `None` looks the schema up across all tenants; it is
+    /// only meant for internal use such as webhook dispatch.
+    async fn get_by_id(&self, id: Uuid, tenant_id: Option<&str>) -> AppResult<Option<Schema>>;
+    async fn get_by_name_latest(&self, name: &str, tenant_id: &str) -> AppResult<Option<Schema>>;
+    async fn get_by_ids(&self, ids: &[Uuid], tenant_id: &str) -> AppResult<Vec<Schema>>;
+    async fn get_by_name_and_version(
+        &self,
+        name: &str,
+        version: &str,
+        tenant_id: &str,
+    ) -> AppResult<Option<Schema>>;
+    async fn get_latest_schema_id(&self, tenant_id: &str) -> AppResult<Option<Uuid>>;
+    /// Retention rules of every schema with `max_retained_logs` or
+    /// `max_log_age_secs` set.
+    async fn get_retention_policies(&self) -> AppResult<Vec<RetentionPolicy>>;
     async fn create(&self, schema: &Schema) -> AppResult<Schema>;
+    /// Only updates the schema if it belongs to `schema.tenant_id`.
     async fn update(&self, id: Uuid, schema: &Schema) -> AppResult<Option<Schema>>;
-    async fn delete(&self, id: Uuid) -> AppResult<Option<Schema>>;
+    async fn delete(&self, id: Uuid, tenant_id: &str) -> AppResult<Option<Schema>>;
+
+    /// Deletes the schema together with all of its logs in one step,
+    /// returning the schema and the deleted log ids; nothing is deleted when
+    /// the schema is not found.
+    async fn delete_with_logs(
+        &self,
+        id: Uuid,
+        tenant_id: &str,
+    ) -> AppResult<Option<(Schema, Vec<i32>)>>;
+
+    /// Deletes every log and schema of every tenant along with the schemas'
+    /// field indexes, and every API key when `include_api_keys` is set.
+    async fn purge_all(&self, include_api_keys: bool) -> AppResult<PurgedCounts>;
+
+    /// Log counts of every schema of the tenant, including schemas without
+    /// logs, ordered by name and version.
+    async fn count_logs_by_schema(
+        &self,
+        tenant_id: &str,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<SchemaLogCount>>;
 }
 
 #[derive(Clone)]
@@ -42,6 +78,7 @@ impl SchemaRepositoryTrait for SchemaRepository {
         let schemas = SchemaQueryBuilder::select()
             .filters(params.as_ref())
             .order_by("created_at", "DESC")
+            .then_order_by("id", "DESC")
             .build()
             .build_query_as::<Schema>()
             .fetch_all(&self.pool)
@@ -74,12 +111,23 @@ impl SchemaRepositoryTrait for SchemaRepository {
         Ok(schemas)
     }
 
-    async fn get_by_name_latest(&self, name: &str) -> AppResult<Option<Schema>> {
+    async fn count(&self, filters: Option<&SchemaQueryParams>) -> AppResult<i64> {
+        let count: i64 = SchemaQueryBuilder::count()
+            .filters(filters)
+            .build()
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn get_by_name_latest(&self, name: &str, tenant_id: &str) -> AppResult<Option<Schema>> {
         let schema = sqlx::query_as::<_, Schema>(
             r#"
             SELECT *
             FROM schemas
-            WHERE name = $1
+            WHERE name = $1 AND tenant_id = $2
             ORDER BY
                 (string_to_array(version, '.'))[1]::int DESC,
                 (string_to_array(version, '.'))[2]::int DESC,
@@ -88,54 +136,89 @@ impl SchemaRepositoryTrait for SchemaRepository {
             "#,
         )
         .bind(name)
+        .bind(tenant_id)
         .fetch_optional(&self.pool)
         .await?;
         Ok(schema)
     }
 
-    async fn get_by_id(&self, id: Uuid) -> AppResult<Option<Schema>> {
-        let schema = sqlx::query_as::<_, Schema>("SELECT * FROM schemas WHERE id = $1")
-            .bind(id)
-            .fetch_optional(&self.pool)
-            .await?;
+    async fn get_by_id(&self, id: Uuid, tenant_id: Option<&str>) -> AppResult<Option<Schema>> {
+        let schema = sqlx::query_as::<_, Schema>(
+            "SELECT * FROM schemas WHERE id = $1 AND ($2::text IS NULL OR tenant_id = $2)",
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
         Ok(schema)
     }
 
+    async fn get_by_ids(&self, ids: &[Uuid], tenant_id: &str) -> AppResult<Vec<Schema>> {
+        let schemas = sqlx::query_as::<_, Schema>(
+            "SELECT * FROM schemas WHERE id = ANY($1) AND tenant_id = $2 ORDER BY created_at, id",
+        )
+        .bind(ids)
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(schemas)
+    }
+
     async fn get_by_name_and_version(
         &self,
         name: &str,
         version: &str,
+        tenant_id: &str,
     ) -> AppResult<Option<Schema>> {
-        let schema =
-            sqlx::query_as::<_, Schema>("SELECT * FROM schemas WHERE name = $1 AND version = $2")
-                .bind(name)
-                .bind(version)
-                .fetch_optional(&self.pool)
-                .await?;
+        let schema = sqlx::query_as::<_, Schema>(
+            "SELECT * FROM schemas WHERE name = $1 AND version = $2 AND tenant_id = $3",
+        )
+        .bind(name)
+        .bind(version)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
         Ok(schema)
     }
 
-    async fn get_latest_schema_id(&self) -> AppResult<Option<Uuid>> {
+    async fn get_latest_schema_id(&self, tenant_id: &str) -> AppResult<Option<Uuid>> {
         let result = sqlx::query_scalar::<_, Uuid>(
             r#"
             SELECT id FROM schemas
-            ORDER BY created_at DESC
+            WHERE tenant_id = $1
+            ORDER BY created_at DESC, id DESC
             LIMIT 1
             "#,
         )
+        .bind(tenant_id)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(result)
     }
 
+    async fn get_retention_policies(&self) -> AppResult<Vec<RetentionPolicy>> {
+        let policies = sqlx::query_as::<_, RetentionPolicy>(
+            r#"
+            SELECT id AS schema_id, max_retained_logs, max_log_age_secs
+            FROM schemas
+            WHERE max_retained_logs IS NOT NULL OR max_log_age_secs IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(policies)
+    }
+
     async fn create(&self, schema: &Schema) -> AppResult<Schema> {
         let created_schema = sqlx::query_as::<_, Schema>(
             r#"
-            INSERT INTO schemas (id, name, version, description, schema_definition, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO schemas (id, name, version, description, schema_definition, webhook_url, webhook_secret, strict_formats, indexed_fields, max_retained_logs, redacted_fields, sample_rate, labels, validation_mode, max_field_bytes, tenant_id, created_by, created_at, updated_at, max_log_age_secs)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
             RETURNING *
             "#
         )
@@ -144,8 +227,21 @@ impl SchemaRepositoryTrait for SchemaRepository {
         .bind(&schema.version)
         .bind(&schema.description)
         .bind(&schema.schema_definition)
+        .bind(&schema.webhook_url)
+        .bind(&schema.webhook_secret)
+        .bind(schema.strict_formats)
+        .bind(&schema.indexed_fields)
+        .bind(schema.max_retained_logs)
+        .bind(&schema.redacted_fields)
+        .bind(schema.sample_rate)
+        .bind(&schema.labels)
+        .bind(schema.validation_mode)
+        .bind(schema.max_field_bytes)
+        .bind(&schema.tenant_id)
+        .bind(schema.created_by)
         .bind(schema.created_at)
         .bind(schema.updated_at)
+        .bind(schema.max_log_age_secs)
         .fetch_one(&self.pool)
         .await?;
 
@@ -156,8 +252,12 @@ impl SchemaRepositoryTrait for SchemaRepository {
         let updated_schema = sqlx::query_as::<_, Schema>(
             r#"
             UPDATE schemas 
-            SET name = $2, version = $3, description = $4, schema_definition = $5, updated_at = $6
-            WHERE id = $1
+            SET name = $2, version = $3, description = $4, schema_definition = $5,
+                webhook_url = $6, webhook_secret = $7, strict_formats = $8, indexed_fields = $9,
+                max_retained_logs = $10, redacted_fields = $11, sample_rate = $12,
+                labels = $13, validation_mode = $14, max_field_bytes = $15, updated_at = $16,
+                max_log_age_secs = $18
+            WHERE id = $1 AND tenant_id = $17
             RETURNING *
             "#,
         )
@@ -166,20 +266,150 @@ impl SchemaRepositoryTrait for SchemaRepository {
         .bind(&schema.version)
         .bind(&schema.description)
         .bind(&schema.schema_definition)
+        .bind(&schema.webhook_url)
+        .bind(&schema.webhook_secret)
+        .bind(schema.strict_formats)
+        .bind(&schema.indexed_fields)
+        .bind(schema.max_retained_logs)
+        .bind(&schema.redacted_fields)
+        .bind(schema.sample_rate)
+        .bind(&schema.labels)
+        .bind(schema.validation_mode)
+        .bind(schema.max_field_bytes)
         .bind(schema.updated_at)
+        .bind(&schema.tenant_id)
+        .bind(schema.max_log_age_secs)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(updated_schema)
     }
 
-    async fn delete(&self, id: Uuid) -> AppResult<Option<Schema>> {
-        let deleted_schema =
-            sqlx::query_as::<_, Schema>("DELETE FROM schemas WHERE id = $1 RETURNING *")
-                .bind(id)
-                .fetch_optional(&self.pool)
-                .await?;
+    async fn delete(&self, id: Uuid, tenant_id: &str) -> AppResult<Option<Schema>> {
+        let deleted_schema = sqlx::query_as::<_, Schema>(
+            "DELETE FROM schemas WHERE id = $1 AND tenant_id = $2 RETURNING *",
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
         Ok(deleted_schema)
     }
+
+    async fn delete_with_logs(
+        &self,
+        id: Uuid,
+        tenant_id: &str,
+    ) -> AppResult<Option<(Schema, Vec<i32>)>> {
+        let mut tx = self.pool.begin().await?;
+
+        let log_ids = sqlx::query_scalar(
+            r#"
+            DELETE FROM logs
+            WHERE schema_id = (SELECT id FROM schemas WHERE id = $1 AND tenant_id = $2)
+            RETURNING id
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let deleted_schema = sqlx::query_as::<_, Schema>(
+            "DELETE FROM schemas WHERE id = $1 AND tenant_id = $2 RETURNING *",
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // Dropping `tx` without committing rolls both deletes back.
+        let Some(schema) = deleted_schema else {
+            return Ok(None);
+        };
+        tx.commit().await?;
+
+        Ok(Some((schema, log_ids)))
+    }
+
+    async fn purge_all(&self, include_api_keys: bool) -> AppResult<PurgedCounts> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locking first keeps the counts exact: nothing can be written
+        // between counting and truncating.
+        let tables = if include_api_keys {
+            "logs, schemas, api_keys"
+        } else {
+            "logs, schemas"
+        };
+        sqlx::query(&format!("LOCK TABLE {} IN ACCESS EXCLUSIVE MODE", tables))
+            .execute(&mut *tx)
+            .await?;
+
+        let (logs, schemas, api_keys): (i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM logs),
+                (SELECT COUNT(*) FROM schemas),
+                CASE WHEN $1 THEN (SELECT COUNT(*) FROM api_keys) ELSE 0 END
+            "#,
+        )
+        .bind(include_api_keys)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Dropped by name rather than from the schemas' `indexed_fields`, so
+        // indexes of schemas created after a listing are not left behind.
+        let indexes: Vec<String> = sqlx::query_scalar(
+            "SELECT indexname FROM pg_indexes WHERE tablename = 'logs' AND starts_with(indexname, $1)",
+        )
+        .bind(FIELD_INDEX_PREFIX)
+        .fetch_all(&mut *tx)
+        .await?;
+        for index in indexes {
+            sqlx::query(&format!("DROP INDEX IF EXISTS {}", index))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(&format!("TRUNCATE {}", tables))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(PurgedCounts {
+            logs,
+            schemas,
+            api_keys,
+        })
+    }
+
+    async fn count_logs_by_schema(
+        &self,
+        tenant_id: &str,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<SchemaLogCount>> {
+        let counts = sqlx::query_as::<_, SchemaLogCount>(
+            r#"
+            SELECT schemas.id AS schema_id, schemas.name, schemas.version, COUNT(logs.id) AS count
+            FROM schemas
+            LEFT JOIN logs
+                ON logs.schema_id = schemas.id
+                AND ($2::timestamptz IS NULL OR logs.created_at >= $2)
+                AND ($3::timestamptz IS NULL OR logs.created_at <= $3)
+            WHERE schemas.tenant_id = $1
+            GROUP BY schemas.id, schemas.name, schemas.version
+            ORDER BY schemas.name, schemas.version
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(date_begin)
+        .bind(date_end)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
 }
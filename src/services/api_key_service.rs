@@ -1,26 +1,90 @@
-use std::{net::IpAddr, sync::Arc};
+use std::{
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
 use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 use rand::{rng, RngCore};
 use sha2::{Digest, Sha256};
+use sqlx::types::ipnetwork::IpNetwork;
 
 use crate::{
+    config::{defaults::DEFAULT_API_KEY_CACHE_TTL_SECS, ApiKeyFormat, ApiKeyHashAlgorithm},
     models::{
         api_key_model::{CreatedApiKey, NewApiKey},
-        ApiKey, CreateApiKey,
+        ApiKey, ApiKeyStats, CreateApiKey,
     },
     repositories::ApiKeyRepository,
-    AppError, AppResult,
+    AppError, AppResult, ErrorCode,
 };
 
 #[derive(Clone)]
 pub struct ApiKeyService {
     api_key_repository: Arc<ApiKeyRepository>,
+    key_format: ApiKeyFormat,
+    hash_algorithm: ApiKeyHashAlgorithm,
+    /// Keys validated by [`Self::authenticate`], by SHA-256 of the plain key.
+    cache: Arc<DashMap<String, CachedApiKey>>,
+    cache_ttl: Duration,
+    max_keys: Option<u64>,
 }
 
+#[derive(Clone)]
+struct CachedApiKey {
+    api_key: ApiKey,
+    cached_at: Instant,
+}
+
+/// Stored hashes starting with this are Argon2 PHC strings; anything else is
+/// SHA-256 hex.
+const ARGON2_HASH_TAG: &str = "$argon2";
+const ARGON2_SALT_BYTES: usize = 16;
+/// Fresh keys drawn before giving up on finding an unused salted prefix.
+const MAX_PREFIX_ATTEMPTS: usize = 5;
+
 impl ApiKeyService {
     pub fn new(api_key_repository: Arc<ApiKeyRepository>) -> Self {
-        Self { api_key_repository }
+        Self {
+            api_key_repository,
+            key_format: ApiKeyFormat::default(),
+            hash_algorithm: ApiKeyHashAlgorithm::default(),
+            cache: Arc::new(DashMap::new()),
+            cache_ttl: Duration::from_secs(DEFAULT_API_KEY_CACHE_TTL_SECS),
+            max_keys: None,
+        }
+    }
+
+    pub fn with_key_format(mut self, key_format: ApiKeyFormat) -> Self {
+        self.key_format = key_format;
+        self
+    }
+
+    pub fn with_hash_algorithm(mut self, hash_algorithm: ApiKeyHashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    /// How long an authenticated key is served from memory before it is
+    /// looked up again; zero disables the cache. Changes made through this
+    /// service invalidate it immediately, others (another instance, direct
+    /// SQL) take effect within the TTL.
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// Caps the number of stored keys, revoked ones included; `None` is
+    /// unlimited.
+    pub fn with_max_keys(mut self, max_keys: Option<u64>) -> Self {
+        self.max_keys = max_keys;
+        self
     }
 
     pub fn hash_key(key: &str) -> String {
@@ -29,25 +93,111 @@ impl ApiKeyService {
         format!("{:x}", hasher.finalize())
     }
 
-    pub fn generate_key() -> String {
-        let mut random_bytes = [0u8; 32];
+    fn hash_key_with(algorithm: ApiKeyHashAlgorithm, key: &str) -> AppResult<String> {
+        match algorithm {
+            ApiKeyHashAlgorithm::Sha256 => Ok(Self::hash_key(key)),
+            ApiKeyHashAlgorithm::Argon2 => {
+                let mut salt = [0u8; ARGON2_SALT_BYTES];
+                rng().fill_bytes(&mut salt);
+                let salt = SaltString::encode_b64(&salt)
+                    .map_err(|e| AppError::internal_error(format!("Invalid salt: {}", e)))?;
+
+                Argon2::default()
+                    .hash_password(key.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|e| AppError::internal_error(format!("Failed to hash key: {}", e)))
+            }
+        }
+    }
+
+    /// Checks `plain_key` against a stored hash, using the algorithm the hash
+    /// is tagged with.
+    pub fn verify_key(plain_key: &str, key_hash: &str) -> bool {
+        if !key_hash.starts_with(ARGON2_HASH_TAG) {
+            return Self::hash_key(plain_key) == key_hash;
+        }
+
+        PasswordHash::new(key_hash)
+            .map(|hash| {
+                Argon2::default()
+                    .verify_password(plain_key.as_bytes(), &hash)
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Hashes a newly generated key with the configured algorithm. Argon2 is
+    /// deliberately slow, so hashing runs off the async runtime.
+    async fn hash_new_key(&self, key: &str) -> AppResult<String> {
+        let algorithm = self.hash_algorithm;
+        let key = key.to_string();
+
+        tokio::task::spawn_blocking(move || Self::hash_key_with(algorithm, &key))
+            .await
+            .map_err(|e| AppError::internal_error(format!("Key hashing task failed: {}", e)))?
+    }
+
+    pub fn generate_key(&self) -> String {
+        let mut random_bytes = vec![0u8; self.key_format.random_bytes];
         rng().fill_bytes(&mut random_bytes);
 
         format!(
-            "sk_{}",
+            "{}{}",
+            self.key_format.prefix,
             general_purpose::URL_SAFE_NO_PAD.encode(random_bytes)
         )
     }
 
+    /// A new key whose stored prefix no salted key uses yet, so salted
+    /// lookups by prefix stay unambiguous. SHA-256 keys are looked up by hash
+    /// and may share prefixes.
+    async fn generate_unused_key(&self) -> AppResult<String> {
+        if self.hash_algorithm == ApiKeyHashAlgorithm::Sha256 {
+            return Ok(self.generate_key());
+        }
+
+        for _ in 0..MAX_PREFIX_ATTEMPTS {
+            let key = self.generate_key();
+            let taken = self
+                .api_key_repository
+                .salted_prefix_exists(&self.key_format.display_prefix(&key))
+                .await
+                .map_err(|e| e.context("Failed to check API key prefix"))?;
+            if !taken {
+                return Ok(key);
+            }
+        }
+
+        Err(AppError::internal_error(
+            "Failed to generate an API key with an unused prefix",
+        ))
+    }
+
     pub async fn create_api_key(&self, request: CreateApiKey) -> AppResult<CreatedApiKey> {
         if request.name.trim().is_empty() {
             return Err(AppError::bad_request("API key name cannot be empty"));
         }
 
-        let api_key_plain = Self::generate_key();
+        if let Some(max) = self.max_keys {
+            let count = self
+                .api_key_repository
+                .count()
+                .await
+                .map_err(|e| e.context("Failed to count API keys"))?;
 
-        let api_key_hash = Self::hash_key(&api_key_plain);
-        let api_key_prefix = Some(format!("{}...", &api_key_plain[..10]));
+            if count as u64 >= max {
+                return Err(
+                    AppError::forbidden(format!("API key limit of {} reached", max))
+                        .with_code(ErrorCode::LimitReached)
+                        .with_detail("limit", max),
+                );
+            }
+        }
+
+        let api_key_plain = self.generate_unused_key().await?;
+
+        let api_key_hash = self.hash_new_key(&api_key_plain).await?;
+        let api_key_prefix = Some(self.key_format.display_prefix(&api_key_plain));
         let allowed_ips: Option<Vec<IpAddr>> = request
             .allowed_ips
             .map(|networks| networks.into_iter().map(|net| net.ip()).collect());
@@ -61,6 +211,7 @@ impl ApiKeyService {
             allowed_ips,
             rate_limit_per_second: request.rate_limit_per_second,
             rate_limit_burst: request.rate_limit_burst,
+            tenant_id: request.tenant_id,
         };
 
         let api_key = self
@@ -78,15 +229,16 @@ impl ApiKeyService {
     pub async fn rotate_api_key(&self, id: i32) -> AppResult<CreatedApiKey> {
         let _ = self.find_by_id(id).await?;
 
-        let new_plain_key = Self::generate_key();
-        let new_key_hash = Self::hash_key(&new_plain_key);
-        let new_key_prefix = Some(format!("{}...", &new_plain_key[..10]));
+        let new_plain_key = self.generate_unused_key().await?;
+        let new_key_hash = self.hash_new_key(&new_plain_key).await?;
+        let new_key_prefix = Some(self.key_format.display_prefix(&new_plain_key));
 
         let rotated_key = self
             .api_key_repository
             .rotate(id, &new_key_hash, new_key_prefix)
             .await
             .map_err(|e| e.context(format!("Failed to rotate API key {}", id)))?;
+        self.invalidate_cached(id);
 
         Ok(CreatedApiKey {
             api_key: rotated_key,
@@ -94,6 +246,26 @@ impl ApiKeyService {
         })
     }
 
+    /// Rotates every active key expiring by `until`, soonest first. Expiry
+    /// dates are kept; only the secrets change.
+    pub async fn rotate_expiring_api_keys(
+        &self,
+        until: DateTime<Utc>,
+    ) -> AppResult<Vec<CreatedApiKey>> {
+        let expiring = self
+            .api_key_repository
+            .get_active_expiring_before(until)
+            .await
+            .map_err(|e| e.context("Failed to list expiring API keys"))?;
+
+        let mut rotated = Vec::with_capacity(expiring.len());
+        for api_key in expiring {
+            rotated.push(self.rotate_api_key(api_key.id).await?);
+        }
+
+        Ok(rotated)
+    }
+
     pub async fn find_by_id(&self, id: i32) -> AppResult<ApiKey> {
         self.api_key_repository
             .get_by_id(id)
@@ -102,12 +274,83 @@ impl ApiKeyService {
             .ok_or_else(|| AppError::not_found(format!("Api key with id {} not found", id)))
     }
 
-    pub async fn find_valid_by_hash(&self, key_hash: &str) -> AppResult<ApiKey> {
-        self.api_key_repository
-            .get_valid_by_hash(key_hash)
+    /// Finds the valid key matching `plain_key`, from the cache while its
+    /// entry is fresh.
+    pub async fn authenticate(&self, plain_key: &str) -> AppResult<ApiKey> {
+        let lookup_hash = Self::hash_key(plain_key);
+
+        if let Some(api_key) = self.get_cached(&lookup_hash) {
+            return Ok(api_key);
+        }
+
+        let api_key = self.authenticate_uncached(plain_key, &lookup_hash).await?;
+
+        if !self.cache_ttl.is_zero() {
+            self.cache.insert(
+                lookup_hash,
+                CachedApiKey {
+                    api_key: api_key.clone(),
+                    cached_at: Instant::now(),
+                },
+            );
+        }
+
+        Ok(api_key)
+    }
+
+    fn get_cached(&self, lookup_hash: &str) -> Option<ApiKey> {
+        let is_fresh = |cached: &CachedApiKey| {
+            cached.cached_at.elapsed() < self.cache_ttl && !cached.api_key.is_expired()
+        };
+
+        if let Some(cached) = self.cache.get(lookup_hash) {
+            if is_fresh(&cached) {
+                return Some(cached.api_key.clone());
+            }
+        }
+
+        self.cache
+            .remove_if(lookup_hash, |_, cached| !is_fresh(cached));
+        None
+    }
+
+    /// Forgets every cached key, for when the keys were removed behind the
+    /// service's back.
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    fn invalidate_cached(&self, id: i32) {
+        self.cache.retain(|_, cached| cached.api_key.id != id);
+    }
+
+    /// SHA-256 hashes are looked up directly; a salted hash is found by its
+    /// unique stored prefix and verified, so at most one Argon2 verification
+    /// runs per request.
+    async fn authenticate_uncached(&self, plain_key: &str, lookup_hash: &str) -> AppResult<ApiKey> {
+        if let Some(api_key) = self
+            .api_key_repository
+            .get_valid_by_hash(lookup_hash)
+            .await
+            .map_err(|e| e.context("Failed to validate API key"))?
+        {
+            return Ok(api_key);
+        }
+
+        let candidate = self
+            .api_key_repository
+            .get_valid_salted_by_prefix(&ApiKeyFormat::display_prefix_candidates(plain_key))
             .await
             .map_err(|e| e.context("Failed to validate API key"))?
-            .ok_or_else(|| AppError::not_found("Valid API key not found".to_string()))
+            .ok_or_else(|| AppError::not_found("Valid API key not found".to_string()))?;
+
+        let plain_key = plain_key.to_string();
+        tokio::task::spawn_blocking(move || {
+            Self::verify_key(&plain_key, &candidate.key_hash).then_some(candidate)
+        })
+        .await
+        .map_err(|e| AppError::internal_error(format!("Key verification task failed: {}", e)))?
+        .ok_or_else(|| AppError::not_found("Valid API key not found".to_string()))
     }
 
     pub async fn update_usage(&self, key_hash: &str) -> AppResult<()> {
@@ -117,6 +360,16 @@ impl ApiKeyService {
             .map_err(|e| e.context("Failed to update API key usage"))
     }
 
+    pub async fn get_api_key_stats(
+        &self,
+        expiring_before: DateTime<Utc>,
+    ) -> AppResult<ApiKeyStats> {
+        self.api_key_repository
+            .stats(expiring_before)
+            .await
+            .map_err(|e| e.context("Failed to compute API key stats"))
+    }
+
     pub async fn list_api_keys(&self) -> AppResult<Vec<ApiKey>> {
         self.api_key_repository
             .get_all()
@@ -125,10 +378,78 @@ impl ApiKeyService {
     }
 
     pub async fn delete_api_key(&self, id: i32) -> AppResult<ApiKey> {
-        self.api_key_repository
+        let api_key = self
+            .api_key_repository
             .delete(id)
             .await
             .map_err(|e| e.context(format!("Failed to delete API key {}", id)))?
-            .ok_or_else(|| AppError::not_found(format!("API key with id {} not found", id)))
+            .ok_or_else(|| AppError::not_found(format!("API key with id {} not found", id)))?;
+        self.invalidate_cached(id);
+
+        Ok(api_key)
+    }
+
+    pub async fn delete_api_keys(&self, ids: &[i32]) -> AppResult<Vec<i32>> {
+        let deleted = self
+            .api_key_repository
+            .delete_many(ids)
+            .await
+            .map_err(|e| e.context("Failed to batch delete API keys"))?;
+        self.cache
+            .retain(|_, cached| !deleted.contains(&cached.api_key.id));
+
+        Ok(deleted)
+    }
+
+    pub async fn revoke_api_key(&self, id: i32) -> AppResult<()> {
+        self.api_key_repository
+            .revoke(id)
+            .await
+            .map_err(|e| e.context(format!("Failed to revoke API key {}", id)))?;
+        self.invalidate_cached(id);
+
+        Ok(())
+    }
+
+    fn parse_cidr(cidr: &str) -> AppResult<IpNetwork> {
+        cidr.trim()
+            .parse::<IpNetwork>()
+            .map_err(|e| AppError::bad_request(format!("Invalid CIDR '{}': {}", cidr, e)))
+    }
+
+    pub async fn add_allowed_ip(&self, id: i32, cidr: &str) -> AppResult<ApiKey> {
+        let network = Self::parse_cidr(cidr)?;
+
+        let api_key = self
+            .api_key_repository
+            .add_allowed_ip(id, network)
+            .await
+            .map_err(|e| e.context(format!("Failed to add allowed IP to API key {}", id)))?
+            .ok_or_else(|| AppError::not_found(format!("API key with id {} not found", id)))?;
+        self.invalidate_cached(id);
+
+        Ok(api_key)
+    }
+
+    pub async fn remove_allowed_ip(&self, id: i32, cidr: &str) -> AppResult<ApiKey> {
+        let network = Self::parse_cidr(cidr)?;
+
+        let api_key = self
+            .api_key_repository
+            .remove_allowed_ip(id, network)
+            .await
+            .map_err(|e| e.context(format!("Failed to remove allowed IP from API key {}", id)))?;
+        let Some(api_key) = api_key else {
+            // Removing the last entry would leave an empty allowlist, which
+            // allows every IP.
+            self.find_by_id(id).await?;
+            return Err(AppError::conflict(format!(
+                "Cannot remove '{}', the last allowed IP of API key {}; delete or revoke the key instead",
+                network, id
+            )));
+        };
+        self.invalidate_cached(id);
+
+        Ok(api_key)
     }
 }
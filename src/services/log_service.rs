@@ -1,30 +1,183 @@
-use crate::dto::{log_dto::Direction, CursorMetadata};
+use crate::config::defaults::DEFAULT_MAX_FUTURE_SKEW_SECS;
+use crate::dto::{log_dto::Direction, CursorMetadata, PatchDocument};
 use crate::error::AppResult;
-use crate::models::query_params::LogQueryParams;
-use crate::models::Log;
-use crate::repositories::log_repository::{LogRepository, LogRepositoryTrait};
-use crate::services::schema_service::SchemaService;
-use crate::AppError;
-use chrono::Utc;
-use serde_json::Value;
+use crate::models::query_params::{validate_date_range, LogQueryParams};
+use crate::models::{
+    DailyLogCount, LevelCountSource, LevelCounts, Log, RetentionPolicy, SchemaLogCount,
+};
+use crate::repositories::log_repository::LogRepositoryTrait;
+use crate::services::schema_service::{child_pointer, SchemaService};
+use crate::{AppError, ErrorCode, LogSampling, Schema, ValidationMode};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
+pub const MAX_COUNT_BY_DAY_DAYS: i32 = 366;
+/// Field of `log_data` holding the validation report of a log stored by a
+/// [`ValidationMode::Lenient`] schema.
+pub const VALIDATION_ERRORS_FIELD: &str = "_validation_errors";
+
+/// JSON pointer and byte length of the first string in `value` longer than
+/// `max` bytes.
+fn oversized_field(value: &Value, max: usize, path: &str) -> Option<(String, usize)> {
+    match value {
+        Value::String(string) if string.len() > max => Some((path.to_string(), string.len())),
+        Value::Object(map) => map
+            .iter()
+            .find_map(|(key, value)| oversized_field(value, max, &child_pointer(path, key))),
+        Value::Array(items) => items.iter().enumerate().find_map(|(index, value)| {
+            oversized_field(value, max, &child_pointer(path, &index.to_string()))
+        }),
+        _ => None,
+    }
+}
+
+/// Strips schemas' `redacted_fields` from logs about to leave the API,
+/// reading each schema once. Lives for one request, so an updated list
+/// applies from the next request on.
+pub struct Redactor {
+    schema_service: Arc<SchemaService>,
+    /// `None` when the schema could not be read.
+    schemas: HashMap<Uuid, Option<Schema>>,
+}
+
+impl Redactor {
+    /// The schema with id `schema_id`, read on first use; `None` (logged) if
+    /// it could not be read. Also serves webhook dispatch, so publishing a
+    /// log reads its schema once.
+    pub async fn schema(&mut self, schema_id: Uuid) -> Option<&Schema> {
+        if !self.schemas.contains_key(&schema_id) {
+            let schema = match self.schema_service.get_schema_by_id(schema_id, None).await {
+                Ok(schema) => Some(schema),
+                Err(e) => {
+                    tracing::warn!("Failed to read schema {}: {}", schema_id, e);
+                    None
+                }
+            };
+            self.schemas.insert(schema_id, schema);
+        }
+
+        self.schemas[&schema_id].as_ref()
+    }
+
+    /// Removes the redacted fields of `log`'s schema; storage is unaffected.
+    /// Never fails, since it also runs on logs that were just written: when
+    /// the schema cannot be read, all of `log_data` is withheld instead.
+    pub async fn redact(&mut self, mut log: Log) -> Log {
+        match self.schema(log.schema_id).await {
+            Some(schema) => log.redact(&schema.redacted_fields),
+            None => log.log_data = Value::Object(Map::new()),
+        }
+        log
+    }
+
+    pub async fn redact_all(&mut self, logs: Vec<Log>) -> Vec<Log> {
+        let mut redacted = Vec::with_capacity(logs.len());
+        for log in logs {
+            redacted.push(self.redact(log).await);
+        }
+        redacted
+    }
+}
+
+/// Which logs match a filter on a redacted field would reveal its values,
+/// so such filters are a 400 `INVALID_FILTER`.
+fn reject_redacted_filters(schema: &Schema, filters: &LogQueryParams) -> AppResult<()> {
+    match filters.filtered_fields().into_iter().find(|field| {
+        schema
+            .redacted_fields
+            .iter()
+            .any(|redacted| redacted == field)
+    }) {
+        Some(field) => Err(AppError::bad_request(format!(
+            "Cannot filter on redacted field '{}'",
+            field
+        ))
+        .with_code(ErrorCode::InvalidFilter)
+        .with_detail("field", field)),
+        None => Ok(()),
+    }
+}
+
+/// Rejects JSON Patch operations on redacted fields or on the whole document:
+/// `copy`, `move` and `test` would reveal the redacted values.
+fn reject_redacted_patch(schema: &Schema, patch: &PatchDocument) -> AppResult<()> {
+    if schema.redacted_fields.is_empty() {
+        return Ok(());
+    }
+
+    for field in patch.json_patch_fields() {
+        let Some(field) = field else {
+            return Err(AppError::bad_request(
+                "Cannot patch the whole log of a schema with redacted fields",
+            )
+            .with_code(ErrorCode::InvalidFilter));
+        };
+        if schema.redacted_fields.contains(&field) {
+            return Err(
+                AppError::bad_request(format!("Cannot patch redacted field '{}'", field))
+                    .with_code(ErrorCode::InvalidFilter)
+                    .with_detail("field", field),
+            );
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct LogService {
-    log_repository: Arc<LogRepository>,
+    log_repository: Arc<dyn LogRepositoryTrait>,
     schema_service: Arc<SchemaService>,
+    max_future_skew: Duration,
+    sampling: LogSampling,
 }
 
 impl LogService {
-    pub fn new(log_repository: Arc<LogRepository>, schema_service: Arc<SchemaService>) -> Self {
+    pub fn new(
+        log_repository: Arc<dyn LogRepositoryTrait>,
+        schema_service: Arc<SchemaService>,
+    ) -> Self {
         Self {
             log_repository,
             schema_service,
+            max_future_skew: Duration::seconds(DEFAULT_MAX_FUTURE_SKEW_SECS),
+            sampling: LogSampling::default(),
         }
     }
 
-    pub async fn get_log_by_id(&self, id: i32) -> AppResult<Log> {
+    /// How far past the server clock an explicit `created_at` may lie.
+    pub fn with_max_future_skew(mut self, max_future_skew: Duration) -> Self {
+        self.max_future_skew = max_future_skew;
+        self
+    }
+
+    /// How [`Self::create_log_sampled`] applies a schema's `sample_rate`.
+    pub fn with_sampling(mut self, sampling: LogSampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Logs belong to the tenant of their schema; a log of another tenant's
+    /// schema is reported as not found.
+    pub async fn get_log_by_id(&self, id: i32, tenant_id: &str) -> AppResult<Log> {
+        let not_found = || AppError::not_found(format!("Log with id {} not found", id));
+
+        let log = self.get_log_by_id_any_tenant(id).await?;
+
+        self.schema_service
+            .get_schema_by_id(log.schema_id, Some(tenant_id))
+            .await
+            .map_err(|e| if e.is_not_found() { not_found() } else { e })?;
+
+        Ok(log)
+    }
+
+    /// Looks a log up regardless of its schema's tenant; for the Admin API.
+    pub async fn get_log_by_id_any_tenant(&self, id: i32) -> AppResult<Log> {
         self.log_repository
             .get_by_id(id)
             .await
@@ -32,7 +185,22 @@ impl LogService {
             .ok_or_else(|| AppError::not_found(format!("Log with id {} not found", id)))
     }
 
-    pub async fn create_log(&self, schema_id: Uuid, log_data: Value) -> AppResult<Log> {
+    /// A [`Redactor`] for the logs of one request.
+    pub fn redactor(&self) -> Redactor {
+        Redactor {
+            schema_service: self.schema_service.clone(),
+            schemas: HashMap::new(),
+        }
+    }
+
+    /// Runs every check `create_log` performs before inserting. Returns the
+    /// log's schema.
+    pub async fn validate_log(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        log_data: &Value,
+    ) -> AppResult<Schema> {
         if schema_id.is_nil() {
             return Err(AppError::bad_request("Schema ID cannot be empty"));
         }
@@ -41,15 +209,141 @@ impl LogService {
             return Err(AppError::bad_request("Log data must be a JSON object"));
         }
 
-        self.schema_service
-            .validate_log_data(schema_id, &log_data)
+        let schema = self
+            .schema_service
+            .validate_log_data(schema_id, tenant_id, log_data)
             .await?;
+        Self::check_field_sizes(&schema, log_data)?;
+
+        Ok(schema)
+    }
+
+    /// Rejects `log_data` holding a string longer than the schema's
+    /// `max_field_bytes`, in every `validation_mode`.
+    fn check_field_sizes(schema: &Schema, log_data: &Value) -> AppResult<()> {
+        let Some(max) = schema.max_field_bytes else {
+            return Ok(());
+        };
+
+        match oversized_field(log_data, max as usize, "") {
+            Some((path, size)) => Err(AppError::bad_request(format!(
+                "Field '{}' is {} bytes, exceeding the schema's limit of {} bytes",
+                path, size, max
+            ))
+            .with_code(ErrorCode::FieldTooLarge)
+            .with_detail("field", path)
+            .with_detail("limit", max)),
+            None => Ok(()),
+        }
+    }
 
+    pub async fn create_log(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        log_data: Value,
+        created_by: Option<i32>,
+    ) -> AppResult<Log> {
+        self.create_log_at(schema_id, tenant_id, log_data, created_by, None)
+            .await
+    }
+
+    /// Like [`Self::create_log`], stamping the log with `created_at` instead
+    /// of the current time when given, e.g. for backfills.
+    pub async fn create_log_at(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        log_data: Value,
+        created_by: Option<i32>,
+        created_at: Option<DateTime<Utc>>,
+    ) -> AppResult<Log> {
+        self.check_created_at(created_at)?;
+        let schema = self.validate_log(schema_id, tenant_id, &log_data).await?;
+        let log_data = Self::annotate(&schema, log_data)?;
+
+        self.insert_log(schema_id, log_data, created_by, created_at)
+            .await
+    }
+
+    /// Like [`Self::create_log_at`], but keeps the log only with the
+    /// probability of its schema's `sample_rate`; a sampled-out log is
+    /// validated but not stored, and `None` is returned. `sample_key`
+    /// identifies the request for [`LogSampling::Deterministic`].
+    pub async fn create_log_sampled(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        log_data: Value,
+        created_by: Option<i32>,
+        created_at: Option<DateTime<Utc>>,
+        sample_key: &str,
+    ) -> AppResult<Option<Log>> {
+        self.check_created_at(created_at)?;
+        let schema = self.validate_log(schema_id, tenant_id, &log_data).await?;
+
+        if !self.is_sampled_in(schema.sample_rate, sample_key) {
+            return Ok(None);
+        }
+        let log_data = Self::annotate(&schema, log_data)?;
+
+        self.insert_log(schema_id, log_data, created_by, created_at)
+            .await
+            .map(Some)
+    }
+
+    /// Under [`ValidationMode::Lenient`], replaces any previous
+    /// [`VALIDATION_ERRORS_FIELD`] with the report of why `log_data` fails
+    /// its schema, or drops it when the data is valid. Other modes return
+    /// the data unchanged.
+    fn annotate(schema: &Schema, mut log_data: Value) -> AppResult<Value> {
+        if schema.validation_mode != ValidationMode::Lenient {
+            return Ok(log_data);
+        }
+        let Value::Object(data) = &mut log_data else {
+            return Ok(log_data);
+        };
+
+        data.remove(VALIDATION_ERRORS_FIELD);
+        let report = SchemaService::validation_report(schema, &log_data)?;
+        if !report.is_valid() {
+            if let Value::Object(data) = &mut log_data {
+                data.insert(VALIDATION_ERRORS_FIELD.to_string(), json!(report));
+            }
+        }
+        Ok(log_data)
+    }
+
+    fn is_sampled_in(&self, sample_rate: Option<f64>, sample_key: &str) -> bool {
+        let Some(rate) = sample_rate else {
+            return true;
+        };
+
+        let draw = match self.sampling {
+            LogSampling::Random => rand::random::<f64>(),
+            LogSampling::Deterministic => {
+                let digest = Sha256::digest(sample_key.as_bytes());
+                let bits = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+                (bits >> 11) as f64 / (1u64 << 53) as f64
+            }
+        };
+
+        draw < rate
+    }
+
+    async fn insert_log(
+        &self,
+        schema_id: Uuid,
+        log_data: Value,
+        created_by: Option<i32>,
+        created_at: Option<DateTime<Utc>>,
+    ) -> AppResult<Log> {
         let log = Log {
             id: 0, // This will be set by the database
             schema_id,
             log_data,
-            created_at: Utc::now(),
+            created_by,
+            created_at: created_at.unwrap_or_else(Utc::now),
         };
 
         self.log_repository
@@ -58,7 +352,112 @@ impl LogService {
             .map_err(|e| e.context(format!("Failed to create log for schema {}", schema_id)))
     }
 
-    pub async fn delete_log(&self, id: i32) -> AppResult<Log> {
+    /// Rejects an explicit timestamp further in the future than the allowed
+    /// clock skew.
+    pub fn check_created_at(&self, created_at: Option<DateTime<Utc>>) -> AppResult<()> {
+        let Some(created_at) = created_at else {
+            return Ok(());
+        };
+
+        if created_at > Utc::now() + self.max_future_skew {
+            return Err(AppError::bad_request(format!(
+                "created_at {} is more than {}s in the future",
+                created_at.to_rfc3339(),
+                self.max_future_skew.num_seconds()
+            ))
+            .with_code(ErrorCode::InvalidTimestamp));
+        }
+        Ok(())
+    }
+
+    /// Replaces the data of an existing log after validating it against the
+    /// log's schema.
+    pub async fn update_log_data(
+        &self,
+        id: i32,
+        tenant_id: &str,
+        log_data: Value,
+    ) -> AppResult<Log> {
+        let log = self.get_log_by_id(id, tenant_id).await?;
+        let schema = self
+            .validate_log(log.schema_id, tenant_id, &log_data)
+            .await?;
+        let log_data = Self::annotate(&schema, log_data)?;
+
+        self.log_repository
+            .update_data(id, &log_data)
+            .await
+            .map_err(|e| e.context(format!("Failed to update log {}", id)))?
+            .ok_or_else(|| AppError::not_found(format!("Log with id {} not found", id)))
+    }
+
+    /// Applies `patch` to the log's data and stores the result like
+    /// [`Self::update_log_data`].
+    pub async fn patch_log_data(
+        &self,
+        id: i32,
+        tenant_id: &str,
+        patch: &PatchDocument,
+    ) -> AppResult<Log> {
+        let log = self.get_log_by_id(id, tenant_id).await?;
+        let schema = self
+            .schema_service
+            .get_schema_by_id(log.schema_id, Some(tenant_id))
+            .await
+            .map_err(|e| {
+                e.context(format!(
+                    "Failed to fetch schema {} of log {}",
+                    log.schema_id, id
+                ))
+            })?;
+        reject_redacted_patch(&schema, patch)?;
+
+        let mut log_data = log.log_data;
+        patch.apply(&mut log_data)?;
+        self.update_log_data(id, tenant_id, log_data).await
+    }
+
+    /// Creates the schema's log addressed by `key`, or replaces its data if
+    /// one already exists. Returns the log and whether it was created.
+    pub async fn put_log_by_key(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        key: &str,
+        log_data: Value,
+        created_by: Option<i32>,
+    ) -> AppResult<(Log, bool)> {
+        if key.trim().is_empty() {
+            return Err(
+                AppError::bad_request("Log key cannot be empty").with_code(ErrorCode::InvalidInput)
+            );
+        }
+
+        let schema = self.validate_log(schema_id, tenant_id, &log_data).await?;
+        let log_data = Self::annotate(&schema, log_data)?;
+
+        let log = Log {
+            id: 0, // This will be set by the database
+            schema_id,
+            log_data,
+            created_by,
+            created_at: Utc::now(),
+        };
+
+        self.log_repository
+            .upsert_by_key(&log, key)
+            .await
+            .map_err(|e| {
+                e.context(format!(
+                    "Failed to store log '{}' for schema {}",
+                    key, schema_id
+                ))
+            })
+    }
+
+    pub async fn delete_log(&self, id: i32, tenant_id: &str) -> AppResult<Log> {
+        self.get_log_by_id(id, tenant_id).await?;
+
         self.log_repository
             .delete(id)
             .await
@@ -77,13 +476,49 @@ impl LogService {
             .map_err(|e| e.context(format!("Failed to count logs for schema {}", schema_id)))
     }
 
+    /// The `total` a [`Self::get_cursor_logs`] page would report, without
+    /// fetching the page.
+    pub async fn count_cursor_logs(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        filters: &LogQueryParams,
+    ) -> AppResult<i64> {
+        if schema_id.is_nil() {
+            return Err(AppError::bad_request("Schema ID cannot be nil"));
+        }
+
+        validate_date_range(filters.date_begin, filters.date_end)?;
+
+        let schema = self
+            .schema_service
+            .get_schema_by_id(schema_id, Some(tenant_id))
+            .await
+            .map_err(|e| {
+                e.context(format!(
+                    "Failed to check schema existence for {}",
+                    schema_id
+                ))
+            })?;
+        reject_redacted_filters(&schema, filters)?;
+        let filters = LogQueryParams {
+            indexed_fields: schema.indexed_fields,
+            ..filters.clone()
+        };
+
+        self.count_logs_by_schema_id(schema_id, &filters).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_cursor_logs(
         &self,
         schema_id: Uuid,
+        tenant_id: &str,
         cursor: Option<i32>,
         limit: i32,
         filters: LogQueryParams,
         direction: Direction,
+        include_total: bool,
     ) -> AppResult<(Vec<Log>, CursorMetadata<i32>)> {
         if schema_id.is_nil() {
             return Err(AppError::bad_request("Schema ID cannot be nil"));
@@ -93,8 +528,11 @@ impl LogService {
             return Err(AppError::bad_request("Limit must be greater than 0"));
         }
 
-        self.schema_service
-            .get_schema_by_id(schema_id)
+        validate_date_range(filters.date_begin, filters.date_end)?;
+
+        let schema = self
+            .schema_service
+            .get_schema_by_id(schema_id, Some(tenant_id))
             .await
             .map_err(|e| {
                 e.context(format!(
@@ -102,9 +540,20 @@ impl LogService {
                     schema_id
                 ))
             })?;
+        reject_redacted_filters(&schema, &filters)?;
+        let filters = LogQueryParams {
+            indexed_fields: schema.indexed_fields,
+            ..filters
+        };
 
         let forward = direction == Direction::Forward;
 
+        let total = if include_total {
+            Some(self.count_logs_by_schema_id(schema_id, &filters).await?)
+        } else {
+            None
+        };
+
         let mut logs = self
             .log_repository
             .get_all_with_cursor(schema_id, cursor, limit, filters, forward)
@@ -126,6 +575,8 @@ impl LogService {
             logs.reverse();
         }
 
+        let has_prev = if forward { cursor.is_some() } else { has_more };
+
         let (next_cursor, prev_cursor) = match direction {
             Direction::Forward => {
                 let next = if has_more {
@@ -154,15 +605,62 @@ impl LogService {
                 next_cursor,
                 prev_cursor,
                 has_more,
+                has_prev,
+                total,
             },
         ))
     }
 
-    pub async fn get_initial_cursor(&self, schema_id: Uuid) -> AppResult<i32> {
+    /// Keyset page for exports: logs strictly after `after` in ascending
+    /// `(created_at, id)` order, and whether more remain.
+    pub async fn export_logs(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        after: Option<(DateTime<Utc>, i32)>,
+        limit: i32,
+    ) -> AppResult<(Vec<Log>, bool)> {
+        if schema_id.is_nil() {
+            return Err(AppError::bad_request("Schema ID cannot be nil"));
+        }
+
+        if limit <= 0 {
+            return Err(AppError::bad_request("Limit must be greater than 0"));
+        }
+
+        self.schema_service
+            .get_schema_by_id(schema_id, Some(tenant_id))
+            .await
+            .map_err(|e| {
+                e.context(format!(
+                    "Failed to check schema existence for {}",
+                    schema_id
+                ))
+            })?;
+
+        let mut logs = self
+            .log_repository
+            .get_after_keyset(schema_id, after, limit)
+            .await
+            .map_err(|e| e.context(format!("Failed to export logs for schema {}", schema_id)))?;
+
+        let has_more = logs.len() > limit as usize;
+        if has_more {
+            logs.pop();
+        }
+
+        Ok((logs, has_more))
+    }
+
+    pub async fn get_initial_cursor(&self, schema_id: Uuid, tenant_id: &str) -> AppResult<i32> {
         if schema_id.is_nil() {
             return Err(AppError::bad_request("Schema ID cannot be nil"));
         }
 
+        self.schema_service
+            .get_schema_by_id(schema_id, Some(tenant_id))
+            .await?;
+
         let latest_id = self
             .log_repository
             .get_latest_log_id(schema_id)
@@ -176,4 +674,146 @@ impl LogService {
 
         Ok(latest_id.map(|id| id + 1).unwrap_or(i32::MAX))
     }
+
+    /// Counts logs per `log_data.level`. Without a time range the pre-aggregated
+    /// 24h summary is used, which may be up to one refresh interval stale.
+    pub async fn get_level_counts(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<LevelCounts> {
+        if schema_id.is_nil() {
+            return Err(AppError::bad_request("Schema ID cannot be nil"));
+        }
+
+        validate_date_range(date_begin, date_end)?;
+
+        self.schema_service
+            .get_schema_by_id(schema_id, Some(tenant_id))
+            .await?;
+
+        if date_begin.is_none() && date_end.is_none() {
+            let (counts, refreshed_at) = self
+                .log_repository
+                .get_level_counts_summary(schema_id)
+                .await
+                .map_err(|e| {
+                    e.context(format!(
+                        "Failed to fetch level summary for schema {}",
+                        schema_id
+                    ))
+                })?;
+
+            return Ok(LevelCounts {
+                counts,
+                source: LevelCountSource::Summary,
+                refreshed_at,
+            });
+        }
+
+        let counts = self
+            .log_repository
+            .count_by_level(schema_id, date_begin, date_end)
+            .await
+            .map_err(|e| e.context(format!("Failed to count logs for schema {}", schema_id)))?;
+
+        Ok(LevelCounts {
+            counts,
+            source: LevelCountSource::Live,
+            refreshed_at: None,
+        })
+    }
+
+    pub async fn get_counts_by_schema(
+        &self,
+        tenant_id: &str,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<SchemaLogCount>> {
+        validate_date_range(date_begin, date_end)?;
+
+        self.schema_service
+            .count_logs_by_schema(tenant_id, date_begin, date_end)
+            .await
+    }
+
+    pub async fn refresh_level_counts_summary(&self) -> AppResult<()> {
+        self.log_repository
+            .refresh_level_counts_summary()
+            .await
+            .map_err(|e| e.context("Failed to refresh log level summary"))
+    }
+
+    /// Deletes the logs of every schema that its `max_log_age_secs` or
+    /// `max_retained_logs` rule removes, returning the number of deleted
+    /// rows. With both set a log is kept only if both rules keep it, so the
+    /// stricter one wins. A failing schema is logged and skipped so it does
+    /// not hold up the others.
+    pub async fn enforce_retention(&self) -> AppResult<i64> {
+        let policies = self.schema_service.get_retention_policies().await?;
+
+        let mut deleted = 0;
+        for policy in policies {
+            match self.apply_retention(&policy).await {
+                Ok(count) => deleted += count,
+                Err(e) => tracing::warn!(
+                    "{}",
+                    e.context(format!(
+                        "Failed to apply retention to schema {}",
+                        policy.schema_id
+                    ))
+                ),
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    async fn apply_retention(&self, policy: &RetentionPolicy) -> AppResult<i64> {
+        let mut deleted = 0;
+        if let Some(max_age) = policy.max_log_age_secs {
+            let cutoff = Utc::now() - Duration::seconds(max_age.into());
+            deleted += self
+                .log_repository
+                .delete_created_before(policy.schema_id, cutoff)
+                .await?;
+        }
+        if let Some(keep) = policy.max_retained_logs {
+            deleted += self
+                .log_repository
+                .delete_beyond_count(policy.schema_id, keep)
+                .await?;
+        }
+        Ok(deleted)
+    }
+
+    pub async fn get_daily_counts(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        days: i32,
+    ) -> AppResult<Vec<DailyLogCount>> {
+        if !(1..=MAX_COUNT_BY_DAY_DAYS).contains(&days) {
+            return Err(AppError::bad_request(format!(
+                "days must be between 1 and {}",
+                MAX_COUNT_BY_DAY_DAYS
+            )));
+        }
+
+        self.schema_service
+            .get_schema_by_id(schema_id, Some(tenant_id))
+            .await?;
+
+        self.log_repository
+            .count_by_day(schema_id, days)
+            .await
+            .map_err(|e| {
+                e.context(format!(
+                    "Failed to count daily logs for schema {}",
+                    schema_id
+                ))
+            })
+    }
 }
@@ -1,7 +1,9 @@
 pub mod api_key_service;
 pub mod log_service;
 pub mod schema_service;
+pub mod webhook_service;
 
 pub use api_key_service::ApiKeyService;
 pub use log_service::LogService;
 pub use schema_service::SchemaService;
+pub use webhook_service::WebhookService;
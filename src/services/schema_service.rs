@@ -1,32 +1,269 @@
+use crate::config::{
+    defaults::DEFAULT_COMPAT_SAMPLE_SIZE, SchemaComplexityLimits, SchemaNamingPolicy,
+};
 use crate::dto::{log_dto::Direction, CursorMetadata};
-use crate::error::{AppError, AppResult};
-use crate::models::{Schema, SchemaNameVersion, SchemaQueryParams};
-use crate::repositories::log_repository::{LogRepository, LogRepositoryTrait};
-use crate::repositories::schema_repository::{SchemaRepository, SchemaRepositoryTrait};
-use chrono::Utc;
-use serde_json::Value;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::models::{
+    query_params::LogQueryParams, CreateSchema, FieldTypeMismatch, FieldViolation, LogRange,
+    LogValidationReport, PurgedCounts, RetentionPolicy, Schema, SchemaChange, SchemaLogCount,
+    SchemaNameVersion, SchemaQueryParams, ValidationMode,
+};
+use crate::repositories::log_repository::LogRepositoryTrait;
+use crate::repositories::schema_repository::SchemaRepositoryTrait;
+use chrono::{DateTime, Utc};
+use jsonschema::error::{TypeKind, ValidationErrorKind};
+use serde_json::{json, Map, Number, Value};
+use std::collections::HashSet;
 use std::sync::Arc;
 use uuid::Uuid;
 
+pub const MAX_INDEXED_FIELDS: usize = 8;
+/// Failing logs reported by an `INCOMPATIBLE_SCHEMA` error.
+const MAX_COMPAT_EXAMPLES: usize = 5;
+
+/// Canonical form of a schema definition: integral floats (`1.0`, `1e2`)
+/// written as integers, so that logically identical definitions are stored,
+/// hashed and diffed identically. Object keys need no sorting, since
+/// `serde_json::Map` already keeps them ordered.
+pub fn normalize_schema_definition(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, normalize_schema_definition(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(normalize_schema_definition).collect())
+        }
+        Value::Number(number) => Value::Number(normalize_number(number)),
+        other => other,
+    }
+}
+
+fn normalize_number(number: Number) -> Number {
+    match number.as_f64() {
+        Some(f) if !number.is_i64() && !number.is_u64() && f.fract() == 0.0 => {
+            if f >= i64::MIN as f64 && f < i64::MAX as f64 {
+                Number::from(f as i64)
+            } else {
+                number
+            }
+        }
+        _ => number,
+    }
+}
+
+/// JSON pointer of `field` inside the value at `parent`, escaping `~` and `/`.
+pub(crate) fn child_pointer(parent: &str, field: &str) -> String {
+    format!("{}/{}", parent, field.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Nesting depth of objects and arrays in `value`; scalars are depth 0.
+fn json_depth(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => 1 + map.values().map(json_depth).max().unwrap_or(0),
+        Value::Array(items) => 1 + items.iter().map(json_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Number of entries across every `properties` object in a schema definition.
+fn count_properties(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| {
+                let own = match (key.as_str(), child) {
+                    ("properties", Value::Object(properties)) => properties.len(),
+                    _ => 0,
+                };
+                own + count_properties(child)
+            })
+            .sum(),
+        Value::Array(items) => items.iter().map(count_properties).sum(),
+        _ => 0,
+    }
+}
+
+/// JSON Schema type name of `value`, as reported in type mismatches.
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Names listed in the `required` keyword of `schema`.
+fn required_fields(schema: &Value) -> HashSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Field-level changes from `old` to `new`, following `properties` of
+/// nested objects. Keywords other than `properties`, `required` and `type`
+/// are not compared.
+pub fn diff_schema_definitions(old: &Value, new: &Value) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    diff_object("", old, new, &mut changes);
+    changes
+}
+
+fn diff_object(path: &str, old: &Value, new: &Value, changes: &mut Vec<SchemaChange>) {
+    let empty = Map::new();
+    let old_properties = old
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let new_properties = new
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let old_required = required_fields(old);
+    let new_required = required_fields(new);
+
+    for (field, old_property) in old_properties {
+        let pointer = child_pointer(path, field);
+        let Some(new_property) = new_properties.get(field) else {
+            changes.push(SchemaChange::FieldRemoved { path: pointer });
+            continue;
+        };
+
+        if let (Some(from), Some(to)) = (old_property.get("type"), new_property.get("type")) {
+            if from != to {
+                changes.push(SchemaChange::TypeChanged {
+                    path: pointer.clone(),
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+        }
+
+        match (
+            old_required.contains(field.as_str()),
+            new_required.contains(field.as_str()),
+        ) {
+            (false, true) => changes.push(SchemaChange::FieldBecameRequired {
+                path: pointer.clone(),
+            }),
+            (true, false) => changes.push(SchemaChange::FieldBecameOptional {
+                path: pointer.clone(),
+            }),
+            _ => {}
+        }
+
+        diff_object(&pointer, old_property, new_property, changes);
+    }
+
+    for field in new_properties.keys() {
+        if !old_properties.contains_key(field) {
+            changes.push(SchemaChange::FieldAdded {
+                path: child_pointer(path, field),
+                required: new_required.contains(field.as_str()),
+            });
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SchemaService {
-    repository: Arc<SchemaRepository>,
-    log_repository: Arc<LogRepository>,
+    repository: Arc<dyn SchemaRepositoryTrait>,
+    log_repository: Arc<dyn LogRepositoryTrait>,
+    naming_policy: SchemaNamingPolicy,
+    complexity_limits: SchemaComplexityLimits,
+    compat_sample_size: i32,
+    inference_field: Option<String>,
+    max_schemas: Option<u64>,
 }
 
 impl SchemaService {
-    pub fn new(repository: Arc<SchemaRepository>, log_repository: Arc<LogRepository>) -> Self {
+    pub fn new(
+        repository: Arc<dyn SchemaRepositoryTrait>,
+        log_repository: Arc<dyn LogRepositoryTrait>,
+    ) -> Self {
         Self {
             repository,
             log_repository,
+            naming_policy: SchemaNamingPolicy::default(),
+            complexity_limits: SchemaComplexityLimits::default(),
+            compat_sample_size: DEFAULT_COMPAT_SAMPLE_SIZE,
+            inference_field: None,
+            max_schemas: None,
         }
     }
 
-    pub async fn resolve_schema(&self, schema_ref: &SchemaNameVersion) -> AppResult<Schema> {
+    pub fn with_naming_policy(mut self, naming_policy: SchemaNamingPolicy) -> Self {
+        self.naming_policy = naming_policy;
+        self
+    }
+
+    pub fn with_complexity_limits(mut self, complexity_limits: SchemaComplexityLimits) -> Self {
+        self.complexity_limits = complexity_limits;
+        self
+    }
+
+    pub fn with_compat_sample_size(mut self, compat_sample_size: i32) -> Self {
+        self.compat_sample_size = compat_sample_size;
+        self
+    }
+
+    pub fn with_inference_field(mut self, inference_field: Option<String>) -> Self {
+        self.inference_field = inference_field;
+        self
+    }
+
+    /// Caps the number of schemas across all tenants; `None` is unlimited.
+    pub fn with_max_schemas(mut self, max_schemas: Option<u64>) -> Self {
+        self.max_schemas = max_schemas;
+        self
+    }
+
+    /// Removes the configured inference field from `log_data` and resolves
+    /// the schema it names. `None` if inference is disabled or the field is
+    /// absent.
+    pub async fn infer_schema(
+        &self,
+        log_data: &mut Value,
+        tenant_id: &str,
+    ) -> AppResult<Option<Schema>> {
+        let Some(field) = self.inference_field.as_deref() else {
+            return Ok(None);
+        };
+        let Some(reference) = log_data.as_object_mut().and_then(|data| data.remove(field)) else {
+            return Ok(None);
+        };
+
+        let schema_ref = reference
+            .as_str()
+            .map(SchemaNameVersion::parse)
+            .ok_or_else(|| {
+                AppError::bad_request(format!(
+                    "Field '{}' must be a string like \"name/version\"",
+                    field
+                ))
+                .with_code(ErrorCode::InvalidSchemaReference)
+            })?;
+        schema_ref.validate()?;
+
+        self.resolve_schema(&schema_ref, tenant_id).await.map(Some)
+    }
+
+    pub async fn resolve_schema(
+        &self,
+        schema_ref: &SchemaNameVersion,
+        tenant_id: &str,
+    ) -> AppResult<Schema> {
         let schema = match &schema_ref.version {
             Some(version) => self
                 .repository
-                .get_by_name_and_version(&schema_ref.name, version)
+                .get_by_name_and_version(&schema_ref.name, version, tenant_id)
                 .await
                 .map_err(|e| {
                     e.context(format!(
@@ -36,7 +273,7 @@ impl SchemaService {
                 })?,
             None => self
                 .repository
-                .get_by_name_latest(&schema_ref.name)
+                .get_by_name_latest(&schema_ref.name, tenant_id)
                 .await
                 .map_err(|e| {
                     e.context(format!("Failed to fetch latest schema {}", schema_ref.name))
@@ -52,23 +289,38 @@ impl SchemaService {
         })
     }
 
-    pub async fn get_schema_id(&self, schema_ref: &SchemaNameVersion) -> AppResult<uuid::Uuid> {
-        let schema = self.resolve_schema(schema_ref).await?;
+    pub async fn get_schema_id(
+        &self,
+        schema_ref: &SchemaNameVersion,
+        tenant_id: &str,
+    ) -> AppResult<uuid::Uuid> {
+        let schema = self.resolve_schema(schema_ref, tenant_id).await?;
         Ok(schema.id)
     }
 
-    pub async fn validate_log_data(&self, schema_id: Uuid, log_data: &Value) -> AppResult<()> {
-        let schema = self.get_schema_by_id(schema_id).await.map_err(|e| {
-            e.context(format!(
-                "Failed to fetch schema {} for validation",
-                schema_id
-            ))
-        })?;
+    /// Checks `log_data` against the schema and returns that schema.
+    pub async fn validate_log_data(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        log_data: &Value,
+    ) -> AppResult<Schema> {
+        let schema = self
+            .get_schema_by_id(schema_id, Some(tenant_id))
+            .await
+            .map_err(|e| {
+                e.context(format!(
+                    "Failed to fetch schema {} for validation",
+                    schema_id
+                ))
+            })?;
 
-        let validator = jsonschema::ValidationOptions::default()
-            .with_draft(jsonschema::Draft::Draft7)
-            .build(&schema.schema_definition)
-            .map_err(|e| AppError::internal_error(format!("Invalid JSON schema: {}", e)))?;
+        // Lenient logs are annotated by the caller instead of rejected.
+        if schema.validation_mode != ValidationMode::Strict {
+            return Ok(schema);
+        }
+
+        let validator = Self::build_validator(&schema)?;
 
         let errors: Vec<_> = validator
             .iter_errors(log_data)
@@ -76,7 +328,7 @@ impl SchemaService {
             .collect();
 
         if errors.is_empty() {
-            Ok(())
+            Ok(schema)
         } else {
             Err(AppError::schema_validation_error(format!(
                 "Schema validation failed: {}",
@@ -85,6 +337,83 @@ impl SchemaService {
         }
     }
 
+    /// Like [`Self::validate_log_data`], but reports every failure sorted
+    /// into missing, wrongly typed and extra fields instead of failing.
+    pub async fn validate_log_data_detailed(
+        &self,
+        schema_id: Uuid,
+        tenant_id: &str,
+        log_data: &Value,
+    ) -> AppResult<LogValidationReport> {
+        let schema = self.get_schema_by_id(schema_id, Some(tenant_id)).await?;
+        Self::validation_report(&schema, log_data)
+    }
+
+    /// [`Self::validate_log_data_detailed`] against an already fetched
+    /// schema, regardless of its `validation_mode`.
+    pub fn validation_report(schema: &Schema, log_data: &Value) -> AppResult<LogValidationReport> {
+        let validator = Self::build_validator(schema)?;
+
+        let mut report = LogValidationReport::default();
+        for error in validator.iter_errors(log_data) {
+            let path = error.instance_path.to_string();
+            match &error.kind {
+                ValidationErrorKind::Required { property } => {
+                    let field = property
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| property.to_string());
+                    report.missing.push(child_pointer(&path, &field));
+                }
+                ValidationErrorKind::Type { kind } => {
+                    let expected = match kind {
+                        TypeKind::Single(json_type) => vec![json_type.to_string()],
+                        TypeKind::Multiple(types) => types.iter().map(|t| t.to_string()).collect(),
+                    };
+                    report.wrong_type.push(FieldTypeMismatch {
+                        path,
+                        expected,
+                        actual: json_type_name(&error.instance).to_string(),
+                    });
+                }
+                ValidationErrorKind::AdditionalProperties { unexpected } => {
+                    report
+                        .extra
+                        .extend(unexpected.iter().map(|field| child_pointer(&path, field)));
+                }
+                _ => {
+                    let schema_path = error.schema_path.to_string();
+                    report.other.push(FieldViolation {
+                        keyword: schema_path
+                            .rsplit('/')
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                        message: error.to_string(),
+                        path,
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn build_validator(schema: &Schema) -> AppResult<jsonschema::Validator> {
+        Self::build_validator_for(&schema.schema_definition, schema.strict_formats)
+    }
+
+    fn build_validator_for(
+        schema_definition: &Value,
+        strict_formats: bool,
+    ) -> AppResult<jsonschema::Validator> {
+        jsonschema::ValidationOptions::default()
+            .with_draft(jsonschema::Draft::Draft7)
+            .should_validate_formats(strict_formats)
+            .build(schema_definition)
+            .map_err(|e| AppError::internal_error(format!("Invalid JSON schema: {}", e)))
+    }
+
     pub async fn get_all_schemas(
         &self,
         params: Option<SchemaQueryParams>,
@@ -95,12 +424,20 @@ impl SchemaService {
             .map_err(|e| e.context("Failed to fetch schemas"))
     }
 
+    pub async fn count_schemas(&self, filters: &SchemaQueryParams) -> AppResult<i64> {
+        self.repository
+            .count(Some(filters))
+            .await
+            .map_err(|e| e.context("Failed to count schemas"))
+    }
+
     pub async fn get_cursor_schemas(
         &self,
         cursor: Option<Uuid>,
         limit: i32,
         filters: SchemaQueryParams,
         direction: Direction,
+        include_total: bool,
     ) -> AppResult<(Vec<Schema>, CursorMetadata<Uuid>)> {
         if limit <= 0 {
             return Err(AppError::bad_request("Limit must be greater than 0"));
@@ -108,6 +445,13 @@ impl SchemaService {
 
         let forward = direction == Direction::Forward;
 
+        let total = if include_total {
+            Some(self.count_schemas(&filters).await?)
+        } else {
+            None
+        };
+
+        let search_filters = filters.clone();
         let mut schemas = self
             .repository
             .get_all_with_cursor(cursor, limit, filters, forward)
@@ -124,6 +468,8 @@ impl SchemaService {
             schemas.reverse();
         }
 
+        let has_prev = if forward { cursor.is_some() } else { has_more };
+
         let (next_cursor, prev_cursor) = match direction {
             Direction::Forward => {
                 let next = if has_more {
@@ -145,6 +491,11 @@ impl SchemaService {
             }
         };
 
+        // Ranking only reorders the page; cursors above still follow creation order.
+        if search_filters.search.is_some() {
+            schemas.sort_by_key(|schema| !search_filters.name_matches_search(&schema.name));
+        }
+
         Ok((
             schemas,
             CursorMetadata::<Uuid> {
@@ -152,46 +503,118 @@ impl SchemaService {
                 next_cursor,
                 prev_cursor,
                 has_more,
+                has_prev,
+                total,
             },
         ))
     }
 
-    pub async fn get_schema_by_id(&self, id: Uuid) -> AppResult<Schema> {
+    /// See [`SchemaRepositoryTrait::get_by_id`] for the meaning of a `None`
+    /// tenant.
+    pub async fn get_schema_by_id(&self, id: Uuid, tenant_id: Option<&str>) -> AppResult<Schema> {
         self.repository
-            .get_by_id(id)
+            .get_by_id(id, tenant_id)
             .await
             .map_err(|e| e.context(format!("Failed to fetch schema {}", id)))?
             .ok_or_else(|| AppError::not_found(format!("Schema with id '{}' not found", id)))
     }
 
-    pub async fn get_schema_by_name(&self, name: &str) -> AppResult<Schema> {
+    pub async fn get_schemas_by_ids(
+        &self,
+        ids: &[Uuid],
+        tenant_id: &str,
+    ) -> AppResult<Vec<Schema>> {
+        self.repository
+            .get_by_ids(ids, tenant_id)
+            .await
+            .map_err(|e| e.context("Failed to batch fetch schemas"))
+    }
+
+    pub async fn get_retention_policies(&self) -> AppResult<Vec<RetentionPolicy>> {
+        self.repository
+            .get_retention_policies()
+            .await
+            .map_err(|e| e.context("Failed to fetch schema retention policies"))
+    }
+
+    /// Oldest and newest `created_at` of the schema's logs, with their count.
+    pub async fn get_log_range(&self, schema_id: Uuid) -> AppResult<LogRange> {
+        self.log_repository.get_range(schema_id).await.map_err(|e| {
+            e.context(format!(
+                "Failed to fetch log range for schema {}",
+                schema_id
+            ))
+        })
+    }
+
+    pub async fn count_logs_by_schema(
+        &self,
+        tenant_id: &str,
+        date_begin: Option<DateTime<Utc>>,
+        date_end: Option<DateTime<Utc>>,
+    ) -> AppResult<Vec<SchemaLogCount>> {
         self.repository
-            .get_by_name_latest(name)
+            .count_logs_by_schema(tenant_id, date_begin, date_end)
+            .await
+            .map_err(|e| e.context("Failed to count logs by schema"))
+    }
+
+    pub async fn get_schema_by_name(&self, name: &str, tenant_id: &str) -> AppResult<Schema> {
+        self.repository
+            .get_by_name_latest(name, tenant_id)
             .await
             .map_err(|e| e.context(format!("Failed to fetch latest schema '{}'", name)))?
             .ok_or_else(|| AppError::not_found(format!("Schema '{}' not found", name)))
     }
 
-    pub async fn get_by_name_and_version(&self, name: &str, version: &str) -> AppResult<Schema> {
+    pub async fn get_by_name_and_version(
+        &self,
+        name: &str,
+        version: &str,
+        tenant_id: &str,
+    ) -> AppResult<Schema> {
         self.repository
-            .get_by_name_and_version(name, version)
+            .get_by_name_and_version(name, version, tenant_id)
             .await
             .map_err(|e| e.context(format!("Failed to fetch schema '{}:{}'", name, version)))?
             .ok_or_else(|| AppError::not_found(format!("Schema '{}:{}' not found", name, version)))
     }
 
-    pub async fn create_schema(
-        &self,
-        name: String,
-        version: String,
-        description: Option<String>,
-        schema_definition: Value,
-    ) -> AppResult<Schema> {
+    pub async fn create_schema(&self, request: CreateSchema) -> AppResult<Schema> {
+        let CreateSchema {
+            name,
+            version,
+            description,
+            schema_definition,
+            webhook_url,
+            webhook_secret,
+            strict_formats,
+            indexed_fields,
+            max_retained_logs,
+            max_log_age_secs,
+            redacted_fields,
+            sample_rate,
+            labels,
+            validation_mode,
+            max_field_bytes,
+            tenant_id,
+            created_by,
+        } = request;
+
+        self.validate_naming(&name, &version)?;
+        let schema_definition = normalize_schema_definition(schema_definition);
         self.validate_schema_definition(&schema_definition)?;
+        Self::validate_indexed_fields(&indexed_fields)?;
+        Self::validate_webhook_url(webhook_url.as_deref())?;
+        Self::validate_retention(max_retained_logs, max_log_age_secs)?;
+        Self::validate_redacted_fields(&redacted_fields)?;
+        Self::validate_sample_rate(sample_rate)?;
+        Self::validate_labels(&labels)?;
+        Self::validate_max_field_bytes(max_field_bytes)?;
 
         let existing = self
             .repository
-            .get_by_name_and_version(&name, &version)
+            .get_by_name_and_version(&name, &version, &tenant_id)
             .await
             .map_err(|e| {
                 e.context(format!(
@@ -200,13 +623,17 @@ impl SchemaService {
                 ))
             })?;
 
-        if existing.is_some() {
+        if let Some(existing) = existing {
             return Err(AppError::conflict(format!(
                 "Schema '{}:{}' already exists",
                 name, version
-            )));
+            ))
+            .with_code(ErrorCode::SchemaConflict)
+            .with_detail("existing_id", existing.id.to_string()));
         }
 
+        self.check_schema_limit().await?;
+
         let now = Utc::now();
         let schema = Schema {
             id: Uuid::new_v4(),
@@ -214,38 +641,78 @@ impl SchemaService {
             version: version.clone(),
             description,
             schema_definition,
+            webhook_url,
+            webhook_secret,
+            strict_formats,
+            indexed_fields,
+            max_retained_logs,
+            max_log_age_secs,
+            redacted_fields,
+            sample_rate,
+            labels,
+            validation_mode,
+            max_field_bytes,
+            tenant_id,
+            created_by,
             created_at: now,
             updated_at: now,
         };
 
-        self.repository
-            .create(&schema)
-            .await
-            .map_err(|e| e.context(format!("Failed to create schema '{}:{}'", name, version)))
+        let created =
+            self.repository.create(&schema).await.map_err(|e| {
+                e.context(format!("Failed to create schema '{}:{}'", name, version))
+            })?;
+
+        self.sync_field_indexes(created.id, &[], &created.indexed_fields)
+            .await;
+
+        Ok(created)
     }
 
-    pub async fn update_schema(
-        &self,
-        id: Uuid,
-        name: String,
-        version: String,
-        description: Option<String>,
-        schema_definition: Value,
-    ) -> AppResult<Schema> {
+    pub async fn update_schema(&self, id: Uuid, request: CreateSchema) -> AppResult<Schema> {
+        let CreateSchema {
+            name,
+            version,
+            description,
+            schema_definition,
+            webhook_url,
+            webhook_secret,
+            strict_formats,
+            indexed_fields,
+            max_retained_logs,
+            max_log_age_secs,
+            redacted_fields,
+            sample_rate,
+            labels,
+            validation_mode,
+            max_field_bytes,
+            tenant_id,
+            created_by: _,
+        } = request;
+
         if id.is_nil() {
             return Err(AppError::bad_request("Schema ID cannot be empty"));
         }
 
+        self.validate_naming(&name, &version)?;
+        let schema_definition = normalize_schema_definition(schema_definition);
         self.validate_schema_definition(&schema_definition)?;
+        Self::validate_indexed_fields(&indexed_fields)?;
+        Self::validate_webhook_url(webhook_url.as_deref())?;
+        Self::validate_retention(max_retained_logs, max_log_age_secs)?;
+        Self::validate_redacted_fields(&redacted_fields)?;
+        Self::validate_sample_rate(sample_rate)?;
+        Self::validate_labels(&labels)?;
+        Self::validate_max_field_bytes(max_field_bytes)?;
 
         let existing_schema = self
-            .get_schema_by_id(id)
+            .get_schema_by_id(id, Some(&tenant_id))
             .await
             .map_err(|e| e.context(format!("Failed to fetch schema {}", id)))?;
 
         let conflicting_schema = self
             .repository
-            .get_by_name_and_version(&name, &version)
+            .get_by_name_and_version(&name, &version, &tenant_id)
             .await
             .map_err(|e| {
                 e.context(format!(
@@ -269,26 +736,136 @@ impl SchemaService {
             version: version.clone(),
             description,
             schema_definition,
+            webhook_url,
+            webhook_secret,
+            strict_formats,
+            indexed_fields,
+            max_retained_logs,
+            max_log_age_secs,
+            redacted_fields,
+            sample_rate,
+            labels,
+            validation_mode,
+            max_field_bytes,
+            tenant_id,
+            created_by: existing_schema.created_by,
             created_at: existing_schema.created_at, // keep original creation time
             updated_at: Utc::now(),
         };
 
-        self.repository
+        let updated = self
+            .repository
             .update(id, &updated_schema)
             .await
             .map_err(|e| e.context(format!("Failed to update schema '{}:{}'", name, version)))?
-            .ok_or_else(|| AppError::not_found(format!("Schema with id '{}' not found", id)))
+            .ok_or_else(|| AppError::not_found(format!("Schema with id '{}' not found", id)))?;
+
+        self.sync_field_indexes(id, &existing_schema.indexed_fields, &updated.indexed_fields)
+            .await;
+
+        Ok(updated)
+    }
+
+    /// Validates the schema's most recent logs (up to the configured sample
+    /// size) against the definition `request` would store, failing with 409
+    /// `INCOMPATIBLE_SCHEMA` and a few example failures if any is rejected.
+    pub async fn check_compatibility(&self, id: Uuid, request: &CreateSchema) -> AppResult<()> {
+        let schema_definition = normalize_schema_definition(request.schema_definition.clone());
+        self.validate_schema_definition(&schema_definition)?;
+        self.get_schema_by_id(id, Some(&request.tenant_id)).await?;
+
+        let validator = Self::build_validator_for(&schema_definition, request.strict_formats)?;
+        let mut logs = self
+            .log_repository
+            .get_all_with_cursor(
+                id,
+                None,
+                self.compat_sample_size,
+                LogQueryParams::default(),
+                true,
+            )
+            .await
+            .map_err(|e| e.context(format!("Failed to sample logs of schema {}", id)))?;
+        logs.truncate(self.compat_sample_size.max(0) as usize);
+
+        let failures: Vec<Value> = logs
+            .iter()
+            .filter_map(|log| {
+                validator.iter_errors(&log.log_data).next().map(|error| {
+                    json!({
+                        "log_id": log.id,
+                        "path": error.instance_path.to_string(),
+                        "message": error.to_string(),
+                    })
+                })
+            })
+            .collect();
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        Err(AppError::conflict(format!(
+            "The new definition rejects {} of the {} most recent logs of schema {}",
+            failures.len(),
+            logs.len(),
+            id
+        ))
+        .with_code(ErrorCode::IncompatibleSchema)
+        .with_detail("checked_logs", logs.len())
+        .with_detail("failed_logs", failures.len())
+        .with_detail(
+            "examples",
+            failures
+                .into_iter()
+                .take(MAX_COMPAT_EXAMPLES)
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// The other versions of schema `id`'s name in the tenant, oldest first,
+    /// each with the changes from `id`'s definition to its own.
+    pub async fn compatible_versions(
+        &self,
+        id: Uuid,
+        tenant_id: &str,
+    ) -> AppResult<Vec<(Schema, Vec<SchemaChange>)>> {
+        let schema = self.get_schema_by_id(id, Some(tenant_id)).await?;
+        let mut versions = self
+            .get_all_schemas(Some(SchemaQueryParams {
+                name: Some(schema.name.clone()),
+                tenant_id: Some(tenant_id.to_string()),
+                ..Default::default()
+            }))
+            .await?;
+        versions.retain(|other| other.id != schema.id);
+        versions.sort_by_key(|other| other.created_at);
+
+        Ok(versions
+            .into_iter()
+            .map(|other| {
+                let changes =
+                    diff_schema_definitions(&schema.schema_definition, &other.schema_definition);
+                (other, changes)
+            })
+            .collect())
     }
 
-    /* TODO(@milo): implement handling transactions by the schema repository just for this function */
-    pub async fn delete_schema(&self, id: Uuid, force: bool) -> AppResult<Schema> {
+    /// Returns the deleted schema and the ids of the logs `force` removed
+    /// with it.
+    pub async fn delete_schema(
+        &self,
+        id: Uuid,
+        tenant_id: &str,
+        force: bool,
+    ) -> AppResult<(Schema, Vec<i32>)> {
         if id.is_nil() {
             return Err(AppError::bad_request("Cannot delete Schema with nil UUID"));
         }
 
         let schema = self
             .repository
-            .get_by_id(id)
+            .get_by_id(id, Some(tenant_id))
             .await
             .map_err(|e| e.context(format!("Failed to fetch schema {}", id)))?;
 
@@ -312,20 +889,225 @@ impl SchemaService {
             )));
         }
 
-        if force && log_count > 0 {
-            let deleted_logs = self
-                .log_repository
-                .delete_all_by_schema_id(id)
+        let (deleted, deleted_log_ids) = if force && log_count > 0 {
+            let (deleted, deleted_log_ids) = self
+                .repository
+                .delete_with_logs(id, tenant_id)
                 .await
-                .map_err(|e| e.context(format!("Failed to delete logs for schema {}", id)))?;
-            tracing::info!("Deleted {} logs for schema {}", deleted_logs, id);
-        }
+                .map_err(|e| e.context(format!("Failed to delete schema {} with its logs", id)))?
+                .ok_or_else(|| AppError::not_found(format!("Schema with id {} not found", id)))?;
+            tracing::info!("Deleted {} logs for schema {}", deleted_log_ids.len(), id);
+            (deleted, deleted_log_ids)
+        } else {
+            let deleted = self
+                .repository
+                .delete(id, tenant_id)
+                .await
+                .map_err(|e| e.context(format!("Failed to delete schema {}", id)))?
+                .ok_or_else(|| AppError::not_found(format!("Schema with id {} not found", id)))?;
+            (deleted, Vec::new())
+        };
+
+        self.sync_field_indexes(id, &deleted.indexed_fields, &[])
+            .await;
+
+        Ok((deleted, deleted_log_ids))
+    }
 
+    /// Deletes every log and schema of every tenant, and every API key when
+    /// `include_api_keys` is set, along with the schemas' field indexes.
+    pub async fn purge_all(&self, include_api_keys: bool) -> AppResult<PurgedCounts> {
         self.repository
-            .delete(id)
+            .purge_all(include_api_keys)
+            .await
+            .map_err(|e| e.context("Failed to purge logs and schemas"))
+    }
+
+    /// Drops the indexes of fields no longer listed and starts building the
+    /// ones for newly listed fields in the background. Indexes only affect
+    /// performance, so failures are logged rather than failing the request.
+    async fn sync_field_indexes(&self, schema_id: Uuid, previous: &[String], current: &[String]) {
+        for field in previous.iter().filter(|field| !current.contains(field)) {
+            if let Err(e) = self.log_repository.drop_field_index(schema_id, field).await {
+                tracing::warn!(
+                    "Failed to drop index on '{}' for schema {}: {}",
+                    field,
+                    schema_id,
+                    e
+                );
+            }
+        }
+
+        let added: Vec<String> = current
+            .iter()
+            .filter(|field| !previous.contains(field))
+            .cloned()
+            .collect();
+        if added.is_empty() {
+            return;
+        }
+
+        // Building an index over a large table takes a while, so the request
+        // does not wait for it; filters work without the index meanwhile.
+        let log_repository = self.log_repository.clone();
+        tokio::spawn(async move {
+            for field in added {
+                if let Err(e) = log_repository.create_field_index(schema_id, &field).await {
+                    tracing::warn!(
+                        "Failed to create index on '{}' for schema {}: {}",
+                        field,
+                        schema_id,
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    fn validate_naming(&self, name: &str, version: &str) -> AppResult<()> {
+        if let Some(pattern) = &self.naming_policy.name_pattern {
+            if !pattern.is_match(name) {
+                return Err(AppError::bad_request(format!(
+                    "Schema name '{}' does not match the required pattern {}",
+                    name, pattern
+                ))
+                .with_code(ErrorCode::InvalidName));
+            }
+        }
+
+        if let Some(pattern) = &self.naming_policy.version_pattern {
+            if !pattern.is_match(version) {
+                return Err(AppError::bad_request(format!(
+                    "Schema version '{}' does not match the required pattern {}",
+                    version, pattern
+                ))
+                .with_code(ErrorCode::InvalidVersion));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_indexed_fields(indexed_fields: &[String]) -> AppResult<()> {
+        if indexed_fields.len() > MAX_INDEXED_FIELDS {
+            return Err(AppError::validation_error(format!(
+                "At most {} indexed fields are allowed",
+                MAX_INDEXED_FIELDS
+            )));
+        }
+
+        for (position, field) in indexed_fields.iter().enumerate() {
+            let mut chars = field.chars();
+            let is_identifier = chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && field.len() <= 63;
+
+            if !is_identifier {
+                return Err(AppError::validation_error(format!(
+                    "Indexed field '{}' must be a top-level field name of letters, digits and underscores",
+                    field
+                )));
+            }
+
+            if indexed_fields[..position].contains(field) {
+                return Err(AppError::validation_error(format!(
+                    "Indexed field '{}' is listed more than once",
+                    field
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_retention(
+        max_retained_logs: Option<i32>,
+        max_log_age_secs: Option<i32>,
+    ) -> AppResult<()> {
+        for (field, value) in [
+            ("max_retained_logs", max_retained_logs),
+            ("max_log_age_secs", max_log_age_secs),
+        ] {
+            if value.is_some_and(|value| value < 1) {
+                return Err(AppError::validation_error(format!(
+                    "{} must be at least 1",
+                    field
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_max_field_bytes(max_field_bytes: Option<i32>) -> AppResult<()> {
+        match max_field_bytes {
+            Some(max) if max < 1 => Err(AppError::validation_error(
+                "max_field_bytes must be at least 1",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_sample_rate(sample_rate: Option<f64>) -> AppResult<()> {
+        match sample_rate {
+            Some(rate) if !(0.0..=1.0).contains(&rate) => Err(AppError::validation_error(
+                "sample_rate must be between 0.0 and 1.0",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    async fn check_schema_limit(&self) -> AppResult<()> {
+        let Some(max) = self.max_schemas else {
+            return Ok(());
+        };
+
+        let count = self
+            .repository
+            .count(None)
             .await
-            .map_err(|e| e.context(format!("Failed to delete schema {}", id)))?
-            .ok_or_else(|| AppError::not_found(format!("Schema with id {} not found", id)))
+            .map_err(|e| e.context("Failed to count schemas"))?;
+
+        if count as u64 >= max {
+            return Err(
+                AppError::forbidden(format!("Schema limit of {} reached", max))
+                    .with_code(ErrorCode::LimitReached)
+                    .with_detail("limit", max),
+            );
+        }
+        Ok(())
+    }
+
+    fn validate_labels(labels: &[String]) -> AppResult<()> {
+        if labels.iter().any(|label| label.trim().is_empty()) {
+            return Err(AppError::validation_error("Schema labels cannot be empty"));
+        }
+        Ok(())
+    }
+
+    fn validate_redacted_fields(redacted_fields: &[String]) -> AppResult<()> {
+        if redacted_fields.iter().any(|field| field.trim().is_empty()) {
+            return Err(AppError::validation_error(
+                "Redacted field names cannot be empty",
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_webhook_url(webhook_url: Option<&str>) -> AppResult<()> {
+        if let Some(url) = webhook_url {
+            let parsed = reqwest::Url::parse(url)
+                .map_err(|e| AppError::validation_error(format!("Invalid webhook URL: {}", e)))?;
+
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(AppError::validation_error(
+                    "Webhook URL must use http or https",
+                ));
+            }
+        }
+
+        Ok(())
     }
 
     fn validate_schema_definition(&self, schema_definition: &Value) -> AppResult<()> {
@@ -335,6 +1117,10 @@ impl SchemaService {
             ));
         }
 
+        // Checked before compiling, which is what a pathological definition
+        // would make expensive.
+        self.validate_complexity(schema_definition)?;
+
         jsonschema::validator_for(schema_definition).map_err(|e| {
             AppError::schema_validation_error(format!("Invalid JSON Schema: {}", e))
         })?;
@@ -342,13 +1128,60 @@ impl SchemaService {
         Ok(())
     }
 
-    pub async fn get_initial_cursor(&self) -> AppResult<Uuid> {
-        let latest_id = self
-            .repository
-            .get_latest_schema_id()
-            .await
-            .map_err(|e| e.context("Failed to get the latest schema ID"))?;
+    fn validate_complexity(&self, schema_definition: &Value) -> AppResult<()> {
+        let limits = &self.complexity_limits;
+        let too_complex = |limit: &str, max: usize, actual: usize, what: &str| {
+            AppError::bad_request(format!(
+                "Schema definition is too complex: {} is {}, the maximum is {}",
+                what, actual, max
+            ))
+            .with_code(ErrorCode::SchemaTooComplex)
+            .with_detail("limit", limit)
+            .with_detail("max", max)
+            .with_detail("actual", actual)
+        };
+
+        let bytes = serde_json::to_vec(schema_definition)
+            .map(|json| json.len())
+            .unwrap_or(0);
+        if bytes > limits.max_bytes {
+            return Err(too_complex(
+                "max_bytes",
+                limits.max_bytes,
+                bytes,
+                "its size in bytes",
+            ));
+        }
+
+        let depth = json_depth(schema_definition);
+        if depth > limits.max_depth {
+            return Err(too_complex(
+                "max_depth",
+                limits.max_depth,
+                depth,
+                "its nesting depth",
+            ));
+        }
 
-        Ok(latest_id.unwrap_or(Uuid::nil()))
+        let properties = count_properties(schema_definition);
+        if properties > limits.max_properties {
+            return Err(too_complex(
+                "max_properties",
+                limits.max_properties,
+                properties,
+                "its number of properties",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Id of the tenant's newest schema, the entry point for cursor
+    /// pagination; `None` when the tenant has no schemas.
+    pub async fn get_initial_cursor(&self, tenant_id: &str) -> AppResult<Option<Uuid>> {
+        self.repository
+            .get_latest_schema_id(tenant_id)
+            .await
+            .map_err(|e| e.context("Failed to get the latest schema ID"))
     }
 }
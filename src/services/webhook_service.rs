@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, StatusCode};
+use sha2::Sha256;
+
+use crate::{
+    dto::LogEvent,
+    middleware::{trace_context::TRACEPARENT_HEADER, TraceContext},
+    Schema,
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Crab-Pot-Signature";
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+#[derive(Clone)]
+pub struct WebhookService {
+    client: reqwest::Client,
+}
+
+impl Default for WebhookService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookService {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self { client }
+    }
+
+    /// HMAC-SHA256 of the request body, hex encoded and prefixed with `sha256=`.
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        format!("sha256={:x}", mac.finalize().into_bytes())
+    }
+
+    /// Delivers the event to `schema`'s webhook, if it has one, from a
+    /// background task. Failures are logged and never propagated to the
+    /// caller. With a trace context the request carries a child
+    /// `traceparent`.
+    pub fn dispatch(&self, event: LogEvent, schema: &Schema, trace_context: Option<TraceContext>) {
+        let Some(url) = schema.webhook_url.clone() else {
+            return;
+        };
+
+        let service = self.clone();
+        let secret = schema.webhook_secret.clone();
+        tokio::spawn(async move { service.deliver(event, url, secret, trace_context).await });
+    }
+
+    /// Connection failures, timeouts and 408/429/5xx responses may succeed
+    /// on a later attempt; any other status will not.
+    fn is_retryable(error: &reqwest::Error) -> bool {
+        match error.status() {
+            Some(status) => {
+                status.is_server_error()
+                    || status == StatusCode::REQUEST_TIMEOUT
+                    || status == StatusCode::TOO_MANY_REQUESTS
+            }
+            None => true,
+        }
+    }
+
+    async fn deliver(
+        &self,
+        event: LogEvent,
+        url: String,
+        secret: Option<String>,
+        trace_context: Option<TraceContext>,
+    ) {
+        let schema_id = event.schema_id();
+
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to serialize webhook payload: {}", e);
+                return;
+            }
+        };
+
+        let signature = secret.as_deref().map(|secret| Self::sign(secret, &body));
+
+        let mut backoff = INITIAL_BACKOFF;
+
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            let mut request = self
+                .client
+                .post(&url)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone());
+
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+            if let Some(trace_context) = &trace_context {
+                request = request.header(TRACEPARENT_HEADER, trace_context.child_header());
+            }
+
+            match request.send().await.and_then(|r| r.error_for_status()) {
+                Ok(_) => {
+                    tracing::debug!("Delivered webhook for schema {} to {}", schema_id, url);
+                    return;
+                }
+                Err(e) if !Self::is_retryable(&e) => {
+                    tracing::error!(
+                        "Webhook delivery for schema {} to {} rejected, not retrying: {}",
+                        schema_id,
+                        url,
+                        e
+                    );
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Webhook delivery to {} failed (attempt {}/{}): {}",
+                        url,
+                        attempt,
+                        MAX_DELIVERY_ATTEMPTS,
+                        e
+                    );
+                }
+            }
+
+            if attempt < MAX_DELIVERY_ATTEMPTS {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+
+        tracing::error!(
+            "Giving up on webhook delivery for schema {} to {}",
+            schema_id,
+            url
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crab_pot::AdminIpAllowlist;
+use reqwest::StatusCode;
+
+use crate::common::{setup_admin_test_app, setup_admin_test_app_with, ErrorResponse};
+
+fn networks(cidrs: &[&str]) -> Vec<sqlx::types::ipnetwork::IpNetwork> {
+    cidrs.iter().map(|cidr| cidr.parse().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn empty_allowlist_allows_any_source() {
+    let app = setup_admin_test_app().await;
+
+    let response = app.client().get("/api-keys").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn allowed_source_ip_is_let_through() {
+    let app = setup_admin_test_app_with(|builder| {
+        builder.with_admin_allowlist(AdminIpAllowlist::new(networks(&["127.0.0.0/8"]), vec![]))
+    })
+    .await;
+
+    let response = app.client().get("/api-keys").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn disallowed_source_ip_is_rejected() {
+    let app = setup_admin_test_app_with(|builder| {
+        builder.with_admin_allowlist(AdminIpAllowlist::new(networks(&["10.0.0.0/8"]), vec![]))
+    })
+    .await;
+
+    let response = app.client().get("/api-keys").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "IP_NOT_ALLOWED");
+}
+
+#[tokio::test]
+async fn forwarded_for_is_ignored_from_untrusted_peers() {
+    let app = setup_admin_test_app_with(|builder| {
+        builder.with_admin_allowlist(AdminIpAllowlist::new(networks(&["10.0.0.0/8"]), vec![]))
+    })
+    .await;
+
+    let response = app
+        .client()
+        .get("/api-keys")
+        .header("X-Forwarded-For", "10.1.2.3")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn forwarded_for_from_trusted_proxy_decides() {
+    let app = setup_admin_test_app_with(|builder| {
+        builder.with_admin_allowlist(AdminIpAllowlist::new(
+            networks(&["10.0.0.0/8"]),
+            networks(&["127.0.0.1/32"]),
+        ))
+    })
+    .await;
+
+    let response = app
+        .client()
+        .get("/api-keys")
+        .header("X-Forwarded-For", "192.168.1.5, 10.1.2.3")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .client()
+        .get("/api-keys")
+        .header("X-Forwarded-For", "10.1.2.3, 192.168.1.5")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,3 @@
+pub mod ip_allowlist;
+pub mod reset;
+pub mod slow_requests;
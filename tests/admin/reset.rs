@@ -0,0 +1,107 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::common::{
+    create_api_key, setup_admin_test_app, setup_admin_test_app_with, valid_api_key_payload,
+    AdminTestApp, ErrorResponse,
+};
+
+async fn reset(app: &AdminTestApp, query: &str) -> reqwest::Response {
+    app.client()
+        .post(format!("/admin/reset{}", query))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn count_rows(app: &AdminTestApp, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reset_is_forbidden_without_the_flag() {
+    let app = setup_admin_test_app().await;
+    let schemas = count_rows(&app, "schemas").await;
+    assert!(schemas > 0);
+
+    let response = reset(&app, "?confirm=DELETE_EVERYTHING").await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "RESET_DISABLED");
+    assert_eq!(count_rows(&app, "schemas").await, schemas);
+}
+
+#[tokio::test]
+async fn reset_requires_confirmation() {
+    let app = setup_admin_test_app_with(|builder| builder.with_allow_reset(true)).await;
+    let schemas = count_rows(&app, "schemas").await;
+
+    for query in ["", "?confirm=yes"] {
+        let response = reset(&app, query).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(body.error, "CONFIRMATION_REQUIRED");
+    }
+    assert_eq!(count_rows(&app, "schemas").await, schemas);
+}
+
+#[tokio::test]
+async fn reset_empties_logs_and_schemas() {
+    let app = setup_admin_test_app_with(|builder| builder.with_allow_reset(true)).await;
+    let response = create_api_key(&app, &valid_api_key_payload("kept-key")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let logs = count_rows(&app, "logs").await;
+    let schemas = count_rows(&app, "schemas").await;
+    assert!(logs > 0);
+
+    let response = reset(&app, "?confirm=DELETE_EVERYTHING").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["deleted_logs"], logs);
+    assert_eq!(body["deleted_schemas"], schemas);
+    assert_eq!(body["deleted_api_keys"], 0);
+    assert_eq!(count_rows(&app, "logs").await, 0);
+    assert_eq!(count_rows(&app, "schemas").await, 0);
+    assert_eq!(count_rows(&app, "api_keys").await, 1);
+}
+
+#[tokio::test]
+async fn reset_can_include_api_keys() {
+    let app = setup_admin_test_app_with(|builder| builder.with_allow_reset(true)).await;
+    let response = create_api_key(&app, &valid_api_key_payload("doomed-key")).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = reset(&app, "?confirm=DELETE_EVERYTHING&include_api_keys=true").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["deleted_api_keys"], 1);
+    assert_eq!(count_rows(&app, "api_keys").await, 0);
+    assert_eq!(count_rows(&app, "schemas").await, 0);
+}
+
+#[tokio::test]
+async fn reset_drops_field_indexes() {
+    let app = setup_admin_test_app_with(|builder| builder.with_allow_reset(true)).await;
+    let field_indexes = "SELECT COUNT(*) FROM pg_indexes \
+        WHERE tablename = 'logs' AND indexname LIKE 'idx\\_logs\\_field\\_%'";
+    sqlx::query("CREATE INDEX idx_logs_field_orphan ON logs ((log_data->>'level'))")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let response = reset(&app, "?confirm=DELETE_EVERYTHING").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let remaining: i64 = sqlx::query_scalar(field_indexes)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
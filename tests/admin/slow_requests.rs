@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::common::{setup_test_app_with, TestApp};
+
+async fn slow_requests(app: &TestApp) -> Value {
+    let response = app
+        .client
+        .get(format!("{}/admin/slow-requests", app.admin_address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn slow_request_is_recorded() {
+    let app =
+        setup_test_app_with(|builder| builder.with_slow_requests(Duration::from_millis(200), 10))
+            .await;
+
+    let fast = app
+        .client
+        .get(format!("{}/health", app.address))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(fast.status(), StatusCode::OK);
+
+    // Holding a lock on `schemas` stalls the listing until it is released.
+    let mut lock = app.db_pool.begin().await.unwrap();
+    sqlx::query("LOCK TABLE schemas IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+    let slow = tokio::spawn({
+        let request = app
+            .auth()
+            .get("/schemas")
+            .header("X-Request-ID", "slow-listing");
+        async move { request.send().await.unwrap() }
+    });
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    lock.rollback().await.unwrap();
+    assert_eq!(slow.await.unwrap().status(), StatusCode::OK);
+
+    let body = slow_requests(&app).await;
+    assert_eq!(body["threshold_ms"], 200);
+    let requests = body["requests"].as_array().unwrap();
+    assert_eq!(requests.len(), 1, "{:?}", requests);
+    assert_eq!(requests[0]["method"], "GET");
+    assert_eq!(requests[0]["path"], "/schemas");
+    assert_eq!(requests[0]["status"], 200);
+    assert_eq!(requests[0]["request_id"], "slow-listing");
+    assert!(requests[0]["duration_ms"].as_u64().unwrap() >= 200);
+}
+
+#[tokio::test]
+async fn buffer_keeps_only_the_most_recent() {
+    let app = setup_test_app_with(|builder| builder.with_slow_requests(Duration::ZERO, 2)).await;
+
+    for path in ["/health", "/version", "/"] {
+        app.client
+            .get(format!("{}{}", app.address, path))
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let body = slow_requests(&app).await;
+    let mut paths: Vec<_> = body["requests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|request| request["path"].as_str().unwrap().to_string())
+        .collect();
+    paths.sort();
+    assert_eq!(paths, ["/", "/version"]);
+}
@@ -0,0 +1,91 @@
+use crate::common::{
+    add_allowed_ip, create_api_key, get_api_key_by_id, remove_allowed_ip, setup_admin_test_app,
+};
+use crab_pot::dto::{ApiKeyResponse, CreateApiKeyResponse};
+use reqwest::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+async fn add_and_remove_allowed_ips() {
+    let app = setup_admin_test_app().await;
+
+    let create_response = create_api_key(&app, &json!({ "name": "ip-key" })).await;
+    let created: CreateApiKeyResponse = create_response.json().await.unwrap();
+
+    let response = add_allowed_ip(&app, created.id, "10.0.0.0/24").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = add_allowed_ip(&app, created.id, "192.168.1.0/24").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let key: ApiKeyResponse = response.json().await.unwrap();
+    assert_eq!(key.allowed_ips.unwrap().len(), 2);
+
+    let response = remove_allowed_ip(&app, created.id, "10.0.0.0/24").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get_api_key_by_id(&app, created.id).await;
+    let key: ApiKeyResponse = response.json().await.unwrap();
+    let allowed_ips: Vec<String> = key
+        .allowed_ips
+        .unwrap()
+        .iter()
+        .map(|network| network.to_string())
+        .collect();
+    assert_eq!(allowed_ips, vec!["192.168.1.0/24"]);
+}
+
+#[tokio::test]
+async fn add_duplicate_allowed_ip_is_ignored() {
+    let app = setup_admin_test_app().await;
+
+    let create_response = create_api_key(&app, &json!({ "name": "ip-key" })).await;
+    let created: CreateApiKeyResponse = create_response.json().await.unwrap();
+
+    add_allowed_ip(&app, created.id, "10.0.0.0/24").await;
+    let response = add_allowed_ip(&app, created.id, "10.0.0.0/24").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let key: ApiKeyResponse = response.json().await.unwrap();
+    assert_eq!(key.allowed_ips.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn invalid_cidr_returns_bad_request() {
+    let app = setup_admin_test_app().await;
+
+    let create_response = create_api_key(&app, &json!({ "name": "ip-key" })).await;
+    let created: CreateApiKeyResponse = create_response.json().await.unwrap();
+
+    let response = add_allowed_ip(&app, created.id, "not-a-cidr").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = remove_allowed_ip(&app, created.id, "10.0.0.0/99").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn allowed_ips_on_missing_key_returns_not_found() {
+    let app = setup_admin_test_app().await;
+
+    let response = add_allowed_ip(&app, 99999, "10.0.0.0/24").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn removing_last_allowed_ip_is_rejected() {
+    let app = setup_admin_test_app().await;
+
+    let create_response = create_api_key(&app, &json!({ "name": "ip-key" })).await;
+    let created: CreateApiKeyResponse = create_response.json().await.unwrap();
+    add_allowed_ip(&app, created.id, "10.0.0.0/24").await;
+
+    let response = remove_allowed_ip(&app, created.id, "10.0.0.0/24").await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = get_api_key_by_id(&app, created.id).await;
+    let key: ApiKeyResponse = response.json().await.unwrap();
+    assert_eq!(key.allowed_ips.unwrap().len(), 1);
+
+    let response = remove_allowed_ip(&app, 99999, "10.0.0.0/24").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,45 @@
+use reqwest::StatusCode;
+
+use crate::common::{setup_test_app, API_KEY_CACHE_TTL};
+
+#[tokio::test]
+async fn revoked_key_is_rejected_once_cache_ttl_lapses() {
+    let app = setup_test_app().await;
+
+    let response = app.auth().get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Revoked behind the service's back, as another instance would.
+    sqlx::query("UPDATE api_keys SET is_active = false WHERE id = $1")
+        .bind(app.api_key_id)
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let response = app.auth().get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    tokio::time::sleep(API_KEY_CACHE_TTL + std::time::Duration::from_millis(100)).await;
+
+    let response = app.auth().get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn deleted_key_is_rejected_immediately() {
+    let app = setup_test_app().await;
+
+    let response = app.auth().get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .client
+        .delete(format!("{}/api-keys/{}", app.admin_address, app.api_key_id))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = app.auth().get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -1,10 +1,15 @@
+use std::sync::Arc;
+
 use crate::common::{
-    create_api_key, get_api_key_by_id, setup_admin_test_app, valid_api_key_payload,
+    create_api_key, get_api_key_by_id, setup_admin_test_app, setup_admin_test_app_with,
+    valid_api_key_payload,
 };
 use chrono::{Duration, Utc};
-use crab_pot::dto::CreateApiKeyResponse;
+use crab_pot::{
+    dto::CreateApiKeyResponse, models::CreateApiKey, ApiKeyFormat, ApiKeyRepository, ApiKeyService,
+};
 use reqwest::StatusCode;
-use serde_json::json;
+use serde_json::{json, Value};
 
 #[tokio::test]
 async fn create_valid_key() {
@@ -120,6 +125,39 @@ async fn create_key_persists_to_database() {
     assert_eq!(get_response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn create_key_with_tenant() {
+    let app = setup_admin_test_app().await;
+
+    let payload = json!({ "name": "tenant-key", "tenant_id": "acme" });
+    let created: CreateApiKeyResponse = create_api_key(&app, &payload).await.json().await.unwrap();
+
+    let stored: Value = get_api_key_by_id(&app, created.id)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored["tenant_id"], "acme");
+}
+
+#[tokio::test]
+async fn create_key_defaults_to_default_tenant() {
+    let app = setup_admin_test_app().await;
+
+    let created: CreateApiKeyResponse = create_api_key(&app, &valid_api_key_payload("plain-key"))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let stored: Value = get_api_key_by_id(&app, created.id)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored["tenant_id"], "default");
+}
+
 #[tokio::test]
 async fn rejects_empty_name() {
     let app = setup_admin_test_app().await;
@@ -176,3 +214,93 @@ async fn create_key_with_all_fields() {
     assert!(response_body.expires_at.is_some());
     assert!(!response_body.key.is_empty());
 }
+
+#[tokio::test]
+async fn create_key_with_custom_prefix_and_minimum_entropy() {
+    let app = setup_admin_test_app().await;
+
+    let service = ApiKeyService::new(Arc::new(ApiKeyRepository::new(app.db_pool.clone())))
+        .with_key_format(
+            ApiKeyFormat::new("acme_".to_string(), ApiKeyFormat::MIN_RANDOM_BYTES).unwrap(),
+        );
+
+    let created = service
+        .create_api_key(CreateApiKey::new("short-key"))
+        .await
+        .unwrap();
+
+    assert!(created.plain_key.starts_with("acme_"));
+    assert_eq!(created.plain_key.len(), "acme_".len() + 22);
+    assert_eq!(
+        created.api_key.key_prefix,
+        Some(format!("{}...", &created.plain_key[.."acme_".len() + 7]))
+    );
+
+    let rotated = service.rotate_api_key(created.api_key.id).await.unwrap();
+    assert!(rotated.plain_key.starts_with("acme_"));
+}
+
+#[tokio::test]
+async fn create_key_with_relative_expiry() {
+    let app = setup_admin_test_app().await;
+
+    let payload = json!({ "name": "relative-expiry-key", "expires_in": "7d" });
+
+    let response = create_api_key(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response_body: CreateApiKeyResponse = response.json().await.unwrap();
+    let expires_at = response_body.expires_at.unwrap();
+    let drift = (expires_at - (Utc::now() + Duration::days(7)))
+        .num_seconds()
+        .abs();
+    assert!(
+        drift < 60,
+        "expires_at {} is not about 7 days out",
+        expires_at
+    );
+}
+
+#[tokio::test]
+async fn rejects_both_expires_at_and_expires_in() {
+    let app = setup_admin_test_app().await;
+
+    let payload = json!({
+        "name": "double-expiry-key",
+        "expires_at": (Utc::now() + Duration::days(1)).to_rfc3339(),
+        "expires_in": "1d"
+    });
+
+    let response = create_api_key(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_EXPIRY");
+}
+
+#[tokio::test]
+async fn rejects_unknown_expires_in_units() {
+    let app = setup_admin_test_app().await;
+
+    for expires_in in ["7y", "d", "0d", "-1d", "1.5h", "99999999999999999999d"] {
+        let payload = json!({ "name": "bad-expiry-key", "expires_in": expires_in });
+
+        let response = create_api_key(&app, &payload).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", expires_in);
+    }
+}
+
+#[tokio::test]
+async fn key_limit_blocks_creation_once_reached() {
+    let app = setup_admin_test_app_with(|builder| builder.with_max_api_keys(Some(2))).await;
+
+    for name in ["capped-1", "capped-2"] {
+        let response = create_api_key(&app, &valid_api_key_payload(name)).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = create_api_key(&app, &valid_api_key_payload("capped-3")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "LIMIT_REACHED");
+    assert_eq!(body["limit"], 2);
+}
@@ -1,7 +1,10 @@
 use crate::common::{
-    create_api_key, delete_api_key, get_api_key_by_id, get_api_keys, setup_admin_test_app,
+    batch_delete_api_keys, create_api_key, delete_api_key, get_api_key_by_id, get_api_keys,
+    setup_admin_test_app,
+};
+use crab_pot::dto::{
+    ApiKeysResponse, BatchDeleteApiKeysResponse, BatchDeleteStatus, CreateApiKeyResponse,
 };
-use crab_pot::dto::{ApiKeysResponse, CreateApiKeyResponse};
 use reqwest::StatusCode;
 use serde_json::json;
 
@@ -145,3 +148,41 @@ async fn delete_does_not_affect_other_keys() {
     let get_response3 = get_api_key_by_id(&app, key3.id).await;
     assert_eq!(get_response3.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn batch_delete_reports_deleted_and_missing_keys() {
+    let app = setup_admin_test_app().await;
+
+    let first: CreateApiKeyResponse = create_api_key(&app, &json!({ "name": "batch-1" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    let second: CreateApiKeyResponse = create_api_key(&app, &json!({ "name": "batch-2" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response =
+        batch_delete_api_keys(&app, &json!({ "ids": [first.id, second.id, 99999] })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: BatchDeleteApiKeysResponse = response.json().await.unwrap();
+    assert_eq!(body.results.len(), 3);
+    assert_eq!(body.results[&first.id], BatchDeleteStatus::Deleted);
+    assert_eq!(body.results[&second.id], BatchDeleteStatus::Deleted);
+    assert_eq!(body.results[&99999], BatchDeleteStatus::NotFound);
+
+    let get_response = get_api_key_by_id(&app, first.id).await;
+    assert_eq!(get_response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn batch_delete_rejects_oversized_batch() {
+    let app = setup_admin_test_app().await;
+
+    let ids: Vec<i32> = (1..=101).collect();
+    let response = batch_delete_api_keys(&app, &json!({ "ids": ids })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crab_pot::{
+    models::CreateApiKey, ApiKeyFormat, ApiKeyHashAlgorithm, ApiKeyRepository, ApiKeyService,
+};
+use reqwest::StatusCode;
+
+use crate::common::{setup_test_app, TestApp};
+
+async fn create_key_hashed_with(app: &TestApp, algorithm: ApiKeyHashAlgorithm) -> (String, String) {
+    let service = ApiKeyService::new(Arc::new(ApiKeyRepository::new(app.db_pool.clone())))
+        .with_hash_algorithm(algorithm);
+
+    let created = service
+        .create_api_key(CreateApiKey::new(format!("{:?} key", algorithm)))
+        .await
+        .unwrap();
+
+    (created.plain_key, created.api_key.key_hash)
+}
+
+#[tokio::test]
+async fn authenticates_with_sha256_hashed_key() {
+    let app = setup_test_app().await;
+
+    let (key, key_hash) = create_key_hashed_with(&app, ApiKeyHashAlgorithm::Sha256).await;
+    assert_eq!(key_hash, ApiKeyService::hash_key(&key));
+
+    let response = app.auth_with(&key).get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn authenticates_with_argon2_hashed_key() {
+    let app = setup_test_app().await;
+
+    let (key, key_hash) = create_key_hashed_with(&app, ApiKeyHashAlgorithm::Argon2).await;
+    assert!(key_hash.starts_with("$argon2id$"), "{}", key_hash);
+    assert!(ApiKeyService::verify_key(&key, &key_hash));
+
+    let response = app.auth_with(&key).get("/schemas").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut wrong_key = key.clone();
+    wrong_key.push('x');
+    let response = app
+        .auth_with(&wrong_key)
+        .get("/schemas")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn argon2_key_authenticates_after_prefix_change() {
+    let app = setup_test_app().await;
+
+    let service = ApiKeyService::new(Arc::new(ApiKeyRepository::new(app.db_pool.clone())))
+        .with_key_format(ApiKeyFormat::new("lgs_prod_".to_string(), 32).unwrap())
+        .with_hash_algorithm(ApiKeyHashAlgorithm::Argon2);
+    let created = service
+        .create_api_key(CreateApiKey::new("old prefix key".to_string()))
+        .await
+        .unwrap();
+    assert!(created.plain_key.starts_with("lgs_prod_"));
+
+    let response = app
+        .auth_with(&created.plain_key)
+        .get("/schemas")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
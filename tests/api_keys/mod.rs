@@ -1,4 +1,8 @@
+pub mod allowed_ips;
+pub mod cache;
 pub mod create;
 pub mod delete;
+pub mod hashing;
 pub mod read;
 pub mod rotate;
+pub mod stats;
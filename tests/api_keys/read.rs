@@ -228,3 +228,28 @@ async fn get_key_with_allowed_ips_shows_ips() {
     assert!(key.allowed_ips.is_some());
     assert!(!key.allowed_ips.unwrap().is_empty());
 }
+
+#[tokio::test]
+async fn page_format_wraps_keys_in_unified_envelope() {
+    let app = setup_admin_test_app().await;
+
+    let response = create_api_key(&app, &json!({ "name": "page-format-key" })).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = app
+        .client()
+        .get("/api-keys?format=page")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body.get("api_keys").is_none());
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["name"], "page-format-key");
+    assert_eq!(body["pagination"]["limit"], 1);
+    assert_eq!(body["pagination"]["total"], 1);
+    assert_eq!(body["pagination"]["has_more"], false);
+    assert!(body["pagination"]["next_cursor"].is_null());
+}
@@ -1,7 +1,7 @@
 use crate::common::{create_api_key, get_api_key_by_id, rotate_api_key, setup_admin_test_app};
-use crab_pot::dto::{ApiKeyResponse, CreateApiKeyResponse};
+use crab_pot::dto::{ApiKeyResponse, CreateApiKeyResponse, RotatedApiKeysResponse};
 use reqwest::StatusCode;
-use serde_json::json;
+use serde_json::{json, Value};
 
 #[tokio::test]
 async fn rotate_existing_key_success() {
@@ -243,3 +243,65 @@ async fn rotate_concurrent_rotations() {
     assert_ne!(rotated1.key, rotated2.key);
     assert_eq!(rotated1.id, rotated2.id);
 }
+
+#[tokio::test]
+async fn rotate_expiring_rotates_only_keys_inside_the_window() {
+    let app = setup_admin_test_app().await;
+
+    let mut soon = Vec::new();
+    for expires_in in ["2d", "1h"] {
+        let response = create_api_key(
+            &app,
+            &json!({ "name": format!("expiring-{}", expires_in), "expires_in": expires_in }),
+        )
+        .await;
+        soon.push(response.json::<CreateApiKeyResponse>().await.unwrap());
+    }
+    let later: CreateApiKeyResponse =
+        create_api_key(&app, &json!({ "name": "later", "expires_in": "30d" }))
+            .await
+            .json()
+            .await
+            .unwrap();
+
+    let response = app
+        .client()
+        .post("/api-keys/rotate-expiring?within=7d")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: RotatedApiKeysResponse = response.json().await.unwrap();
+
+    // Soonest expiry first.
+    let ids: Vec<i32> = body.rotated.iter().map(|key| key.id).collect();
+    assert_eq!(ids, vec![soon[1].id, soon[0].id]);
+    assert!(!ids.contains(&later.id));
+
+    let unchanged: ApiKeyResponse = get_api_key_by_id(&app, later.id)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(unchanged.key_prefix, later.key_prefix);
+
+    for (rotated, original) in body.rotated.iter().zip(soon.iter().rev()) {
+        assert_ne!(rotated.key, original.key);
+        assert_eq!(rotated.expires_at, original.expires_at);
+    }
+}
+
+#[tokio::test]
+async fn rotate_expiring_rejects_invalid_window() {
+    let app = setup_admin_test_app().await;
+
+    let response = app
+        .client()
+        .post("/api-keys/rotate-expiring?within=soon")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_WINDOW");
+}
@@ -0,0 +1,69 @@
+use crab_pot::dto::ApiKeyStatsResponse;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_api_key, setup_admin_test_app, AdminTestApp};
+
+async fn get_stats(app: &AdminTestApp, query: &str) -> reqwest::Response {
+    app.client()
+        .get(format!("/api-keys/stats{}", query))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn create_key(app: &AdminTestApp, payload: Value) -> i32 {
+    let response = create_api_key(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.json::<Value>().await.unwrap()["id"]
+        .as_i64()
+        .unwrap() as i32
+}
+
+#[tokio::test]
+async fn stats_count_keys_in_each_state() {
+    let app = setup_admin_test_app().await;
+    let before: ApiKeyStatsResponse = get_stats(&app, "").await.json().await.unwrap();
+
+    create_key(&app, json!({ "name": "stats-active" })).await;
+    create_key(&app, json!({ "name": "stats-soon", "expires_in": "2d" })).await;
+    create_key(&app, json!({ "name": "stats-later", "expires_in": "30d" })).await;
+
+    let expired = create_key(&app, json!({ "name": "stats-expired" })).await;
+    sqlx::query("UPDATE api_keys SET expires_at = NOW() - INTERVAL '1 hour' WHERE id = $1")
+        .bind(expired)
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let revoked = create_key(&app, json!({ "name": "stats-revoked" })).await;
+    sqlx::query("UPDATE api_keys SET is_active = false WHERE id = $1")
+        .bind(revoked)
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let response = get_stats(&app, "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let after: ApiKeyStatsResponse = response.json().await.unwrap();
+
+    assert_eq!(after.total - before.total, 5);
+    assert_eq!(after.active - before.active, 3);
+    assert_eq!(after.expired - before.expired, 1);
+    assert_eq!(after.revoked - before.revoked, 1);
+    assert_eq!(after.expiring_soon - before.expiring_soon, 1);
+
+    let wide: ApiKeyStatsResponse = get_stats(&app, "?within=60d").await.json().await.unwrap();
+    assert_eq!(wide.expiring_soon - before.expiring_soon, 2);
+}
+
+#[tokio::test]
+async fn stats_reject_invalid_window() {
+    let app = setup_admin_test_app().await;
+
+    let response = get_stats(&app, "?within=soon").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_WINDOW");
+}
@@ -29,6 +29,39 @@ pub async fn delete_api_key(app: &AdminTestApp, key_id: i32) -> reqwest::Respons
         .unwrap()
 }
 
+pub async fn batch_delete_api_keys(
+    app: &AdminTestApp,
+    payload: &serde_json::Value,
+) -> reqwest::Response {
+    app.client()
+        .post("/api-keys/batch-delete")
+        .json(&payload)
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn add_allowed_ip(app: &AdminTestApp, key_id: i32, cidr: &str) -> reqwest::Response {
+    app.client()
+        .post(format!("/api-keys/{}/allowed-ips", key_id))
+        .json(&serde_json::json!({ "cidr": cidr }))
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn remove_allowed_ip(app: &AdminTestApp, key_id: i32, cidr: &str) -> reqwest::Response {
+    app.client()
+        .delete(format!(
+            "/api-keys/{}/allowed-ips/{}",
+            key_id,
+            cidr.replace('/', "%2F")
+        ))
+        .send()
+        .await
+        .unwrap()
+}
+
 pub async fn rotate_api_key(app: &AdminTestApp, key_id: i32) -> reqwest::Response {
     app.client()
         .post(format!("/api-keys/{}/rotate", key_id))
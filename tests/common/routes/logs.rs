@@ -1,3 +1,5 @@
+use uuid::Uuid;
+
 use crate::common::{
     fixtures::{valid_log_payload, valid_log_payload_with_message},
     test_app::TestApp,
@@ -105,3 +107,121 @@ pub async fn get_logs_with_cursor<S: AsRef<str>>(
         .await
         .unwrap()
 }
+
+pub async fn log_action(app: &TestApp, payload: &serde_json::Value) -> reqwest::Response {
+    app.auth()
+        .post("/logs/action")
+        .json(payload)
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn export_logs<S: AsRef<str>>(
+    app: &TestApp,
+    schema_id: S,
+    after: Option<(&str, i64)>,
+    limit: i32,
+) -> reqwest::Response {
+    let mut query_params = vec![("limit", limit.to_string())];
+    if let Some((created_at, id)) = after {
+        query_params.push(("after_created_at", created_at.to_string()));
+        query_params.push(("after_id", id.to_string()));
+    }
+
+    app.auth()
+        .get(format!("/logs/schemas/{}/export", schema_id.as_ref()))
+        .query(&query_params)
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn create_logs_bulk(
+    app: &TestApp,
+    payload: &serde_json::Value,
+    validate_only: bool,
+) -> reqwest::Response {
+    app.auth()
+        .post("/logs/bulk")
+        .query(&[("validate_only", validate_only)])
+        .json(payload)
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn ingest_logs(app: &TestApp, body: String) -> reqwest::Response {
+    app.auth()
+        .post("/logs/ingest")
+        .header("Content-Type", "application/x-ndjson")
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn get_logs_by_schema_name_with_cursor(
+    app: &TestApp,
+    path: &str,
+    cursor: Option<i64>,
+    limit: i32,
+    direction: &str,
+) -> reqwest::Response {
+    let mut query_params = vec![
+        ("limit", limit.to_string()),
+        ("direction", direction.to_string()),
+    ];
+    if let Some(c) = cursor {
+        query_params.push(("cursor", c.to_string()));
+    }
+
+    app.auth()
+        .get(format!("/logs/by-schema-name/{}", path))
+        .query(&query_params)
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn patch_log(
+    app: &TestApp,
+    id: i32,
+    content_type: &str,
+    patch: &serde_json::Value,
+) -> reqwest::Response {
+    app.auth()
+        .patch(format!("/logs/{}", id))
+        .header("Content-Type", content_type)
+        .body(patch.to_string())
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn put_log_by_key(
+    app: &TestApp,
+    schema_id: Uuid,
+    key: &str,
+    log_data: &serde_json::Value,
+) -> reqwest::Response {
+    app.auth()
+        .put(format!("/logs/schemas/{}/by-key/{}", schema_id, key))
+        .json(&serde_json::json!({ "log_data": log_data }))
+        .send()
+        .await
+        .unwrap()
+}
+
+pub async fn filter_logs<S: AsRef<str>>(
+    app: &TestApp,
+    schema_id: S,
+    body: &serde_json::Value,
+) -> reqwest::Response {
+    app.auth()
+        .post(format!("/logs/schemas/{}/query", schema_id.as_ref()))
+        .json(body)
+        .send()
+        .await
+        .unwrap()
+}
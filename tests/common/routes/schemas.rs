@@ -22,6 +22,15 @@ pub async fn get_schemas(app: &TestApp) -> reqwest::Response {
     app.auth().get("/schemas").send().await.unwrap()
 }
 
+pub async fn batch_get_schemas(app: &TestApp, payload: &serde_json::Value) -> reqwest::Response {
+    app.auth()
+        .post("/schemas/batch-get")
+        .json(payload)
+        .send()
+        .await
+        .unwrap()
+}
+
 pub async fn get_schema_by_id(app: &TestApp, schema_id: &str) -> reqwest::Response {
     app.auth()
         .get(format!("/schemas/{}", schema_id))
@@ -45,6 +54,14 @@ pub async fn get_schema_by_name_and_version(
         .unwrap()
 }
 
+pub async fn get_schema_definition(app: &TestApp, schema_id: &str) -> reqwest::Response {
+    app.auth()
+        .get(format!("/schemas/{}/definition", schema_id))
+        .send()
+        .await
+        .unwrap()
+}
+
 pub async fn update_schema(
     app: &TestApp,
     schema_id: &str,
@@ -58,6 +75,21 @@ pub async fn update_schema(
         .unwrap()
 }
 
+pub async fn patch_schema(
+    app: &TestApp,
+    schema_id: &str,
+    content_type: &str,
+    patch: &serde_json::Value,
+) -> reqwest::Response {
+    app.auth()
+        .patch(format!("/schemas/{}", schema_id))
+        .header("Content-Type", content_type)
+        .body(patch.to_string())
+        .send()
+        .await
+        .unwrap()
+}
+
 pub async fn delete_schema(app: &TestApp, schema_id: &str) -> reqwest::Response {
     app.auth()
         .delete(format!("/schemas/{}", schema_id))
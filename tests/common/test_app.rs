@@ -1,23 +1,42 @@
-use crab_pot::{
-    create_admin_app, create_app, middleware::RateLimiter, ApiKeyRepository, ApiKeyService,
-    AppState, LogRepository, LogService, SchemaRepository, SchemaService,
-};
+use crab_pot::{create_admin_app, create_app, ApiKeyService, AppState, AppStateBuilder};
 use reqwest::{Client, Method, RequestBuilder};
 use sqlx::{Pool, Postgres};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use testcontainers_modules::{
     postgres,
     testcontainers::{runners::AsyncRunner, ContainerAsync},
 };
-use tokio::{net::TcpListener, sync::broadcast};
+use tokio::net::TcpListener;
 use tokio_postgres::NoTls;
+use tokio_util::sync::CancellationToken;
+
+/// API key cache TTL of the apps spawned by [`setup_test_app`].
+#[allow(unused)]
+pub const API_KEY_CACHE_TTL: Duration = Duration::from_secs(1);
+
+/// WebSocket connection limit of the apps spawned by [`setup_test_app`].
+#[allow(unused)]
+pub const WS_MAX_CONNECTIONS: usize = 8;
 
 pub struct TestApp {
     pub address: String,
+    /// Admin API sharing this app's state.
+    #[allow(unused)]
+    pub admin_address: String,
     pub client: Client,
     #[allow(unused)]
     pub db_pool: Pool<Postgres>,
     pub api_key_service: Arc<ApiKeyService>,
+    #[allow(unused)]
+    pub api_key_id: i32,
+    /// The app state's shutdown token; cancelling it simulates a server
+    /// shutdown.
+    #[allow(unused)]
+    pub shutdown: CancellationToken,
+    /// `http://` address of the gRPC ingestion service sharing this app's
+    /// state.
+    #[cfg(feature = "grpc")]
+    pub grpc_address: String,
     api_key: String,
     _container: ContainerAsync<postgres::Postgres>,
 }
@@ -36,7 +55,16 @@ impl TestApp {
     }
 
     pub fn auth(&self) -> AuthClient<'_> {
-        AuthClient { app: self }
+        self.auth_with(&self.api_key)
+    }
+
+    /// Like [`Self::auth`], authenticating with another API key.
+    pub fn auth_with<'a>(&'a self, api_key: &'a str) -> AuthClient<'a> {
+        AuthClient { app: self, api_key }
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
     }
 
     pub async fn create_api_key_with_limits(
@@ -51,6 +79,7 @@ impl TestApp {
             allowed_ips: None,
             rate_limit_per_second: Some(rate_limit_per_second),
             rate_limit_burst: Some(rate_limit_burst),
+            tenant_id: crab_pot::models::DEFAULT_TENANT_ID.to_string(),
         };
 
         let created_key = self
@@ -61,6 +90,21 @@ impl TestApp {
 
         created_key.plain_key
     }
+
+    pub async fn create_api_key_for_tenant(&self, tenant_id: &str) -> String {
+        let create_request = crab_pot::models::CreateApiKey {
+            tenant_id: tenant_id.to_string(),
+            ..crab_pot::models::CreateApiKey::new(format!("Test Key ({})", tenant_id))
+        };
+
+        let created_key = self
+            .api_key_service
+            .create_api_key(create_request)
+            .await
+            .expect("Failed to create test API key for tenant");
+
+        created_key.plain_key
+    }
 }
 
 impl AdminTestApp {
@@ -75,6 +119,7 @@ impl AdminTestApp {
 
 pub struct AuthClient<'a> {
     app: &'a TestApp,
+    api_key: &'a str,
 }
 
 pub struct AdminClient<'a> {
@@ -90,7 +135,7 @@ impl<'a> AuthClient<'a> {
         self.app
             .client
             .request(method, self.url(path))
-            .header("Authorization", format!("Bearer {}", self.app.api_key))
+            .header("Authorization", format!("Bearer {}", self.api_key))
     }
 
     pub fn get(&self, path: impl AsRef<str>) -> reqwest::RequestBuilder {
@@ -105,6 +150,10 @@ impl<'a> AuthClient<'a> {
         self.request(Method::PUT, path)
     }
 
+    pub fn patch(&self, path: impl AsRef<str>) -> reqwest::RequestBuilder {
+        self.request(Method::PATCH, path)
+    }
+
     pub fn delete(&self, path: impl AsRef<str>) -> reqwest::RequestBuilder {
         self.request(Method::DELETE, path)
     }
@@ -137,6 +186,14 @@ impl<'a> AdminClient<'a> {
 }
 
 pub async fn setup_test_app() -> TestApp {
+    setup_test_app_with(|builder| builder).await
+}
+
+/// Like [`setup_test_app`], with `configure` applied to the state builder
+/// after the test defaults.
+pub async fn setup_test_app_with(
+    configure: impl FnOnce(AppStateBuilder) -> AppStateBuilder,
+) -> TestApp {
     let container = postgres::Postgres::default().start().await.unwrap();
     let host = container.get_host().await.unwrap().to_string();
     let port = container.get_host_port_ipv4(5432).await.unwrap();
@@ -175,13 +232,15 @@ pub async fn setup_test_app() -> TestApp {
         .await
         .expect("Failed to conncet to DB");
 
-    let schema_repo = Arc::new(SchemaRepository::new(pool.clone()));
-    let log_repo = Arc::new(LogRepository::new(pool.clone()));
-    let api_key_repo = Arc::new(ApiKeyRepository::new(pool.clone()));
-
-    let schema_service = Arc::new(SchemaService::new(schema_repo.clone(), log_repo.clone()));
-    let log_service = Arc::new(LogService::new(log_repo.clone(), schema_service.clone()));
-    let api_key_service = Arc::new(ApiKeyService::new(api_key_repo.clone()));
+    let app_state = configure(
+        AppState::builder(pool.clone())
+            .with_broadcast_channel_size(16)
+            .with_api_key_cache_ttl(API_KEY_CACHE_TTL)
+            .with_ws_max_connections(WS_MAX_CONNECTIONS),
+    )
+    .build();
+    let api_key_service = app_state.api_key_service.clone();
+    let shutdown = app_state.shutdown.clone();
 
     let create_api_key_request = crab_pot::models::CreateApiKey::new("Test API Key");
     let test_api_key = api_key_service
@@ -189,19 +248,8 @@ pub async fn setup_test_app() -> TestApp {
         .await
         .expect("Failed to create test API key");
 
-    let (tx, _) = broadcast::channel(16);
-
-    let rate_limiter = Arc::new(RateLimiter::new());
-
-    let app_state = AppState {
-        schema_service,
-        log_service,
-        api_key_service: api_key_service.clone(),
-        log_broadcast: tx,
-        rate_limiter,
-    };
-
-    let app = create_app(app_state, pool.clone());
+    let app = create_app(app_state.clone(), pool.clone());
+    let admin_app = create_admin_app(app_state.clone());
 
     let listener = TcpListener::bind("127.0.0.1:0")
         .await
@@ -219,19 +267,67 @@ pub async fn setup_test_app() -> TestApp {
         .expect("Failed to run server");
     });
 
+    let admin_listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind to random port");
+    let admin_address = format!("http://{}", admin_listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        axum::serve(
+            admin_listener,
+            admin_app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("Failed to run admin server");
+    });
+
+    #[cfg(feature = "grpc")]
+    let grpc_address = {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind to random port");
+        let grpc_address = format!("http://{}", listener.local_addr().unwrap());
+        let incoming =
+            tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+        let service = crab_pot::grpc::LogIngestService::new(app_state).into_server();
+
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming)
+                .await
+                .expect("Failed to run gRPC server");
+        });
+
+        grpc_address
+    };
+
     let client = Client::new();
 
     TestApp {
         address: address_str,
+        admin_address,
         client,
         db_pool: pool,
         api_key_service,
+        api_key_id: test_api_key.api_key.id,
         api_key: test_api_key.plain_key,
+        shutdown,
+        #[cfg(feature = "grpc")]
+        grpc_address,
         _container: container,
     }
 }
 
 pub async fn setup_admin_test_app() -> AdminTestApp {
+    setup_admin_test_app_with(|builder| builder).await
+}
+
+/// Like [`setup_admin_test_app`], with `configure` applied to the state
+/// builder after the test defaults.
+pub async fn setup_admin_test_app_with(
+    configure: impl FnOnce(AppStateBuilder) -> AppStateBuilder,
+) -> AdminTestApp {
     let container = postgres::Postgres::default().start().await.unwrap();
     let host = container.get_host().await.unwrap().to_string();
     let port = container.get_host_port_ipv4(5432).await.unwrap();
@@ -270,25 +366,8 @@ pub async fn setup_admin_test_app() -> AdminTestApp {
         .await
         .expect("Failed to connect to DB");
 
-    let schema_repo = Arc::new(SchemaRepository::new(pool.clone()));
-    let log_repo = Arc::new(LogRepository::new(pool.clone()));
-    let api_key_repo = Arc::new(ApiKeyRepository::new(pool.clone()));
-
-    let schema_service = Arc::new(SchemaService::new(schema_repo.clone(), log_repo.clone()));
-    let log_service = Arc::new(LogService::new(log_repo.clone(), schema_service.clone()));
-    let api_key_service = Arc::new(ApiKeyService::new(api_key_repo.clone()));
-
-    let (tx, _) = broadcast::channel(16);
-
-    let rate_limiter = Arc::new(RateLimiter::new());
-
-    let app_state = AppState {
-        schema_service,
-        log_service,
-        api_key_service,
-        log_broadcast: tx,
-        rate_limiter,
-    };
+    let app_state =
+        configure(AppState::builder(pool.clone()).with_broadcast_channel_size(16)).build();
 
     let admin_app = create_admin_app(app_state);
 
@@ -300,9 +379,12 @@ pub async fn setup_admin_test_app() -> AdminTestApp {
     let address_str = format!("http://{}", address);
 
     tokio::spawn(async move {
-        axum::serve(listener, admin_app)
-            .await
-            .expect("Failed to run admin server");
+        axum::serve(
+            listener,
+            admin_app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("Failed to run admin server");
     });
 
     let http_client = Client::new();
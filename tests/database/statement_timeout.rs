@@ -0,0 +1,55 @@
+use std::time::{Duration, Instant};
+
+use axum::{http::StatusCode, response::IntoResponse};
+use crab_pot::{repositories::log_repository::LogRepositoryTrait, LogRepository};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::common::setup_test_app;
+
+#[tokio::test]
+async fn slow_read_is_cancelled_with_query_timeout() {
+    let app = setup_test_app().await;
+    let repository = LogRepository::new(app.db_pool.clone()).with_statement_timeout(100);
+
+    // A lock held elsewhere makes the read wait, which statement_timeout
+    // counts against it.
+    let mut lock = app.db_pool.begin().await.unwrap();
+    sqlx::query("LOCK TABLE logs IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    let err = repository
+        .count_by_schema_id(Uuid::new_v4(), None)
+        .await
+        .expect_err("read should be cancelled by statement_timeout");
+    assert!(started.elapsed() < Duration::from_secs(2));
+    lock.rollback().await.unwrap();
+
+    let response = err.into_response();
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"], "QUERY_TIMEOUT");
+
+    // The timeout is scoped to the read's transaction: the pool's connections
+    // keep the server default, and reads work again once the lock is gone.
+    let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(timeout, "0");
+    assert_eq!(
+        repository
+            .count_by_schema_id(Uuid::new_v4(), None)
+            .await
+            .unwrap(),
+        0
+    );
+    repository.refresh_level_counts_summary().await.unwrap();
+}
@@ -0,0 +1,176 @@
+use crab_pot::{
+    grpc::proto::{log_ingest_client::LogIngestClient, CreateLogRequest},
+    Schema,
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use tonic::{transport::Channel, Code, Request};
+
+use crate::common::{create_valid_schema, setup_test_app, TestApp};
+
+async fn connect(app: &TestApp) -> LogIngestClient<Channel> {
+    LogIngestClient::connect(app.grpc_address.clone())
+        .await
+        .unwrap()
+}
+
+fn authorized<T>(app: &TestApp, message: T) -> Request<T> {
+    authorized_with(app.api_key(), message)
+}
+
+fn authorized_with<T>(api_key: &str, message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", api_key).parse().unwrap(),
+    );
+    request
+}
+
+fn log_request(schema: &Schema, message: &str) -> CreateLogRequest {
+    CreateLogRequest {
+        schema_id: schema.id.to_string(),
+        log_data_json: json!({ "message": message }).to_string(),
+    }
+}
+
+#[tokio::test]
+async fn creates_logs_from_client_stream() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "grpc-stream")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let mut client = connect(&app).await;
+
+    let items: Vec<_> = (0..3)
+        .map(|i| log_request(&schema, &format!("streamed {}", i)))
+        .collect();
+    let reply = client
+        .create_logs_stream(authorized(&app, futures::stream::iter(items)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(reply.ids.len(), 3);
+
+    for (i, id) in reply.ids.iter().enumerate() {
+        let response = app
+            .auth()
+            .get(format!("/logs/{}", id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let log: Value = response.json().await.unwrap();
+        assert_eq!(log["log_data"]["message"], format!("streamed {}", i));
+    }
+}
+
+#[tokio::test]
+async fn create_log_validates_against_schema() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "grpc-unary")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let mut client = connect(&app).await;
+
+    let reply = client
+        .create_log(authorized(&app, log_request(&schema, "unary")))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(reply.schema_id, schema.id.to_string());
+
+    let invalid = CreateLogRequest {
+        schema_id: schema.id.to_string(),
+        log_data_json: json!({ "other": 1 }).to_string(),
+    };
+    let status = client
+        .create_log(authorized(&app, invalid))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(
+        status.metadata().get("x-error-code").unwrap(),
+        "SCHEMA_VALIDATION_ERROR"
+    );
+}
+
+#[tokio::test]
+async fn rejects_calls_without_api_key() {
+    let app = setup_test_app().await;
+    let mut client = connect(&app).await;
+
+    let request = CreateLogRequest {
+        schema_id: uuid::Uuid::new_v4().to_string(),
+        log_data_json: "{}".to_string(),
+    };
+    let status = client.create_log(request).await.unwrap_err();
+
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn stream_messages_are_rate_limited() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "grpc-rate-limit")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let api_key = app.create_api_key_with_limits(1, 2).await;
+    let mut client = connect(&app).await;
+
+    let items: Vec<_> = (0..5)
+        .map(|i| log_request(&schema, &format!("streamed {}", i)))
+        .collect();
+    let status = client
+        .create_logs_stream(authorized_with(&api_key, futures::stream::iter(items)))
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert!(status.message().starts_with("Log at index 2 failed"));
+}
+
+#[tokio::test]
+async fn rejects_writes_in_maintenance_mode() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "grpc-maintenance")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let response = app
+        .client
+        .post(format!("{}/admin/maintenance", app.admin_address))
+        .json(&json!({ "enabled": true }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut client = connect(&app).await;
+
+    let status = client
+        .create_log(authorized(&app, log_request(&schema, "unary")))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unavailable);
+    assert_eq!(
+        status.metadata().get("x-error-code").unwrap(),
+        "MAINTENANCE"
+    );
+
+    let status = client
+        .create_logs_stream(authorized(
+            &app,
+            futures::stream::iter(vec![log_request(&schema, "streamed")]),
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unavailable);
+}
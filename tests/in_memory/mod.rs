@@ -0,0 +1,2 @@
+pub mod naming_policy;
+pub mod services;
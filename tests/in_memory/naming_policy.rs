@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::IntoResponse};
+use crab_pot::{
+    models::{CreateSchema, DEFAULT_TENANT_ID},
+    AppError, InMemorySchemaRepository, SchemaNamingPolicy, SchemaService, ValidationMode,
+};
+use serde_json::{json, Value};
+
+fn schema_service(policy: SchemaNamingPolicy) -> SchemaService {
+    let schema_repo = Arc::new(InMemorySchemaRepository::new());
+    let log_repo = schema_repo.logs();
+
+    SchemaService::new(schema_repo, log_repo).with_naming_policy(policy)
+}
+
+fn kebab_semver_policy() -> SchemaNamingPolicy {
+    SchemaNamingPolicy::new(Some(r"[a-z0-9]+(-[a-z0-9]+)*"), Some(r"\d+\.\d+\.\d+")).unwrap()
+}
+
+fn create_schema_request(name: &str, version: &str) -> CreateSchema {
+    CreateSchema {
+        name: name.to_string(),
+        version: version.to_string(),
+        description: None,
+        schema_definition: json!({ "type": "object" }),
+        webhook_url: None,
+        webhook_secret: None,
+        strict_formats: false,
+        indexed_fields: Vec::new(),
+        max_retained_logs: None,
+        max_log_age_secs: None,
+        redacted_fields: Vec::new(),
+        sample_rate: None,
+        labels: Vec::new(),
+        validation_mode: ValidationMode::default(),
+        max_field_bytes: None,
+        tenant_id: DEFAULT_TENANT_ID.to_string(),
+        created_by: None,
+    }
+}
+
+async fn error_body(error: AppError) -> (StatusCode, Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn no_policy_accepts_any_name_and_version() {
+    let service = schema_service(SchemaNamingPolicy::default());
+
+    let result = service
+        .create_schema(create_schema_request("Schema!@#$", "v1"))
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn policy_accepts_matching_name_and_version() {
+    let service = schema_service(kebab_semver_policy());
+
+    let schema = service
+        .create_schema(create_schema_request("web-server-logs", "1.0.0"))
+        .await
+        .unwrap();
+
+    assert_eq!(schema.name, "web-server-logs");
+}
+
+#[tokio::test]
+async fn policy_rejects_invalid_name() {
+    let service = schema_service(kebab_semver_policy());
+
+    let error = service
+        .create_schema(create_schema_request("Web_Server", "1.0.0"))
+        .await
+        .unwrap_err();
+    let (status, body) = error_body(error).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "INVALID_NAME");
+}
+
+#[tokio::test]
+async fn policy_rejects_invalid_version() {
+    let service = schema_service(kebab_semver_policy());
+
+    let error = service
+        .create_schema(create_schema_request("web-server", "1.0"))
+        .await
+        .unwrap_err();
+    let (status, body) = error_body(error).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "INVALID_VERSION");
+}
+
+#[tokio::test]
+async fn policy_must_match_whole_value() {
+    let service = schema_service(kebab_semver_policy());
+
+    let error = service
+        .create_schema(create_schema_request("web-server", "1.0.0-beta"))
+        .await
+        .unwrap_err();
+    let (_, body) = error_body(error).await;
+
+    assert_eq!(body["error"], "INVALID_VERSION");
+}
+
+#[tokio::test]
+async fn policy_applies_to_updates() {
+    let service = schema_service(kebab_semver_policy());
+
+    let schema = service
+        .create_schema(create_schema_request("web-server", "1.0.0"))
+        .await
+        .unwrap();
+
+    let error = service
+        .update_schema(schema.id, create_schema_request("Web Server", "1.0.0"))
+        .await
+        .unwrap_err();
+    let (status, body) = error_body(error).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "INVALID_NAME");
+}
+
+#[test]
+fn invalid_policy_pattern_is_rejected() {
+    assert!(SchemaNamingPolicy::new(Some("[unclosed"), None).is_err());
+}
@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::IntoResponse};
+use crab_pot::{
+    dto::Direction,
+    models::{query_params::LogQueryParams, CreateSchema, DEFAULT_TENANT_ID},
+    AppError, InMemorySchemaRepository, LogService, SchemaService, ValidationMode,
+};
+use serde_json::json;
+
+fn services() -> (Arc<SchemaService>, LogService) {
+    let schema_repo = Arc::new(InMemorySchemaRepository::new());
+    let log_repo = schema_repo.logs();
+
+    let schema_service = Arc::new(SchemaService::new(schema_repo, log_repo.clone()));
+    let log_service = LogService::new(log_repo, schema_service.clone());
+
+    (schema_service, log_service)
+}
+
+fn create_schema_request(name: &str, version: &str) -> CreateSchema {
+    CreateSchema {
+        name: name.to_string(),
+        version: version.to_string(),
+        description: None,
+        schema_definition: json!({
+            "type": "object",
+            "properties": { "message": { "type": "string" } },
+            "required": ["message"]
+        }),
+        webhook_url: None,
+        webhook_secret: None,
+        strict_formats: false,
+        indexed_fields: Vec::new(),
+        max_retained_logs: None,
+        max_log_age_secs: None,
+        redacted_fields: Vec::new(),
+        sample_rate: None,
+        labels: Vec::new(),
+        validation_mode: ValidationMode::default(),
+        max_field_bytes: None,
+        tenant_id: DEFAULT_TENANT_ID.to_string(),
+        created_by: None,
+    }
+}
+
+fn status(error: AppError) -> StatusCode {
+    error.into_response().status()
+}
+
+fn no_filters() -> LogQueryParams {
+    LogQueryParams {
+        date_begin: None,
+        date_end: None,
+        json_filters: None,
+        created_by: None,
+        anchor: None,
+        expression: None,
+        indexed_fields: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn resolves_latest_schema_version() {
+    let (schema_service, _) = services();
+
+    for version in ["1.2.0", "1.10.0", "1.9.3"] {
+        schema_service
+            .create_schema(create_schema_request("in-memory", version))
+            .await
+            .unwrap();
+    }
+
+    let latest = schema_service
+        .get_schema_by_name("in-memory", DEFAULT_TENANT_ID)
+        .await
+        .unwrap();
+    assert_eq!(latest.version, "1.10.0");
+
+    let duplicate = schema_service
+        .create_schema(create_schema_request("in-memory", "1.2.0"))
+        .await
+        .unwrap_err();
+    assert_eq!(status(duplicate), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn validates_and_pages_logs() {
+    let (schema_service, log_service) = services();
+
+    let schema = schema_service
+        .create_schema(create_schema_request("in-memory-logs", "1.0.0"))
+        .await
+        .unwrap();
+
+    let invalid = log_service
+        .create_log(schema.id, DEFAULT_TENANT_ID, json!({ "message": 42 }), None)
+        .await
+        .unwrap_err();
+    assert_eq!(status(invalid), StatusCode::UNPROCESSABLE_ENTITY);
+
+    for i in 1..=5 {
+        log_service
+            .create_log(
+                schema.id,
+                DEFAULT_TENANT_ID,
+                json!({ "message": format!("message-{}", i) }),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let (first_page, cursor) = log_service
+        .get_cursor_logs(
+            schema.id,
+            DEFAULT_TENANT_ID,
+            None,
+            3,
+            no_filters(),
+            Direction::Forward,
+            false,
+        )
+        .await
+        .unwrap();
+    assert_eq!(first_page.len(), 3);
+    assert!(cursor.has_more);
+
+    let (second_page, cursor) = log_service
+        .get_cursor_logs(
+            schema.id,
+            DEFAULT_TENANT_ID,
+            cursor.next_cursor,
+            3,
+            no_filters(),
+            Direction::Forward,
+            false,
+        )
+        .await
+        .unwrap();
+    assert_eq!(second_page.len(), 2);
+    assert!(!cursor.has_more);
+
+    let messages: Vec<_> = first_page
+        .iter()
+        .chain(&second_page)
+        .map(|log| log.log_data["message"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "message-5",
+            "message-4",
+            "message-3",
+            "message-2",
+            "message-1"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn deleting_schema_with_logs_requires_force() {
+    let (schema_service, log_service) = services();
+
+    let schema = schema_service
+        .create_schema(create_schema_request("in-memory-delete", "1.0.0"))
+        .await
+        .unwrap();
+    let log = log_service
+        .create_log(
+            schema.id,
+            DEFAULT_TENANT_ID,
+            json!({ "message": "keep me" }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let conflict = schema_service
+        .delete_schema(schema.id, DEFAULT_TENANT_ID, false)
+        .await
+        .unwrap_err();
+    assert_eq!(status(conflict), StatusCode::CONFLICT);
+
+    let (_, deleted_log_ids) = schema_service
+        .delete_schema(schema.id, DEFAULT_TENANT_ID, true)
+        .await
+        .unwrap();
+    assert_eq!(deleted_log_ids, vec![log.id]);
+
+    let missing = log_service
+        .get_log_by_id(log.id, DEFAULT_TENANT_ID)
+        .await
+        .unwrap_err();
+    assert!(missing.is_not_found());
+}
+
+#[tokio::test]
+async fn counts_and_purges_logs_across_schemas() {
+    let (schema_service, log_service) = services();
+
+    let mut schemas = Vec::new();
+    for (version, logs) in [("1.0.0", 2), ("2.0.0", 0)] {
+        let schema = schema_service
+            .create_schema(create_schema_request("in-memory-purge", version))
+            .await
+            .unwrap();
+        for _ in 0..logs {
+            log_service
+                .create_log(
+                    schema.id,
+                    DEFAULT_TENANT_ID,
+                    json!({ "message": "m" }),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        schemas.push(schema);
+    }
+
+    let counts = schema_service
+        .count_logs_by_schema(DEFAULT_TENANT_ID, None, None)
+        .await
+        .unwrap();
+    let counts: Vec<_> = counts.iter().map(|c| (c.schema_id, c.count)).collect();
+    assert_eq!(counts, vec![(schemas[0].id, 2), (schemas[1].id, 0)]);
+
+    let purged = schema_service.purge_all(true).await.unwrap();
+    assert_eq!((purged.logs, purged.schemas, purged.api_keys), (2, 2, 0));
+    assert!(schema_service
+        .count_logs_by_schema(DEFAULT_TENANT_ID, None, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn schema_limit_blocks_creation_once_reached() {
+    let schema_repo = Arc::new(InMemorySchemaRepository::new());
+    let log_repo = schema_repo.logs();
+    let schema_service = SchemaService::new(schema_repo, log_repo).with_max_schemas(Some(2));
+
+    for version in ["1.0.0", "2.0.0"] {
+        schema_service
+            .create_schema(create_schema_request("capped", version))
+            .await
+            .unwrap();
+    }
+
+    let error = schema_service
+        .create_schema(create_schema_request("capped", "3.0.0"))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Schema limit of 2 reached"));
+    assert_eq!(status(error), StatusCode::FORBIDDEN);
+
+    let duplicate = schema_service
+        .create_schema(create_schema_request("capped", "1.0.0"))
+        .await
+        .unwrap_err();
+    assert_eq!(status(duplicate), StatusCode::CONFLICT);
+}
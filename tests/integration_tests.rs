@@ -1,8 +1,24 @@
+#![allow(
+    clippy::bool_assert_comparison,
+    clippy::clone_on_copy,
+    clippy::get_first,
+    clippy::len_zero
+)]
+
+mod admin;
 mod api_keys;
+#[allow(dead_code)]
 mod common;
+mod database;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod in_memory;
 mod logs;
+mod maintenance;
 mod rate_limiting;
 mod schemas;
+mod tenancy;
+mod websockets;
 
 mod health {
     use crate::common::test_app::setup_test_app;
@@ -28,3 +44,135 @@ mod health {
         assert!(body["timestamp"].is_string());
     }
 }
+
+mod version {
+    use crate::common::test_app::setup_test_app;
+    use reqwest::StatusCode;
+
+    #[tokio::test]
+    async fn version_reports_crate_version_and_build() {
+        let app = setup_test_app().await;
+
+        let response = app
+            .client
+            .get(format!("{}/version", app.address))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["x-server-version"],
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert!(!body["git_sha"].as_str().unwrap().is_empty());
+        chrono::DateTime::parse_from_rfc3339(body["built_at"].as_str().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn error_responses_carry_server_version() {
+        let app = setup_test_app().await;
+
+        let response = app
+            .client
+            .get(format!("{}/schemas", app.address))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()["x-server-version"],
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+}
+
+mod cors {
+    use crate::common::test_app::setup_test_app;
+    use reqwest::{header, Method, StatusCode};
+
+    #[tokio::test]
+    async fn preflight_skips_api_key_check() {
+        let app = setup_test_app().await;
+
+        let response = app
+            .client
+            .request(Method::OPTIONS, format!("{}/schemas", app.address))
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "x-api-key")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert!(response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_METHODS));
+
+        let response = app
+            .client
+            .get(format!("{}/schemas", app.address))
+            .header(header::ORIGIN, "https://app.example.com")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod timestamps {
+    use crate::common::{create_valid_log, create_valid_schema, test_app::setup_test_app};
+    use regex::Regex;
+    use serde_json::Value;
+
+    fn assert_rfc3339_utc(value: &Value) {
+        let pattern =
+            Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d{3}|\.\d{6}|\.\d{9})?Z$")
+                .unwrap();
+        let timestamp = value.as_str().expect("timestamp is not a string");
+        assert!(
+            pattern.is_match(timestamp),
+            "unexpected format: {}",
+            timestamp
+        );
+    }
+
+    #[tokio::test]
+    async fn schema_log_and_api_key_timestamps_share_one_format() {
+        let app = setup_test_app().await;
+
+        let schema: Value = create_valid_schema(&app, "timestamp-format")
+            .await
+            .json()
+            .await
+            .unwrap();
+        assert_rfc3339_utc(&schema["created_at"]);
+        assert_rfc3339_utc(&schema["updated_at"]);
+
+        let log: Value = create_valid_log(&app, schema["id"].as_str().unwrap().to_string())
+            .await
+            .json()
+            .await
+            .unwrap();
+        assert_rfc3339_utc(&log["created_at"]);
+
+        let api_key: Value = app
+            .client
+            .get(format!("{}/api-keys/{}", app.admin_address, app.api_key_id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_rfc3339_utc(&api_key["created_at"]);
+    }
+}
@@ -0,0 +1,95 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_valid_log, create_valid_schema, get_log, log_action, setup_test_app};
+
+#[tokio::test]
+async fn create_action_creates_log() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "action-create")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = log_action(
+        &app,
+        &json!({
+            "action": "create",
+            "schema_id": schema.id,
+            "log_data": { "message": "via action" }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["action"], "created");
+    assert_eq!(body["log"]["log_data"]["message"], "via action");
+    assert_eq!(body["log"]["created_by"], app.api_key_id);
+
+    let log_id = body["log"]["id"].as_i64().unwrap();
+    let response = get_log(&app, log_id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn create_action_validates_against_schema() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "action-invalid")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = log_action(
+        &app,
+        &json!({
+            "action": "create",
+            "schema_id": schema.id,
+            "log_data": { "message": 42 }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn delete_action_deletes_log() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "action-delete")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log: Value = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = log_action(&app, &json!({ "action": "delete", "id": log["id"] })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["action"], "deleted");
+    assert_eq!(body["log"]["id"], log["id"]);
+
+    let response = get_log(&app, log["id"].to_string()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = log_action(&app, &json!({ "action": "delete", "id": log["id"] })).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn unknown_action_is_rejected() {
+    let app = setup_test_app().await;
+
+    let response = log_action(&app, &json!({ "action": "update", "id": 1 })).await;
+    assert!(response.status().is_client_error());
+}
@@ -0,0 +1,79 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_logs_bulk, create_valid_schema, setup_test_app};
+
+async fn count_logs(app: &crate::common::TestApp, schema: &Schema) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM logs WHERE schema_id = $1")
+        .bind(schema.id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn creates_valid_items_and_reports_failures() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "bulk-create")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let payload = json!([
+        { "schema_id": schema.id, "log_data": { "message": "first" } },
+        { "schema_id": schema.id, "log_data": { "level": "INFO" } },
+        { "schema_id": schema.id, "log_data": { "message": "third" } },
+    ]);
+
+    let response = create_logs_bulk(&app, &payload, false).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["validate_only"], false);
+    assert_eq!(body["succeeded"], 2);
+    assert_eq!(body["failed"], 1);
+    assert!(body["results"][0]["id"].is_i64());
+    assert_eq!(body["results"][1]["ok"], false);
+    assert_eq!(
+        body["results"][1]["error"]["error"],
+        "SCHEMA_VALIDATION_ERROR"
+    );
+
+    assert_eq!(count_logs(&app, &schema).await, 2);
+}
+
+#[tokio::test]
+async fn validate_only_reports_errors_without_inserting() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "bulk-validate-only")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let payload = json!([
+        { "schema_id": schema.id, "log_data": { "message": "valid" } },
+        { "schema_id": schema.id, "log_data": { "message": 42 } },
+        { "schema_id": "not-a-uuid", "log_data": { "message": "bad schema id" } },
+    ]);
+
+    let response = create_logs_bulk(&app, &payload, true).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["validate_only"], true);
+    assert_eq!(body["succeeded"], 1);
+    assert_eq!(body["failed"], 2);
+
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results[0]["ok"], true);
+    assert!(results[0].get("id").is_none());
+    assert_eq!(results[1]["error"]["error"], "SCHEMA_VALIDATION_ERROR");
+    assert_eq!(results[2]["error"]["error"], "BAD_REQUEST");
+
+    assert_eq!(count_logs(&app, &schema).await, 0);
+}
@@ -0,0 +1,69 @@
+use crab_pot::{Log, Schema};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_valid_schema, put_log_by_key, setup_test_app};
+
+#[tokio::test]
+async fn repeated_put_updates_the_same_log() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "log-by-key")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response =
+        put_log_by_key(&app, schema.id, "order-42", &json!({ "message": "first" })).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Log = response.json().await.unwrap();
+
+    let response =
+        put_log_by_key(&app, schema.id, "order-42", &json!({ "message": "second" })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let updated: Log = response.json().await.unwrap();
+
+    assert_eq!(updated.id, created.id);
+    assert_eq!(updated.log_data, json!({ "message": "second" }));
+
+    let rows: Vec<(Value,)> = sqlx::query_as("SELECT log_data FROM logs WHERE schema_id = $1")
+        .bind(schema.id)
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, vec![(json!({ "message": "second" }),)]);
+}
+
+#[tokio::test]
+async fn keys_are_scoped_to_their_schema() {
+    let app = setup_test_app().await;
+    let first: Schema = create_valid_schema(&app, "log-by-key-a")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let second: Schema = create_valid_schema(&app, "log-by-key-b")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let data = json!({ "message": "hello" });
+    let response = put_log_by_key(&app, first.id, "shared", &data).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = put_log_by_key(&app, second.id, "shared", &data).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn rejects_data_failing_the_schema() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "log-by-key-invalid")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = put_log_by_key(&app, schema.id, "order-1", &json!({ "level": "info" })).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,153 @@
+use crab_pot::{repositories::log_repository::LogRepositoryTrait, Log, LogRepository, Schema};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{
+    create_log, create_valid_schema, get_log, setup_test_app, setup_test_app_with,
+};
+
+const THRESHOLD: usize = 1024;
+
+#[tokio::test]
+async fn large_payload_is_stored_compressed_and_read_back_unchanged() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "compressed-logs")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let entries: Vec<_> = (0..500)
+        .map(|i| json!({ "index": i, "path": format!("/api/v1/items/{}", i), "ok": i % 3 != 0 }))
+        .collect();
+    let log_data = json!({
+        "message": "request trace",
+        "nested": { "entries": entries, "unicode": "żółw 🦀", "empty": {} },
+        "ratio": 0.125,
+        "missing": null,
+    });
+
+    let repository =
+        LogRepository::new(app.db_pool.clone()).with_compression_threshold(Some(THRESHOLD));
+    let created = repository
+        .create(&Log {
+            id: 0,
+            schema_id: schema.id,
+            log_data: log_data.clone(),
+            created_by: None,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.log_data, log_data);
+
+    let (searchable, compressed_len): (Value, Option<i32>) = sqlx::query_as(
+        "SELECT log_data, octet_length(log_data_compressed) FROM logs WHERE id = $1",
+    )
+    .bind(created.id)
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        searchable,
+        json!({ "message": "request trace", "ratio": 0.125, "missing": null })
+    );
+    let compressed_len = compressed_len.unwrap() as usize;
+    assert!(compressed_len < log_data.to_string().len());
+
+    let response = get_log(&app, created.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let fetched: Log = response.json().await.unwrap();
+    assert_eq!(fetched.log_data, log_data);
+}
+
+#[tokio::test]
+async fn small_payload_stays_jsonb() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "uncompressed-logs")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let repository =
+        LogRepository::new(app.db_pool.clone()).with_compression_threshold(Some(THRESHOLD));
+    let created = repository
+        .create(&Log {
+            id: 0,
+            schema_id: schema.id,
+            log_data: json!({ "message": "short" }),
+            created_by: None,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let message: Option<String> =
+        sqlx::query_scalar("SELECT log_data->>'message' FROM logs WHERE id = $1")
+            .bind(created.id)
+            .fetch_one(&app.db_pool)
+            .await
+            .unwrap();
+    assert_eq!(message.as_deref(), Some("short"));
+}
+
+#[tokio::test]
+async fn compressed_logs_can_be_filtered_and_counted() {
+    let app =
+        setup_test_app_with(|builder| builder.with_log_compression_threshold(Some(THRESHOLD)))
+            .await;
+
+    let schema: Schema = create_valid_schema(&app, "compressed-queries")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for (level, trace) in [
+        ("ERROR", "x".repeat(4 * THRESHOLD)),
+        ("INFO", "short".into()),
+    ] {
+        let payload = json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "traced", "level": level, "trace": trace }
+        });
+        assert_eq!(
+            create_log(&app, &payload).await.status(),
+            StatusCode::CREATED
+        );
+    }
+    let compressed: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM logs WHERE log_data_compressed IS NOT NULL")
+            .fetch_one(&app.db_pool)
+            .await
+            .unwrap();
+    assert_eq!(compressed, 1);
+
+    let filters = json!({ "level": "ERROR" }).to_string();
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("filters", filters.as_str())])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data: Value = response.json().await.unwrap();
+    let logs = data["logs"].as_array().unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["log_data"]["trace"], "x".repeat(4 * THRESHOLD));
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}/counts/level", schema.id))
+        .query(&[("date_begin", "2000-01-01T00:00:00Z")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["counts"], json!({ "ERROR": 1, "INFO": 1 }));
+}
@@ -0,0 +1,238 @@
+use chrono::{Duration, Utc};
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::common::{create_log, create_valid_log, create_valid_schema, setup_test_app, TestApp};
+
+async fn create_leveled_logs(app: &TestApp, schema_id: Uuid) {
+    for level in ["INFO", "INFO", "ERROR"] {
+        let payload = json!({
+            "schema_id": schema_id,
+            "log_data": { "message": "leveled", "level": level }
+        });
+        let response = create_log(app, &payload).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let payload = json!({
+        "schema_id": schema_id,
+        "log_data": { "message": "no level" }
+    });
+    create_log(app, &payload).await;
+}
+
+async fn get_level_counts(app: &TestApp, schema_id: Uuid, query: &[(&str, &str)]) -> Value {
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}/counts/level", schema_id))
+        .query(query)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn counts_by_level_live_for_explicit_range() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "counts-live")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_leveled_logs(&app, schema.id).await;
+
+    let data = get_level_counts(&app, schema.id, &[("date_begin", "2000-01-01T00:00:00Z")]).await;
+
+    assert_eq!(data["source"], "live");
+    assert_eq!(
+        data["counts"],
+        json!({ "ERROR": 1, "INFO": 2, "UNKNOWN": 1 })
+    );
+}
+
+#[tokio::test]
+async fn counts_reject_reversed_date_range() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "counts-reversed")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let range = [
+        ("date_begin", "2030-01-02T00:00:00Z"),
+        ("date_end", "2030-01-01T00:00:00Z"),
+    ];
+
+    for path in [
+        format!("/logs/schemas/{}/counts/level", schema.id),
+        "/logs/counts-by-schema".to_string(),
+    ] {
+        let response = app.auth().get(&path).query(&range).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", path);
+
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "INVALID_DATE_RANGE");
+    }
+}
+
+#[tokio::test]
+async fn counts_by_level_from_summary_without_range() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "counts-summary")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_leveled_logs(&app, schema.id).await;
+
+    sqlx::query("REFRESH MATERIALIZED VIEW log_level_counts_24h")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let data = get_level_counts(&app, schema.id, &[]).await;
+
+    assert_eq!(data["source"], "summary");
+    assert!(data["refreshed_at"].is_string());
+    assert_eq!(
+        data["counts"],
+        json!({ "ERROR": 1, "INFO": 2, "UNKNOWN": 1 })
+    );
+}
+
+#[tokio::test]
+async fn counts_by_level_for_unknown_schema() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}/counts/level", Uuid::new_v4()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn counts_by_day_with_zero_filled_days() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "count-by-day")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO logs (schema_id, log_data, created_at)
+        VALUES ($1, '{"message": "yesterday"}', NOW() - INTERVAL '1 day'),
+               ($1, '{"message": "today"}', NOW()),
+               ($1, '{"message": "today"}', NOW())
+        "#,
+    )
+    .bind(schema.id)
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let response = app
+        .auth()
+        .get(format!("/schemas/{}/logs/count-by-day", schema.id))
+        .query(&[("days", "3")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let today = Utc::now().date_naive();
+    let expected: Vec<Value> = [(2, 0), (1, 1), (0, 2)]
+        .into_iter()
+        .map(|(days_ago, count)| {
+            json!({
+                "day": (today - Duration::days(days_ago)).to_string(),
+                "count": count
+            })
+        })
+        .collect();
+
+    let series: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(series, expected);
+}
+
+#[tokio::test]
+async fn counts_by_day_for_unknown_schema() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .get(format!("/schemas/{}/logs/count-by-day", Uuid::new_v4()))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn counts_by_schema_across_schemas() {
+    let app = setup_test_app().await;
+
+    let mut schemas = Vec::new();
+    for (name, log_count) in [("counts-by-schema-a", 2), ("counts-by-schema-b", 3)] {
+        let schema: Schema = create_valid_schema(&app, name).await.json().await.unwrap();
+        for _ in 0..log_count {
+            let response = create_valid_log(&app, schema.id.to_string()).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        schemas.push((schema, log_count));
+    }
+
+    let response = app
+        .auth()
+        .get("/logs/counts-by-schema")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["cache-control"], "private, max-age=30");
+
+    let counts: Vec<Value> = response.json().await.unwrap();
+    for (schema, log_count) in &schemas {
+        let entry = counts
+            .iter()
+            .find(|c| c["schema_id"] == json!(schema.id))
+            .expect("schema missing from counts");
+        assert_eq!(entry["name"], schema.name);
+        assert_eq!(entry["version"], schema.version);
+        assert_eq!(entry["count"], *log_count);
+    }
+
+    let response = app
+        .auth()
+        .get("/logs/counts-by-schema")
+        .query(&[
+            ("date_begin", "2000-01-01T00:00:00Z"),
+            ("date_end", "2000-01-02T00:00:00Z"),
+        ])
+        .send()
+        .await
+        .unwrap();
+    let counts: Vec<Value> = response.json().await.unwrap();
+    for (schema, _) in &schemas {
+        let entry = counts
+            .iter()
+            .find(|c| c["schema_id"] == json!(schema.id))
+            .expect("schema missing from counts");
+        assert_eq!(entry["count"], 0);
+    }
+}
@@ -1,10 +1,11 @@
 use crab_pot::{Log, Schema};
 use reqwest::StatusCode;
-use serde_json::json;
+use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::common::{
-    create_log, create_valid_log, create_valid_schema, setup_test_app, ErrorResponse,
+    create_log, create_schema, create_valid_log, create_valid_schema, setup_test_app,
+    valid_schema_payload, ErrorResponse,
 };
 
 #[tokio::test]
@@ -74,7 +75,81 @@ async fn rejects_missing_required_fields() {
     });
 
     let response = create_log(&app, &invalid_payload).await;
-    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "MISSING_SCHEMA_ID");
+}
+
+#[tokio::test]
+async fn rejects_malformed_json_body() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .post("/logs")
+        .header("Content-Type", "application/json")
+        .body(r#"{"schema_id": "#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_JSON");
+    assert!(error
+        .message
+        .contains("Failed to parse the request body as JSON"));
+}
+
+#[tokio::test]
+async fn rejects_body_without_json_content_type() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .post("/logs")
+        .header("Content-Type", "text/plain")
+        .body(r#"{"schema_id": "log-create-test", "log_data": {}}"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_CONTENT_TYPE");
+}
+
+#[tokio::test]
+async fn rejects_oversized_body() {
+    let app = setup_test_app().await;
+
+    let payload = json!({
+        "schema_id": Uuid::new_v4(),
+        "log_data": { "message": "x".repeat(3 * 1024 * 1024) }
+    });
+    let response = create_log(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "PAYLOAD_TOO_LARGE");
+}
+
+#[tokio::test]
+async fn rejects_empty_body() {
+    let app = setup_test_app().await;
+
+    for content_type in [Some("application/json"), None] {
+        let mut request = app.auth().post("/logs");
+        if let Some(content_type) = content_type {
+            request = request.header("Content-Type", content_type);
+        }
+        let response = request.send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.error, "EMPTY_BODY");
+    }
 }
 
 // #[tokio::test]
@@ -125,3 +200,223 @@ async fn accepts_additional_properties() {
     assert_eq!(log.log_data["level"], "INFO");
     assert_eq!(log.log_data["extra_data"]["nested"], "value");
 }
+
+fn email_schema_payload(name: &str, strict_formats: bool) -> serde_json::Value {
+    json!({
+        "name": name,
+        "version": "1.0.0",
+        "strict_formats": strict_formats,
+        "schema_definition": {
+            "type": "object",
+            "properties": {
+                "email": { "type": "string", "format": "email" },
+                "seen_at": { "type": "string", "format": "date-time" }
+            },
+            "required": ["email"]
+        }
+    })
+}
+
+#[tokio::test]
+async fn strict_formats_rejects_invalid_email() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_schema(&app, &email_schema_payload("strict-formats", true))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "email": "not-an-email" }
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "SCHEMA_VALIDATION_ERROR");
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "email": "crab@example.com", "seen_at": "yesterday" }
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "email": "crab@example.com", "seen_at": "2024-01-01T12:00:00Z" }
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn formats_are_annotations_without_strict_formats() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_schema(&app, &email_schema_payload("lenient-formats", false))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(!schema.strict_formats);
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "email": "not-an-email" }
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn records_creating_api_key() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "log-created-by")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = create_valid_log(&app, schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.created_by, Some(app.api_key_id));
+
+    let fetched: Log = app
+        .auth()
+        .get(format!("/logs/{}", log.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(fetched.created_by, Some(app.api_key_id));
+}
+
+#[tokio::test]
+async fn rejects_unknown_top_level_field() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "log-unknown-field")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "message": "hello" },
+        "level": "INFO"
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let error_text = response.text().await.unwrap();
+    assert!(
+        error_text.contains("unknown field `level`"),
+        "{}",
+        error_text
+    );
+}
+
+#[tokio::test]
+async fn accepts_explicit_past_created_at() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "log-backfill")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "message": "from the past" },
+        "created_at": "2020-01-02T03:04:05Z"
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.created_at.to_rfc3339(), "2020-01-02T03:04:05+00:00");
+}
+
+#[tokio::test]
+async fn rejects_far_future_created_at() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "log-future")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let log_payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "message": "from the future" },
+        "created_at": (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339()
+    });
+
+    let response = create_log(&app, &log_payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_TIMESTAMP");
+}
+
+#[tokio::test]
+async fn rejects_string_field_over_schema_limit() {
+    let app = setup_test_app().await;
+
+    let mut payload = valid_schema_payload("log-field-limit");
+    payload["max_field_bytes"] = json!(16);
+    let schema: Schema = create_schema(&app, &payload).await.json().await.unwrap();
+
+    let response = create_log(
+        &app,
+        &json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "ok", "details": { "trace": "x".repeat(17) } }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "FIELD_TOO_LARGE");
+    assert_eq!(body["field"], "/details/trace");
+    assert_eq!(body["limit"], 16);
+
+    let response = create_log(
+        &app,
+        &json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "ok", "details": { "trace": "x".repeat(16) } }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn rejects_non_positive_field_limit() {
+    let app = setup_test_app().await;
+
+    let mut payload = valid_schema_payload("log-field-limit-zero");
+    payload["max_field_bytes"] = json!(0);
+    let response = create_schema(&app, &payload).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -77,3 +77,54 @@ async fn double_delete_returns_404() {
     let error: ErrorResponse = second_delete.json().await.unwrap();
     assert_eq!(error.error, "NOT_FOUND");
 }
+
+#[tokio::test]
+async fn delete_echoes_deleted_log_by_default() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "delete-echo")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log: Log = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = delete_log(&app, log.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["deleted"], true);
+    assert_eq!(body["data"]["id"], log.id);
+}
+
+#[tokio::test]
+async fn delete_without_return_deleted_returns_no_content() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "delete-no-content")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log: Log = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = app
+        .auth()
+        .delete(format!("/logs/{}?return_deleted=false", log.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(response.text().await.unwrap().is_empty());
+
+    let response = get_log(&app, log.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
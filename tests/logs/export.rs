@@ -0,0 +1,93 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::common::{create_valid_schema, export_logs, setup_test_app};
+
+#[tokio::test]
+async fn walks_all_logs_in_ascending_keyset_order() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "export-keyset")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Several logs share each timestamp, so ties must be broken by id.
+    sqlx::query(
+        r#"
+        INSERT INTO logs (schema_id, log_data, created_at)
+        SELECT $1, jsonb_build_object('message', 'message-' || i),
+               NOW() - (i % 4) * INTERVAL '1 minute'
+        FROM generate_series(1, 22) AS i
+        "#,
+    )
+    .bind(schema.id)
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let expected_ids: Vec<i32> =
+        sqlx::query_scalar("SELECT id FROM logs WHERE schema_id = $1 ORDER BY created_at, id")
+            .bind(schema.id)
+            .fetch_all(&app.db_pool)
+            .await
+            .unwrap();
+
+    let mut seen_ids = Vec::new();
+    let mut after: Option<(String, i64)> = None;
+    loop {
+        let response = export_logs(
+            &app,
+            schema.id.to_string(),
+            after.as_ref().map(|(ts, id)| (ts.as_str(), *id)),
+            5,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let data: Value = response.json().await.unwrap();
+        let logs = data["logs"].as_array().unwrap();
+        assert!(logs.len() <= 5);
+        seen_ids.extend(logs.iter().map(|l| l["id"].as_i64().unwrap() as i32));
+
+        if data["has_more"] == false {
+            break;
+        }
+
+        let next = &data["next"];
+        after = Some((
+            next["after_created_at"].as_str().unwrap().to_string(),
+            next["after_id"].as_i64().unwrap(),
+        ));
+    }
+
+    assert_eq!(seen_ids, expected_ids);
+}
+
+#[tokio::test]
+async fn rejects_partial_keyset() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "export-partial-keyset")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}/export", schema.id))
+        .query(&[("after_id", "10")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert!(body["message"]
+        .as_str()
+        .unwrap()
+        .contains("after_created_at"));
+}
@@ -0,0 +1,111 @@
+use crab_pot::{Log, Schema};
+use reqwest::StatusCode;
+use serde_json::json;
+
+use crate::common::{
+    create_log, create_schema, setup_test_app, setup_test_app_with, valid_schema_payload,
+    ErrorResponse, TestApp,
+};
+
+async fn setup_inference_app() -> TestApp {
+    setup_test_app_with(|builder| builder.with_schema_inference_field(Some("_schema".into()))).await
+}
+
+async fn create_version(app: &TestApp, name: &str, version: &str) -> Schema {
+    let mut payload = valid_schema_payload(name);
+    payload["version"] = json!(version);
+
+    let response = create_schema(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn infers_schema_from_log_data_and_strips_the_field() {
+    let app = setup_inference_app().await;
+    let v1 = create_version(&app, "inferred-logs", "1.0.0").await;
+    create_version(&app, "inferred-logs", "2.0.0").await;
+
+    let response = create_log(
+        &app,
+        &json!({
+            "log_data": { "_schema": "inferred-logs/1.0.0", "message": "self-describing" }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.schema_id, v1.id);
+    assert_eq!(log.log_data, json!({ "message": "self-describing" }));
+
+    let stored: Log = app
+        .auth()
+        .get(format!("/logs/{}", log.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.log_data, json!({ "message": "self-describing" }));
+}
+
+#[tokio::test]
+async fn infers_latest_version_from_bare_name() {
+    let app = setup_inference_app().await;
+    create_version(&app, "inferred-latest", "1.0.0").await;
+    let latest = create_version(&app, "inferred-latest", "1.1.0").await;
+
+    let response = create_log(
+        &app,
+        &json!({ "log_data": { "_schema": "inferred-latest", "message": "hi" } }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.schema_id, latest.id);
+}
+
+#[tokio::test]
+async fn rejects_log_without_schema_id_or_inference_field() {
+    let app = setup_inference_app().await;
+
+    let response = create_log(&app, &json!({ "log_data": { "message": "anonymous" } })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "MISSING_SCHEMA_ID");
+}
+
+#[tokio::test]
+async fn rejects_non_string_inference_field() {
+    let app = setup_inference_app().await;
+
+    let response = create_log(
+        &app,
+        &json!({ "log_data": { "_schema": 7, "message": "hi" } }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_SCHEMA_REFERENCE");
+}
+
+#[tokio::test]
+async fn inference_field_is_ignored_when_disabled() {
+    let app = setup_test_app().await;
+    create_version(&app, "inferred-disabled", "1.0.0").await;
+
+    let response = create_log(
+        &app,
+        &json!({ "log_data": { "_schema": "inferred-disabled/1.0.0", "message": "hi" } }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "MISSING_SCHEMA_ID");
+}
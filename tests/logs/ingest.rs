@@ -0,0 +1,70 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_valid_schema, ingest_logs, setup_test_app};
+
+#[tokio::test]
+async fn ingests_lines_and_reports_failures() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ingest-ndjson")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let line = |message: &str| {
+        json!({ "schema_id": schema.id, "log_data": { "message": message } }).to_string()
+    };
+    let body = [
+        line("first"),
+        line("second"),
+        "{\"schema_id\": not json".to_string(),
+        String::new(),
+        json!({ "schema_id": schema.id, "log_data": { "level": "INFO" } }).to_string(),
+        line("last"),
+    ]
+    .join("\n");
+
+    let response = ingest_logs(&app, body).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let summary: Value = response.json().await.unwrap();
+    assert_eq!(summary["created"], 3);
+
+    let failed = summary["failed"].as_array().unwrap();
+    assert_eq!(failed.len(), 2);
+    assert_eq!(failed[0]["line"], 3);
+    assert!(failed[0]["error"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid JSON"));
+    assert_eq!(failed[1]["line"], 5);
+
+    let messages: Vec<String> = sqlx::query_scalar(
+        "SELECT log_data->>'message' FROM logs WHERE schema_id = $1 ORDER BY id",
+    )
+    .bind(schema.id)
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(messages, vec!["first", "second", "last"]);
+}
+
+#[tokio::test]
+async fn rejects_non_ndjson_content_type() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .post("/logs/ingest")
+        .json(&json!([]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_CONTENT_TYPE");
+}
@@ -1,4 +1,21 @@
+pub mod action;
+pub mod bulk;
+pub mod by_key;
+pub mod compression;
+pub mod counts;
 pub mod create;
 pub mod delete;
+pub mod export;
+pub mod inference;
+pub mod ingest;
 pub mod pagination;
+pub mod patch;
+pub mod query;
 pub mod read;
+pub mod redaction;
+pub mod replay;
+pub mod retention;
+pub mod sampling;
+pub mod stream;
+pub mod validation_mode;
+pub mod webhook;
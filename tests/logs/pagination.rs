@@ -1,9 +1,10 @@
-use crab_pot::{Log, Schema};
-use reqwest::StatusCode;
+use crab_pot::{Log, PageLimits, Schema};
+use reqwest::{Method, StatusCode};
 use serde_json::Value;
 
 use crate::common::{
-    create_valid_log_with_message, create_valid_schema, get_logs_with_cursor, setup_test_app,
+    create_valid_log_with_message, create_valid_schema, get_logs_by_schema_name_with_cursor,
+    get_logs_with_cursor, setup_test_app,
 };
 
 #[tokio::test]
@@ -15,7 +16,6 @@ async fn forward_pagination_fetches_older_logs() {
 
     for i in 1..=5 {
         create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
     }
 
     let response = get_logs_with_cursor(&app, &schema.id.to_string(), None, 3, "forward").await;
@@ -32,6 +32,7 @@ async fn forward_pagination_fetches_older_logs() {
     assert_eq!(logs.len(), 3);
     assert_eq!(data["cursor"]["limit"], 3);
     assert_eq!(data["cursor"]["has_more"], true);
+    assert_eq!(data["cursor"]["has_prev"], false);
 
     let first_page_messages: Vec<&str> = logs
         .iter()
@@ -60,6 +61,7 @@ async fn forward_pagination_fetches_older_logs() {
     let logs2 = data2["logs"].as_array().unwrap();
     assert_eq!(logs2.len(), 2);
     assert_eq!(data2["cursor"]["has_more"], false);
+    assert_eq!(data2["cursor"]["has_prev"], true);
 
     let second_page_messages: Vec<&str> = logs2
         .iter()
@@ -82,7 +84,6 @@ async fn backward_pagination_fetches_newer_logs() {
                 .await;
         let log: Log = log_response.json().await.unwrap();
         log_ids.push(log.id);
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
     }
 
     let cursor = log_ids[1];
@@ -113,7 +114,6 @@ async fn prev_cursor_navigates_to_newer_logs_in_forward_mode() {
 
     for i in 1..=7 {
         create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
     }
 
     let response1 = get_logs_with_cursor(&app, &schema.id.to_string(), None, 3, "forward").await;
@@ -190,7 +190,6 @@ async fn pagination_respects_filters() {
             format!("odd-{}", i)
         };
         create_valid_log_with_message(&app, schema.id.to_string(), &message).await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
     }
 
     let response = app
@@ -216,7 +215,6 @@ async fn default_direction_is_forward() {
 
     for i in 1..=3 {
         create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
     }
 
     let response = app
@@ -238,3 +236,402 @@ async fn default_direction_is_forward() {
         .collect();
     assert_eq!(messages, vec!["message-3", "message-2"]);
 }
+
+#[tokio::test]
+async fn pagination_is_stable_for_identical_timestamps() {
+    let app = setup_test_app().await;
+
+    let schema_response = create_valid_schema(&app, "pagination-identical-timestamps").await;
+    let schema: Schema = schema_response.json().await.unwrap();
+
+    let mut log_ids: Vec<i32> = sqlx::query_scalar(
+        r#"
+        INSERT INTO logs (schema_id, log_data, created_at)
+        SELECT $1, jsonb_build_object('message', 'message-' || i), NOW()
+        FROM generate_series(1, 25) AS i
+        RETURNING id
+        "#,
+    )
+    .bind(schema.id)
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap();
+
+    let mut seen_ids = Vec::new();
+    let mut cursor = None;
+    loop {
+        let response =
+            get_logs_with_cursor(&app, &schema.id.to_string(), cursor, 4, "forward").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let data: Value = response.json().await.unwrap();
+        seen_ids.extend(
+            data["logs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|l| l["id"].as_i64().unwrap() as i32),
+        );
+
+        match data["cursor"]["next_cursor"].as_i64() {
+            Some(next) => cursor = Some(next as i32),
+            None => break,
+        }
+    }
+
+    log_ids.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(seen_ids, log_ids);
+}
+
+#[tokio::test]
+async fn limit_defaults_and_clamps_to_configured_bounds() {
+    let app = setup_test_app().await;
+    let page_limits = PageLimits::default();
+
+    let schema_response = create_valid_schema(&app, "pagination-limits").await;
+    let schema: Schema = schema_response.json().await.unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO logs (schema_id, log_data)
+        SELECT $1, jsonb_build_object('message', 'message-' || i)
+        FROM generate_series(1, $2) AS i
+        "#,
+    )
+    .bind(schema.id)
+    .bind(page_limits.max_limit + 5)
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["limit"], page_limits.default_limit);
+    assert_eq!(
+        data["logs"].as_array().unwrap().len(),
+        page_limits.default_limit as usize
+    );
+
+    let over_max = (page_limits.max_limit * 10).to_string();
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("limit", over_max.as_str())])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["limit"], page_limits.max_limit);
+    assert_eq!(
+        data["logs"].as_array().unwrap().len(),
+        page_limits.max_limit as usize
+    );
+    assert_eq!(data["cursor"]["has_more"], true);
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("limit", "0")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["limit"], 1);
+}
+
+#[tokio::test]
+async fn include_total_reports_matching_log_count() {
+    let app = setup_test_app().await;
+
+    let schema_response = create_valid_schema(&app, "pagination-total").await;
+    let schema: Schema = schema_response.json().await.unwrap();
+
+    for i in 1..=4 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
+    }
+
+    let response = get_logs_with_cursor(&app, schema.id.to_string(), None, 2, "forward").await;
+    let data: Value = response.json().await.unwrap();
+    assert!(data["cursor"].get("total").is_none());
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("limit", "2"), ("include_total", "true")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["logs"].as_array().unwrap().len(), 2);
+    assert_eq!(data["cursor"]["total"], 4);
+}
+
+#[tokio::test]
+async fn anchor_keeps_pages_stable_while_logs_arrive() {
+    let app = setup_test_app().await;
+
+    let schema_response = create_valid_schema(&app, "pagination-anchor").await;
+    let schema: Schema = schema_response.json().await.unwrap();
+
+    for i in 1..=5 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
+    }
+
+    let anchored_page = |cursor: Option<i64>, anchor: i64| {
+        let mut query = vec![
+            ("limit", "2".to_string()),
+            ("anchor", anchor.to_string()),
+            ("include_total", "true".to_string()),
+        ];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor.to_string()));
+        }
+        app.auth()
+            .get(format!("/logs/schemas/{}", schema.id))
+            .query(&query)
+            .send()
+    };
+
+    let first_load: Value = get_logs_with_cursor(&app, schema.id.to_string(), None, 2, "forward")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let anchor = first_load["logs"][0]["id"].as_i64().unwrap();
+
+    let first: Value = anchored_page(None, anchor)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let next_cursor = first["cursor"]["next_cursor"].as_i64();
+    let second: Value = anchored_page(next_cursor, anchor)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page_messages(&first), vec!["message-5", "message-4"]);
+    assert_eq!(page_messages(&second), vec!["message-3", "message-2"]);
+    assert_eq!(first["query"]["anchor"], anchor);
+
+    for i in 6..=8 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
+    }
+
+    let first_again: Value = anchored_page(None, anchor)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let second_again: Value = anchored_page(next_cursor, anchor)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page_messages(&first_again), page_messages(&first));
+    assert_eq!(page_messages(&second_again), page_messages(&second));
+    assert_eq!(first_again["cursor"]["total"], 5);
+}
+
+#[tokio::test]
+async fn rejects_unknown_direction() {
+    let app = setup_test_app().await;
+
+    let schema_response = create_valid_schema(&app, "pagination-invalid-direction").await;
+    let schema: Schema = schema_response.json().await.unwrap();
+
+    let response = get_logs_with_cursor(&app, &schema.id.to_string(), None, 10, "sideways").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_DIRECTION");
+    assert!(body["message"].as_str().unwrap().contains("sideways"));
+}
+
+async fn insert_messages(app: &crate::common::TestApp, schema: &Schema, count: i32) {
+    sqlx::query(
+        r#"
+        INSERT INTO logs (schema_id, log_data)
+        SELECT $1, jsonb_build_object('message', 'message-' || i)
+        FROM generate_series(1, $2) AS i
+        "#,
+    )
+    .bind(schema.id)
+    .bind(count)
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+}
+
+fn page_messages(data: &Value) -> Vec<String> {
+    data["logs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["log_data"]["message"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn by_name_and_version_route_paginates_with_cursor() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "pagination-by-name-version")
+        .await
+        .json()
+        .await
+        .unwrap();
+    insert_messages(&app, &schema, 5).await;
+
+    let path = "pagination-by-name-version/versions/1.0.0";
+    let response = get_logs_by_schema_name_with_cursor(&app, path, None, 2, "forward").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let first: Value = response.json().await.unwrap();
+    assert_eq!(page_messages(&first), vec!["message-5", "message-4"]);
+    assert_eq!(first["cursor"]["has_more"], true);
+
+    let next = first["cursor"]["next_cursor"].as_i64();
+    let response = get_logs_by_schema_name_with_cursor(&app, path, next, 2, "forward").await;
+    let second: Value = response.json().await.unwrap();
+    assert_eq!(page_messages(&second), vec!["message-3", "message-2"]);
+
+    let prev = second["cursor"]["prev_cursor"].as_i64();
+    let response = get_logs_by_schema_name_with_cursor(&app, path, prev, 2, "backward").await;
+    let back: Value = response.json().await.unwrap();
+    assert_eq!(page_messages(&back), vec!["message-5", "message-4"]);
+}
+
+#[tokio::test]
+async fn by_name_latest_route_paginates_with_cursor() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "pagination-by-name-latest")
+        .await
+        .json()
+        .await
+        .unwrap();
+    insert_messages(&app, &schema, 3).await;
+
+    let path = "pagination-by-name-latest/latest";
+    let mut seen = Vec::new();
+    let mut cursor = None;
+    loop {
+        let response = get_logs_by_schema_name_with_cursor(&app, path, cursor, 2, "forward").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let data: Value = response.json().await.unwrap();
+        assert_eq!(data["schema_id"], schema.id.to_string());
+        seen.extend(page_messages(&data));
+
+        match data["cursor"]["next_cursor"].as_i64() {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    assert_eq!(seen, vec!["message-3", "message-2", "message-1"]);
+}
+
+#[tokio::test]
+async fn page_format_wraps_logs_in_unified_envelope() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "page-format-logs")
+        .await
+        .json()
+        .await
+        .unwrap();
+    for i in 1..=3 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
+    }
+
+    let response = app
+        .auth()
+        .get(format!(
+            "/logs/schemas/{}?format=page&limit=2&alias=log_data.message:msg",
+            schema.id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert!(body.get("logs").is_none());
+    assert!(body.get("query").is_none());
+    let logs = body["data"].as_array().unwrap();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0]["log_data"]["msg"], "message-3");
+    assert_eq!(body["pagination"]["limit"], 2);
+    assert_eq!(body["pagination"]["has_more"], true);
+}
+
+#[tokio::test]
+async fn head_reports_total_count_without_body() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "head-count-logs")
+        .await
+        .json()
+        .await
+        .unwrap();
+    for i in 1..=3 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i)).await;
+    }
+
+    let response = app
+        .auth()
+        .request(Method::HEAD, format!("/logs/schemas/{}?limit=1", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "3");
+    assert!(response.headers().get("link").is_none());
+    assert!(response.bytes().await.unwrap().is_empty());
+
+    let response = app
+        .auth()
+        .request(Method::HEAD, format!("/logs/schemas/{}", schema.id))
+        .query(&[("filters", r#"{"message":"message-2"}"#)])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-total-count"], "1");
+}
+
+#[tokio::test]
+async fn head_on_unknown_schema_is_not_found() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .request(
+            Method::HEAD,
+            format!("/logs/schemas/{}", uuid::Uuid::new_v4()),
+        )
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get("x-total-count").is_none());
+}
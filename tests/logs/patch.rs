@@ -0,0 +1,145 @@
+use crab_pot::{Log, Schema};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{
+    create_valid_log, create_valid_schema, get_log, patch_log, setup_test_app, TestApp,
+};
+
+const JSON_PATCH: &str = "application/json-patch+json";
+const MERGE_PATCH: &str = "application/merge-patch+json";
+
+async fn setup_log(app: &TestApp, schema_name: &str) -> Log {
+    let schema: Schema = create_valid_schema(app, schema_name)
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_valid_log(app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn applies_json_patch_replace() {
+    let app = setup_test_app().await;
+    let log = setup_log(&app, "patch-log-replace").await;
+
+    let patch = json!([
+        { "op": "test", "path": "/message", "value": "Test log message" },
+        { "op": "replace", "path": "/message", "value": "patched" },
+        { "op": "add", "path": "/tags", "value": ["a"] },
+        { "op": "add", "path": "/tags/-", "value": "b" }
+    ]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let patched: Log = response.json().await.unwrap();
+    assert_eq!(
+        patched.log_data,
+        json!({ "message": "patched", "tags": ["a", "b"] })
+    );
+
+    let stored: Log = get_log(&app, log.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.log_data, patched.log_data);
+}
+
+#[tokio::test]
+async fn failed_test_operation_returns_conflict_and_leaves_log_unchanged() {
+    let app = setup_test_app().await;
+    let log = setup_log(&app, "patch-log-test-failed").await;
+
+    let patch = json!([
+        { "op": "replace", "path": "/message", "value": "patched" },
+        { "op": "test", "path": "/message", "value": "something else" }
+    ]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &patch).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "PATCH_TEST_FAILED");
+
+    let stored: Log = get_log(&app, log.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.log_data, json!({ "message": "Test log message" }));
+}
+
+#[tokio::test]
+async fn test_operation_compares_values_exactly() {
+    let app = setup_test_app().await;
+    let log = setup_log(&app, "patch-log-test-numbers").await;
+
+    let setup = json!([{ "op": "add", "path": "/count", "value": 1 }]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &setup).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let patch = json!([
+        { "op": "test", "path": "/count", "value": 1.0 },
+        { "op": "replace", "path": "/count", "value": 2 }
+    ]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &patch).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let patch = json!([
+        { "op": "test", "path": "/count", "value": 1 },
+        { "op": "replace", "path": "/count", "value": 2 }
+    ]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let patched: Log = response.json().await.unwrap();
+    assert_eq!(patched.log_data["count"], 2);
+}
+
+#[tokio::test]
+async fn rejects_malformed_json_patch() {
+    let app = setup_test_app().await;
+    let log = setup_log(&app, "patch-log-malformed").await;
+
+    let unknown_op = json!([{ "op": "frobnicate", "path": "/message" }]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &unknown_op).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_PATCH");
+
+    let missing_path = json!([{ "op": "remove", "path": "/nope" }]);
+    let response = patch_log(&app, log.id, JSON_PATCH, &missing_path).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn merge_patch_is_the_default_and_revalidates() {
+    let app = setup_test_app().await;
+    let log = setup_log(&app, "patch-log-merge").await;
+
+    let response = patch_log(
+        &app,
+        log.id,
+        "application/json",
+        &json!({ "message": "merged", "extra": 1 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let patched: Log = response.json().await.unwrap();
+    assert_eq!(patched.log_data, json!({ "message": "merged", "extra": 1 }));
+
+    let response = patch_log(&app, log.id, MERGE_PATCH, &json!({ "message": null })).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn patching_missing_log_returns_not_found() {
+    let app = setup_test_app().await;
+
+    let response = patch_log(&app, i32::MAX, MERGE_PATCH, &json!({ "message": "x" })).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
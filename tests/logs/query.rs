@@ -0,0 +1,222 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_log, create_valid_schema, filter_logs, setup_test_app, TestApp};
+
+async fn seed_logs(app: &TestApp, name: &str) -> Schema {
+    let schema: Schema = create_valid_schema(app, name).await.json().await.unwrap();
+
+    for (message, level, status) in [
+        ("boom", "error", 200),
+        ("slow", "warn", 503),
+        ("fine", "info", 200),
+    ] {
+        let response = create_log(
+            app,
+            &json!({
+                "schema_id": schema.id,
+                "log_data": { "message": message, "level": level, "status": status }
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    schema
+}
+
+async fn messages(response: reqwest::Response) -> Vec<String> {
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    let mut messages: Vec<String> = body["logs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|log| log["log_data"]["message"].as_str().unwrap().to_string())
+        .collect();
+    messages.sort();
+    messages
+}
+
+#[tokio::test]
+async fn or_matches_either_condition() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-or").await;
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({
+            "where": {
+                "or": [
+                    { "field": "level", "op": "eq", "value": "error" },
+                    { "field": "status", "op": "gte", "value": 500 }
+                ]
+            }
+        }),
+    )
+    .await;
+
+    assert_eq!(messages(response).await, vec!["boom", "slow"]);
+}
+
+#[tokio::test]
+async fn not_negates_a_condition() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-not").await;
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({ "where": { "not": { "field": "level", "op": "eq", "value": "info" } } }),
+    )
+    .await;
+    assert_eq!(messages(response).await, vec!["boom", "slow"]);
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({ "where": { "not": { "field": "missing", "op": "eq", "value": 1 } } }),
+    )
+    .await;
+    assert_eq!(messages(response).await, vec!["boom", "fine", "slow"]);
+}
+
+#[tokio::test]
+async fn ne_requires_the_field_to_be_present() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-ne").await;
+    let response = create_log(
+        &app,
+        &json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "no level", "status": 200 }
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({ "where": { "field": "level", "op": "ne", "value": "info" } }),
+    )
+    .await;
+    assert_eq!(messages(response).await, vec!["boom", "slow"]);
+}
+
+#[tokio::test]
+async fn combines_with_simple_filters() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-combined").await;
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({
+            "filters": { "status": 200 },
+            "where": { "field": "level", "op": "in", "value": ["error", "warn"] },
+            "include_total": true
+        }),
+    )
+    .await;
+
+    assert_eq!(messages(response).await, vec!["boom"]);
+}
+
+#[tokio::test]
+async fn rejects_invalid_expressions() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-invalid").await;
+
+    let mut too_deep = json!({ "field": "level", "op": "eq", "value": "error" });
+    for _ in 0..10 {
+        too_deep = json!({ "not": too_deep });
+    }
+
+    for expression in [
+        too_deep,
+        json!({ "field": "level'; DROP TABLE logs; --", "value": "x" }),
+        json!({ "field": "status", "op": "between", "value": 1 }),
+        json!({ "or": [] }),
+        json!({ "xor": [] }),
+    ] {
+        let response =
+            filter_logs(&app, schema.id.to_string(), &json!({ "where": expression })).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "INVALID_FILTER");
+    }
+}
+
+#[tokio::test]
+async fn echoes_applied_query() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-echo").await;
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({
+            "filters": { "level": "error" },
+            "date_begin": "2020-01-01T00:00:00Z",
+            "limit": 5000
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    let query = &body["query"];
+    assert_eq!(query["filters"], json!({ "level": "error" }));
+    assert_eq!(query["date_begin"], "2020-01-01T00:00:00Z");
+    assert_eq!(query["limit"], 100);
+    assert_eq!(query["direction"], "forward");
+    assert!(query.get("date_end").is_none());
+}
+
+#[tokio::test]
+async fn rejects_reversed_date_range() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-reversed-range").await;
+
+    let response = filter_logs(
+        &app,
+        schema.id.to_string(),
+        &json!({
+            "date_begin": "2030-01-02T00:00:00Z",
+            "date_end": "2030-01-01T00:00:00Z"
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_DATE_RANGE");
+}
+
+#[tokio::test]
+async fn single_date_bound_is_open_ended() {
+    let app = setup_test_app().await;
+    let schema = seed_logs(&app, "query-single-bound").await;
+
+    for (bound, expected) in [
+        (json!({ "date_begin": "2000-01-01T00:00:00Z" }), 3),
+        (json!({ "date_begin": "2999-01-01T00:00:00Z" }), 0),
+        (json!({ "date_end": "2999-01-01T00:00:00Z" }), 3),
+        (json!({ "date_end": "2000-01-01T00:00:00Z" }), 0),
+    ] {
+        let response = filter_logs(&app, schema.id.to_string(), &bound).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(
+            body["logs"].as_array().unwrap().len(),
+            expected,
+            "bound: {}",
+            bound
+        );
+    }
+}
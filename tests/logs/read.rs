@@ -1,9 +1,9 @@
 use crab_pot::{Log, Schema};
 use reqwest::StatusCode;
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use crate::common::{
-    create_valid_log, create_valid_log_with_message, create_valid_schema, get_log,
+    create_log, create_valid_log, create_valid_log_with_message, create_valid_schema, get_log,
     get_logs_by_schema_name, get_logs_by_schema_name_and_version, setup_test_app, ErrorResponse,
 };
 
@@ -43,6 +43,10 @@ async fn rejects_invalid_log_id_format() {
 
     let response = get_log(&app, "invalid").await;
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_PATH");
+    assert!(error.message.contains("Cannot parse"), "{}", error.message);
 }
 
 #[tokio::test]
@@ -153,5 +157,303 @@ async fn rejects_empty_schema_name() {
     let app = setup_test_app().await;
 
     let response = get_logs_by_schema_name(&app, "").await;
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_INPUT");
+    assert_eq!(error.message, "Schema name cannot be empty");
+
+    let response = get_logs_by_schema_name(&app, "%20").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn rejects_empty_schema_name_or_version_with_version() {
+    let app = setup_test_app().await;
+
+    let response = get_logs_by_schema_name_and_version(&app, "", "1.0.0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_INPUT");
+
+    let response = get_logs_by_schema_name_and_version(&app, "some-schema", "").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "INVALID_INPUT");
+    assert_eq!(error.message, "Schema version cannot be empty");
+}
+
+#[tokio::test]
+async fn filters_logs_by_creating_api_key() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "created-by-filter")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        create_valid_log(&app, schema.id.to_string()).await;
+    }
+
+    let other_key = app.create_api_key_with_limits(100, 100).await;
+    let other_log: Log = app
+        .client
+        .post(format!("{}/logs", app.address))
+        .header("Authorization", format!("Bearer {}", other_key))
+        .json(&serde_json::json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "from the other key" }
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let other_key_id = other_log.created_by.unwrap();
+    assert_ne!(other_key_id, app.api_key_id);
+
+    for (key_id, expected) in [(app.api_key_id, 2), (other_key_id, 1)] {
+        let response = app
+            .auth()
+            .get(format!("/logs/schemas/{}", schema.id))
+            .query(&[("created_by", key_id.to_string())])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let data: Value = response.json().await.unwrap();
+        let logs = data["logs"].as_array().unwrap();
+        assert_eq!(logs.len(), expected);
+        assert!(logs.iter().all(|l| l["created_by"] == key_id));
+    }
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("created_by", "not-a-key-id")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn filters_logs_by_array_element() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "array-contains-filter")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for (message, tags) in [
+        ("first", json!(["a", "b", "c"])),
+        ("second", json!(["c", "d"])),
+        ("third", json!(["b"])),
+    ] {
+        let response = create_log(
+            &app,
+            &json!({
+                "schema_id": schema.id,
+                "log_data": { "message": message, "tags": tags }
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let response = app
+        .auth()
+        .post(format!("/logs/schemas/{}", schema.id))
+        .json(&json!({ "filters": { "tags": { "$contains": "b" } } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    let mut messages: Vec<&str> = data["logs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|l| l["log_data"]["message"].as_str())
+        .collect();
+    messages.sort();
+    assert_eq!(messages, vec!["first", "third"]);
+
+    let filters = json!({ "message": "first", "tags": { "$contains": "c" } }).to_string();
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("filters", filters.as_str())])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    let logs = data["logs"].as_array().unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["log_data"]["message"], "first");
+}
+
+#[tokio::test]
+async fn filters_logs_by_any_of_several_values() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "in-filter")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for (message, level, service) in [
+        ("debug", "DEBUG", "api"),
+        ("error", "ERROR", "api"),
+        ("fatal", "FATAL", "api"),
+        ("info", "INFO", "api"),
+        ("worker-error", "ERROR", "worker"),
+    ] {
+        let response = create_log(
+            &app,
+            &json!({
+                "schema_id": schema.id,
+                "log_data": { "message": message, "level": level, "service": service }
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let messages = |data: Value| {
+        let mut messages: Vec<String> = data["logs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|l| l["log_data"]["message"].as_str().map(String::from))
+            .collect();
+        messages.sort();
+        messages
+    };
+
+    let filters = json!({ "level": { "$in": ["ERROR", "FATAL"] } }).to_string();
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("filters", filters.as_str())])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        messages(response.json().await.unwrap()),
+        ["error", "fatal", "worker-error"]
+    );
+
+    let response = app
+        .auth()
+        .post(format!("/logs/schemas/{}", schema.id))
+        .json(&json!({
+            "filters": { "level": { "$in": ["ERROR", "FATAL"] }, "service": "api" }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(messages(response.json().await.unwrap()), ["error", "fatal"]);
+}
+
+#[tokio::test]
+async fn rejects_non_scalar_in_values() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "in-filter-invalid")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for operand in [
+        json!("ERROR"),
+        json!([["ERROR"]]),
+        json!([{ "a": 1 }]),
+        json!([null]),
+    ] {
+        let response = app
+            .auth()
+            .post(format!("/logs/schemas/{}", schema.id))
+            .json(&json!({ "filters": { "level": { "$in": operand } } }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", operand);
+
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "INVALID_FILTER");
+        assert_eq!(body["field"], "level");
+    }
+}
+
+#[tokio::test]
+async fn renames_output_keys_with_alias() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "read-alias")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_valid_log_with_message(&app, schema.id.to_string(), "aliased").await;
+
+    let response = app
+        .auth()
+        .get(format!("/logs/schemas/{}", schema.id))
+        .query(&[("alias", "created_at:timestamp,log_data.message:msg")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    let log = &body["logs"][0];
+    assert!(log["timestamp"].is_string());
+    assert!(log.get("created_at").is_none());
+    assert_eq!(log["log_data"]["msg"], "aliased");
+    assert!(log["log_data"].get("message").is_none());
+    assert_eq!(log["schema_id"], schema.id.to_string());
+}
+
+#[tokio::test]
+async fn rejects_malformed_alias_spec() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "read-alias-malformed")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for spec in [
+        "created_at",
+        "created_at:",
+        "log_data..msg:message",
+        "a:b.c",
+    ] {
+        let response = app
+            .auth()
+            .get(format!("/logs/schemas/{}", schema.id))
+            .query(&[("alias", spec)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "spec: {}", spec);
+
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.error, "INVALID_ALIAS");
+    }
 }
@@ -0,0 +1,224 @@
+use crab_pot::{LogEvent, Schema};
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+};
+
+use crate::common::{
+    create_log, create_schema, export_logs, filter_logs, get_log, get_logs_by_schema_name,
+    patch_log, setup_test_app,
+};
+
+#[tokio::test]
+async fn redacted_field_is_stored_but_never_returned() {
+    let app = setup_test_app().await;
+
+    let response = create_schema(
+        &app,
+        &json!({
+            "name": "redaction-ssn",
+            "version": "1.0.0",
+            "schema_definition": {
+                "type": "object",
+                "properties": {
+                    "message": { "type": "string" },
+                    "ssn": { "type": "string" }
+                },
+                "required": ["message"]
+            },
+            "redacted_fields": ["ssn"]
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let schema: Schema = response.json().await.unwrap();
+    assert_eq!(schema.redacted_fields, vec!["ssn".to_string()]);
+
+    let url = format!(
+        "{}/ws/logs?schema_id={}",
+        app.address.replace("http", "ws"),
+        schema.id
+    );
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+    // Subscribed control frame.
+    ws_stream.next().await.unwrap().unwrap();
+
+    let payload = json!({
+        "schema_id": schema.id,
+        "log_data": { "message": "hello", "ssn": "123-45-6789" }
+    });
+    let response = create_log(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Value = response.json().await.unwrap();
+    assert_eq!(created["log_data"], json!({ "message": "hello" }));
+    let id = created["id"].as_i64().unwrap() as i32;
+
+    let stored: Value = sqlx::query_scalar("SELECT log_data FROM logs WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(stored["ssn"], "123-45-6789");
+
+    let fetched: Value = get_log(&app, id.to_string()).await.json().await.unwrap();
+    assert_eq!(fetched["log_data"], json!({ "message": "hello" }));
+
+    let listed: Value = get_logs_by_schema_name(&app, "redaction-ssn")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(listed["logs"][0]["log_data"], json!({ "message": "hello" }));
+
+    let exported: Value = export_logs(&app, schema.id.to_string(), None, 10)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        exported["logs"][0]["log_data"],
+        json!({ "message": "hello" })
+    );
+
+    let message = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .expect("Timeout waiting for WebSocket message")
+        .unwrap()
+        .unwrap();
+    let Message::Text(text) = message else {
+        panic!("Expected text message, got {:?}", message);
+    };
+    match serde_json::from_str(&text).unwrap() {
+        LogEvent::Created { log_data, .. } => assert_eq!(log_data, json!({ "message": "hello" })),
+        event => panic!("Expected created event, got {:?}", event),
+    }
+}
+
+#[tokio::test]
+async fn filters_on_redacted_fields_are_rejected() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_schema(
+        &app,
+        &json!({
+            "name": "redaction-filter",
+            "version": "1.0.0",
+            "schema_definition": {
+                "type": "object",
+                "properties": {
+                    "message": { "type": "string" },
+                    "ssn": { "type": "string" }
+                },
+                "required": ["message"]
+            },
+            "redacted_fields": ["ssn"]
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    create_log(
+        &app,
+        &json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "hello", "ssn": "123-45-6789" }
+        }),
+    )
+    .await;
+
+    for response in [
+        app.auth()
+            .post(format!("/logs/schemas/{}", schema.id))
+            .json(&json!({ "filters": { "ssn": "123-45-6789" } }))
+            .send()
+            .await
+            .unwrap(),
+        filter_logs(
+            &app,
+            schema.id.to_string(),
+            &json!({ "where": { "not": { "field": "ssn", "op": "eq", "value": "000-00-0000" } } }),
+        )
+        .await,
+    ] {
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let error: Value = response.json().await.unwrap();
+        assert_eq!(error["error"], "INVALID_FILTER");
+        assert_eq!(error["field"], "ssn");
+    }
+
+    let response = app
+        .auth()
+        .post(format!("/logs/schemas/{}", schema.id))
+        .json(&json!({ "filters": { "message": "hello" } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn json_patch_cannot_reach_redacted_fields() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_schema(
+        &app,
+        &json!({
+            "name": "redaction-patch",
+            "version": "1.0.0",
+            "schema_definition": {
+                "type": "object",
+                "properties": {
+                    "message": { "type": "string" },
+                    "ssn": { "type": "string" }
+                },
+                "required": ["message"]
+            },
+            "redacted_fields": ["ssn"]
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let log: Value = create_log(
+        &app,
+        &json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "hello", "ssn": "123-45-6789" }
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    let id = log["id"].as_i64().unwrap() as i32;
+
+    for patch in [
+        json!([{ "op": "copy", "from": "/ssn", "path": "/message" }]),
+        json!([{ "op": "move", "from": "/ssn", "path": "/leak" }]),
+        json!([{ "op": "test", "path": "/ssn", "value": "123-45-6789" }]),
+        json!([{ "op": "copy", "from": "", "path": "/leak" }]),
+    ] {
+        let response = patch_log(&app, id, "application/json-patch+json", &patch).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = response.text().await.unwrap();
+        assert!(!body.contains("123-45-6789"));
+        let error: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(error["error"], "INVALID_FILTER");
+    }
+
+    let log: Value = get_log(&app, id.to_string()).await.json().await.unwrap();
+    assert_eq!(log["log_data"], json!({ "message": "hello" }));
+}
@@ -0,0 +1,118 @@
+use crab_pot::{dto::SequencedLogEvent, LogEvent, Schema};
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::common::{create_valid_log, create_valid_schema, setup_test_app, TestApp};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn subscribe(app: &TestApp, schema: &Schema) -> WsStream {
+    let url = format!(
+        "{}/ws/logs?schema_id={}",
+        app.address.replace("http", "ws"),
+        schema.id
+    );
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+
+    // Wait for the subscribed frame so no event is missed.
+    let first = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .unwrap();
+    assert!(matches!(first, Some(Ok(Message::Text(_)))));
+
+    ws_stream
+}
+
+async fn next_event(ws_stream: &mut WsStream) -> LogEvent {
+    loop {
+        let message = timeout(Duration::from_secs(5), ws_stream.next())
+            .await
+            .expect("Timeout waiting for WebSocket message")
+            .expect("WebSocket stream ended")
+            .expect("Failed to receive message");
+
+        if let Message::Text(text) = message {
+            if let Ok(frame) = serde_json::from_str::<SequencedLogEvent>(&text) {
+                return frame.event;
+            }
+        }
+    }
+}
+
+async fn replay_log(app: &TestApp, id: i32) -> reqwest::Response {
+    app.client
+        .post(format!("{}/logs/{}/replay", app.admin_address, id))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn replay_rebroadcasts_existing_log() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "replay-log")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let mut ws_stream = subscribe(&app, &schema).await;
+
+    let log: serde_json::Value = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log_id = log["id"].as_i64().unwrap() as i32;
+    assert!(matches!(
+        next_event(&mut ws_stream).await,
+        LogEvent::Created { id, .. } if id == log_id
+    ));
+
+    let response = replay_log(&app, log_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["id"], log_id);
+
+    match next_event(&mut ws_stream).await {
+        LogEvent::Created {
+            id,
+            schema_id,
+            log_data,
+            ..
+        } => {
+            assert_eq!(id, log_id);
+            assert_eq!(schema_id, schema.id);
+            assert_eq!(log_data, log["log_data"]);
+        }
+        other => panic!("Expected Created event, got {:?}", other),
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM logs WHERE schema_id = $1")
+        .bind(schema.id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn replay_of_missing_log_is_not_found() {
+    let app = setup_test_app().await;
+
+    let response = replay_log(&app, 999_999).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
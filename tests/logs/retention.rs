@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crab_pot::{
+    models::DEFAULT_TENANT_ID, LogRepository, LogService, Schema, SchemaRepository, SchemaService,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+
+use crate::common::{create_schema, setup_test_app, valid_schema_payload, TestApp};
+
+fn log_service(app: &TestApp) -> LogService {
+    let log_repo = Arc::new(LogRepository::new(app.db_pool.clone()));
+    let schema_repo = Arc::new(SchemaRepository::new(app.db_pool.clone()));
+    let schema_service = Arc::new(SchemaService::new(schema_repo, log_repo.clone()));
+    LogService::new(log_repo, schema_service)
+}
+
+async fn remaining_messages(app: &TestApp, schema_id: uuid::Uuid) -> Vec<String> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT log_data->>'message' FROM logs WHERE schema_id = $1 ORDER BY id",
+    )
+    .bind(schema_id)
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn sweep_keeps_only_the_newest_logs() {
+    let app = setup_test_app().await;
+
+    let mut payload = valid_schema_payload("retention-keep-last");
+    payload["max_retained_logs"] = json!(10);
+    let response = create_schema(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let schema: Schema = response.json().await.unwrap();
+    assert_eq!(schema.max_retained_logs, Some(10));
+
+    let unlimited: Schema = create_schema(&app, &valid_schema_payload("retention-unlimited"))
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    // Inserted through the service to stay clear of the API key rate limit.
+    let service = log_service(&app);
+    for i in 0..15 {
+        for schema_id in [schema.id, unlimited.id] {
+            service
+                .create_log(
+                    schema_id,
+                    DEFAULT_TENANT_ID,
+                    json!({ "message": i.to_string() }),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    let deleted = service.enforce_retention().await.unwrap();
+    assert_eq!(deleted, 5);
+
+    let expected: Vec<String> = (5..15).map(|i| i.to_string()).collect();
+    assert_eq!(remaining_messages(&app, schema.id).await, expected);
+    assert_eq!(remaining_messages(&app, unlimited.id).await.len(), 15);
+
+    assert_eq!(service.enforce_retention().await.unwrap(), 0);
+}
+
+async fn backdate_oldest(app: &TestApp, schema_id: uuid::Uuid, count: i64) {
+    sqlx::query(
+        r#"
+        UPDATE logs SET created_at = NOW() - INTERVAL '2 hours'
+        WHERE id IN (SELECT id FROM logs WHERE schema_id = $1 ORDER BY id LIMIT $2)
+        "#,
+    )
+    .bind(schema_id)
+    .bind(count)
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn stricter_of_count_and_age_limits_wins() {
+    let app = setup_test_app().await;
+    let service = log_service(&app);
+
+    // 15 logs each, capped at the newest 10 and at one hour; `backdated` of
+    // them are two hours old.
+    for (name, backdated, kept_from) in
+        [("retention-age-wins", 8, 8), ("retention-count-wins", 2, 5)]
+    {
+        let mut payload = valid_schema_payload(name);
+        payload["max_retained_logs"] = json!(10);
+        payload["max_log_age_secs"] = json!(3600);
+        let response = create_schema(&app, &payload).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let schema: Schema = response.json().await.unwrap();
+        assert_eq!(schema.max_log_age_secs, Some(3600));
+
+        for i in 0..15 {
+            service
+                .create_log(
+                    schema.id,
+                    DEFAULT_TENANT_ID,
+                    json!({ "message": i.to_string() }),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        backdate_oldest(&app, schema.id, backdated).await;
+
+        service.enforce_retention().await.unwrap();
+
+        let expected: Vec<String> = (kept_from..15).map(|i| i.to_string()).collect();
+        assert_eq!(remaining_messages(&app, schema.id).await, expected);
+    }
+}
+
+#[tokio::test]
+async fn rejects_non_positive_retention_limits() {
+    let app = setup_test_app().await;
+
+    let mut payload = valid_schema_payload("retention-invalid");
+    payload["max_retained_logs"] = json!(0);
+    let response = create_schema(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let mut payload = valid_schema_payload("retention-invalid-age");
+    payload["max_log_age_secs"] = json!(0);
+    let response = create_schema(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
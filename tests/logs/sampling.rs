@@ -0,0 +1,131 @@
+use crab_pot::{LogSampling, Schema};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_log, create_schema, setup_test_app, setup_test_app_with, TestApp};
+
+async fn create_sampled_schema(app: &TestApp, name: &str, sample_rate: f64) -> Schema {
+    let response = create_schema(
+        app,
+        &json!({
+            "name": name,
+            "version": "1.0.0",
+            "schema_definition": {
+                "type": "object",
+                "properties": { "message": { "type": "string" } },
+                "required": ["message"]
+            },
+            "sample_rate": sample_rate
+        }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.json().await.unwrap()
+}
+
+async fn stored_log_count(app: &TestApp, schema: &Schema) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM logs WHERE schema_id = $1")
+        .bind(schema.id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn zero_sample_rate_accepts_but_stores_nothing() {
+    let app = setup_test_app().await;
+    let schema = create_sampled_schema(&app, "sampling-zero", 0.0).await;
+    assert_eq!(schema.sample_rate, Some(0.0));
+
+    for i in 0..5 {
+        let response = create_log(
+            &app,
+            &json!({ "schema_id": schema.id, "log_data": { "message": format!("m{}", i) } }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "ACCEPTED_SAMPLED_OUT");
+        assert_eq!(body["schema_id"], schema.id.to_string());
+    }
+
+    assert_eq!(stored_log_count(&app, &schema).await, 0);
+}
+
+#[tokio::test]
+async fn full_sample_rate_keeps_every_log() {
+    let app = setup_test_app().await;
+    let schema = create_sampled_schema(&app, "sampling-full", 1.0).await;
+
+    let response = create_log(
+        &app,
+        &json!({ "schema_id": schema.id, "log_data": { "message": "kept" } }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(stored_log_count(&app, &schema).await, 1);
+}
+
+#[tokio::test]
+async fn sampled_out_logs_are_still_validated() {
+    let app = setup_test_app().await;
+    let schema = create_sampled_schema(&app, "sampling-invalid", 0.0).await;
+
+    let response = create_log(
+        &app,
+        &json!({ "schema_id": schema.id, "log_data": { "message": 42 } }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn rejects_sample_rate_outside_unit_interval() {
+    let app = setup_test_app().await;
+
+    let response = create_schema(
+        &app,
+        &json!({
+            "name": "sampling-out-of-range",
+            "version": "1.0.0",
+            "schema_definition": { "type": "object" },
+            "sample_rate": 1.5
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn deterministic_sampling_repeats_the_decision_per_request_id() {
+    let app =
+        setup_test_app_with(|builder| builder.with_log_sampling(LogSampling::Deterministic)).await;
+    let schema = create_sampled_schema(&app, "sampling-deterministic", 0.5).await;
+
+    let mut statuses = Vec::new();
+    for i in 0..8 {
+        let request_id = format!("sampling-request-{}", i);
+        let mut decisions = Vec::new();
+        for _ in 0..2 {
+            let response = app
+                .auth()
+                .post("/logs")
+                .header("X-Request-Id", &request_id)
+                .json(&json!({ "schema_id": schema.id, "log_data": { "message": "retry" } }))
+                .send()
+                .await
+                .unwrap();
+            decisions.push(response.status());
+        }
+        assert_eq!(decisions[0], decisions[1], "request id {}", request_id);
+        statuses.push(decisions[0]);
+    }
+
+    assert!(statuses
+        .iter()
+        .all(|status| *status == StatusCode::CREATED || *status == StatusCode::ACCEPTED));
+}
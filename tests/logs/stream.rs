@@ -0,0 +1,130 @@
+use crab_pot::{Log, LogEvent, Schema};
+use reqwest::StatusCode;
+use tokio::time::{timeout, Duration};
+
+use crate::common::{create_valid_log, create_valid_schema, setup_test_app, TestApp};
+
+async fn open_stream(app: &TestApp, schema_id: &str, query: &str) -> reqwest::Response {
+    app.auth()
+        .get(format!("/logs/schemas/{}/stream{}", schema_id, query))
+        .send()
+        .await
+        .unwrap()
+}
+
+/// Reads chunks until a full SSE frame (terminated by a blank line) arrives.
+async fn next_frame(response: &mut reqwest::Response, buffer: &mut String) -> String {
+    loop {
+        if let Some(end) = buffer.find("\n\n") {
+            let frame = buffer[..end].to_string();
+            buffer.drain(..end + 2);
+            return frame;
+        }
+
+        let chunk = timeout(Duration::from_secs(5), response.chunk())
+            .await
+            .expect("Timeout waiting for SSE frame")
+            .unwrap()
+            .expect("SSE stream ended");
+        buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+}
+
+#[tokio::test]
+async fn streams_created_events_for_the_schema() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "sse-stream-created")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let other: Schema = create_valid_schema(&app, "sse-stream-other")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut response = open_stream(&app, &schema.id.to_string(), "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+
+    create_valid_log(&app, other.id.to_string()).await;
+    let created: Log = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut buffer = String::new();
+    let frame = next_frame(&mut response, &mut buffer).await;
+    let mut lines = frame.lines();
+    assert_eq!(lines.next(), Some("event: created"));
+
+    let data = lines.next().unwrap().strip_prefix("data: ").unwrap();
+    match serde_json::from_str(data).unwrap() {
+        LogEvent::Created { id, schema_id, .. } => {
+            assert_eq!(id, created.id);
+            assert_eq!(schema_id, schema.id);
+        }
+        event => panic!("Expected created event, got {:?}", event),
+    }
+}
+
+#[tokio::test]
+async fn sends_heartbeat_comments() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "sse-stream-heartbeat")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut response = open_stream(&app, &schema.id.to_string(), "?heartbeat=1").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut buffer = String::new();
+    assert_eq!(next_frame(&mut response, &mut buffer).await, ": heartbeat");
+}
+
+#[tokio::test]
+async fn rejects_unknown_schema_and_invalid_heartbeat() {
+    let app = setup_test_app().await;
+
+    let response = open_stream(&app, &uuid::Uuid::new_v4().to_string(), "").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let schema: Schema = create_valid_schema(&app, "sse-stream-invalid")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let response = open_stream(&app, &schema.id.to_string(), "?heartbeat=0").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn stream_ends_on_shutdown() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "sse-stream-shutdown")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut response = open_stream(&app, &schema.id.to_string(), "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    app.shutdown.cancel();
+
+    loop {
+        let chunk = timeout(Duration::from_secs(5), response.chunk())
+            .await
+            .expect("SSE stream still open after shutdown");
+        if chunk.map_or(true, |chunk| chunk.is_none()) {
+            break;
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crab_pot::{Log, Schema};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_log, create_schema, setup_test_app, valid_schema_payload, TestApp};
+
+async fn schema_with_mode(app: &TestApp, name: &str, mode: Option<&str>) -> Schema {
+    let mut payload = valid_schema_payload(name);
+    if let Some(mode) = mode {
+        payload["validation_mode"] = json!(mode);
+    }
+
+    let response = create_schema(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.json().await.unwrap()
+}
+
+async fn create_invalid_log(app: &TestApp, schema: &Schema) -> reqwest::Response {
+    create_log(
+        app,
+        &json!({ "schema_id": schema.id, "log_data": { "level": "info" } }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn strict_mode_rejects_invalid_logs_by_default() {
+    let app = setup_test_app().await;
+    let response = create_schema(&app, &valid_schema_payload("mode-default")).await;
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["validation_mode"], "strict");
+
+    let schema = schema_with_mode(&app, "mode-strict", Some("strict")).await;
+    let response = create_invalid_log(&app, &schema).await;
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn lenient_mode_stores_invalid_logs_with_their_errors() {
+    let app = setup_test_app().await;
+    let schema = schema_with_mode(&app, "mode-lenient", Some("lenient")).await;
+
+    let response = create_invalid_log(&app, &schema).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.log_data["level"], "info");
+    assert_eq!(
+        log.log_data["_validation_errors"]["missing"],
+        json!(["/message"])
+    );
+}
+
+#[tokio::test]
+async fn lenient_mode_leaves_valid_logs_unannotated() {
+    let app = setup_test_app().await;
+    let schema = schema_with_mode(&app, "mode-lenient-valid", Some("lenient")).await;
+
+    let response = create_log(
+        &app,
+        &json!({ "schema_id": schema.id, "log_data": { "message": "ok" } }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.log_data, json!({ "message": "ok" }));
+}
+
+#[tokio::test]
+async fn off_mode_stores_invalid_logs_unchanged() {
+    let app = setup_test_app().await;
+    let schema = schema_with_mode(&app, "mode-off", Some("off")).await;
+
+    let response = create_invalid_log(&app, &schema).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let log: Log = response.json().await.unwrap();
+    assert_eq!(log.log_data, json!({ "level": "info" }));
+}
+
+#[tokio::test]
+async fn rejects_unknown_mode() {
+    let app = setup_test_app().await;
+    let mut payload = valid_schema_payload("mode-unknown");
+    payload["validation_mode"] = json!("sometimes");
+
+    let response = create_schema(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
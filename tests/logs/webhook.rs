@@ -0,0 +1,259 @@
+use axum::{body::Bytes, extract::State, http::HeaderMap, routing::post, Router};
+use crab_pot::{services::webhook_service::SIGNATURE_HEADER, LogEvent, Schema, WebhookService};
+use reqwest::StatusCode;
+use serde_json::json;
+use tokio::{
+    net::TcpListener,
+    sync::mpsc,
+    time::{timeout, Duration},
+};
+
+use crate::common::{create_schema, create_valid_log, patch_log, setup_test_app};
+
+type Callbacks = mpsc::UnboundedSender<(HeaderMap, Bytes)>;
+
+async fn capture_callback(State(tx): State<Callbacks>, headers: HeaderMap, body: Bytes) {
+    let _ = tx.send((headers, body));
+}
+
+async fn spawn_webhook_receiver() -> (String, mpsc::UnboundedReceiver<(HeaderMap, Bytes)>) {
+    spawn_webhook_receiver_answering(200).await
+}
+
+/// A receiver that records every callback and answers it with `status`.
+async fn spawn_webhook_receiver_answering(
+    status: u16,
+) -> (String, mpsc::UnboundedReceiver<(HeaderMap, Bytes)>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let status = axum::http::StatusCode::from_u16(status).unwrap();
+
+    let app = Router::new()
+        .route(
+            "/hook",
+            post(move |state, headers, body| async move {
+                capture_callback(state, headers, body).await;
+                status
+            }),
+        )
+        .with_state(tx);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (format!("http://{}/hook", address), rx)
+}
+
+#[tokio::test]
+async fn posts_signed_event_to_schema_webhook() {
+    let app = setup_test_app().await;
+    let (webhook_url, mut callbacks) = spawn_webhook_receiver().await;
+
+    let schema_response = create_schema(
+        &app,
+        &json!({
+            "name": "webhook-test",
+            "version": "1.0.0",
+            "schema_definition": {
+                "type": "object",
+                "properties": { "message": { "type": "string" } }
+            },
+            "webhook_url": webhook_url,
+            "webhook_secret": "top-secret"
+        }),
+    )
+    .await;
+    assert_eq!(schema_response.status(), StatusCode::CREATED);
+    let schema: Schema = schema_response.json().await.unwrap();
+    assert_eq!(schema.webhook_url.as_deref(), Some(webhook_url.as_str()));
+
+    let log_response = create_valid_log(&app, schema.id.to_string()).await;
+    assert_eq!(log_response.status(), StatusCode::CREATED);
+    let log: serde_json::Value = log_response.json().await.unwrap();
+
+    let (headers, body) = timeout(Duration::from_secs(5), callbacks.recv())
+        .await
+        .expect("Timeout waiting for webhook callback")
+        .expect("Webhook receiver closed");
+
+    let signature = headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+    assert_eq!(signature, WebhookService::sign("top-secret", &body));
+
+    match serde_json::from_slice::<LogEvent>(&body).unwrap() {
+        LogEvent::Created { id, schema_id, .. } => {
+            assert_eq!(id, log["id"].as_i64().unwrap() as i32);
+            assert_eq!(schema_id, schema.id);
+        }
+        other => panic!("Expected Created event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn posts_updated_event_when_log_is_patched() {
+    let app = setup_test_app().await;
+    let (webhook_url, mut callbacks) = spawn_webhook_receiver().await;
+
+    let schema_response = create_schema(
+        &app,
+        &json!({
+            "name": "webhook-patch-test",
+            "version": "1.0.0",
+            "schema_definition": {
+                "type": "object",
+                "properties": { "message": { "type": "string" } }
+            },
+            "webhook_url": webhook_url
+        }),
+    )
+    .await;
+    assert_eq!(schema_response.status(), StatusCode::CREATED);
+    let schema: Schema = schema_response.json().await.unwrap();
+
+    let log: serde_json::Value = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log_id = log["id"].as_i64().unwrap() as i32;
+    timeout(Duration::from_secs(5), callbacks.recv())
+        .await
+        .expect("Timeout waiting for created callback")
+        .expect("Webhook receiver closed");
+
+    let response = patch_log(
+        &app,
+        log_id,
+        "application/merge-patch+json",
+        &json!({ "message": "patched" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_, body) = timeout(Duration::from_secs(5), callbacks.recv())
+        .await
+        .expect("Timeout waiting for updated callback")
+        .expect("Webhook receiver closed");
+
+    match serde_json::from_slice::<LogEvent>(&body).unwrap() {
+        LogEvent::Updated { id, log_data, .. } => {
+            assert_eq!(id, log_id);
+            assert_eq!(log_data, json!({ "message": "patched" }));
+        }
+        other => panic!("Expected Updated event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn propagates_traceparent_to_webhook() {
+    let app = setup_test_app().await;
+    let (webhook_url, mut callbacks) = spawn_webhook_receiver().await;
+
+    let schema: Schema = create_schema(
+        &app,
+        &json!({
+            "name": "webhook-traceparent",
+            "version": "1.0.0",
+            "schema_definition": { "type": "object" },
+            "webhook_url": webhook_url
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+
+    let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+    let response = app
+        .auth()
+        .post("/logs")
+        .header(
+            "traceparent",
+            format!("00-{}-00f067aa0ba902b7-01", trace_id),
+        )
+        .json(&json!({ "schema_id": schema.id, "log_data": { "message": "traced" } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let (headers, _) = timeout(Duration::from_secs(5), callbacks.recv())
+        .await
+        .expect("Timeout waiting for webhook callback")
+        .expect("Webhook receiver closed");
+
+    let traceparent = headers.get("traceparent").unwrap().to_str().unwrap();
+    let parts: Vec<&str> = traceparent.split('-').collect();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0], "00");
+    assert_eq!(parts[1], trace_id);
+    assert_ne!(parts[2], "00f067aa0ba902b7");
+    assert_eq!(parts[3], "01");
+}
+
+async fn create_log_with_webhook(name: &str, webhook_url: &str) {
+    let app = setup_test_app().await;
+    let schema: Schema = create_schema(
+        &app,
+        &json!({
+            "name": name,
+            "version": "1.0.0",
+            "schema_definition": { "type": "object" },
+            "webhook_url": webhook_url
+        }),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+
+    let response = create_valid_log(&app, schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn retries_webhook_on_server_error() {
+    let (webhook_url, mut callbacks) = spawn_webhook_receiver_answering(503).await;
+    create_log_with_webhook("webhook-retry-5xx", &webhook_url).await;
+
+    for _ in 0..2 {
+        timeout(Duration::from_secs(5), callbacks.recv())
+            .await
+            .expect("Timeout waiting for webhook attempt")
+            .expect("Webhook receiver closed");
+    }
+}
+
+#[tokio::test]
+async fn does_not_retry_webhook_rejected_with_client_error() {
+    let (webhook_url, mut callbacks) = spawn_webhook_receiver_answering(400).await;
+    create_log_with_webhook("webhook-no-retry-4xx", &webhook_url).await;
+
+    timeout(Duration::from_secs(5), callbacks.recv())
+        .await
+        .expect("Timeout waiting for webhook attempt")
+        .expect("Webhook receiver closed");
+    assert!(
+        timeout(Duration::from_secs(2), callbacks.recv())
+            .await
+            .is_err(),
+        "a 400 response must not be retried"
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_webhook_url() {
+    let app = setup_test_app().await;
+
+    let response = create_schema(
+        &app,
+        &json!({
+            "name": "webhook-invalid",
+            "version": "1.0.0",
+            "schema_definition": { "type": "object" },
+            "webhook_url": "ftp://example.com/hook"
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,97 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{
+    create_valid_log, create_valid_schema, get_log, get_schema_by_id, setup_test_app,
+    valid_schema_payload, TestApp,
+};
+
+async fn set_maintenance(app: &TestApp, enabled: bool) -> reqwest::Response {
+    app.client
+        .post(format!("{}/admin/maintenance", app.admin_address))
+        .json(&json!({ "enabled": enabled }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn maintenance_blocks_writes_but_not_reads() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "maintenance-schema")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log: Value = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log_id = log["id"].as_i64().unwrap();
+
+    let response = set_maintenance(&app, true).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["enabled"], true);
+
+    let response = create_valid_log(&app, schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "MAINTENANCE");
+
+    let response = app
+        .auth()
+        .post("/schemas")
+        .json(&valid_schema_payload("blocked-schema"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let response = app
+        .auth()
+        .delete(format!("/logs/{}", log_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let response = get_schema_by_id(&app, &schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get_log(&app, log_id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .auth()
+        .post(format!("/logs/schemas/{}", schema.id))
+        .json(&json!({}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn disabling_maintenance_restores_writes() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "maintenance-toggle")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    set_maintenance(&app, true).await;
+    let response = create_valid_log(&app, schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let response = set_maintenance(&app, false).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = create_valid_log(&app, schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
@@ -1,5 +1,5 @@
 use crate::common::{create_valid_schema, TestApp};
-use crab_pot::dto::SchemaResponse;
+use crab_pot::{dto::SchemaResponse, middleware::rate_limiter::MAX_RETRY_JITTER_MS};
 use reqwest::StatusCode;
 
 #[tokio::test]
@@ -131,3 +131,50 @@ async fn test_rate_limit_per_key_isolation() {
         .unwrap();
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_rate_limit_retry_after_includes_jitter() {
+    let app = TestApp::spawn().await;
+    let schema_response = create_valid_schema(&app, "test-schema").await;
+    let schema: SchemaResponse = schema_response.json().await.unwrap();
+
+    let limited_key = app.create_api_key_with_limits(1, 1).await;
+
+    let mut response = None;
+    for _ in 0..2 {
+        response = Some(
+            app.client
+                .get(format!(
+                    "{}/logs/by-schema-name/{}/versions/{}",
+                    app.address, schema.name, schema.version
+                ))
+                .header("Authorization", format!("Bearer {}", limited_key))
+                .send()
+                .await
+                .unwrap(),
+        );
+    }
+
+    let response = response.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let headers = response.headers();
+    let jitter_ms: u64 = headers
+        .get("x-ratelimit-retry-jitter")
+        .expect("jitter header should be present")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(jitter_ms <= MAX_RETRY_JITTER_MS);
+
+    let retry_after: u64 = headers
+        .get("retry-after")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after >= 1, "must not suggest retrying before reset");
+    assert!(retry_after <= 1 + MAX_RETRY_JITTER_MS.div_ceil(1000));
+}
@@ -0,0 +1,137 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{
+    create_log, create_valid_schema, setup_test_app, setup_test_app_with, update_schema,
+    valid_schema_payload, TestApp,
+};
+
+async fn create_message_log(app: &TestApp, schema: &Schema, message: &str) -> i64 {
+    let response = create_log(
+        app,
+        &json!({ "schema_id": schema.id, "log_data": { "message": message } }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.json::<Value>().await.unwrap()["id"]
+        .as_i64()
+        .unwrap()
+}
+
+/// The fixture schema with `message` capped at five characters.
+fn stricter_payload(name: &str) -> Value {
+    let mut payload = valid_schema_payload(name);
+    payload["schema_definition"]["properties"]["message"]["maxLength"] = json!(5);
+    payload
+}
+
+#[tokio::test]
+async fn check_compat_rejects_definition_failing_existing_logs() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "compat-strict")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_message_log(&app, &schema, "short").await;
+    let long_id = create_message_log(&app, &schema, "much too long for the new rule").await;
+
+    let response = app
+        .auth()
+        .put(format!("/schemas/{}?check_compat=true", schema.id))
+        .json(&stricter_payload("compat-strict"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INCOMPATIBLE_SCHEMA");
+    assert_eq!(body["checked_logs"], 2);
+    assert_eq!(body["failed_logs"], 1);
+    assert_eq!(body["examples"][0]["log_id"], long_id);
+    assert_eq!(body["examples"][0]["path"], "/message");
+
+    // The schema is left as it was.
+    let stored: Schema = app
+        .auth()
+        .get(format!("/schemas/{}", schema.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.schema_definition, schema.schema_definition);
+}
+
+#[tokio::test]
+async fn update_without_check_compat_is_not_checked() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "compat-unchecked")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_message_log(&app, &schema, "much too long for the new rule").await;
+
+    let response = update_schema(
+        &app,
+        &schema.id.to_string(),
+        &stricter_payload("compat-unchecked"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn check_compat_on_patch_rejects_incompatible_definition() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "compat-patch")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_message_log(&app, &schema, "much too long for the new rule").await;
+
+    let response = app
+        .auth()
+        .patch(format!("/schemas/{}?check_compat=true", schema.id))
+        .header("Content-Type", "application/merge-patch+json")
+        .body(
+            json!({ "schema_definition": { "properties": { "message": { "maxLength": 5 } } } })
+                .to_string(),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INCOMPATIBLE_SCHEMA");
+}
+
+#[tokio::test]
+async fn check_compat_only_samples_the_most_recent_logs() {
+    let app = setup_test_app_with(|builder| builder.with_schema_compat_sample_size(1)).await;
+
+    let schema: Schema = create_valid_schema(&app, "compat-sample")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_message_log(&app, &schema, "much too long for the new rule").await;
+    create_message_log(&app, &schema, "short").await;
+
+    let response = app
+        .auth()
+        .put(format!("/schemas/{}?check_compat=true", schema.id))
+        .json(&stricter_payload("compat-sample"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,117 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::common::{create_schema, setup_test_app, valid_schema_payload, TestApp};
+
+async fn create_version(
+    app: &TestApp,
+    name: &str,
+    version: &str,
+    edit: impl Fn(&mut Value),
+) -> Schema {
+    let mut payload = valid_schema_payload(name);
+    payload["version"] = json!(version);
+    edit(&mut payload["schema_definition"]);
+
+    let response = create_schema(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    response.json().await.unwrap()
+}
+
+async fn get_compatible_versions(app: &TestApp, id: Uuid) -> reqwest::Response {
+    app.auth()
+        .get(format!("/schemas/{}/compatible-versions", id))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn compatible_versions_classifies_each_other_version() {
+    let app = setup_test_app().await;
+    let name = "compat-versions";
+
+    let base = create_version(&app, name, "1.0.0", |_| {}).await;
+    create_version(&app, name, "1.1.0", |definition| {
+        definition["properties"]["user"] = json!({ "type": "string" });
+    })
+    .await;
+    create_version(&app, name, "2.0.0", |definition| {
+        definition["properties"] = json!({ "text": { "type": "string" } });
+        definition["required"] = json!(["text"]);
+    })
+    .await;
+    create_version(&app, "compat-versions-other", "9.0.0", |_| {}).await;
+
+    let response = get_compatible_versions(&app, base.id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body,
+        json!([
+            {
+                "schema_id": body[0]["schema_id"],
+                "version": "1.1.0",
+                "compatible": true,
+                "breaking_changes": []
+            },
+            {
+                "schema_id": body[1]["schema_id"],
+                "version": "2.0.0",
+                "compatible": false,
+                "breaking_changes": [
+                    { "change": "field_removed", "path": "/message" },
+                    { "change": "field_added", "path": "/text", "required": true }
+                ]
+            }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn compatible_versions_reports_nested_type_changes() {
+    let app = setup_test_app().await;
+    let name = "compat-versions-nested";
+
+    let base = create_version(&app, name, "1.0.0", |definition| {
+        definition["properties"]["meta"] = json!({
+            "type": "object",
+            "properties": { "count": { "type": "integer" } }
+        });
+    })
+    .await;
+    create_version(&app, name, "1.0.1", |definition| {
+        definition["properties"]["meta"] = json!({
+            "type": "object",
+            "properties": { "count": { "type": "string" } }
+        });
+    })
+    .await;
+
+    let body: Value = get_compatible_versions(&app, base.id)
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body[0]["compatible"], false);
+    assert_eq!(
+        body[0]["breaking_changes"],
+        json!([{
+            "change": "type_changed",
+            "path": "/meta/count",
+            "from": "integer",
+            "to": "string"
+        }])
+    );
+}
+
+#[tokio::test]
+async fn compatible_versions_of_unknown_schema_is_not_found() {
+    let app = setup_test_app().await;
+
+    let response = get_compatible_versions(&app, Uuid::new_v4()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
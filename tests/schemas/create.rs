@@ -1,9 +1,9 @@
 use crab_pot::Schema;
 use reqwest::StatusCode;
-use serde_json::json;
+use serde_json::{json, Value};
 use uuid::Uuid;
 
-use crate::common::{create_schema, create_valid_schema, setup_test_app, ErrorResponse};
+use crate::common::{create_schema, create_valid_schema, get_schema_definition, setup_test_app};
 
 #[tokio::test]
 async fn creates_schema_with_valid_data() {
@@ -42,12 +42,18 @@ async fn returns_201_with_location_header() {
 async fn rejects_duplicate_schema_name() {
     let app = setup_test_app().await;
 
-    let _ = create_valid_schema(&app, "duplicate").await;
+    let existing: Schema = create_valid_schema(&app, "duplicate")
+        .await
+        .json()
+        .await
+        .unwrap();
     let response = create_valid_schema(&app, "duplicate").await;
     assert_eq!(response.status(), StatusCode::CONFLICT);
 
-    let error: ErrorResponse = response.json().await.unwrap();
-    assert!(error.message.contains("already exists"));
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "SCHEMA_CONFLICT");
+    assert_eq!(body["existing_id"], existing.id.to_string());
+    assert!(body["message"].as_str().unwrap().contains("already exists"));
 }
 
 #[tokio::test]
@@ -63,3 +69,98 @@ async fn rejects_missing_required_fields() {
     let error_text = response.text().await.unwrap();
     assert!(error_text.contains("missing field") || error_text.contains("name"));
 }
+
+#[tokio::test]
+async fn rejects_empty_body() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .post("/schemas")
+        .header("Content-Type", "application/json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "EMPTY_BODY");
+}
+
+#[tokio::test]
+async fn rejects_misspelled_field_by_name() {
+    let app = setup_test_app().await;
+
+    let payload = json!({
+        "name": "misspelled-field",
+        "version": "1.0.0",
+        "scema_definition": { "type": "object" }
+    });
+    let response = create_schema(&app, &payload).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let error_text = response.text().await.unwrap();
+    assert!(
+        error_text.contains("unknown field `scema_definition`"),
+        "{}",
+        error_text
+    );
+}
+
+#[tokio::test]
+async fn records_creating_api_key() {
+    let app = setup_test_app().await;
+
+    let response = create_valid_schema(&app, "schema-created-by").await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let schema: Schema = response.json().await.unwrap();
+    assert_eq!(schema.created_by, Some(app.api_key_id));
+}
+
+#[tokio::test]
+async fn stores_schema_definition_in_canonical_form() {
+    let app = setup_test_app().await;
+
+    let bodies = [
+        r#"{"name": "normalized-a", "version": "1.0.0", "schema_definition":
+            {"type": "object", "properties": {"level": {"type": "integer", "minimum": 1.0,
+            "maximum": 1e2, "multipleOf": 0.5}}, "required": ["level"]}}"#,
+        r#"{"schema_definition": {"required": ["level"],
+            "properties": {"level": {"multipleOf": 0.50, "maximum": 100, "minimum": 1, "type": "integer"}}, "type": "object"},
+            "version": "1.0.0", "name": "normalized-b"}"#,
+        r#"{"name":"normalized-c","version":"1.0.0","schema_definition":{"properties":{"level":{"maximum":100.0,"minimum":10E-1,"multipleOf":5e-1,"type":"integer"}},"required":["level"],"type":"object"}}"#,
+    ];
+
+    let mut definitions = Vec::new();
+    for body in bodies {
+        let response = app
+            .auth()
+            .post("/schemas")
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let schema: Schema = response.json().await.unwrap();
+
+        let response = get_schema_definition(&app, &schema.id.to_string()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers().get("ETag").cloned().unwrap();
+        let stored: String =
+            sqlx::query_scalar("SELECT schema_definition::text FROM schemas WHERE id = $1")
+                .bind(schema.id)
+                .fetch_one(&app.db_pool)
+                .await
+                .unwrap();
+        definitions.push((etag, response.text().await.unwrap(), stored));
+    }
+
+    assert_eq!(definitions[0], definitions[1]);
+    assert_eq!(definitions[0], definitions[2]);
+    assert_eq!(
+        definitions[0].1,
+        r#"{"properties":{"level":{"maximum":100,"minimum":1,"multipleOf":0.5,"type":"integer"}},"required":["level"],"type":"object"}"#
+    );
+}
@@ -3,7 +3,8 @@ use reqwest::StatusCode;
 use uuid::Uuid;
 
 use crate::common::{
-    create_valid_schema, delete_schema, get_schema_by_id, setup_test_app, ErrorResponse,
+    create_valid_log, create_valid_schema, delete_schema, get_schema_by_id, setup_test_app,
+    ErrorResponse,
 };
 
 #[tokio::test]
@@ -67,3 +68,151 @@ async fn schema_not_accessible_after_deletion() {
     let get_after_delete_response = get_schema_by_id(&app, &schema.id.to_string()).await;
     assert_eq!(get_after_delete_response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn delete_echoes_deleted_schema_by_default() {
+    let app = setup_test_app().await;
+
+    let schema: SchemaResponse = create_valid_schema(&app, "delete-echo")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = delete_schema(&app, &schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["deleted"], true);
+    assert_eq!(body["data"]["id"], schema.id.to_string());
+}
+
+#[tokio::test]
+async fn delete_without_return_deleted_returns_no_content() {
+    let app = setup_test_app().await;
+
+    let schema: SchemaResponse = create_valid_schema(&app, "delete-no-content")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = app
+        .auth()
+        .delete(format!("/schemas/{}?return_deleted=false", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(response.text().await.unwrap().is_empty());
+
+    let response = get_schema_by_id(&app, &schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn force_delete_reports_deleted_log_count() {
+    let app = setup_test_app().await;
+
+    let schema: SchemaResponse = create_valid_schema(&app, "delete-force-count")
+        .await
+        .json()
+        .await
+        .unwrap();
+    for _ in 0..3 {
+        create_valid_log(&app, schema.id.to_string()).await;
+    }
+
+    let response = app
+        .auth()
+        .delete(format!("/schemas/{}?force=true", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["deleted"], true);
+    assert_eq!(body["deleted_logs"], 3);
+    assert_eq!(body["data"]["id"], schema.id.to_string());
+}
+
+#[tokio::test]
+async fn force_delete_without_return_deleted_still_reports_log_count() {
+    let app = setup_test_app().await;
+
+    let schema: SchemaResponse = create_valid_schema(&app, "delete-force-no-echo")
+        .await
+        .json()
+        .await
+        .unwrap();
+    create_valid_log(&app, schema.id.to_string()).await;
+
+    let response = app
+        .auth()
+        .delete(format!(
+            "/schemas/{}?force=true&return_deleted=false",
+            schema.id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["deleted_logs"], 1);
+    assert!(body.get("data").is_none());
+}
+
+#[tokio::test]
+async fn force_delete_rolls_back_logs_when_schema_delete_fails() {
+    let app = setup_test_app().await;
+
+    let schema: SchemaResponse = create_valid_schema(&app, "delete-force-rollback")
+        .await
+        .json()
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        create_valid_log(&app, schema.id.to_string()).await;
+    }
+
+    // Fail the schema delete after the logs were already deleted.
+    sqlx::query(
+        r#"
+        CREATE FUNCTION fail_schema_delete() RETURNS trigger AS $$
+        BEGIN
+            RAISE EXCEPTION 'injected failure';
+        END;
+        $$ LANGUAGE plpgsql
+        "#,
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "CREATE TRIGGER fail_schema_delete BEFORE DELETE ON schemas \
+         FOR EACH ROW EXECUTE FUNCTION fail_schema_delete()",
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let response = app
+        .auth()
+        .delete(format!("/schemas/{}?force=true", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM logs WHERE schema_id = $1")
+        .bind(schema.id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 2);
+
+    let response = get_schema_by_id(&app, &schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
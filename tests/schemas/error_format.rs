@@ -0,0 +1,72 @@
+use crab_pot::ErrorBodyFormat;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::common::{create_valid_schema, get_schema_by_id, setup_test_app, setup_test_app_with};
+
+fn remapped(wrap: bool) -> ErrorBodyFormat {
+    ErrorBodyFormat {
+        code_field: "code".to_string(),
+        message_field: "detail".to_string(),
+        wrap,
+    }
+}
+
+#[tokio::test]
+async fn default_format_uses_error_and_message_keys() {
+    let app = setup_test_app().await;
+
+    let response = get_schema_by_id(&app, &Uuid::new_v4().to_string()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "NOT_FOUND");
+    assert!(body["message"].is_string());
+}
+
+#[tokio::test]
+async fn handler_errors_use_the_configured_field_names() {
+    let app = setup_test_app_with(|builder| builder.with_error_body_format(remapped(false))).await;
+
+    let response = get_schema_by_id(&app, &Uuid::new_v4().to_string()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().contains_key("x-request-id"));
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "NOT_FOUND");
+    assert!(body["detail"].is_string());
+    assert!(body.get("error").is_none());
+    assert!(body.get("message").is_none());
+}
+
+#[tokio::test]
+async fn middleware_and_admin_errors_are_wrapped_when_configured() {
+    let app = setup_test_app_with(|builder| builder.with_error_body_format(remapped(true))).await;
+
+    let response = app
+        .client
+        .post(format!("{}/admin/maintenance", app.admin_address))
+        .json(&json!({ "enabled": "yes" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "INVALID_JSON");
+
+    let response = app
+        .client
+        .post(format!("{}/admin/maintenance", app.admin_address))
+        .json(&json!({ "enabled": true }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = create_valid_schema(&app, "error-format-maintenance").await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "MAINTENANCE");
+    assert!(body["error"]["detail"].is_string());
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_log, create_schema, setup_test_app, update_schema, TestApp};
+
+fn indexed_schema_payload(name: &str, indexed_fields: Value) -> Value {
+    json!({
+        "name": name,
+        "version": "1.0.0",
+        "schema_definition": {
+            "type": "object",
+            "properties": {
+                "message": { "type": "string" },
+                "level": { "type": "string" }
+            },
+            "required": ["message", "level"]
+        },
+        "indexed_fields": indexed_fields
+    })
+}
+
+async fn field_index_count(app: &TestApp, schema: &Schema) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM pg_indexes WHERE tablename = 'logs' AND indexdef LIKE $1",
+    )
+    .bind(format!("%{}%", schema.id))
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap()
+}
+
+/// Indexes are built in the background, so poll until `expected` of them
+/// exist.
+async fn wait_for_field_index_count(app: &TestApp, schema: &Schema, expected: i64) {
+    for _ in 0..50 {
+        if field_index_count(app, schema).await == expected {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(field_index_count(app, schema).await, expected);
+}
+
+#[tokio::test]
+async fn filter_on_indexed_field_returns_matching_logs() {
+    let app = setup_test_app().await;
+
+    let response = create_schema(&app, &indexed_schema_payload("indexed", json!(["level"]))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let schema: Schema = response.json().await.unwrap();
+    assert_eq!(schema.indexed_fields, vec!["level"]);
+    wait_for_field_index_count(&app, &schema, 1).await;
+
+    for level in ["INFO", "ERROR", "INFO", "ERROR", "WARN"] {
+        create_log(
+            &app,
+            &json!({
+                "schema_id": schema.id,
+                "log_data": { "message": format!("{} message", level), "level": level }
+            }),
+        )
+        .await;
+    }
+
+    let response = app
+        .auth()
+        .post(format!("/logs/schemas/{}", schema.id))
+        .json(&json!({ "filters": { "level": "ERROR" } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    let logs = data["logs"].as_array().unwrap();
+    assert_eq!(logs.len(), 2);
+    assert!(logs.iter().all(|l| l["log_data"]["level"] == "ERROR"));
+
+    let response = app
+        .auth()
+        .post(format!("/logs/schemas/{}", schema.id))
+        .json(&json!({ "filters": { "level": { "$in": ["WARN", "INFO"] } } }))
+        .send()
+        .await
+        .unwrap();
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["logs"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn filters_on_indexed_fields_use_the_index() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_schema(
+        &app,
+        &indexed_schema_payload("indexed-plan", json!(["level"])),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    wait_for_field_index_count(&app, &schema, 1).await;
+
+    let index_name: String = sqlx::query_scalar(
+        "SELECT indexname FROM pg_indexes WHERE tablename = 'logs' AND indexdef LIKE $1",
+    )
+    .bind(format!("%{}%", schema.id))
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap();
+
+    // The predicates LogQueryBuilder emits for `{"level": "ERROR"}`.
+    let mut conn = app.db_pool.acquire().await.unwrap();
+    sqlx::query("SET enable_seqscan = off")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    let plan: Vec<String> = sqlx::query_scalar(&format!(
+        "EXPLAIN SELECT * FROM logs WHERE schema_id = '{}' \
+         AND log_data @> '{{\"level\": \"ERROR\"}}' AND (log_data ->> 'level') = 'ERROR'",
+        schema.id
+    ))
+    .fetch_all(&mut *conn)
+    .await
+    .unwrap();
+    conn.close().await.unwrap();
+
+    assert!(
+        plan.iter().any(|line| line.contains(&index_name)),
+        "{:?}",
+        plan
+    );
+}
+
+#[tokio::test]
+async fn removing_indexed_field_drops_index() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_schema(
+        &app,
+        &indexed_schema_payload("indexed-update", json!(["level"])),
+    )
+    .await
+    .json()
+    .await
+    .unwrap();
+    wait_for_field_index_count(&app, &schema, 1).await;
+
+    let response = update_schema(
+        &app,
+        &schema.id.to_string(),
+        &indexed_schema_payload("indexed-update", json!([])),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(field_index_count(&app, &schema).await, 0);
+}
+
+#[tokio::test]
+async fn rejects_invalid_indexed_field_names() {
+    let app = setup_test_app().await;
+
+    for indexed_fields in [
+        json!(["level'); DROP TABLE logs; --"]),
+        json!(["nested.field"]),
+        json!(["level", "level"]),
+    ] {
+        let response = create_schema(
+            &app,
+            &indexed_schema_payload("indexed-invalid", indexed_fields),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
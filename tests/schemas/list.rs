@@ -1,7 +1,8 @@
 use reqwest::StatusCode;
+use serde_json::{json, Value};
 
 use crate::common::{
-    routes::schemas::{create_valid_schema, get_schemas},
+    routes::schemas::{create_schema, create_valid_schema, get_schemas},
     test_app::setup_test_app,
 };
 
@@ -34,3 +35,158 @@ async fn lists_all_schemas() {
     assert!(schemas_names.contains(&"list-test-1"));
     assert!(schemas_names.contains(&"list-test-2"));
 }
+
+#[tokio::test]
+async fn searches_schemas_by_name_and_description_substring() {
+    let app = setup_test_app().await;
+
+    for (name, description) in [
+        ("zebra-access", "HTTP access logs"),
+        ("billing-events", "Invoices emitted by the ZEBRA frontend"),
+        ("ZebraSocket-frames", "Realtime frames"),
+        ("batch-jobs", "Nightly zulu runs"),
+        ("quux_100%", "Literal wildcard characters"),
+    ] {
+        let payload = json!({
+            "name": name,
+            "version": "1.0.0",
+            "description": description,
+            "schema_definition": { "type": "object" }
+        });
+        assert_eq!(
+            create_schema(&app, &payload).await.status(),
+            StatusCode::CREATED
+        );
+    }
+
+    let search = |query: &'static str| {
+        let app = &app;
+        async move {
+            let response = app
+                .auth()
+                .get("/schemas")
+                .query(&[("search", query), ("limit", "100")])
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let data: Value = response.json().await.unwrap();
+            data["schemas"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let names = search("zebra").await;
+    assert_eq!(names.len(), 3);
+    assert_eq!(names[2], "billing-events", "name matches rank first");
+    assert!(names[..2].contains(&"zebra-access".to_string()));
+    assert!(names[..2].contains(&"ZebraSocket-frames".to_string()));
+
+    assert_eq!(search("nightly zulu").await, vec!["batch-jobs"]);
+    assert_eq!(search("%").await, vec!["quux_100%"]);
+    assert_eq!(search("x_1").await, vec!["quux_100%"]);
+    assert!(search("xx_1").await.is_empty());
+}
+
+#[tokio::test]
+async fn page_format_wraps_schemas_in_unified_envelope() {
+    let app = setup_test_app().await;
+
+    let _ = create_valid_schema(&app, "page-format-1").await;
+    let _ = create_valid_schema(&app, "page-format-2").await;
+
+    let response = app
+        .auth()
+        .get("/schemas?format=page&limit=1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert!(body.get("schemas").is_none());
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert!(body["data"][0]["name"].is_string());
+    assert_eq!(body["pagination"]["limit"], 1);
+    assert_eq!(body["pagination"]["has_more"], true);
+    assert!(body["pagination"]["next_cursor"].is_string());
+}
+
+#[tokio::test]
+async fn filters_schemas_by_label() {
+    let app = setup_test_app().await;
+
+    for (name, labels) in [
+        ("payments-prod", json!(["team:payments", "env:prod"])),
+        ("payments-staging", json!(["team:payments", "env:staging"])),
+        ("search-prod", json!(["team:search", "env:prod"])),
+        ("unlabeled", json!([])),
+    ] {
+        let payload = json!({
+            "name": name,
+            "version": "1.0.0",
+            "schema_definition": { "type": "object" },
+            "labels": labels
+        });
+        let response = create_schema(&app, &payload).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["labels"], labels);
+    }
+
+    let by_label = |label: &'static str| {
+        let app = &app;
+        async move {
+            let response = app
+                .auth()
+                .get("/schemas")
+                .query(&[("label", label)])
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let data: Value = response.json().await.unwrap();
+            let mut names = data["schemas"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+    };
+
+    assert_eq!(
+        by_label("team:payments").await,
+        vec!["payments-prod", "payments-staging"]
+    );
+    assert_eq!(
+        by_label("env:prod").await,
+        vec!["payments-prod", "search-prod"]
+    );
+    assert!(by_label("team").await.is_empty());
+}
+
+#[tokio::test]
+async fn rejects_blank_label() {
+    let app = setup_test_app().await;
+
+    let payload = json!({
+        "name": "blank-label",
+        "version": "1.0.0",
+        "schema_definition": { "type": "object" },
+        "labels": ["team:payments", " "]
+    });
+
+    assert_eq!(
+        create_schema(&app, &payload).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
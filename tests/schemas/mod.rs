@@ -1,6 +1,14 @@
+pub mod compatibility;
+pub mod compatible_versions;
 pub mod create;
 pub mod delete;
+pub mod error_format;
+pub mod indexed_fields;
 pub mod list;
 pub mod pagination;
+pub mod patch;
+pub mod query_params;
 pub mod read;
+pub mod trailing_slash;
 pub mod update;
+pub mod validate;
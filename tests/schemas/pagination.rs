@@ -1,5 +1,5 @@
-use crab_pot::Schema;
-use reqwest::StatusCode;
+use crab_pot::{PageLimits, Schema};
+use reqwest::{Method, StatusCode};
 use serde_json::{json, Value};
 
 use crate::common::{create_schema, get_schemas_with_cursor, setup_test_app, valid_schema_payload};
@@ -232,6 +232,8 @@ async fn cursor_metadata_structure() {
     assert!(cursor["limit"].is_number());
     assert_eq!(cursor["limit"], 2);
     assert!(cursor["has_more"].is_boolean());
+    assert!(cursor["has_prev"].is_boolean());
+    assert!(cursor.get("total").is_none());
 
     if let Some(next) = cursor["next_cursor"].as_str() {
         assert!(uuid::Uuid::parse_str(next).is_ok());
@@ -241,3 +243,175 @@ async fn cursor_metadata_structure() {
         assert!(uuid::Uuid::parse_str(prev).is_ok());
     }
 }
+
+#[tokio::test]
+async fn over_max_limit_is_clamped() {
+    let app = setup_test_app().await;
+    let page_limits = PageLimits::default();
+
+    let over_max = (page_limits.max_limit + 1).to_string();
+    let response = app
+        .auth()
+        .get("/schemas")
+        .query(&[("limit", over_max.as_str())])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["limit"], page_limits.max_limit);
+
+    let response = app.auth().get("/schemas").send().await.unwrap();
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["limit"], page_limits.default_limit);
+}
+
+#[tokio::test]
+async fn has_prev_is_false_on_first_page_and_true_after_advancing() {
+    let app = setup_test_app().await;
+
+    for i in 1..=4 {
+        create_schema(
+            &app,
+            &valid_schema_payload(&format!("schema-has-prev-{}", i)),
+        )
+        .await;
+    }
+
+    let response = get_schemas_with_cursor(&app, None, 2, "forward").await;
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["has_prev"], false);
+
+    let next_cursor = data["cursor"]["next_cursor"].as_str().map(String::from);
+    let response = get_schemas_with_cursor(&app, next_cursor, 2, "forward").await;
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["cursor"]["has_prev"], true);
+}
+
+#[tokio::test]
+async fn include_total_reports_matching_schema_count() {
+    let app = setup_test_app().await;
+
+    for version in ["1.0.0", "1.1.0", "2.0.0"] {
+        let mut payload = valid_schema_payload("schema-total");
+        payload["version"] = json!(version);
+        create_schema(&app, &payload).await;
+    }
+
+    let response = app
+        .auth()
+        .get("/schemas")
+        .query(&[
+            ("name", "schema-total"),
+            ("limit", "2"),
+            ("include_total", "true"),
+        ])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let data: Value = response.json().await.unwrap();
+    assert_eq!(data["schemas"].as_array().unwrap().len(), 2);
+    assert_eq!(data["cursor"]["total"], 3);
+}
+
+#[tokio::test]
+async fn rejects_unknown_direction() {
+    let app = setup_test_app().await;
+
+    let response = get_schemas_with_cursor(&app, None, 10, "sideways").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_DIRECTION");
+    assert!(body["message"].as_str().unwrap().contains("sideways"));
+}
+
+#[tokio::test]
+async fn head_reports_total_count_without_body() {
+    let app = setup_test_app().await;
+
+    for i in 1..=3 {
+        create_schema(&app, &valid_schema_payload(&format!("schema-head-{}", i))).await;
+    }
+    create_schema(&app, &valid_schema_payload("other-head")).await;
+
+    let response = app
+        .auth()
+        .request(Method::HEAD, "/schemas?search=schema-head&limit=1")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "3");
+    assert!(response.bytes().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn head_sends_the_same_link_header_as_get() {
+    let app = setup_test_app().await;
+
+    for i in 1..=3 {
+        create_schema(&app, &valid_schema_payload(&format!("schema-link-{}", i))).await;
+    }
+
+    for (limit, has_next) in [(1, true), (3, false)] {
+        let uri = format!("/schemas?search=schema-link&limit={}", limit);
+        let get = app.auth().get(&uri).send().await.unwrap();
+        let head = app.auth().request(Method::HEAD, &uri).send().await.unwrap();
+
+        assert_eq!(head.status(), StatusCode::OK);
+        assert_eq!(get.headers().contains_key("link"), has_next);
+        assert_eq!(head.headers().get("link"), get.headers().get("link"));
+    }
+}
+
+#[tokio::test]
+async fn initial_cursor_is_the_newest_schema() {
+    let app = setup_test_app().await;
+
+    let mut newest = None;
+    for i in 1..=3 {
+        let schema: Schema = create_schema(
+            &app,
+            &valid_schema_payload(&format!("schema-initial-cursor-{}", i)),
+        )
+        .await
+        .json()
+        .await
+        .unwrap();
+        newest = Some(schema.id);
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+
+    let response = app
+        .auth()
+        .get("/schemas/cursor/initial")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "initial_cursor": newest.unwrap() }));
+}
+
+#[tokio::test]
+async fn initial_cursor_is_null_without_schemas() {
+    let app = setup_test_app().await;
+    let other_key = app.create_api_key_for_tenant("initial-cursor-empty").await;
+
+    let response = app
+        .auth_with(&other_key)
+        .get("/schemas/cursor/initial")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body, json!({ "initial_cursor": null }));
+}
@@ -0,0 +1,109 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_valid_schema, get_schema_by_id, patch_schema, setup_test_app};
+
+const JSON_PATCH: &str = "application/json-patch+json";
+
+#[tokio::test]
+async fn applies_json_patch_replace() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "patch-schema-replace")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let patch = json!([
+        { "op": "replace", "path": "/version", "value": "1.1.0" },
+        { "op": "add", "path": "/description", "value": "patched" },
+        {
+            "op": "add",
+            "path": "/schema_definition/properties/level",
+            "value": { "type": "string" }
+        }
+    ]);
+    let response = patch_schema(&app, &schema.id.to_string(), JSON_PATCH, &patch).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let patched: Schema = response.json().await.unwrap();
+    assert_eq!(patched.name, "patch-schema-replace");
+    assert_eq!(patched.version, "1.1.0");
+    assert_eq!(patched.description.as_deref(), Some("patched"));
+    assert_eq!(
+        patched.schema_definition["properties"]["level"],
+        json!({ "type": "string" })
+    );
+}
+
+#[tokio::test]
+async fn failed_test_operation_returns_conflict() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "patch-schema-test-failed")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let patch = json!([
+        { "op": "test", "path": "/version", "value": "9.9.9" },
+        { "op": "replace", "path": "/version", "value": "2.0.0" }
+    ]);
+    let response = patch_schema(&app, &schema.id.to_string(), JSON_PATCH, &patch).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "PATCH_TEST_FAILED");
+
+    let stored: Schema = get_schema_by_id(&app, &schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.version, "1.0.0");
+}
+
+#[tokio::test]
+async fn rejects_malformed_patch_and_unsupported_content_type() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "patch-schema-malformed")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let id = schema.id.to_string();
+
+    let response = patch_schema(&app, &id, JSON_PATCH, &json!({ "op": "replace" })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_PATCH");
+
+    let response = patch_schema(&app, &id, "text/plain", &json!({})).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_CONTENT_TYPE");
+}
+
+#[tokio::test]
+async fn merge_patch_updates_description() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "patch-schema-merge")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = patch_schema(
+        &app,
+        &schema.id.to_string(),
+        "application/merge-patch+json",
+        &json!({ "description": "merged" }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let patched: Schema = response.json().await.unwrap();
+    assert_eq!(patched.description.as_deref(), Some("merged"));
+    assert_eq!(patched.version, "1.0.0");
+}
@@ -0,0 +1,66 @@
+use crab_pot::{Schema, UnknownQueryParams};
+use reqwest::StatusCode;
+
+use crate::common::{create_valid_schema, setup_test_app, setup_test_app_with, ErrorResponse};
+
+#[tokio::test]
+async fn unknown_query_params_are_ignored_by_default() {
+    let app = setup_test_app().await;
+
+    let response = app.auth().get("/schemas?lim=10").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn strict_mode_rejects_unknown_query_params() {
+    let app = setup_test_app_with(|builder| {
+        builder.with_unknown_query_params(UnknownQueryParams::Reject)
+    })
+    .await;
+
+    let response = app.auth().get("/schemas?lim=10").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "UNKNOWN_QUERY_PARAM");
+    assert!(body.message.contains("`lim`"), "{}", body.message);
+}
+
+#[tokio::test]
+async fn strict_mode_accepts_known_query_params() {
+    let app = setup_test_app_with(|builder| {
+        builder.with_unknown_query_params(UnknownQueryParams::Reject)
+    })
+    .await;
+
+    let schema: Schema = create_valid_schema(&app, "strict-query")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = app
+        .auth()
+        .get("/schemas?limit=10&include_total=true")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .auth()
+        .get(format!(
+            "/logs/schemas/{}?limit=5&direction=forward&filters=%7B%7D",
+            schema.id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.auth().get("/schemas?limit=ten").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(body.error, "INVALID_QUERY");
+}
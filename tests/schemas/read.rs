@@ -1,8 +1,14 @@
-use crab_pot::Schema;
-use reqwest::StatusCode;
+use crab_pot::{dto::schema_dto::BatchGetSchemasResponse, Schema};
+use reqwest::{header, StatusCode};
+use serde_json::{json, Value};
+use uuid::Uuid;
 
 use crate::common::{
-    routes::schemas::{create_valid_schema, get_schema_by_id, get_schema_by_name_and_version},
+    routes::logs::create_log,
+    routes::schemas::{
+        batch_get_schemas, create_valid_schema, get_schema_by_id, get_schema_by_name_and_version,
+        get_schema_definition,
+    },
     test_app::setup_test_app,
 };
 
@@ -21,6 +27,83 @@ async fn retrieves_existing_schema_by_id() {
     assert_eq!(retrieved.name, "get-test");
 }
 
+#[tokio::test]
+async fn log_range_is_attached_on_request() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "log-range")
+        .await
+        .json()
+        .await
+        .unwrap();
+    for created_at in [
+        "2021-03-04T05:06:07Z",
+        "2020-01-02T03:04:05Z",
+        "2022-06-07T08:09:10Z",
+    ] {
+        let payload = json!({
+            "schema_id": schema.id,
+            "log_data": { "message": "ranged" },
+            "created_at": created_at
+        });
+        assert_eq!(
+            create_log(&app, &payload).await.status(),
+            StatusCode::CREATED
+        );
+    }
+
+    let body: Value = get_schema_by_id(&app, &schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(body.get("log_range").is_none());
+
+    let response = app
+        .auth()
+        .get(format!("/schemas/{}?include_log_range=true", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(
+        body["log_range"],
+        json!({
+            "logs_from": "2020-01-02T03:04:05Z",
+            "logs_to": "2022-06-07T08:09:10Z",
+            "log_count": 3
+        })
+    );
+}
+
+#[tokio::test]
+async fn log_range_is_null_without_logs() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "log-range-empty")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let body: Value = app
+        .auth()
+        .get(format!("/schemas/{}?include_log_range=true", schema.id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        body["log_range"],
+        json!({ "logs_from": null, "logs_to": null, "log_count": 0 })
+    );
+}
+
 #[tokio::test]
 async fn retrieves_existing_schema_by_name_and_version() {
     let app = setup_test_app().await;
@@ -51,3 +134,86 @@ async fn rejects_invalid_uuid_format() {
     let response = get_schema_by_id(&app, "not-a-uuid").await;
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn retrieves_raw_schema_definition() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "definition-test")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = get_schema_definition(&app, &schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/schema+json"
+    );
+    let etag = response.headers()[header::ETAG].clone();
+
+    let definition: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(definition, schema.schema_definition);
+
+    let response = app
+        .auth()
+        .get(format!(
+            "/schemas/by-name/{}/versions/{}/definition",
+            schema.name, schema.version
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::ETAG], etag);
+
+    let response = app
+        .auth()
+        .get(format!("/schemas/{}/definition", schema.id))
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn batch_get_partitions_found_and_missing_ids() {
+    let app = setup_test_app().await;
+
+    let first: Schema = create_valid_schema(&app, "batch-get-a")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let second: Schema = create_valid_schema(&app, "batch-get-b")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let missing = Uuid::new_v4();
+
+    let response = batch_get_schemas(&app, &json!({ "ids": [first.id, missing, second.id] })).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: BatchGetSchemasResponse = response.json().await.unwrap();
+    let mut found: Vec<Uuid> = body.schemas.iter().map(|schema| schema.id).collect();
+    found.sort();
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+    assert_eq!(found, expected);
+    assert_eq!(body.missing, vec![missing]);
+}
+
+#[tokio::test]
+async fn batch_get_rejects_empty_and_oversized_batches() {
+    let app = setup_test_app().await;
+
+    let response = batch_get_schemas(&app, &json!({ "ids": [] })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let ids: Vec<Uuid> = (0..101).map(|_| Uuid::new_v4()).collect();
+    let response = batch_get_schemas(&app, &json!({ "ids": ids })).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,55 @@
+use crab_pot::{Schema, TrailingSlash};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::common::{create_valid_schema, setup_test_app, setup_test_app_with};
+
+#[tokio::test]
+async fn trailing_slash_is_a_404_by_default() {
+    let app = setup_test_app().await;
+
+    let response = app.auth().get("/schemas/").send().await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn trim_mode_routes_trailing_slash_to_the_same_handler() {
+    let app = setup_test_app_with(|builder| builder.with_trailing_slash(TrailingSlash::Trim)).await;
+    let schema: Schema = create_valid_schema(&app, "trailing-slash")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut bodies = Vec::new();
+    for path in ["/schemas", "/schemas/"] {
+        let response = app.auth().get(path).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        bodies.push(response.json::<Value>().await.unwrap());
+    }
+    assert_eq!(bodies[0], bodies[1]);
+    assert_eq!(bodies[1]["schemas"][0]["id"], schema.id.to_string());
+
+    let response = app
+        .auth()
+        .get(format!("/schemas/{}/", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn trim_mode_keeps_authentication() {
+    let app = setup_test_app_with(|builder| builder.with_trailing_slash(TrailingSlash::Trim)).await;
+
+    let response = app
+        .client
+        .get(format!("{}/schemas/", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
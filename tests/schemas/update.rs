@@ -423,3 +423,27 @@ async fn preserves_id_and_created_at_fields() {
     assert_eq!(updated_schema.name, "preserve-test-updated");
     assert_ne!(updated_schema.updated_at, created_schema.updated_at);
 }
+
+#[tokio::test]
+async fn replaces_labels() {
+    let app = setup_test_app().await;
+
+    let created_schema: Schema = create_valid_schema(&app, "update-labels")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(created_schema.labels.is_empty());
+
+    let update_payload = json!({
+        "name": "update-labels",
+        "version": created_schema.version,
+        "schema_definition": created_schema.schema_definition,
+        "labels": ["team:payments"]
+    });
+    let response = update_schema(&app, &created_schema.id.to_string(), &update_payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let updated_schema: Schema = response.json().await.unwrap();
+    assert_eq!(updated_schema.labels, vec!["team:payments".to_string()]);
+}
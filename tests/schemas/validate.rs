@@ -0,0 +1,97 @@
+use crab_pot::Schema;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::common::{create_schema, setup_test_app, TestApp};
+
+async fn create_strict_schema(app: &TestApp) -> Schema {
+    let payload = json!({
+        "name": "validate-peek",
+        "version": "1.0.0",
+        "schema_definition": {
+            "type": "object",
+            "properties": {
+                "message": { "type": "string" },
+                "level": { "type": "string" },
+                "count": { "type": "integer", "minimum": 0 }
+            },
+            "required": ["message", "level"],
+            "additionalProperties": false
+        }
+    });
+
+    create_schema(app, &payload).await.json().await.unwrap()
+}
+
+async fn validate(app: &TestApp, schema_id: Uuid, log_data: Value, verbose: bool) -> Value {
+    let response = app
+        .auth()
+        .post(format!("/schemas/{}/validate", schema_id))
+        .query(&[("verbose", verbose.to_string())])
+        .json(&json!({ "log_data": log_data }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn reports_each_failure_category() {
+    let app = setup_test_app().await;
+    let schema = create_strict_schema(&app).await;
+
+    let log_data = json!({ "message": 42, "count": -1, "host": "web-1" });
+    let report = validate(&app, schema.id, log_data, true).await;
+
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["missing"], json!(["/level"]));
+    assert_eq!(
+        report["wrong_type"],
+        json!([{ "path": "/message", "expected": ["string"], "actual": "integer" }])
+    );
+    assert_eq!(report["extra"], json!(["/host"]));
+    assert_eq!(report["other"][0]["path"], "/count");
+    assert_eq!(report["other"][0]["keyword"], "minimum");
+}
+
+#[tokio::test]
+async fn reports_valid_log_data_with_empty_categories() {
+    let app = setup_test_app().await;
+    let schema = create_strict_schema(&app).await;
+
+    let log_data = json!({ "message": "ok", "level": "INFO", "count": 3 });
+    let report = validate(&app, schema.id, log_data, true).await;
+
+    assert_eq!(
+        report,
+        json!({ "valid": true, "missing": [], "wrong_type": [], "extra": [], "other": [] })
+    );
+}
+
+#[tokio::test]
+async fn omits_breakdown_without_verbose() {
+    let app = setup_test_app().await;
+    let schema = create_strict_schema(&app).await;
+
+    let report = validate(&app, schema.id, json!({ "message": "ok" }), false).await;
+
+    assert_eq!(report, json!({ "valid": false }));
+}
+
+#[tokio::test]
+async fn validate_against_unknown_schema() {
+    let app = setup_test_app().await;
+
+    let response = app
+        .auth()
+        .post(format!("/schemas/{}/validate", Uuid::new_v4()))
+        .json(&json!({ "log_data": {} }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,184 @@
+use crab_pot::{dto::SchemaResponse, Log};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{
+    create_valid_log, create_valid_schema, get_log, get_schema_by_id, setup_test_app,
+    valid_log_payload, valid_schema_payload, TestApp,
+};
+
+async fn create_tenant_schema(app: &TestApp, name: &str) -> (String, SchemaResponse) {
+    let other_key = app.create_api_key_for_tenant("tenant-b").await;
+    let response = create_valid_schema(app, name).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    (other_key, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn schemas_are_invisible_to_other_tenants() {
+    let app = setup_test_app().await;
+    let (other_key, schema) = create_tenant_schema(&app, "tenancy-schema").await;
+    let other = app.auth_with(&other_key);
+
+    let response = other
+        .get(format!("/schemas/{}", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .get("/schemas/by-name/tenancy-schema/latest")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body: Value = other
+        .get("/schemas?include_total=true")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["schemas"], json!([]));
+    assert_eq!(body["cursor"]["total"], 0);
+}
+
+#[tokio::test]
+async fn schema_writes_do_not_cross_tenants() {
+    let app = setup_test_app().await;
+    let (other_key, schema) = create_tenant_schema(&app, "tenancy-writes").await;
+    let other = app.auth_with(&other_key);
+
+    let response = other
+        .put(format!("/schemas/{}", schema.id))
+        .json(&valid_schema_payload("tenancy-hijacked"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .delete(format!("/schemas/{}", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let stored: SchemaResponse = get_schema_by_id(&app, &schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.name, "tenancy-writes");
+}
+
+#[tokio::test]
+async fn tenants_have_separate_schema_namespaces() {
+    let app = setup_test_app().await;
+    let (other_key, schema) = create_tenant_schema(&app, "tenancy-shared-name").await;
+
+    let response = app
+        .auth_with(&other_key)
+        .post("/schemas")
+        .json(&valid_schema_payload("tenancy-shared-name"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let other_schema: SchemaResponse = response.json().await.unwrap();
+    assert_ne!(other_schema.id, schema.id);
+
+    let own: SchemaResponse = app
+        .auth()
+        .get("/schemas/by-name/tenancy-shared-name/latest")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(own.id, schema.id);
+}
+
+#[tokio::test]
+async fn logs_are_invisible_to_other_tenants() {
+    let app = setup_test_app().await;
+    let (other_key, schema) = create_tenant_schema(&app, "tenancy-logs").await;
+    let other = app.auth_with(&other_key);
+
+    let log: Log = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = other.get(format!("/logs/{}", log.id)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .get(format!("/logs/schemas/{}", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .get("/logs/by-schema-name/tenancy-logs/latest")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .get(format!("/logs/schemas/{}/export", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn log_writes_do_not_cross_tenants() {
+    let app = setup_test_app().await;
+    let (other_key, schema) = create_tenant_schema(&app, "tenancy-log-writes").await;
+    let other = app.auth_with(&other_key);
+
+    let log: Log = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let response = other
+        .post("/logs")
+        .json(&valid_log_payload(&schema.id.to_string()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .patch(format!("/logs/{}", log.id))
+        .json(&json!({ "message": "overwritten" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = other
+        .delete(format!("/logs/{}", log.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let stored: Log = get_log(&app, log.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(stored.log_data, log.log_data);
+}
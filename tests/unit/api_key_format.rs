@@ -0,0 +1,61 @@
+use crab_pot::{ApiKeyFormat, ApiKeyHashAlgorithm};
+
+#[test]
+fn display_prefix_keeps_prefix_and_visible_characters() {
+    let format = ApiKeyFormat::default();
+
+    assert_eq!(format.display_prefix("sk_abcdefghijkl"), "sk_abcdefg...");
+}
+
+#[test]
+fn display_prefix_handles_keys_shorter_than_prefix_length() {
+    let format = ApiKeyFormat::default();
+
+    assert_eq!(format.display_prefix("sk_ab"), "sk_ab...");
+    assert_eq!(format.display_prefix("s"), "s...");
+    assert_eq!(format.display_prefix(""), "...");
+}
+
+#[test]
+fn display_prefix_does_not_split_multibyte_characters() {
+    let format = ApiKeyFormat::new("clé_".to_string(), 32).unwrap();
+
+    assert_eq!(format.display_prefix("clé_ääääääää"), "clé_äääääää...");
+}
+
+#[test]
+fn display_prefix_candidates_cover_every_configurable_prefix() {
+    let key = format!("lgs_{}", "a".repeat(43));
+    let candidates = ApiKeyFormat::display_prefix_candidates(&key);
+
+    assert_eq!(candidates.len(), ApiKeyFormat::MAX_PREFIX_LEN + 1);
+    assert_eq!(candidates[0], "lgs_aaa...");
+    for prefix in ["", "sk_", "lgs_"] {
+        let format = ApiKeyFormat::new(prefix.to_string(), 32).unwrap();
+        assert!(candidates.contains(&format.display_prefix(&key)));
+    }
+
+    assert_eq!(
+        ApiKeyFormat::display_prefix_candidates("sk_ab"),
+        ["sk_ab..."]
+    );
+}
+
+#[test]
+fn parses_hash_algorithm_names() {
+    assert_eq!(
+        "sha256".parse::<ApiKeyHashAlgorithm>().unwrap(),
+        ApiKeyHashAlgorithm::Sha256
+    );
+    assert_eq!(
+        "Argon2".parse::<ApiKeyHashAlgorithm>().unwrap(),
+        ApiKeyHashAlgorithm::Argon2
+    );
+    assert!("bcrypt".parse::<ApiKeyHashAlgorithm>().is_err());
+}
+
+#[test]
+fn rejects_fewer_than_minimum_random_bytes() {
+    assert!(ApiKeyFormat::new("sk_".to_string(), ApiKeyFormat::MIN_RANDOM_BYTES - 1).is_err());
+    assert!(ApiKeyFormat::new("sk_".to_string(), ApiKeyFormat::MIN_RANDOM_BYTES).is_ok());
+}
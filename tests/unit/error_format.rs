@@ -0,0 +1,120 @@
+use axum::http::StatusCode;
+use std::collections::HashSet;
+
+use crab_pot::{middleware::RequestId, AppError, ErrorBodyFormat, ErrorCode};
+use serde_json::{json, Value};
+
+async fn render(error: AppError, format: &ErrorBodyFormat) -> (StatusCode, Value) {
+    let response = error.into_response_with(format);
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+fn not_found() -> AppError {
+    AppError::not_found("Schema not found").with_request_id(&RequestId("req-1".to_string()))
+}
+
+#[tokio::test]
+async fn default_format_keeps_error_and_message_keys() {
+    let (status, body) = render(not_found(), &ErrorBodyFormat::default()).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body,
+        json!({
+            "error": "NOT_FOUND",
+            "message": "Schema not found",
+            "request_id": "req-1",
+        })
+    );
+}
+
+#[tokio::test]
+async fn remapped_field_names_replace_default_keys() {
+    let format = ErrorBodyFormat {
+        code_field: "code".to_string(),
+        message_field: "detail".to_string(),
+        wrap: false,
+    };
+
+    let (status, body) = render(not_found(), &format).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body,
+        json!({
+            "code": "NOT_FOUND",
+            "detail": "Schema not found",
+            "request_id": "req-1",
+        })
+    );
+}
+
+#[tokio::test]
+async fn wrapped_format_nests_fields_under_error() {
+    let format = ErrorBodyFormat {
+        code_field: "code".to_string(),
+        message_field: "detail".to_string(),
+        wrap: true,
+    };
+
+    let error = AppError::bad_request("Invalid cursor")
+        .with_code(ErrorCode::InvalidCursor)
+        .with_request_id(&RequestId("req-2".to_string()));
+    let (status, body) = render(error, &format).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        json!({
+            "error": {
+                "code": "INVALID_CURSOR",
+                "detail": "Invalid cursor",
+                "request_id": "req-2",
+            }
+        })
+    );
+}
+
+#[test]
+fn error_code_catalog_is_unique_and_documented() {
+    let srd = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/docs/SRD.md")).unwrap();
+    let mut seen = HashSet::new();
+
+    for code in ErrorCode::ALL {
+        let text = code.as_str();
+
+        assert!(seen.insert(text), "duplicate error code {}", text);
+        assert!(
+            text.chars().all(|c| c.is_ascii_uppercase() || c == '_'),
+            "{} is not SCREAMING_SNAKE_CASE",
+            text
+        );
+        assert_eq!(serde_json::to_value(code).unwrap(), json!(text));
+        assert!(
+            srd.contains(&format!("| `{}` |", text)),
+            "{} is missing from the SRD error code table",
+            text
+        );
+    }
+}
+
+#[tokio::test]
+async fn kind_defaults_come_from_the_catalog() {
+    let cases = [
+        (AppError::not_found("x"), ErrorCode::NotFound),
+        (AppError::conflict("x"), ErrorCode::Conflict),
+        (AppError::internal_error("x"), ErrorCode::InternalError),
+        (AppError::bad_request("x"), ErrorCode::BadRequest),
+    ];
+
+    for (error, code) in cases {
+        assert_eq!(error.code(), code);
+        let (_, body) = render(error, &ErrorBodyFormat::default()).await;
+        assert_eq!(body["error"], json!(code.as_str()));
+    }
+}
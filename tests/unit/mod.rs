@@ -0,0 +1,7 @@
+pub mod api_key_format;
+pub mod config;
+pub mod error_format;
+pub mod schema_limits;
+pub mod secrets;
+pub mod timestamps;
+pub mod trace_context;
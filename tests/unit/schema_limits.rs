@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::IntoResponse};
+use crab_pot::{
+    models::{CreateSchema, DEFAULT_TENANT_ID},
+    AppError, InMemorySchemaRepository, SchemaComplexityLimits, SchemaService, ValidationMode,
+};
+use serde_json::{json, Map, Value};
+
+const LIMITS: SchemaComplexityLimits = SchemaComplexityLimits {
+    max_bytes: 4096,
+    max_depth: 8,
+    max_properties: 20,
+};
+
+fn schema_service() -> SchemaService {
+    let schema_repo = Arc::new(InMemorySchemaRepository::new());
+    let log_repo = schema_repo.logs();
+
+    SchemaService::new(schema_repo, log_repo).with_complexity_limits(LIMITS)
+}
+
+fn create_schema_request(schema_definition: Value) -> CreateSchema {
+    CreateSchema {
+        name: "complex".to_string(),
+        version: "1.0.0".to_string(),
+        description: None,
+        schema_definition,
+        webhook_url: None,
+        webhook_secret: None,
+        strict_formats: false,
+        indexed_fields: Vec::new(),
+        max_retained_logs: None,
+        max_log_age_secs: None,
+        redacted_fields: Vec::new(),
+        sample_rate: None,
+        labels: Vec::new(),
+        validation_mode: ValidationMode::default(),
+        max_field_bytes: None,
+        tenant_id: DEFAULT_TENANT_ID.to_string(),
+        created_by: None,
+    }
+}
+
+fn object_with_properties(count: usize) -> Value {
+    let properties: Map<String, Value> = (0..count)
+        .map(|i| (format!("field_{}", i), json!({ "type": "string" })))
+        .collect();
+    json!({ "type": "object", "properties": properties })
+}
+
+/// An object schema nesting `levels` objects through `properties.child`.
+fn nested_object(levels: usize) -> Value {
+    (0..levels).fold(
+        json!({ "type": "string" }),
+        |child, _| json!({ "type": "object", "properties": { "child": child } }),
+    )
+}
+
+async fn rejection(schema_definition: Value) -> (StatusCode, Value) {
+    let error: AppError = schema_service()
+        .create_schema(create_schema_request(schema_definition))
+        .await
+        .unwrap_err();
+    let response = error.into_response();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn accepts_definition_within_limits() {
+    let result = schema_service()
+        .create_schema(create_schema_request(object_with_properties(20)))
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn rejects_too_many_properties() {
+    let (status, body) = rejection(object_with_properties(21)).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "SCHEMA_TOO_COMPLEX");
+    assert_eq!(body["limit"], "max_properties");
+    assert_eq!(body["actual"], 21);
+}
+
+#[tokio::test]
+async fn rejects_too_deep_nesting() {
+    let (status, body) = rejection(nested_object(5)).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "SCHEMA_TOO_COMPLEX");
+    assert_eq!(body["limit"], "max_depth");
+}
+
+#[tokio::test]
+async fn rejects_oversized_definition() {
+    let definition = json!({
+        "type": "object",
+        "description": "x".repeat(5000),
+    });
+
+    let (status, body) = rejection(definition).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "SCHEMA_TOO_COMPLEX");
+    assert_eq!(body["limit"], "max_bytes");
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crab_pot::Config;
+
+fn secret_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}", name, uuid::Uuid::new_v4()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn load(vars: &[(&str, &str)]) -> anyhow::Result<Config> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    Config::from_lookup(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn reads_secret_from_file_without_trailing_newline() {
+    let path = secret_file("db-url", "postgres://user:pass@db/logs\n");
+
+    let config = load(&[("DATABASE_URL_FILE", path.to_str().unwrap())]).unwrap();
+
+    assert_eq!(config.database_url, "postgres://user:pass@db/logs");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn reads_secret_from_plain_variable() {
+    let config = load(&[("DATABASE_URL", "postgres://plain")]).unwrap();
+
+    assert_eq!(config.database_url, "postgres://plain");
+}
+
+#[test]
+fn missing_secret_is_an_error() {
+    let error = load(&[]).unwrap_err();
+
+    assert!(error.to_string().contains("DATABASE_URL"), "{}", error);
+}
+
+#[test]
+fn rejects_both_variable_and_file() {
+    let path = secret_file("both", "from-file");
+
+    let error = load(&[
+        ("DATABASE_URL", "from-env"),
+        ("DATABASE_URL_FILE", path.to_str().unwrap()),
+    ])
+    .unwrap_err();
+
+    assert!(error.to_string().contains("not both"), "{}", error);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn rejects_unreadable_or_empty_file() {
+    let missing = std::env::temp_dir().join("crab-pot-no-such-secret");
+    assert!(load(&[("DATABASE_URL_FILE", missing.to_str().unwrap())]).is_err());
+
+    let path = secret_file("empty", "\n");
+    assert!(load(&[("DATABASE_URL_FILE", path.to_str().unwrap())]).is_err());
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,33 @@
+use chrono::{TimeZone, Utc};
+use crab_pot::dto::timestamp::{format_with, TimestampPrecision};
+
+#[test]
+fn formats_utc_with_requested_precision() {
+    let timestamp = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap()
+        + chrono::Duration::microseconds(123_456);
+
+    let cases = [
+        (TimestampPrecision::Auto, "2025-01-02T03:04:05.123456Z"),
+        (TimestampPrecision::Secs, "2025-01-02T03:04:05Z"),
+        (TimestampPrecision::Millis, "2025-01-02T03:04:05.123Z"),
+        (TimestampPrecision::Micros, "2025-01-02T03:04:05.123456Z"),
+        (TimestampPrecision::Nanos, "2025-01-02T03:04:05.123456000Z"),
+    ];
+    for (precision, expected) in cases {
+        assert_eq!(
+            format_with(&timestamp, precision),
+            expected,
+            "{:?}",
+            precision
+        );
+    }
+}
+
+#[test]
+fn parses_precision_names() {
+    assert_eq!(
+        " Millis ".parse::<TimestampPrecision>().unwrap(),
+        TimestampPrecision::Millis
+    );
+    assert!("hours".parse::<TimestampPrecision>().is_err());
+}
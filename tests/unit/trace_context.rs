@@ -0,0 +1,104 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use axum::http::Request;
+use crab_pot::{
+    middleware::{RequestId, TraceContext},
+    RequestIdMakeSpan,
+};
+use tower_http::trace::MakeSpan;
+use tracing_subscriber::fmt::MakeWriter;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const PARENT_ID: &str = "00f067aa0ba902b7";
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Captured;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[test]
+fn parses_valid_traceparent() {
+    let context = TraceContext::parse(&format!("00-{}-{}-01", TRACE_ID, PARENT_ID)).unwrap();
+
+    assert_eq!(context.trace_id, TRACE_ID);
+    assert_eq!(context.parent_id, PARENT_ID);
+    assert_eq!(context.flags, "01");
+}
+
+#[test]
+fn rejects_malformed_traceparent() {
+    for value in [
+        "",
+        "garbage",
+        &format!("ff-{}-{}-01", TRACE_ID, PARENT_ID),
+        &format!("00-{}-{}-01", "0".repeat(32), PARENT_ID),
+        &format!("00-{}-{}-01", TRACE_ID, "0".repeat(16)),
+        &format!("00-{}-{}-01", TRACE_ID.to_uppercase(), PARENT_ID),
+        &format!("00-{}-{}-01-extra", TRACE_ID, PARENT_ID),
+        &format!("00-{}-{}", TRACE_ID, PARENT_ID),
+    ] {
+        assert_eq!(TraceContext::parse(value), None, "{}", value);
+    }
+}
+
+#[test]
+fn child_header_keeps_trace_id_with_new_span_id() {
+    let context = TraceContext::parse(&format!("00-{}-{}-01", TRACE_ID, PARENT_ID)).unwrap();
+
+    let child = TraceContext::parse(&context.child_header()).unwrap();
+    assert_eq!(child.trace_id, TRACE_ID);
+    assert_eq!(child.flags, "01");
+    assert_ne!(child.parent_id, PARENT_ID);
+}
+
+#[test]
+fn trace_id_is_recorded_on_request_span() {
+    let captured = Captured::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(captured.clone())
+        .with_ansi(false)
+        .finish();
+
+    let mut request = Request::builder().uri("/logs").body(()).unwrap();
+    request.extensions_mut().insert(RequestId("req-1".into()));
+    request
+        .extensions_mut()
+        .insert(TraceContext::parse(&format!("00-{}-{}-01", TRACE_ID, PARENT_ID)).unwrap());
+
+    tracing::subscriber::with_default(subscriber, || {
+        let span = RequestIdMakeSpan.make_span(&request);
+        span.in_scope(|| tracing::info!("handled"));
+    });
+
+    let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert!(
+        output.contains(&format!("trace_id={}", TRACE_ID)),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains(&format!("parent_span_id={}", PARENT_ID)),
+        "{}",
+        output
+    );
+}
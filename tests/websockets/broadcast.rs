@@ -0,0 +1,94 @@
+use crab_pot::{LogEvent, Schema};
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+use serde_json::json;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+};
+
+use crate::common::{create_valid_log, create_valid_schema, setup_test_app, valid_log_payload};
+
+#[tokio::test]
+async fn broadcast_false_creates_logs_without_notifying_subscribers() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-quiet-backfill")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let payload = valid_log_payload(&schema.id.to_string());
+
+    let url = format!(
+        "{}/ws/logs?schema_id={}",
+        app.address.replace("http", "ws"),
+        schema.id
+    );
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+
+    // The subscribed frame proves the socket is receiving broadcasts.
+    let first = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .unwrap();
+    assert!(matches!(first, Some(Ok(Message::Text(_)))));
+
+    let created = app
+        .auth()
+        .post("/logs?broadcast=false")
+        .json(&payload)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+
+    let bulk = app
+        .auth()
+        .post("/logs/bulk?broadcast=false")
+        .json(&json!([payload, payload]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(bulk.status(), StatusCode::OK);
+
+    let ingested = app
+        .auth()
+        .post("/logs/ingest?broadcast=false")
+        .header("Content-Type", "application/x-ndjson")
+        .body(payload.to_string())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(ingested.status(), StatusCode::OK);
+
+    // A broadcast log created last: it must be the only event received.
+    let loud: serde_json::Value = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut created_ids = Vec::new();
+    while let Ok(Some(Ok(message))) = timeout(Duration::from_secs(1), ws_stream.next()).await {
+        if let Message::Text(text) = message {
+            if let Ok(LogEvent::Created { id, .. }) = serde_json::from_str(&text) {
+                created_ids.push(id);
+            }
+        }
+    }
+
+    assert_eq!(created_ids, vec![loud["id"].as_i64().unwrap() as i32]);
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM logs WHERE schema_id = $1")
+        .bind(schema.id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, 5);
+}
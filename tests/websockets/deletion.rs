@@ -0,0 +1,64 @@
+use crab_pot::{LogEvent, Schema};
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+};
+
+use crate::common::{create_valid_log, create_valid_schema, delete_log, setup_test_app};
+
+#[tokio::test]
+async fn concurrent_deletes_broadcast_the_deleted_row_once() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-delete-race")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log: serde_json::Value = create_valid_log(&app, schema.id.to_string())
+        .await
+        .json()
+        .await
+        .unwrap();
+    let log_id = log["id"].as_i64().unwrap() as i32;
+
+    let url = format!(
+        "{}/ws/logs?schema_id={}",
+        app.address.replace("http", "ws"),
+        schema.id
+    );
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+
+    // The subscribed frame proves the socket is receiving broadcasts.
+    let first = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .unwrap();
+    assert!(matches!(first, Some(Ok(Message::Text(_)))));
+
+    let (first, second) = tokio::join!(
+        delete_log(&app, log_id.to_string()),
+        delete_log(&app, log_id.to_string())
+    );
+    let mut statuses = [first.status(), second.status()];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::NOT_FOUND]);
+
+    let mut deleted = Vec::new();
+    while let Ok(Some(Ok(message))) = timeout(Duration::from_secs(1), ws_stream.next()).await {
+        if let Message::Text(text) = message {
+            if let Ok(LogEvent::Deleted { id, schema_id }) = serde_json::from_str(&text) {
+                deleted.push((id, schema_id));
+            }
+        }
+    }
+
+    assert_eq!(deleted, vec![(log_id, schema.id)]);
+}
@@ -0,0 +1,135 @@
+use crab_pot::{
+    dto::{SequencedLogEvent, WsControlFrame},
+    LogEvent, Schema,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::common::{create_valid_log_with_message, create_valid_schema, setup_test_app, TestApp};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn connect(app: &TestApp, query: &str) -> WsStream {
+    let url = format!("{}/ws/logs{}", app.address.replace("http", "ws"), query);
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+
+    connect_async(request).await.unwrap().0
+}
+
+async fn next_text(ws_stream: &mut WsStream) -> Option<String> {
+    loop {
+        let message = timeout(Duration::from_secs(5), ws_stream.next())
+            .await
+            .ok()??
+            .ok()?;
+
+        if let Message::Text(text) = message {
+            return Some(text.to_string());
+        }
+    }
+}
+
+async fn next_control(ws_stream: &mut WsStream) -> WsControlFrame {
+    let text = next_text(ws_stream).await.expect("No control frame");
+    serde_json::from_str(&text).expect("Expected a control frame")
+}
+
+async fn subscribe(ws_stream: &mut WsStream, filters: serde_json::Value) -> WsControlFrame {
+    let frame = json!({ "type": "subscribe", "filters": filters });
+    ws_stream
+        .send(Message::Text(frame.to_string()))
+        .await
+        .unwrap();
+    next_control(ws_stream).await
+}
+
+#[tokio::test]
+async fn subscribe_filters_withhold_non_matching_events() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-filters")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut ws_stream = connect(&app, &format!("?schema_id={}", schema.id)).await;
+    assert!(matches!(
+        next_control(&mut ws_stream).await,
+        WsControlFrame::Subscribed { .. }
+    ));
+
+    let frame = subscribe(&mut ws_stream, json!({ "message": "keep" })).await;
+    let WsControlFrame::FiltersApplied { filters } = frame else {
+        panic!("Expected a filters_applied frame, got: {:?}", frame);
+    };
+    assert_eq!(filters, Some(json!({ "message": "keep" })));
+
+    for message in ["skip", "keep", "skip", "keep"] {
+        create_valid_log_with_message(&app, schema.id.to_string(), message).await;
+    }
+
+    let mut received = Vec::new();
+    while received.len() < 2 {
+        let text = next_text(&mut ws_stream).await.expect("Missing event");
+        let frame: SequencedLogEvent = serde_json::from_str(&text).unwrap();
+        let LogEvent::Created { log_data, .. } = frame.event else {
+            panic!("Expected a created event");
+        };
+        received.push((frame.seq, log_data["message"].clone()));
+    }
+
+    assert_eq!(received, vec![(1, json!("keep")), (2, json!("keep"))]);
+}
+
+#[tokio::test]
+async fn null_filters_restore_every_event() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-filters-cleared")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut ws_stream = connect(&app, &format!("?schema_id={}", schema.id)).await;
+    next_control(&mut ws_stream).await;
+
+    subscribe(&mut ws_stream, json!({ "message": "keep" })).await;
+    let frame = subscribe(&mut ws_stream, json!(null)).await;
+    assert!(matches!(
+        frame,
+        WsControlFrame::FiltersApplied { filters: None }
+    ));
+
+    create_valid_log_with_message(&app, schema.id.to_string(), "skip").await;
+
+    let text = next_text(&mut ws_stream).await.expect("Missing event");
+    let frame: SequencedLogEvent = serde_json::from_str(&text).unwrap();
+    assert!(matches!(frame.event, LogEvent::Created { .. }));
+}
+
+#[tokio::test]
+async fn invalid_filters_are_answered_with_an_error_frame() {
+    let app = setup_test_app().await;
+
+    let mut ws_stream = connect(&app, "").await;
+    next_control(&mut ws_stream).await;
+
+    let frame = subscribe(&mut ws_stream, json!(["level", "ERROR"])).await;
+    let WsControlFrame::Error { error, .. } = frame else {
+        panic!("Expected an error frame, got: {:?}", frame);
+    };
+    assert_eq!(error, "INVALID_FILTER");
+}
@@ -0,0 +1,100 @@
+use crab_pot::Schema;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::StatusCode;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::common::{create_valid_schema, delete_schema, setup_test_app, ErrorResponse, TestApp};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn subscribe(app: &TestApp, schema: &Schema) -> WsStream {
+    let url = format!(
+        "{}/ws/logs?schema_id={}",
+        app.address.replace("http", "ws"),
+        schema.id
+    );
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+
+    let first = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .unwrap();
+    assert!(matches!(first, Some(Ok(Message::Text(_)))));
+    ws_stream
+}
+
+#[tokio::test]
+async fn delete_schema_with_live_subscribers_is_rejected() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "ws-in-use")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let mut ws_stream = subscribe(&app, &schema).await;
+
+    let response = delete_schema(&app, &schema.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let error: ErrorResponse = response.json().await.unwrap();
+    assert_eq!(error.error, "SCHEMA_IN_USE");
+
+    // Once the subscriber leaves, the schema can be deleted.
+    ws_stream.send(Message::Close(None)).await.unwrap();
+    let mut status = StatusCode::CONFLICT;
+    for _ in 0..50 {
+        status = delete_schema(&app, &schema.id.to_string()).await.status();
+        if status != StatusCode::CONFLICT {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn force_deletes_schema_with_live_subscribers() {
+    let app = setup_test_app().await;
+    let schema: Schema = create_valid_schema(&app, "ws-in-use-force")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let _ws_stream = subscribe(&app, &schema).await;
+
+    let response = app
+        .auth()
+        .delete(format!("/schemas/{}?force=true", schema.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn subscribers_of_other_schemas_do_not_block_deletion() {
+    let app = setup_test_app().await;
+    let watched: Schema = create_valid_schema(&app, "ws-in-use-watched")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let unwatched: Schema = create_valid_schema(&app, "ws-in-use-unwatched")
+        .await
+        .json()
+        .await
+        .unwrap();
+    let _ws_stream = subscribe(&app, &watched).await;
+
+    let response = delete_schema(&app, &unwatched.id.to_string()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
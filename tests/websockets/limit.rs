@@ -0,0 +1,63 @@
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Error, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::common::{setup_test_app, TestApp, WS_MAX_CONNECTIONS};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn connect(app: &TestApp) -> Result<WsStream, Error> {
+    let url = format!("{}/ws/logs", app.address.replace("http", "ws"));
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+
+    connect_async(request).await.map(|(stream, _)| stream)
+}
+
+#[tokio::test]
+async fn rejects_connections_beyond_the_limit() {
+    let app = setup_test_app().await;
+
+    let mut sockets = Vec::new();
+    for _ in 0..WS_MAX_CONNECTIONS {
+        sockets.push(connect(&app).await.unwrap());
+    }
+
+    match connect(&app).await {
+        Err(Error::Http(response)) => {
+            assert_eq!(response.status(), 503);
+            let body: serde_json::Value =
+                serde_json::from_slice(response.body().as_deref().unwrap()).unwrap();
+            assert_eq!(body["error"], "TOO_MANY_CONNECTIONS");
+        }
+        other => panic!("Expected HTTP 503, got: {:?}", other.map(|_| ())),
+    }
+
+    // Closing a socket frees its slot once the server notices.
+    let mut closed = sockets.pop().unwrap();
+    closed.close(None).await.unwrap();
+    while let Ok(Some(Ok(message))) = timeout(Duration::from_secs(5), closed.next()).await {
+        if matches!(message, Message::Close(_)) {
+            break;
+        }
+    }
+
+    let mut reconnected = None;
+    for _ in 0..50 {
+        if let Ok(socket) = connect(&app).await {
+            reconnected = Some(socket);
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    let mut reconnected = reconnected.expect("slot was not released");
+    reconnected.send(Message::Close(None)).await.unwrap();
+}
@@ -0,0 +1,9 @@
+pub mod broadcast;
+pub mod deletion;
+pub mod filters;
+pub mod in_use;
+pub mod limit;
+pub mod rejection;
+pub mod replay;
+pub mod shutdown;
+pub mod subscription;
@@ -0,0 +1,69 @@
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::Response, Error},
+};
+
+use crate::common::{setup_test_app, TestApp};
+
+const REQUEST_ID: &str = "ws-rejection-request";
+
+/// Attempts an upgrade that the server is expected to refuse.
+async fn rejected_upgrade(app: &TestApp, query: &str) -> Response<Option<Vec<u8>>> {
+    let url = format!("{}/ws/logs{}", app.address.replace("http", "ws"), query);
+    let mut request = url.into_client_request().unwrap();
+    let headers = request.headers_mut();
+    headers.insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    headers.insert("X-Request-Id", REQUEST_ID.parse().unwrap());
+
+    match connect_async(request).await {
+        Err(Error::Http(response)) => response,
+        other => panic!("Expected an HTTP error, got: {:?}", other.map(|_| ())),
+    }
+}
+
+fn json_body(response: &Response<Option<Vec<u8>>>) -> serde_json::Value {
+    serde_json::from_slice(response.body().as_deref().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn unknown_schema_upgrade_carries_request_id_and_not_found_body() {
+    let app = setup_test_app().await;
+    let schema_id = uuid::Uuid::new_v4();
+
+    let response = rejected_upgrade(&app, &format!("?schema_id={}", schema_id)).await;
+
+    assert_eq!(response.status(), 404);
+    assert_eq!(response.headers()["x-request-id"], REQUEST_ID);
+    assert_eq!(response.headers()["x-error-code"], "NOT_FOUND");
+
+    let body = json_body(&response);
+    assert_eq!(body["error"], "NOT_FOUND");
+    assert_eq!(body["request_id"], REQUEST_ID);
+
+    let rest: serde_json::Value = app
+        .auth()
+        .get(format!("/schemas/{}", schema_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["error"], rest["error"]);
+    assert_eq!(body["message"], rest["message"]);
+}
+
+#[tokio::test]
+async fn invalid_replay_upgrade_repeats_error_code_in_header() {
+    let app = setup_test_app().await;
+
+    let response = rejected_upgrade(&app, "?replay=5").await;
+
+    assert_eq!(response.status(), 400);
+    assert_eq!(response.headers()["x-request-id"], REQUEST_ID);
+    assert_eq!(response.headers()["x-error-code"], "BAD_REQUEST");
+    assert_eq!(json_body(&response)["error"], "BAD_REQUEST");
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+
+use crab_pot::{
+    dto::{SequencedLogEvent, WsControlFrame},
+    LogEvent, Schema,
+};
+use futures_util::StreamExt;
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Error, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use crate::common::{create_valid_log_with_message, create_valid_schema, setup_test_app, TestApp};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn connect(app: &TestApp, query: &str) -> Result<WsStream, Error> {
+    let url = format!("{}/ws/logs{}", app.address.replace("http", "ws"), query);
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+
+    connect_async(request).await.map(|(stream, _)| stream)
+}
+
+async fn next_text(ws_stream: &mut WsStream) -> Option<String> {
+    loop {
+        let message = timeout(Duration::from_secs(5), ws_stream.next())
+            .await
+            .ok()??
+            .ok()?;
+
+        if let Message::Text(text) = message {
+            return Some(text.to_string());
+        }
+    }
+}
+
+async fn created_ids(ws_stream: &mut WsStream, count: usize) -> Vec<i32> {
+    let mut ids = Vec::new();
+    while ids.len() < count {
+        let Some(text) = next_text(ws_stream).await else {
+            break;
+        };
+        if let Ok(LogEvent::Created { id, .. }) = serde_json::from_str(&text) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+#[tokio::test]
+async fn replay_sends_most_recent_logs_oldest_first() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-replay")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let mut log_ids = Vec::new();
+    for i in 1..=3 {
+        let log: serde_json::Value =
+            create_valid_log_with_message(&app, schema.id.to_string(), &format!("message-{}", i))
+                .await
+                .json()
+                .await
+                .unwrap();
+        log_ids.push(log["id"].as_i64().unwrap() as i32);
+    }
+
+    let mut ws_stream = connect(&app, &format!("?schema_id={}&replay=2", schema.id))
+        .await
+        .unwrap();
+
+    let frame: WsControlFrame =
+        serde_json::from_str(&next_text(&mut ws_stream).await.unwrap()).unwrap();
+    let WsControlFrame::Subscribed { replay, .. } = frame else {
+        panic!("Expected a subscribed frame");
+    };
+    assert_eq!(replay, 2);
+
+    let replayed = created_ids(&mut ws_stream, 2).await;
+    assert_eq!(replayed, log_ids[1..]);
+}
+
+#[tokio::test]
+async fn replay_and_live_events_are_delivered_exactly_once() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-replay-race")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for i in 1..=5 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("before-{}", i)).await;
+    }
+
+    let client = app.client.clone();
+    let address = app.address.clone();
+    let api_key = app.create_api_key_with_limits(1000, 1000).await;
+    let schema_id = schema.id;
+    let writer = tokio::spawn(async move {
+        for i in 1..=8 {
+            let response = client
+                .post(format!("{}/logs", address))
+                .header("Authorization", format!("Bearer {}", api_key))
+                .json(&json!({
+                    "schema_id": schema_id,
+                    "log_data": { "message": format!("during-{}", i) }
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+        }
+    });
+
+    let mut ws_stream = connect(&app, &format!("?schema_id={}&replay=100", schema.id))
+        .await
+        .unwrap();
+    writer.await.unwrap();
+
+    let ids = created_ids(&mut ws_stream, 13).await;
+    assert_eq!(ids.len(), 13, "expected every log exactly once: {:?}", ids);
+
+    let unique: HashSet<_> = ids.iter().collect();
+    assert_eq!(unique.len(), ids.len(), "duplicate events: {:?}", ids);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", ids);
+
+    let extra = timeout(Duration::from_millis(300), ws_stream.next()).await;
+    assert!(extra.is_err(), "unexpected extra event: {:?}", extra);
+}
+
+#[tokio::test]
+async fn replay_requires_schema_id() {
+    let app = setup_test_app().await;
+
+    match connect(&app, "?replay=5").await {
+        Err(Error::Http(response)) => {
+            assert_eq!(response.status(), 400);
+        }
+        other => panic!("Expected HTTP 400, got: {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn event_sequence_numbers_increase_by_one() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-sequence")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    for i in 1..=2 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("before-{}", i)).await;
+    }
+
+    let mut ws_stream = connect(&app, &format!("?schema_id={}&replay=2", schema.id))
+        .await
+        .unwrap();
+
+    for i in 1..=3 {
+        create_valid_log_with_message(&app, schema.id.to_string(), &format!("after-{}", i)).await;
+    }
+
+    let mut sequence = Vec::new();
+    while sequence.len() < 5 {
+        let Some(text) = next_text(&mut ws_stream).await else {
+            break;
+        };
+        if let Ok(frame) = serde_json::from_str::<SequencedLogEvent>(&text) {
+            assert!(matches!(frame.event, LogEvent::Created { .. }));
+            sequence.push(frame.seq);
+        }
+    }
+
+    assert_eq!(sequence, vec![1, 2, 3, 4, 5]);
+}
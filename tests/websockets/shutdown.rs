@@ -0,0 +1,42 @@
+use crab_pot::handlers::ws_handlers::SHUTDOWN_CLOSE_REASON;
+use futures_util::StreamExt;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, protocol::frame::coding::CloseCode, Message},
+};
+
+use crate::common::setup_test_app;
+
+#[tokio::test]
+async fn shutdown_closes_sockets_with_going_away() {
+    let app = setup_test_app().await;
+
+    let url = format!("{}/ws/logs", app.address.replace("http", "ws"));
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+
+    // The subscribed frame proves the socket task is running.
+    let first = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .unwrap();
+    assert!(matches!(first, Some(Ok(Message::Text(_)))));
+
+    app.shutdown.cancel();
+
+    let close = loop {
+        match timeout(Duration::from_secs(5), ws_stream.next()).await {
+            Ok(Some(Ok(Message::Close(frame)))) => break frame,
+            Ok(Some(Ok(_))) => continue,
+            other => panic!("expected a close frame, got {:?}", other),
+        }
+    };
+
+    let frame = close.expect("close frame without code");
+    assert_eq!(frame.code, CloseCode::Away);
+    assert_eq!(frame.reason, SHUTDOWN_CLOSE_REASON);
+}
@@ -0,0 +1,61 @@
+use crab_pot::{dto::WsControlFrame, Schema};
+use futures_util::StreamExt;
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+};
+
+use crate::common::{create_valid_schema, setup_test_app, TestApp};
+
+async fn first_frame(app: &TestApp, query: &str) -> WsControlFrame {
+    let url = format!("{}/ws/logs{}", app.address.replace("http", "ws"), query);
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", app.api_key()).parse().unwrap(),
+    );
+
+    let (mut ws_stream, _) = connect_async(request).await.unwrap();
+
+    let message = timeout(Duration::from_secs(5), ws_stream.next())
+        .await
+        .expect("Timeout waiting for WebSocket message")
+        .expect("WebSocket stream ended")
+        .expect("Failed to receive message");
+
+    ws_stream.close(None).await.unwrap();
+
+    match message {
+        Message::Text(text) => serde_json::from_str(&text).expect("Expected a control frame"),
+        other => panic!("Expected text message, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn first_frame_confirms_schema_subscription() {
+    let app = setup_test_app().await;
+
+    let schema: Schema = create_valid_schema(&app, "ws-subscribed")
+        .await
+        .json()
+        .await
+        .unwrap();
+
+    let frame = first_frame(&app, &format!("?schema_id={}", schema.id)).await;
+    let WsControlFrame::Subscribed { schema_id, replay } = frame else {
+        panic!("Expected a subscribed frame");
+    };
+    assert_eq!(schema_id, Some(schema.id));
+    assert_eq!(replay, 0);
+}
+
+#[tokio::test]
+async fn first_frame_confirms_subscription_to_all_schemas() {
+    let app = setup_test_app().await;
+
+    let WsControlFrame::Subscribed { schema_id, .. } = first_frame(&app, "").await else {
+        panic!("Expected a subscribed frame");
+    };
+    assert_eq!(schema_id, None);
+}